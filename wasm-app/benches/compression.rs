@@ -0,0 +1,104 @@
+//! Benchmark of LZ4 (wasm_memory_process_data_arrow_lz4 of wasm-module2) and zstd compression of data in Arrow IPC format of 1 MB, 10 MB and 100 MB
+//! The compression ratio of each size is printed before its benchmark. Run it with cargo bench --bench compression
+use std::sync::Arc;
+
+use arrow::array::{Float64Array, StringArray, TimestampSecondArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/// Sizes of the data in Arrow IPC format
+const DATA_SIZES: [(&str, usize); 3] = [
+    ("1MB", 1_000_000),
+    ("10MB", 10_000_000),
+    ("100MB", 100_000_000),
+];
+
+/// Compression level of zstd (default of the zstd command line tool)
+const ZSTD_LEVEL: i32 = 3;
+
+/// Documents with the schema of the example data of wasm-app
+/// # Arguments
+/// * `size` - approximate size of the data in bytes
+///
+/// returns the documents in Arrow IPC format
+fn example_data(size: usize) -> Vec<u8> {
+    // a row takes about 100 bytes
+    let rows: usize = size / 100;
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from_iter_values(0..rows as u64)),
+            Arc::new(StringArray::from_iter_values((0..rows).map(|i| {
+                format!("this is a test of document {i} with some content")
+            }))),
+            Arc::new(StringArray::from_iter_values(
+                (0..rows).map(|i| format!("test {}", i % 1_000)),
+            )),
+            // 2022-01-01T12:00:00Z plus one second per document
+            Arc::new(
+                TimestampSecondArray::from_iter_values((0..rows as i64).map(|i| 1_641_038_400 + i))
+                    .with_timezone("+00:00"),
+            ),
+            Arc::new(Float64Array::from_iter_values(
+                (0..rows).map(|i| (i % 100) as f64 / 100.0),
+            )),
+        ],
+    )
+    .unwrap();
+    let mut stream_writer = StreamWriter::try_new(Vec::new(), &batch.schema()).unwrap();
+    stream_writer.write(&batch).unwrap();
+    stream_writer.into_inner().unwrap()
+}
+
+/// Compares the compression and decompression of LZ4 and zstd for each size of the data
+/// # Arguments
+/// * `c` - benchmark manager
+fn compression(c: &mut Criterion) {
+    for (name, size) in DATA_SIZES {
+        let data: Vec<u8> = example_data(size);
+        // LZ4 with the prepended uncompressed size, like the module expects it
+        let lz4_compressed: Vec<u8> = lz4_flex::compress_prepend_size(&data);
+        let zstd_compressed: Vec<u8> = zstd::encode_all(data.as_slice(), ZSTD_LEVEL).unwrap();
+        println!(
+            "{name}: {} bytes, LZ4 {} bytes (ratio {:.2}), zstd {} bytes (ratio {:.2})",
+            data.len(),
+            lz4_compressed.len(),
+            data.len() as f64 / lz4_compressed.len() as f64,
+            zstd_compressed.len(),
+            data.len() as f64 / zstd_compressed.len() as f64
+        );
+        let mut group = c.benchmark_group(format!("compression_{name}"));
+        // the larger sizes take too long for the default of 100 samples
+        group.sample_size(10);
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_function(BenchmarkId::new("compress", "lz4"), |b| {
+            b.iter(|| lz4_flex::compress_prepend_size(&data))
+        });
+        group.bench_function(BenchmarkId::new("compress", "zstd"), |b| {
+            b.iter(|| zstd::encode_all(data.as_slice(), ZSTD_LEVEL).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("decompress", "lz4"), |b| {
+            b.iter(|| lz4_flex::decompress_size_prepended(&lz4_compressed).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("decompress", "zstd"), |b| {
+            b.iter(|| zstd::decode_all(zstd_compressed.as_slice()).unwrap())
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, compression);
+criterion_main!(benches);
@@ -0,0 +1,150 @@
+//! Processing of data that exceeds the memory budget of a WASM module in chunks whose size is adapted to the budget
+use std::fmt;
+use std::sync::Arc;
+
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use wasmtime::Engine;
+use wasmtime::Module;
+
+use crate::profiler::ExecutionProfiler;
+use crate::sandbox::{create_sandboxed_instance, SandboxConfig};
+use crate::{
+    call_wasm_process_data_arrow_serialized, create_arrow_example_meta_data, serialize_arrow_batch,
+};
+
+/// Status of a WasmResult if the WASM module could not allocate memory
+pub const WASM_RESULT_ERROR_OUT_OF_MEMORY: i32 = -5;
+
+/// Status of a WasmResult if the result exceeds the limit of the size of results of the WASM module
+pub const WASM_RESULT_ERROR_OUTPUT_TOO_LARGE: i32 = -40;
+
+/// Number of times the size of a chunk is halved and its processing is retried if the WASM module runs out of memory or its result is too large
+const MAX_OUT_OF_MEMORY_RETRIES: u32 = 4;
+
+/// Error if the WASM module could not allocate memory, e.g. for the data or the result
+#[derive(Debug)]
+pub struct OutOfMemoryError(pub String);
+
+impl fmt::Display for OutOfMemoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Error: WASM module is out of memory: {}", self.0)
+    }
+}
+
+impl std::error::Error for OutOfMemoryError {}
+
+/// Error if the result of the WASM module exceeds the limit of the size of results (see SandboxConfig::max_output_bytes)
+#[derive(Debug)]
+pub struct OutputTooLargeError(pub String);
+
+impl fmt::Display for OutputTooLargeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Error: Result of WASM module is too large: {}", self.0)
+    }
+}
+
+impl std::error::Error for OutputTooLargeError {}
+
+/// Returns the size of a record batch serialized in Arrow IPC format, ie the payload written to the WASM module memory
+/// # Arguments
+/// * `batch` - record batch
+///
+/// returns the size in bytes
+pub fn get_serialized_size(batch: &RecordBatch) -> usize {
+    serialize_arrow_batch(batch).len()
+}
+
+/// Processes data with process_data_arrow in chunks that do not exceed a payload size. Each chunk is processed by a new instance of the WASM module. The number of rows of a chunk is halved until its payload fits and, if the WASM module runs out of memory or its result is too large, up to MAX_OUT_OF_MEMORY_RETRIES more times
+/// # Arguments
+/// * `engine` - wasmtime engine to use for the stores
+/// * `module` - module containing the WASM function
+/// * `profiler` - profiler to record the calls of the WASM function
+/// * `config` - sandbox configuration applied to the instances
+/// * `full_batch` - data to be processed
+/// * `command` - command of the meta data, e.g. "validate" to validate the data
+/// * `max_payload_bytes` - maximum size of the data of a chunk in Arrow IPC format
+///
+/// returns the results of all chunks concatenated in the order of the chunks. Returns an error if the data has no rows, a single row exceeds max_payload_bytes or a chunk could not be processed
+pub fn adaptive_batch_processor(
+    engine: &Engine,
+    module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+    full_batch: RecordBatch,
+    command: &str,
+    max_payload_bytes: usize,
+) -> anyhow::Result<RecordBatch> {
+    if full_batch.num_rows() == 0 {
+        anyhow::bail!("Error: Data has no rows");
+    }
+    let mut result_batches: Vec<RecordBatch> = Vec::new();
+    // the chunks do not get larger again, the payload of the following rows is probably similar
+    let mut chunk_rows: usize = full_batch.num_rows();
+    let mut offset: usize = 0;
+    let mut retries: u32 = 0;
+    while offset < full_batch.num_rows() {
+        chunk_rows = chunk_rows.min(full_batch.num_rows() - offset);
+        while chunk_rows > 1
+            && get_serialized_size(&full_batch.slice(offset, chunk_rows)) > max_payload_bytes
+        {
+            chunk_rows /= 2;
+        }
+        let chunk: RecordBatch = full_batch.slice(offset, chunk_rows);
+        let chunk_size: usize = get_serialized_size(&chunk);
+        if chunk_size > max_payload_bytes {
+            anyhow::bail!(
+                "Error: Row {offset} has a payload of {chunk_size} bytes that exceeds the budget of {max_payload_bytes} bytes"
+            );
+        }
+        match process_chunk(engine, module, profiler, config, &chunk, command) {
+            Ok(chunk_result_batches) => {
+                result_batches.extend(chunk_result_batches);
+                offset += chunk_rows;
+                retries = 0;
+            }
+            Err(e)
+                if (e.downcast_ref::<OutOfMemoryError>().is_some()
+                    || e.downcast_ref::<OutputTooLargeError>().is_some())
+                    && chunk_rows > 1
+                    && retries < MAX_OUT_OF_MEMORY_RETRIES =>
+            {
+                retries += 1;
+                chunk_rows /= 2;
+                tracing::warn!("Retrying with chunks of {chunk_rows} rows: {e}");
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    let schema = result_batches[0].schema();
+    Ok(arrow::compute::concat_batches(&schema, &result_batches)?)
+}
+
+/// Processes a chunk of data with process_data_arrow of a new instance of the WASM module
+/// # Arguments
+/// * `engine` - wasmtime engine to use for the store
+/// * `module` - module containing the WASM function
+/// * `profiler` - profiler to record the call of the WASM function
+/// * `config` - sandbox configuration applied to the instance
+/// * `chunk` - data to be processed
+/// * `command` - command of the meta data
+///
+/// returns the result batches
+fn process_chunk(
+    engine: &Engine,
+    module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+    chunk: &RecordBatch,
+    command: &str,
+) -> anyhow::Result<Vec<RecordBatch>> {
+    let (instance, mut store) = create_sandboxed_instance(engine, module, profiler, config)?;
+    // the chunk is not dictionary encoded, so that the payload has the size checked against the budget
+    let result_arrow_ipc: Vec<u8> = call_wasm_process_data_arrow_serialized(
+        instance,
+        &mut store,
+        &create_arrow_example_meta_data(command),
+        &serialize_arrow_batch(chunk),
+    )?;
+    Ok(StreamReader::try_new(result_arrow_ipc.as_slice(), None)?.collect::<Result<_, _>>()?)
+}
@@ -0,0 +1,272 @@
+//! Command line interface to call functions of arbitrary WASM modules without changing the application
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use clap::{Parser, Subcommand, ValueEnum};
+use wasmtime::Engine;
+use wasmtime::ExternType;
+use wasmtime::Module;
+
+use crate::compatibility::{check_module_exports, compute_module_api_diff, ModuleApiDiff};
+use crate::introspection::{introspect_function, print_function_table, FunctionSignature};
+use crate::profiler::ExecutionProfiler;
+use crate::sandbox::SandboxConfig;
+use crate::{
+    required_exports_memory_management, wrapper_wasm_call_single_buffer, wrapper_wasm_version,
+};
+
+/// Loads WASM modules and runs their functions. Without a command, the examples of module 1 and module 2 are run
+#[derive(Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// replays the calls of a recording (created with the environment variable WASM_RECORD=1) and prints their results
+    #[arg(long, value_name = "RECORDING")]
+    pub replay: Option<PathBuf>,
+    /// path to the WASM module to replay the recording with. Module 2 is used if not provided
+    #[arg(long, value_name = "MODULE_PATH", requires = "replay")]
+    pub replay_module: Option<PathBuf>,
+}
+
+/// Commands of the command line interface
+#[derive(Subcommand)]
+pub enum Command {
+    /// Compares the exported functions of two versions of a WASM module. Fails if the new version has breaking changes, ie removed functions or changed signatures
+    ApiDiff {
+        /// path to the version of the WASM module the applications are using
+        old_module_path: PathBuf,
+        /// path to the new version of the WASM module
+        new_module_path: PathBuf,
+    },
+    /// Calls a function of a WASM module that processes one input of Arrow data, e.g. wasm_memory_process_tagged_docs_arrow
+    Call {
+        /// path to the WASM module
+        module_path: PathBuf,
+        /// name of the exported function of the WASM module
+        function_name: String,
+        /// file with the input data in Arrow IPC stream format
+        #[arg(long)]
+        input: PathBuf,
+        /// format of the output
+        #[arg(long, value_enum, default_value_t = OutputFormat::Arrow)]
+        format: OutputFormat,
+        /// file to write the output to. The output is written to stdout if not provided
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Lists the exports of a WASM module with their types
+    ListExports {
+        /// path to the WASM module
+        module_path: PathBuf,
+        /// prints only the signature of the exported function with this name
+        #[arg(long)]
+        function: Option<String>,
+    },
+    /// Prints the version of a WASM module
+    Version {
+        /// path to the WASM module
+        module_path: PathBuf,
+    },
+}
+
+/// Format of the output of the command call
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    /// Arrow IPC stream format as returned by the module
+    Arrow,
+    /// JSON array with one object per row
+    Json,
+    /// MessagePack array with one map per row
+    Msgpack,
+}
+
+/// Runs a command of the command line interface
+/// # Arguments
+/// * `engine` - wasmtime engine to use for the stores
+/// * `profiler` - profiler to record the calls of the WASM functions
+/// * `config` - sandbox configuration applied to the instances
+/// * `command` - command to run
+pub fn run_command(
+    engine: &Engine,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+    command: Command,
+) -> anyhow::Result<()> {
+    match command {
+        Command::ApiDiff {
+            old_module_path,
+            new_module_path,
+        } => {
+            let old_module: Module = Module::from_file(engine, &old_module_path)?;
+            let new_module: Module = Module::from_file(engine, &new_module_path)?;
+            let diff: ModuleApiDiff = compute_module_api_diff(&old_module, &new_module);
+            print!("{diff}");
+            if diff.is_breaking() {
+                anyhow::bail!(
+                    "{} has breaking changes compared to {}",
+                    new_module_path.display(),
+                    old_module_path.display()
+                );
+            }
+        }
+        Command::Call {
+            module_path,
+            function_name,
+            input,
+            format,
+            output,
+        } => {
+            let module: Module = Module::from_file(engine, &module_path)?;
+            // the function is called via the shared memory of the module
+            check_module_exports(&module, &required_exports_memory_management())?;
+            let input_data: Vec<u8> = std::fs::read(&input)?;
+            let result_arrow_ipc: Vec<u8> = wrapper_wasm_call_single_buffer(
+                engine,
+                &module,
+                profiler,
+                config,
+                &function_name,
+                &input_data,
+            )?;
+            let output_data: Vec<u8> = convert_output(result_arrow_ipc, format)?;
+            match output {
+                Some(output_path) => std::fs::write(output_path, output_data)?,
+                None => std::io::stdout().write_all(&output_data)?,
+            }
+        }
+        Command::ListExports {
+            module_path,
+            function,
+        } => {
+            let module: Module = Module::from_file(engine, &module_path)?;
+            if let Some(function_name) = function {
+                let signature: FunctionSignature = introspect_function(&module, &function_name)?;
+                println!("{function_name}: {signature}");
+                return Ok(());
+            }
+            print_function_table(&module);
+            // exports that are not functions, e.g. the memory of the module
+            for export in module.exports() {
+                match export.ty() {
+                    ExternType::Func(_) => {}
+                    ExternType::Memory(memory_type) => println!(
+                        "{}: memory (minimum {} pages)",
+                        export.name(),
+                        memory_type.minimum()
+                    ),
+                    ExternType::Global(global_type) => {
+                        println!("{}: global {}", export.name(), global_type.content())
+                    }
+                    ExternType::Table(table_type) => {
+                        println!("{}: table {}", export.name(), table_type.element())
+                    }
+                }
+            }
+        }
+        Command::Version { module_path } => {
+            let module: Module = Module::from_file(engine, &module_path)?;
+            let version: String = wrapper_wasm_version(engine, &module, profiler, config)?;
+            println!("{version}");
+        }
+    }
+    Ok(())
+}
+
+/// Converts the result of a function of a WASM module to the output format
+/// # Arguments
+/// * `result_arrow_ipc` - result of the function in Arrow IPC stream format
+/// * `format` - output format
+///
+/// returns the output
+fn convert_output(result_arrow_ipc: Vec<u8>, format: OutputFormat) -> anyhow::Result<Vec<u8>> {
+    if let OutputFormat::Arrow = format {
+        return Ok(result_arrow_ipc);
+    }
+    let stream_reader = StreamReader::try_new(result_arrow_ipc.as_slice(), None)?;
+    let batches: Vec<RecordBatch> = stream_reader.collect::<Result<Vec<RecordBatch>, _>>()?;
+    let mut json_writer = arrow::json::ArrayWriter::new(Vec::new());
+    json_writer.write_batches(&batches.iter().collect::<Vec<&RecordBatch>>())?;
+    json_writer.finish()?;
+    let json_data: Vec<u8> = json_writer.into_inner();
+    match format {
+        OutputFormat::Msgpack => {
+            // an empty result is written by the JSON writer without any rows
+            let json_value: serde_json::Value = if json_data.is_empty() {
+                serde_json::Value::Array(Vec::new())
+            } else {
+                serde_json::from_slice(&json_data)?
+            };
+            let mut msgpack_data: Vec<u8> = Vec::new();
+            write_msgpack(&json_value, &mut msgpack_data);
+            Ok(msgpack_data)
+        }
+        _ => Ok(json_data),
+    }
+}
+
+/// Writes a JSON value in MessagePack format (https://github.com/msgpack/msgpack/blob/master/spec.md)
+/// # Arguments
+/// * `value` - value to write
+/// * `out` - buffer to write the value to
+fn write_msgpack(value: &serde_json::Value, out: &mut Vec<u8>) {
+    match value {
+        serde_json::Value::Null => out.push(0xc0),
+        serde_json::Value::Bool(false) => out.push(0xc2),
+        serde_json::Value::Bool(true) => out.push(0xc3),
+        serde_json::Value::Number(number) => {
+            if let Some(x) = number.as_u64() {
+                out.push(0xcf);
+                out.extend_from_slice(&x.to_be_bytes());
+            } else if let Some(x) = number.as_i64() {
+                out.push(0xd3);
+                out.extend_from_slice(&x.to_be_bytes());
+            } else {
+                out.push(0xcb);
+                out.extend_from_slice(&number.as_f64().unwrap_or(f64::NAN).to_be_bytes());
+            }
+        }
+        serde_json::Value::String(x) => {
+            write_msgpack_header(out, x.len(), [0xa0, 0xd9, 0xda, 0xdb]);
+            out.extend_from_slice(x.as_bytes());
+        }
+        serde_json::Value::Array(items) => {
+            write_msgpack_header(out, items.len(), [0x90, 0, 0xdc, 0xdd]);
+            for item in items {
+                write_msgpack(item, out);
+            }
+        }
+        serde_json::Value::Object(entries) => {
+            write_msgpack_header(out, entries.len(), [0x80, 0, 0xde, 0xdf]);
+            for (key, item) in entries {
+                write_msgpack(&serde_json::Value::String(key.clone()), out);
+                write_msgpack(item, out);
+            }
+        }
+    }
+}
+
+/// Writes the header of a MessagePack string, array or map
+/// # Arguments
+/// * `out` - buffer to write the header to
+/// * `len` - number of bytes (string) or elements (array, map)
+/// * `markers` - markers of the fix, 8 bit (0 if not available), 16 bit and 32 bit variant of the type
+fn write_msgpack_header(out: &mut Vec<u8>, len: usize, markers: [u8; 4]) {
+    // fix variants store the length in the marker: up to 31 bytes for strings and up to 15 elements for arrays and maps
+    let fix_max: usize = if markers[0] == 0xa0 { 31 } else { 15 };
+    if len <= fix_max {
+        out.push(markers[0] | len as u8);
+    } else if markers[1] != 0 && len <= u8::MAX as usize {
+        out.push(markers[1]);
+        out.push(len as u8);
+    } else if len <= u16::MAX as usize {
+        out.push(markers[2]);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.push(markers[3]);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
@@ -0,0 +1,226 @@
+//! Checks that a WASM module provides the exports (API) expected by the application
+use std::collections::HashMap;
+use std::fmt;
+
+use wasmtime::ExternType;
+use wasmtime::Module;
+use wasmtime::ValType;
+
+/// Function that a WASM module needs to export to be compatible with the application
+pub struct RequiredExport<'a> {
+    /// name of the exported function
+    pub name: &'a str,
+    /// types of the parameters of the function
+    pub params: Vec<ValType>,
+    /// types of the results of the function
+    pub results: Vec<ValType>,
+}
+
+/// Error returned if a WASM module is not compatible with the application
+#[derive(Debug)]
+pub struct ExportCompatibilityError {
+    /// required exports not found in the module
+    pub missing: Vec<String>,
+    /// required exports found in the module, but with a different type, e.g. "answer: expected func () -> (i32), found func (i32) -> (i32)"
+    pub mismatched: Vec<String>,
+}
+
+impl fmt::Display for ExportCompatibilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "WASM module is not compatible with the application.")?;
+        if !self.missing.is_empty() {
+            write!(f, " Missing exports: {}.", self.missing.join(", "))?;
+        }
+        if !self.mismatched.is_empty() {
+            write!(f, " Mismatched exports: {}.", self.mismatched.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ExportCompatibilityError {}
+
+/// Checks that a module exports all required functions with the expected signatures
+/// # Arguments
+/// * `module` - module to check
+/// * `required` - functions the module needs to export
+///
+/// returns an ExportCompatibilityError listing all missing or mismatched exports if the module is not compatible
+pub fn check_module_exports(module: &Module, required: &[RequiredExport]) -> anyhow::Result<()> {
+    let mut missing: Vec<String> = Vec::new();
+    let mut mismatched: Vec<String> = Vec::new();
+    for required_export in required {
+        let export_type: Option<ExternType> = module
+            .exports()
+            .find(|export| export.name() == required_export.name)
+            .map(|export| export.ty());
+        match export_type {
+            None => missing.push(required_export.name.to_string()),
+            Some(ExternType::Func(func_type)) => {
+                let params: Vec<ValType> = func_type.params().collect();
+                let results: Vec<ValType> = func_type.results().collect();
+                if !val_types_eq(&params, &required_export.params)
+                    || !val_types_eq(&results, &required_export.results)
+                {
+                    mismatched.push(format!(
+                        "{}: expected func {} -> {}, found func {} -> {}",
+                        required_export.name,
+                        format_val_types(&required_export.params),
+                        format_val_types(&required_export.results),
+                        format_val_types(&params),
+                        format_val_types(&results)
+                    ));
+                }
+            }
+            Some(_) => mismatched.push(format!(
+                "{}: expected a function, found another kind of export",
+                required_export.name
+            )),
+        }
+    }
+    if missing.is_empty() && mismatched.is_empty() {
+        Ok(())
+    } else {
+        Err(ExportCompatibilityError {
+            missing,
+            mismatched,
+        }
+        .into())
+    }
+}
+
+/// Function exported by a WASM module
+#[derive(Debug, Clone)]
+pub struct ExportInfo {
+    /// name of the exported function
+    pub name: String,
+    /// types of the parameters of the function
+    pub params: Vec<ValType>,
+    /// types of the results of the function
+    pub results: Vec<ValType>,
+}
+
+impl fmt::Display for ExportInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: func {} -> {}",
+            self.name,
+            format_val_types(&self.params),
+            format_val_types(&self.results)
+        )
+    }
+}
+
+/// Differences between the functions exported by two versions of a WASM module
+#[derive(Debug, Default)]
+pub struct ModuleApiDiff {
+    /// functions exported only by the new version
+    pub added: Vec<ExportInfo>,
+    /// functions exported only by the old version
+    pub removed: Vec<ExportInfo>,
+    /// functions exported by both versions with different signatures as (old, new)
+    pub signature_changed: Vec<(ExportInfo, ExportInfo)>,
+}
+
+impl ModuleApiDiff {
+    /// Checks if applications using the old version may fail with the new version
+    ///
+    /// returns true if functions have been removed or their signature has changed. Added functions are not breaking
+    pub fn is_breaking(&self) -> bool {
+        !self.removed.is_empty() || !self.signature_changed.is_empty()
+    }
+}
+
+impl fmt::Display for ModuleApiDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for export in &self.added {
+            writeln!(f, "added: {export}")?;
+        }
+        for export in &self.removed {
+            writeln!(f, "removed: {export}")?;
+        }
+        for (old_export, new_export) in &self.signature_changed {
+            writeln!(f, "changed: {old_export} => {new_export}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the functions exported by two versions of a module, e.g. to detect breaking changes before deploying a new version. Exports that are not functions, e.g. the memory, are not compared
+/// # Arguments
+/// * `old_module` - version of the module the applications are using
+/// * `new_module` - version of the module to compare with
+///
+/// returns the differences of the exported functions matched by name. They are in the order of the exports of the module
+pub fn compute_module_api_diff(old_module: &Module, new_module: &Module) -> ModuleApiDiff {
+    let old_exports: Vec<ExportInfo> = exported_functions(old_module);
+    let new_exports: Vec<ExportInfo> = exported_functions(new_module);
+    let new_exports_by_name: HashMap<&str, &ExportInfo> = new_exports
+        .iter()
+        .map(|export| (export.name.as_str(), export))
+        .collect();
+    let mut diff = ModuleApiDiff::default();
+    for old_export in &old_exports {
+        match new_exports_by_name.get(old_export.name.as_str()) {
+            None => diff.removed.push(old_export.clone()),
+            Some(new_export) => {
+                if !val_types_eq(&old_export.params, &new_export.params)
+                    || !val_types_eq(&old_export.results, &new_export.results)
+                {
+                    diff.signature_changed
+                        .push((old_export.clone(), (*new_export).clone()));
+                }
+            }
+        }
+    }
+    diff.added = new_exports
+        .iter()
+        .filter(|new_export| {
+            !old_exports
+                .iter()
+                .any(|old_export| old_export.name == new_export.name)
+        })
+        .cloned()
+        .collect();
+    diff
+}
+
+/// Lists the functions exported by a module
+/// # Arguments
+/// * `module` - module to list the functions of
+///
+/// returns the exported functions with their signatures
+fn exported_functions(module: &Module) -> Vec<ExportInfo> {
+    module
+        .exports()
+        .filter_map(|export| match export.ty() {
+            ExternType::Func(func_type) => Some(ExportInfo {
+                name: export.name().to_string(),
+                params: func_type.params().collect(),
+                results: func_type.results().collect(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Compares two lists of value types
+/// # Arguments
+/// * `a` - first list of value types
+/// * `b` - second list of value types
+///
+/// returns true if both lists contain the same types in the same order
+fn val_types_eq(a: &[ValType], b: &[ValType]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| ValType::eq(x, y))
+}
+
+/// Formats a list of value types, e.g. "(i32, i32)"
+/// # Arguments
+/// * `val_types` - list of value types
+///
+/// returns the formatted list
+pub fn format_val_types(val_types: &[ValType]) -> String {
+    let val_types: Vec<String> = val_types.iter().map(|x| x.to_string()).collect();
+    format!("({})", val_types.join(", "))
+}
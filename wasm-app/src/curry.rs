@@ -0,0 +1,88 @@
+//! Processing of many data batches with the same meta data by one instance of a WASM module, which writes the meta data to the module memory only once
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::time::Instant;
+
+use wasmtime::Engine;
+use wasmtime::Memory;
+use wasmtime::Module;
+use wasmtime::Store;
+use wasmtime::TypedFunc;
+
+use crate::profiler::ExecutionProfiler;
+use crate::sandbox::{create_sandboxed_instance, reset_call_limits, SandboxConfig};
+use crate::{
+    read_wasm_result, record_call, wrapper_wasm_allocate, wrapper_wasm_deallocate, MyState,
+};
+
+/// Creates an instance of the WASM module with the meta data already written to its memory and returns a function that calls process_data_arrow of the instance with the fixed meta data
+/// # Arguments
+/// * `engine` - wasmtime engine to use for the store
+/// * `module` - module containing the WASM function
+/// * `profiler` - profiler to record the calls of the WASM function
+/// * `config` - sandbox configuration applied to the instance. The fuel and the deadline are reset before each call
+/// * `fixed_meta` - meta data in Arrow IPC format used for all calls, e.g. create_arrow_example_meta_data
+///
+/// returns a function that takes data in Arrow IPC format and returns the result data of process_data_arrow in Arrow IPC format. The meta data is deallocated together with the instance when the function is dropped. Calls are not recorded (see global_recorder), because a recording is replayed with one instance per call
+pub fn curry_arrow_processor(
+    engine: &Engine,
+    module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+    fixed_meta: Vec<u8>,
+) -> anyhow::Result<impl Fn(Vec<u8>) -> anyhow::Result<Vec<u8>>> {
+    let (instance, mut store) = create_sandboxed_instance(engine, module, profiler, config)?;
+    let func_validated: TypedFunc<(u32, u32, u32, u32), u32> =
+        instance.get_typed_func(&mut store, "wasm_memory_process_data_arrow")?;
+    let memory: Memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or(anyhow::format_err!("failed to find `memory` export"))?;
+    // the meta data is allocated and written once for all calls
+    let offset_meta_data: u32 =
+        wrapper_wasm_allocate(instance, &mut store, fixed_meta.len() as u32)? as u32;
+    if offset_meta_data == 0 {
+        anyhow::bail!("Error: Could not allocate shared WASM module memory for meta data");
+    }
+    memory.write(&mut store, offset_meta_data as usize, &fixed_meta)?;
+    let meta_data_size: u32 = fixed_meta.len() as u32;
+    let config: SandboxConfig = config.clone();
+    // the function only has shared access to the store, but calls need exclusive access
+    let store: RefCell<Store<MyState>> = RefCell::new(store);
+    Ok(move |data: Vec<u8>| -> anyhow::Result<Vec<u8>> {
+        let mut store = store.borrow_mut();
+        reset_call_limits(&mut store, &config)?;
+        let offset_data: u32 =
+            wrapper_wasm_allocate(instance, &mut *store, data.len() as u32)? as u32;
+        if offset_data == 0 {
+            anyhow::bail!("Error: Could not allocate shared WASM module memory for data");
+        }
+        memory.write(&mut *store, offset_data as usize, &data)?;
+        let call_start: Instant = Instant::now();
+        let result_offset = func_validated.call(
+            &mut *store,
+            (
+                offset_meta_data,
+                meta_data_size,
+                offset_data,
+                data.len() as u32,
+            ),
+        );
+        // only the data is deallocated, the meta data is reused by the next call
+        let dealloc_data_code: i32 =
+            wrapper_wasm_deallocate(instance, &mut *store, offset_data as *const u8)?;
+        if dealloc_data_code != 0 {
+            println!("Error: Could not deallocate shared WASM module memory for data");
+        }
+        let result_arrow_ipc: anyhow::Result<Vec<u8>> = result_offset.and_then(|result_offset| {
+            read_wasm_result(instance, &mut store, &memory, result_offset)
+        });
+        record_call(
+            &store,
+            "wasm_memory_process_data_arrow",
+            call_start,
+            meta_data_size as usize + data.len(),
+            &result_arrow_ipc,
+        );
+        result_arrow_ipc
+    })
+}
@@ -0,0 +1,142 @@
+//! Dictionary encoding of string columns with few distinct values to reduce the size of data exchanged with WASM modules
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+/// Share of distinct values of a string column below which it is dictionary encoded by default
+pub const DEFAULT_CARDINALITY_THRESHOLD: f64 = 0.5;
+
+/// Dictionary encodes string columns with a low cardinality (few distinct values compared to the number of rows), e.g. titles that often repeat
+/// # Arguments
+/// * `batch` - record batch to encode
+/// * `cardinality_threshold` - string columns with a share of distinct values (distinct values / rows) below the threshold are encoded, e.g. 0.5
+///
+/// returns the record batch where low cardinality string columns are of type Dictionary(Int32, Utf8). Other columns are not changed
+pub fn auto_dictionary_encode(batch: &RecordBatch, cardinality_threshold: f64) -> RecordBatch {
+    let dictionary_data_type: DataType =
+        DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+    let mut fields: Vec<Field> = Vec::with_capacity(batch.num_columns());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(batch.num_columns());
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        let encode: bool = match column.as_any().downcast_ref::<StringArray>() {
+            Some(string_column) if batch.num_rows() > 0 => {
+                let distinct_count: usize = string_column
+                    .iter()
+                    .flatten()
+                    .collect::<HashSet<&str>>()
+                    .len();
+                (distinct_count as f64 / batch.num_rows() as f64) < cardinality_threshold
+            }
+            _ => false,
+        };
+        if encode {
+            fields.push(
+                field
+                    .as_ref()
+                    .clone()
+                    .with_data_type(dictionary_data_type.clone()),
+            );
+            columns.push(arrow::compute::cast(column, &dictionary_data_type).unwrap());
+        } else {
+            fields.push(field.as_ref().clone());
+            columns.push(column.clone());
+        }
+    }
+    // keep the schema metadata, e.g. the provenance of the data
+    let schema: Schema = Schema::new_with_metadata(fields, batch.schema().metadata().clone());
+    RecordBatch::try_new(Arc::new(schema), columns).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, StringArray, UInt64Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::ipc::writer::StreamWriter;
+    use arrow::record_batch::RecordBatch;
+
+    use super::{auto_dictionary_encode, DEFAULT_CARDINALITY_THRESHOLD};
+
+    /// Documents with a distinct content for each row and titles that repeat
+    /// # Arguments
+    /// * `rows` - number of rows
+    /// * `distinct_titles` - number of distinct titles
+    ///
+    /// returns the record batch {id: UInt64, content: Utf8, title: Utf8} with the schema metadata source
+    fn documents(rows: usize, distinct_titles: usize) -> RecordBatch {
+        let schema = Schema::new_with_metadata(
+            vec![
+                Field::new("id", DataType::UInt64, false),
+                Field::new("content", DataType::Utf8, false),
+                Field::new("title", DataType::Utf8, false),
+            ],
+            HashMap::from([("source".to_string(), "test".to_string())]),
+        );
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(UInt64Array::from_iter_values(0..rows as u64)),
+            Arc::new(StringArray::from_iter_values(
+                (0..rows).map(|i| format!("this is the content of document {i}")),
+            )),
+            Arc::new(StringArray::from_iter_values((0..rows).map(|i| {
+                format!("title of the documents {}", i % distinct_titles)
+            }))),
+        ];
+        RecordBatch::try_new(Arc::new(schema), columns).unwrap()
+    }
+
+    /// Serializes a record batch in Arrow IPC format
+    /// # Arguments
+    /// * `batch` - record batch to serialize
+    ///
+    /// returns the size of the record batch in Arrow IPC format
+    fn serialized_size(batch: &RecordBatch) -> usize {
+        let mut stream_writer = StreamWriter::try_new(Vec::new(), &batch.schema()).unwrap();
+        stream_writer.write(batch).unwrap();
+        stream_writer.into_inner().unwrap().len()
+    }
+
+    #[test]
+    fn only_columns_with_a_low_cardinality_are_encoded() {
+        let batch: RecordBatch = documents(100, 10);
+        let encoded: RecordBatch = auto_dictionary_encode(&batch, DEFAULT_CARDINALITY_THRESHOLD);
+        let schema = encoded.schema();
+        // ids are not strings and each content is distinct
+        assert_eq!(schema.field(0).data_type(), &DataType::UInt64);
+        assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+        assert_eq!(
+            schema.field(2).data_type(),
+            &DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+        );
+        assert_eq!(schema.metadata(), batch.schema().metadata());
+        // the values are not changed by the encoding
+        let titles = arrow::compute::cast(encoded.column(2), &DataType::Utf8).unwrap();
+        assert_eq!(&titles, batch.column(2));
+    }
+
+    #[test]
+    fn no_columns_are_encoded_below_the_threshold() {
+        // 10 distinct titles in 100 rows is a share of 0.1
+        let batch: RecordBatch = documents(100, 10);
+        assert_eq!(auto_dictionary_encode(&batch, 0.1), batch);
+        // batches without rows have no cardinality
+        let batch: RecordBatch = documents(0, 1);
+        assert_eq!(auto_dictionary_encode(&batch, 1.0), batch);
+    }
+
+    #[test]
+    fn encoded_titles_reduce_the_payload_size() {
+        let batch: RecordBatch = documents(10_000, 100);
+        let plain_size: usize = serialized_size(&batch);
+        let encoded_size: usize = serialized_size(&auto_dictionary_encode(
+            &batch,
+            DEFAULT_CARDINALITY_THRESHOLD,
+        ));
+        println!("Payload size of 10,000 rows with 100 distinct titles: {plain_size} bytes, dictionary encoded: {encoded_size} bytes");
+        assert!(encoded_size < plain_size);
+    }
+}
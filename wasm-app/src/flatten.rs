@@ -0,0 +1,57 @@
+//! Flattening of nested fields to one field per child named with dots (e.g. config.filename), the counterpart of the reconstruction of nested fields by wasm-module2 for applications without support of nested types
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, BooleanArray, StructArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+/// Replaces a field of type Struct by one field per child named <field>.<child> (e.g. config: {filename, encoding} by config.filename and config.encoding) at the position of the field
+/// # Arguments
+/// * `batch` - record batch, e.g. meta data of process_data_arrow
+/// * `field_name` - name of the field of type Struct
+///
+/// returns the record batch with the flattened field. Children are null in rows in which the struct is null. Returns an error if the field does not exist or is not of type Struct
+pub fn flatten_struct_column(batch: &RecordBatch, field_name: &str) -> anyhow::Result<RecordBatch> {
+    let schema = batch.schema();
+    let index: usize = schema.index_of(field_name)?;
+    let struct_field: &Field = schema.field(index);
+    let DataType::Struct(_) = struct_field.data_type() else {
+        anyhow::bail!(
+            "Field '{field_name}' has type {} instead of Struct",
+            struct_field.data_type()
+        );
+    };
+    let struct_column: &StructArray = batch.column(index).as_struct();
+    // rows in which the struct is null
+    let struct_nulls: Option<BooleanArray> = (struct_column.null_count() > 0)
+        .then(|| arrow::compute::is_null(struct_column))
+        .transpose()?;
+    let mut fields: Vec<Field> = Vec::with_capacity(batch.num_columns());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(batch.num_columns());
+    for (i, (field, column)) in schema.fields().iter().zip(batch.columns()).enumerate() {
+        if i != index {
+            fields.push(field.as_ref().clone());
+            columns.push(column.clone());
+            continue;
+        }
+        for (child_field, child_column) in
+            struct_column.fields().iter().zip(struct_column.columns())
+        {
+            fields.push(
+                child_field
+                    .as_ref()
+                    .clone()
+                    .with_name(format!("{field_name}.{}", child_field.name()))
+                    .with_nullable(child_field.is_nullable() || struct_field.is_nullable()),
+            );
+            columns.push(match &struct_nulls {
+                Some(struct_nulls) => arrow::compute::nullif(child_column, struct_nulls)?,
+                None => child_column.clone(),
+            });
+        }
+    }
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )?)
+}
@@ -0,0 +1,92 @@
+//! Introspection of the functions exported by a WASM module, e.g. to find out which types a function expects before writing a wrapper
+use std::fmt;
+use std::sync::Arc;
+
+use arrow::array::StringArray;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use arrow::util::pretty::print_batches;
+use wasmtime::ExternType;
+use wasmtime::FuncType;
+use wasmtime::Module;
+use wasmtime::ValType;
+
+use crate::compatibility::format_val_types;
+
+/// Signature of a function exported by a WASM module
+pub struct FunctionSignature {
+    /// types of the parameters of the function
+    pub params: Vec<ValType>,
+    /// types of the results of the function
+    pub results: Vec<ValType>,
+}
+
+impl From<&FuncType> for FunctionSignature {
+    fn from(func_type: &FuncType) -> Self {
+        FunctionSignature {
+            params: func_type.params().collect(),
+            results: func_type.results().collect(),
+        }
+    }
+}
+
+impl fmt::Display for FunctionSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "func {} -> {}",
+            format_val_types(&self.params),
+            format_val_types(&self.results)
+        )
+    }
+}
+
+/// Determines the signature of a function exported by a module
+/// # Arguments
+/// * `module` - module that exports the function
+/// * `name` - name of the exported function
+///
+/// returns the signature of the function. Returns an error if the module has no export with this name or the export is not a function
+pub fn introspect_function(module: &Module, name: &str) -> anyhow::Result<FunctionSignature> {
+    let export_type: ExternType = module
+        .exports()
+        .find(|export| export.name() == name)
+        .map(|export| export.ty())
+        .ok_or_else(|| anyhow::anyhow!("WASM module has no export \"{name}\""))?;
+    match export_type {
+        ExternType::Func(func_type) => Ok(FunctionSignature::from(&func_type)),
+        _ => anyhow::bail!("Export \"{name}\" of the WASM module is not a function"),
+    }
+}
+
+/// Prints a table with the name, the parameter types and the result types of all functions exported by a module
+/// # Arguments
+/// * `module` - module to print the functions of
+pub fn print_function_table(module: &Module) {
+    let mut names: Vec<String> = Vec::new();
+    let mut params: Vec<String> = Vec::new();
+    let mut results: Vec<String> = Vec::new();
+    for export in module.exports() {
+        if let ExternType::Func(func_type) = export.ty() {
+            let signature: FunctionSignature = FunctionSignature::from(&func_type);
+            names.push(export.name().to_string());
+            params.push(format_val_types(&signature.params));
+            results.push(format_val_types(&signature.results));
+        }
+    }
+    let schema = Schema::new(vec![
+        Field::new("function", DataType::Utf8, false),
+        Field::new("params", DataType::Utf8, false),
+        Field::new("results", DataType::Utf8, false),
+    ]);
+    let batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from(names)),
+            Arc::new(StringArray::from(params)),
+            Arc::new(StringArray::from(results)),
+        ],
+    )
+    .unwrap();
+    print_batches(&[batch]).unwrap();
+}
@@ -1,68 +1,703 @@
 //!  mostly adapted from: https://docs.rs/wasmtime/latest/wasmtime/
 use anyhow;
 use wasmtime::AsContextMut;
+use wasmtime::Caller;
 use wasmtime::Engine;
+use wasmtime::Extern;
 use wasmtime::Instance;
 use wasmtime::Linker;
+use wasmtime::Memory;
 use wasmtime::Module;
 use wasmtime::Store;
-use wasi_common::sync::WasiCtxBuilder;
+use wasmtime::StoreLimits;
+use wasmtime::ValType;
 use wasi_common::WasiCtx;
+use wasi_common::pipe::WritePipe;
 
+use std::collections::HashMap;
 use std::ffi::CStr;
 use std::ffi::CString;
+use std::fs::File;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::RwLock;
+use std::time::Duration;
+use std::time::Instant;
 
 use arrow::array::{
-    ArrayRef, Float64Array, StringArray, StructArray, TimestampSecondArray, UInt64Array,
+    Array, ArrayRef, AsArray, Decimal128Array, Float16Array, Float64Array, Int64Array, ListBuilder,
+    MapBuilder, MapFieldNames, StringArray, StringBuilder, StructArray, TimestampSecondArray,
+    UInt32Array, UInt64Array,
 };
-use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::datatypes::{DataType, Field, Float64Type, Schema, TimeUnit};
+use arrow::error::ArrowError;
 use arrow::ipc::reader::StreamReader;
 use arrow::ipc::writer::StreamWriter;
 use arrow::record_batch::RecordBatch;
 use arrow::util::pretty::print_batches;
 
+use clap::Parser;
+
+use half::f16;
+
+use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+
 use time::macros::datetime;
+use time::OffsetDateTime;
+
+use tracing::Level;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+mod adaptive;
+use adaptive::{
+    adaptive_batch_processor, get_serialized_size, OutOfMemoryError, OutputTooLargeError,
+    WASM_RESULT_ERROR_OUTPUT_TOO_LARGE, WASM_RESULT_ERROR_OUT_OF_MEMORY,
+};
+mod cli;
+use cli::Cli;
+mod compatibility;
+use compatibility::{check_module_exports, RequiredExport};
+mod curry;
+use curry::curry_arrow_processor;
+mod dictionary;
+use dictionary::{auto_dictionary_encode, DEFAULT_CARDINALITY_THRESHOLD};
+mod flatten;
+use flatten::flatten_struct_column;
+mod introspection;
+mod pipeline;
+use pipeline::{ModulePipeline, PipelineStage};
+mod pool;
+use pool::{spawn_health_checks, warm_up_instance, InstancePool};
+mod profiler;
+use profiler::ExecutionProfiler;
+mod recorder;
+use recorder::{global_recorder, replay_recording};
+mod runner;
+use runner::SafeModuleRunner;
+mod sandbox;
+use sandbox::{create_engine, create_sandboxed_instance, reset_call_limits, SandboxConfig};
+mod telemetry;
+use telemetry::{ModuleTelemetry, TELEMETRY_SIZE};
+mod validator;
+use validator::ValidatedArrowCaller;
+
+/// Sandbox configuration applied to all instances of WASM modules. The default configuration is used if the file does not exist
+const SANDBOX_CONFIG_PATH: &str = "../../sandbox.toml";
+
+/// Number of new instances whose first call is measured to compare the latency with and without warm-up
+const FIRST_CALL_SAMPLES: usize = 20;
+
+/// Mode of wasm_set_null_handling of module 2 that skips rows with null values
+const NULL_HANDLING_SKIP: u32 = 2;
+
+/// Return code of the host function host_kv_get if the key does not exist
+const HOST_KV_KEY_NOT_FOUND: i32 = -1;
+
+/// Return code of the host function host_kv_get if the value is larger than the memory provided by the WASM module
+const HOST_KV_BUFFER_TOO_SMALL: i32 = -2;
+
+/// Number of rows of the data to compare the payload size of scores with Float64 and Float16
+const FLOAT16_BENCHMARK_ROWS: usize = 1_000;
+
+/// Number of rows of the data whose repeated strings are dictionary encoded before it is validated
+const DICTIONARY_EXAMPLE_ROWS: usize = 8;
+
+/// Number of rows of the data that is validated in chunks of at most the payload size of half of the rows
+const ADAPTIVE_BATCH_EXAMPLE_ROWS: usize = 16;
+
+/// Number of sequential calls with the same meta data to compare writing the meta data once (curry_arrow_processor) with writing it for each call
+const CURRY_BENCHMARK_CALLS: usize = 1_000;
 
 struct MyState {
     wasi: WasiCtx,
+    profiler: Arc<ExecutionProfiler>,
+    limits: StoreLimits,
+    /// key-value store that the WASM module reads and writes via host_kv_get and host_kv_set, e.g. configuration values of tenants
+    kv_store: HashMap<Vec<u8>, Vec<u8>>,
 }
 
 /// Main function that loads a WASM module
 fn main() {
+    // log all messages of the WASM modules, but only important ones of the runtime
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(
+            Targets::new()
+                .with_target("wasm_module", Level::TRACE)
+                .with_default(Level::INFO),
+        )
+        .init();
+    let cli: Cli = Cli::parse();
+    let sandbox_config: SandboxConfig = init_sandbox_config().unwrap();
+    // replay a recording instead of running the examples
+    if let Some(recording_path) = cli.replay {
+        let engine: Engine = init_wasm_engine(&sandbox_config).unwrap();
+        let profiler: Arc<ExecutionProfiler> = Arc::new(ExecutionProfiler::default());
+        let module: Module = match cli.replay_module {
+            Some(module_path) => Module::from_file(&engine, module_path),
+            None => init_wasm_module_2(&engine, &sandbox_config),
+        }
+        .unwrap();
+        match replay_recording(
+            &engine,
+            &module,
+            &profiler,
+            &sandbox_config,
+            &recording_path,
+        ) {
+            Ok(result_batches) => print_batches(&result_batches).unwrap(),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    // run a command of the command line interface instead of the examples
+    if let Some(command) = cli.command {
+        let engine: Engine = init_wasm_engine(&sandbox_config).unwrap();
+        let profiler: Arc<ExecutionProfiler> = Arc::new(ExecutionProfiler::default());
+        if let Err(e) = cli::run_command(&engine, &profiler, &sandbox_config, command) {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
     println!("Initializing WASM engine...");
-    let engine: Engine = init_wasm_engine().unwrap();
+    let engine: Engine = init_wasm_engine(&sandbox_config).unwrap();
+    let profiler: Arc<ExecutionProfiler> = Arc::new(ExecutionProfiler::default());
     println!("Loading WASM module 1...");
-    let module: Module = init_wasm_module_1(&engine).unwrap();
+    let module: Module = init_wasm_module_1(&engine, &sandbox_config).unwrap();
+    let module_1: Arc<Module> = Arc::new(module.clone());
     println!("Module1: Running WASM function answer...");
-    let result_answer = wrapper_answer(&engine, &module).unwrap();
+    let result_answer = wrapper_answer(&engine, &module, &profiler, &sandbox_config).unwrap();
     println!("Result from WASM function \"answer\": {}", result_answer);
     println!("Module 1: Running WASM function c_format_hello_world...");
-    let result_c_format_hello_world =
-        wrapper_wasm_c_format_hello_world(&engine, &module, "Rust (C ABI)").unwrap();
+    let result_c_format_hello_world = wrapper_wasm_c_format_hello_world(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        "Rust (C ABI)",
+    )
+    .unwrap();
     println!(
         "Result from WASM function \"c_format_hello_world\": {}",
         result_c_format_hello_world
     );
     println!("Module 1: Running WASM function rust_format_hello_world...");
-    let result_rust_format_hello_world =
-        wrapper_wasm_rust_format_hello_world(&engine, &module, "Rust (Rust ABI)".to_string())
-            .unwrap();
+    let result_rust_format_hello_world = wrapper_wasm_rust_format_hello_world(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        "Rust (Rust ABI)".to_string(),
+    )
+    .unwrap();
     println!(
         "Result from WASM function \"rust_format_hello_world\": {}",
         result_rust_format_hello_world
     );
     println!("Loading WASM module 2...");
-    let module: Module = init_wasm_module_2(&engine).unwrap();
+    let module: Module = init_wasm_module_2(&engine, &sandbox_config).unwrap();
     println!("Module 2: Running WASM function arrow_process_document...");
-    wrapper_wasm_process_data_arrow(&engine, &module).unwrap();
+    wrapper_wasm_process_data_arrow(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        &create_arrow_example_data(),
+        "test",
+        false,
+        None,
+    )
+    .unwrap();
+    println!("Module 2: Running WASM function arrow_process_document with LargeUtf8 strings...");
+    wrapper_wasm_process_data_arrow(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        &create_arrow_example_large_utf8_data(),
+        "test",
+        false,
+        None,
+    )
+    .unwrap();
+    println!("Module 2: Running WASM function arrow_process_document with Float16 scores...");
+    wrapper_wasm_process_data_arrow(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        &create_arrow_example_data_f16(),
+        "test",
+        false,
+        None,
+    )
+    .unwrap();
+    let payload_size_f64: usize = serialize_arrow_batch(&repeat_rows(
+        &create_arrow_example_data(),
+        FLOAT16_BENCHMARK_ROWS,
+    ))
+    .len();
+    let payload_size_f16: usize = serialize_arrow_batch(&repeat_rows(
+        &create_arrow_example_data_f16(),
+        FLOAT16_BENCHMARK_ROWS,
+    ))
+    .len();
+    println!(
+        "Payload size of {} rows with Float64 scores: {} bytes, with Float16 scores: {} bytes ({:.1}% smaller)",
+        FLOAT16_BENCHMARK_ROWS,
+        payload_size_f64,
+        payload_size_f16,
+        (1.0 - payload_size_f16 as f64 / payload_size_f64 as f64) * 100.0
+    );
+    println!("Module 2: Running WASM function arrow_process_document with the command validate...");
+    wrapper_wasm_process_data_arrow(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        &create_arrow_example_data(),
+        "validate",
+        false,
+        None,
+    )
+    .unwrap();
+    println!("Module 2: Running WASM function arrow_process_document with the command validate and dictionary encoded strings...");
+    wrapper_wasm_process_data_arrow(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        &repeat_rows(&create_arrow_example_data(), DICTIONARY_EXAMPLE_ROWS),
+        "validate",
+        false,
+        Some(DEFAULT_CARDINALITY_THRESHOLD),
+    )
+    .unwrap();
+    println!(
+        "Module 2: Running WASM function arrow_process_document with the command validate and the score threshold of the tenant..."
+    );
+    let (instance, mut store) =
+        create_sandboxed_instance(&engine, &module, &profiler, &sandbox_config).unwrap();
+    store
+        .data_mut()
+        .kv_store
+        .insert(b"tenant/example/score_threshold".to_vec(), b"2.0".to_vec());
+    call_wasm_process_data_arrow(
+        instance,
+        &mut store,
+        &create_arrow_example_data(),
+        "validate",
+        false,
+        None,
+    )
+    .unwrap();
+    println!("Module 2: Running WASM function arrow_process_document in dry-run mode...");
+    wrapper_wasm_process_data_arrow(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        &create_arrow_example_data(),
+        "test",
+        true,
+        None,
+    )
+    .unwrap();
+    println!("Module 2: Running WASM function arrow_process_document with host-side validation...");
+    let validated_caller = ValidatedArrowCaller::new(
+        create_arrow_example_data().schema(),
+        Arc::new(Schema::new(vec![
+            Field::new("id", DataType::UInt64, false),
+            Field::new("content", DataType::Utf8, false),
+        ])),
+        1..=10_000,
+    )
+    .with_numeric_range("score", 0.0..=10.0);
+    let result_batches: Vec<RecordBatch> = validated_caller
+        .call(
+            &engine,
+            &module,
+            &profiler,
+            &sandbox_config,
+            &create_arrow_example_data(),
+        )
+        .unwrap();
+    print_batches(&result_batches).unwrap();
+    let example_batch: RecordBatch = create_arrow_example_data();
+    let implausible_batch: RecordBatch = RecordBatch::try_new(
+        example_batch.schema(),
+        example_batch
+            .schema()
+            .fields()
+            .iter()
+            .zip(example_batch.columns())
+            .map(|(field, column)| match field.name().as_str() {
+                "score" => Arc::new(Float64Array::from(vec![-1.0])) as ArrayRef,
+                _ => column.clone(),
+            })
+            .collect(),
+    )
+    .unwrap();
+    match validated_caller.call(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        &implausible_batch,
+    ) {
+        Ok(_) => println!("Error: Expected the validation to fail for an implausible score"),
+        Err(e) => println!("Result from host-side validation: {e}"),
+    }
+    println!(
+        "Module 2: Running WASM function arrow_process_document skipping rows with null values..."
+    );
+    let (instance, mut store) =
+        create_sandboxed_instance(&engine, &module, &profiler, &sandbox_config).unwrap();
+    wrapper_wasm_set_null_handling(instance, &mut store, NULL_HANDLING_SKIP).unwrap();
+    call_wasm_process_data_arrow(
+        instance,
+        &mut store,
+        &create_arrow_example_data_with_null_row(),
+        "test",
+        false,
+        None,
+    )
+    .unwrap();
+    println!(
+        "Module 2: Telemetry: {}",
+        wrapper_wasm_get_telemetry(instance, &mut store).unwrap()
+    );
+    println!(
+        "Module 2: Running WASM function arrow_process_document measuring the processing time in the module..."
+    );
+    let (instance, mut store) =
+        create_sandboxed_instance(&engine, &module, &profiler, &sandbox_config).unwrap();
+    let call_start: Instant = Instant::now();
+    call_wasm_process_data_arrow(
+        instance,
+        &mut store,
+        &create_arrow_example_data(),
+        "test",
+        false,
+        None,
+    )
+    .unwrap();
+    let call_duration: Duration = call_start.elapsed();
+    // the overhead is mainly the serialization of the data and the copies to and from the module memory
+    let processing_duration: Duration = Duration::from_nanos(
+        wrapper_wasm_get_last_processing_duration_ns(instance, &mut store).unwrap(),
+    );
+    println!(
+        "Module 2: Call took {call_duration:?}, processing in the module took {processing_duration:?}, overhead {:?}",
+        call_duration.saturating_sub(processing_duration)
+    );
+    println!("Module 2: Running WASM function arrow_process_document with flattened meta data...");
+    let (instance, mut store) =
+        create_sandboxed_instance(&engine, &module, &profiler, &sandbox_config).unwrap();
+    let result_arrow_ipc: Vec<u8> = call_wasm_process_data_arrow_serialized(
+        instance,
+        &mut store,
+        &create_arrow_example_meta_data_flattened("test"),
+        &serialize_arrow_batch(&create_arrow_example_data()),
+    )
+    .unwrap();
+    let result_batches: Vec<RecordBatch> = StreamReader::try_new(result_arrow_ipc.as_slice(), None)
+        .unwrap()
+        .collect::<Result<Vec<RecordBatch>, _>>()
+        .unwrap();
+    print_batches(&result_batches).unwrap();
+    println!("Module 2: Running WASM function process_csv_file...");
+    wrapper_wasm_process_csv_file(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        "documents.csv",
+        true,
+    )
+    .unwrap();
+    println!("Module 2: Running WASM function process_csv_file with a file of another schema...");
+    match wrapper_wasm_process_csv_file(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        "products.csv",
+        true,
+    ) {
+        Ok(_) => println!("Error: Expected a schema mismatch for products.csv"),
+        Err(e) => println!("Result from WASM function \"process_csv_file\": {e}"),
+    }
+    println!("Module 2: Running WASM function process_csv_file without filesystem access...");
+    let no_filesystem_config: SandboxConfig = SandboxConfig {
+        allow_filesystem: false,
+        ..sandbox_config.clone()
+    };
+    match wrapper_wasm_process_csv_file(
+        &engine,
+        &module,
+        &profiler,
+        &no_filesystem_config,
+        "documents.csv",
+        true,
+    ) {
+        Ok(_) => println!("Error: Expected the filesystem access to be denied"),
+        Err(e) => println!("Result from WASM function \"process_csv_file\": {e}"),
+    }
+    println!("Module 2: Running WASM function process_and_write_parquet...");
+    wrapper_wasm_process_and_write_parquet(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        &create_arrow_example_data(),
+        "result.parquet",
+    )
+    .unwrap();
+    println!("Module 2: Running WASM function stream_process_arrow...");
+    wrapper_wasm_stream_process_arrow(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        &create_arrow_example_data(),
+        1,
+    )
+    .unwrap();
+    println!("Module 2: Running WASM function arrow_process_tagged_docs...");
+    wrapper_wasm_process_single_arrow(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        "wasm_memory_process_tagged_docs_arrow",
+        create_arrow_example_tagged_docs_data(),
+    )
+    .unwrap();
+    println!("Module 2: Running WASM function arrow_process_financial...");
+    wrapper_wasm_process_single_arrow(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        "wasm_memory_process_financial_arrow",
+        create_arrow_example_financial_data(),
+    )
+    .unwrap();
+    println!("Module 1 and 2: Running a pipeline greeting a name and checking the data quality of the greeting as document...");
+    let pipeline: ModulePipeline = ModulePipeline::new(&profiler, &sandbox_config)
+        .with_stage(PipelineStage {
+            module: module_1,
+            function: "wasm_memory_rust_format_hello_world".to_string(),
+            transform: Some(Arc::new(greeting_to_document)),
+        })
+        .with_stage(PipelineStage {
+            module: Arc::new(module.clone()),
+            function: "wasm_memory_validate_data_quality_arrow".to_string(),
+            transform: None,
+        });
+    let result_pipeline: Vec<u8> = pipeline
+        .run(&engine, "Rust (Pipeline)".as_bytes().to_vec())
+        .unwrap();
+    println!("Displaying Arrow answer from pipeline");
+    let stream_reader = StreamReader::try_new(result_pipeline.as_slice(), None).unwrap();
+    for item in stream_reader {
+        print_batches(&[item.unwrap()]).unwrap();
+    }
+    println!("Module 2: Running WASM function health_check on pooled instances...");
+    let pool: Arc<Mutex<InstancePool>> = Arc::new(Mutex::new(
+        InstancePool::new(&engine, &module, &profiler, &sandbox_config, 2).unwrap(),
+    ));
+    spawn_health_checks(Arc::clone(&pool));
+    // instances are acquired for a request of a tenant and released afterwards
+    let mut pooled_instance = pool.lock().unwrap().acquire_for_tenant(1).unwrap();
+    let health_check_code: i32 =
+        wrapper_wasm_health_check(pooled_instance.instance, &mut pooled_instance.store).unwrap();
+    println!(
+        "Result from WASM function \"health_check\" of acquired instance: {}",
+        health_check_code
+    );
+    pool.lock().unwrap().release(pooled_instance);
+    let replaced_instances: usize = pool
+        .lock()
+        .unwrap()
+        .check_idle_instances(Duration::ZERO)
+        .unwrap();
+    println!(
+        "Health check of pooled instances: {} of {} instances replaced",
+        replaced_instances,
+        pool.lock().unwrap().idle_count()
+    );
+    println!("Module 2: Measuring the latency of the first call of arrow_process_document with and without warm-up...");
+    for warm_up in [false, true] {
+        let p99_ms: f64 =
+            measure_first_call_latency(&engine, &module, &sandbox_config, warm_up).unwrap();
+        println!(
+            "p99 latency of the first call of {} new instances {} warm-up: {:.3} ms",
+            FIRST_CALL_SAMPLES,
+            if warm_up { "with" } else { "without" },
+            p99_ms
+        );
+    }
+    println!("Module 2: Running WASM function arrow_process_document with the command validate and data split into chunks that fit the memory budget...");
+    let example_batch: RecordBatch = create_arrow_example_data();
+    let max_payload_bytes: usize = get_serialized_size(&repeat_rows(
+        &example_batch,
+        ADAPTIVE_BATCH_EXAMPLE_ROWS / 2,
+    ));
+    let result_batch: RecordBatch = adaptive_batch_processor(
+        &engine,
+        &module,
+        &profiler,
+        &sandbox_config,
+        repeat_rows(&example_batch, ADAPTIVE_BATCH_EXAMPLE_ROWS),
+        "validate",
+        max_payload_bytes,
+    )
+    .unwrap();
+    print_batches(&[result_batch]).unwrap();
+    println!("Module 2: Running WASM function arrow_process_document with the same meta data for all calls...");
+    for curried in [false, true] {
+        let duration: Duration =
+            measure_fixed_meta_data_calls(&engine, &module, &profiler, &sandbox_config, curried)
+                .unwrap();
+        println!(
+            "{} sequential calls {} writing the meta data once: {:.3} ms",
+            CURRY_BENCHMARK_CALLS,
+            if curried { "with" } else { "without" },
+            duration.as_secs_f64() * 1000.0
+        );
+    }
+    println!("Module 2: Running WASM function arrow_process_document with data that makes the module panic on pooled instances...");
+    let runner: SafeModuleRunner = SafeModuleRunner::new(Arc::clone(&pool), true);
+    // the module expects exactly one row and panics otherwise (batches without rows are rejected before processing)
+    let example_batch: RecordBatch = create_arrow_example_data();
+    let duplicated_batch: RecordBatch = arrow::compute::concat_batches(
+        &example_batch.schema(),
+        &[example_batch.clone(), example_batch],
+    )
+    .unwrap();
+    match runner.call(|instance, store| {
+        call_wasm_process_data_arrow(instance, store, &duplicated_batch, "test", false, None)
+    }) {
+        Ok(_) => println!("Error: Expected the WASM module to panic"),
+        Err(e) => println!("Result from WASM function \"arrow_process_document\": {e}"),
+    }
+    println!(
+        "Idle instances in pool after replacing crashed instances: {}",
+        pool.lock().unwrap().idle_count()
+    );
+    profiler.print_summary();
+}
+
+/// Measures the latency of the first call of process_data_arrow of FIRST_CALL_SAMPLES new instances of the WASM module
+/// # Arguments
+/// * `engine` - wasmtime engine to use for the stores
+/// * `module` - module containing the WASM function
+/// * `config` - sandbox configuration applied to the instances
+/// * `warm_up` - true if the instances are warmed up (as by the instance pool) before the first call
+///
+/// returns the p99 latency of the first calls in milliseconds
+fn measure_first_call_latency(
+    engine: &Engine,
+    module: &Module,
+    config: &SandboxConfig,
+    warm_up: bool,
+) -> anyhow::Result<f64> {
+    // the first calls are recorded separately from the other calls
+    let first_call_profiler: Arc<ExecutionProfiler> = Arc::new(ExecutionProfiler::default());
+    let example_batch: RecordBatch = create_arrow_example_data();
+    for _ in 0..FIRST_CALL_SAMPLES {
+        let (instance, mut store) =
+            create_sandboxed_instance(engine, module, &first_call_profiler, config)?;
+        if warm_up {
+            warm_up_instance(instance, &mut store, config)?;
+        }
+        call_wasm_process_data_arrow_ipc(
+            instance,
+            &mut store,
+            &example_batch,
+            "test",
+            false,
+            None,
+        )?;
+    }
+    let summary: RecordBatch = first_call_profiler.to_arrow_batch();
+    let p99_ms: f64 = summary
+        .column_by_name("p99_ms")
+        .ok_or(anyhow::format_err!("Profiler summary has no field p99_ms"))?
+        .as_primitive::<Float64Type>()
+        .value(0);
+    Ok(p99_ms)
+}
+
+/// Measures the duration of CURRY_BENCHMARK_CALLS sequential calls of process_data_arrow of one instance of the WASM module with the same meta data
+/// # Arguments
+/// * `engine` - wasmtime engine to use for the store
+/// * `module` - module containing the WASM function
+/// * `profiler` - profiler to record the calls of the WASM function
+/// * `config` - sandbox configuration applied to the instance
+/// * `curried` - true if the meta data is written once (see curry_arrow_processor), false if it is allocated and written for each call
+///
+/// returns the duration of all calls
+fn measure_fixed_meta_data_calls(
+    engine: &Engine,
+    module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+    curried: bool,
+) -> anyhow::Result<Duration> {
+    let serialized_meta_data: Vec<u8> = create_arrow_example_meta_data("test");
+    let serialized_data: Vec<u8> = serialize_arrow_batch(&create_arrow_example_data());
+    if curried {
+        let process =
+            curry_arrow_processor(engine, module, profiler, config, serialized_meta_data)?;
+        let start: Instant = Instant::now();
+        for _ in 0..CURRY_BENCHMARK_CALLS {
+            process(serialized_data.clone())?;
+        }
+        return Ok(start.elapsed());
+    }
+    let (instance, mut store) = create_sandboxed_instance(engine, module, profiler, config)?;
+    let start: Instant = Instant::now();
+    for _ in 0..CURRY_BENCHMARK_CALLS {
+        reset_call_limits(&mut store, config)?;
+        call_wasm_process_data_arrow_serialized(
+            instance,
+            &mut store,
+            &serialized_meta_data,
+            &serialized_data,
+        )?;
+    }
+    Ok(start.elapsed())
+}
+
+/// Init the sandbox configuration from SANDBOX_CONFIG_PATH
+/// returns the sandbox configuration. It is the default configuration if the file does not exist
+fn init_sandbox_config() -> anyhow::Result<SandboxConfig> {
+    let path: &Path = Path::new(SANDBOX_CONFIG_PATH);
+    if !path.exists() {
+        tracing::info!("No sandbox configuration found at {SANDBOX_CONFIG_PATH}, using the default configuration");
+        return Ok(SandboxConfig::default());
+    }
+    SandboxConfig::from_file(path)
 }
 
 /// Init the WASM Engine
+/// # Arguments
+/// * `config` - sandbox configuration the engine needs to support
 /// returns the WASM engine
-fn init_wasm_engine() -> anyhow::Result<Engine> {
+fn init_wasm_engine(config: &SandboxConfig) -> anyhow::Result<Engine> {
     // Create an "Engine" to run wasm modules
-    let engine = Engine::default();
+    let engine = create_engine(config)?;
     Ok(engine)
 }
 
@@ -70,13 +705,17 @@ fn init_wasm_engine() -> anyhow::Result<Engine> {
 /// # Arguments
 /// * `engine` - wasmtime engine to use for the store
 /// * `store` - in-memory store to use to exchange data with the function
+/// * `config` - sandbox configuration applied to the instance that determines the build information
 /// returns the module
-fn init_wasm_module_1(engine: &Engine) -> anyhow::Result<Module> {
+fn init_wasm_module_1(engine: &Engine, config: &SandboxConfig) -> anyhow::Result<Module> {
     // load WASM module
     let module = Module::from_file(
         &engine,
         "../../../wasm-module1/target/wasm32-wasi/release/wasm_module1.wasm",
     )?;
+    // check that the module provides all functions used by the application
+    check_module_exports(&module, &required_exports_module_1())?;
+    log_build_info(engine, &module, config);
     Ok(module)
 }
 
@@ -84,35 +723,136 @@ fn init_wasm_module_1(engine: &Engine) -> anyhow::Result<Module> {
 /// # Arguments
 /// * `engine` - wasmtime engine to use for the store
 /// * `store` - in-memory store to use to exchange data with the function
+/// * `config` - sandbox configuration applied to the instance that determines the build information
 /// returns the module
-fn init_wasm_module_2(engine: &Engine) -> anyhow::Result<Module> {
+fn init_wasm_module_2(engine: &Engine, config: &SandboxConfig) -> anyhow::Result<Module> {
     // load WASM module
     let module = Module::from_file(
         &engine,
         "../../../wasm-module2/target/wasm32-wasi/release/wasm_module2.wasm",
     )?;
+    // check that the module provides all functions used by the application
+    check_module_exports(&module, &required_exports_module_2())?;
+    log_build_info(engine, &module, config);
     Ok(module)
 }
 
+/// Logs the build information of a WASM module (see wasm_get_build_info), e.g. to find out which build of the module runs in production
+/// # Arguments
+/// * `engine` - wasmtime engine to use for the store
+/// * `module` - loaded module
+/// * `config` - sandbox configuration applied to the instance that determines the build information
+fn log_build_info(engine: &Engine, module: &Module, config: &SandboxConfig) {
+    match wrapper_wasm_get_build_info(engine, module, config) {
+        Ok(build_info) => tracing::info!("Loaded WASM module with build information {build_info}"),
+        Err(e) => {
+            tracing::warn!("Could not determine the build information of the WASM module: {e}")
+        }
+    }
+}
+
+/// Functions that WASM module 1 needs to export to be used by the application
+/// returns the required exports
+fn required_exports_module_1() -> Vec<RequiredExport<'static>> {
+    vec![
+        RequiredExport {
+            name: "answer",
+            params: vec![],
+            results: vec![ValType::I32],
+        },
+        RequiredExport {
+            name: "wasm_memory_c_format_hello_world",
+            params: vec![ValType::I32],
+            results: vec![ValType::I32],
+        },
+        RequiredExport {
+            name: "wasm_memory_rust_format_hello_world",
+            params: vec![ValType::I32, ValType::I32],
+            results: vec![ValType::I32],
+        },
+    ]
+    .into_iter()
+    .chain(required_exports_memory_management())
+    .collect()
+}
+
+/// Functions that WASM module 2 needs to export to be used by the application
+/// returns the required exports
+fn required_exports_module_2() -> Vec<RequiredExport<'static>> {
+    vec![
+        RequiredExport {
+            name: "wasm_memory_process_data_arrow",
+            params: vec![ValType::I32, ValType::I32, ValType::I32, ValType::I32],
+            results: vec![ValType::I32],
+        },
+        RequiredExport {
+            name: "wasm_memory_process_tagged_docs_arrow",
+            params: vec![ValType::I32, ValType::I32],
+            results: vec![ValType::I32],
+        },
+        RequiredExport {
+            name: "wasm_memory_process_financial_arrow",
+            params: vec![ValType::I32, ValType::I32],
+            results: vec![ValType::I32],
+        },
+        RequiredExport {
+            name: "wasm_process_csv_file",
+            params: vec![ValType::I32, ValType::I32, ValType::I32],
+            results: vec![ValType::I32],
+        },
+        RequiredExport {
+            name: "wasm_health_check",
+            params: vec![],
+            results: vec![ValType::I32],
+        },
+        RequiredExport {
+            name: "wasm_allocate_batch",
+            params: vec![ValType::I32, ValType::I32],
+            results: vec![ValType::I32],
+        },
+    ]
+    .into_iter()
+    .chain(required_exports_memory_management())
+    .collect()
+}
+
+/// Functions that every WASM module needs to export to exchange data with the application via the module memory
+/// returns the required exports
+fn required_exports_memory_management() -> Vec<RequiredExport<'static>> {
+    vec![
+        RequiredExport {
+            name: "wasm_allocate",
+            params: vec![ValType::I32],
+            results: vec![ValType::I32],
+        },
+        RequiredExport {
+            name: "wasm_deallocate",
+            params: vec![ValType::I32],
+            results: vec![ValType::I32],
+        },
+        RequiredExport {
+            name: "wasm_last_error",
+            params: vec![],
+            results: vec![ValType::I32],
+        },
+    ]
+}
+
 /// Wrapper around the function answer of the WASM Module. This is needed as the standardization of the componennt model and webassembly interface types is still work-in-progress
 /// # Arguments (note the function `answer` of the WASM module itself has no parameters. The parameters are just to initialize the runtime environment)
 /// * `engine` - wasmtime engine to use for the store
 /// * `module` - module containing the WASM function
+/// * `profiler` - profiler to record the call of the WASM function
+/// * `config` - sandbox configuration applied to the instance
 /// returns the result of the function `answer`
-fn wrapper_answer(engine: &Engine, module: &Module) -> anyhow::Result<i32> {
-    // Load function an instantiate it
-    let mut linker = Linker::new(&engine);
-    wasi_common::sync::add_to_linker(&mut linker, |state: &mut MyState| &mut state.wasi)?;
-    // store to exchange data with the WASM module
-    let wasi = WasiCtxBuilder::new()
-        .inherit_stdio()
-        .inherit_args()?
-        .build();
-    let mut store = Store::new(&engine, MyState { wasi: wasi });
-    // instantiate module
-    // let instance = Instance::new(&mut store, &module, &[])?;
-    linker.module(&mut store, "", &module)?;
-    let instance: Instance = linker.instantiate(&mut store, &module).unwrap();
+fn wrapper_answer(
+    engine: &Engine,
+    module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+) -> anyhow::Result<i32> {
+    // instantiate module with the restrictions of the sandbox
+    let (instance, mut store) = create_sandboxed_instance(engine, module, profiler, config)?;
     // get the function
     let func_def = instance
         .get_func(&mut store, "answer")
@@ -120,38 +860,36 @@ fn wrapper_answer(engine: &Engine, module: &Module) -> anyhow::Result<i32> {
     // validate that it corresponds to the parameters and return types we need
     let func_validated = func_def.typed::<(), i32>(&store)?;
     // call function
-    let result = func_validated.call(&mut store, ())?;
-    Ok(result)
+    let call_start: Instant = Instant::now();
+    let result = func_validated.call(&mut store, ());
+    store
+        .data()
+        .profiler
+        .record("answer", call_start, 0, 0, result.is_ok());
+    result
 }
 
 /// Wrapper around the function format_hello_world (C ABI) of the WASM Module. This is needed as the standardization of the component model and webassembly interface types is still work-in-progress
 /// # Arguments (note the function `format_hello_world` of the WASM module itself has just one parameter: `func_name`. The pther parameters are just to initialize the runtime environment)
 /// * `engine` - wasmtime engine to use for the store
 /// * `module` - module containing the WASM function
+/// * `profiler` - profiler to record the call of the WASM function
+/// * `config` - sandbox configuration applied to the instance
 /// * `func_name` - Parameter `name` for the function
 /// returns the result of the function `format_hello_world`
 fn wrapper_wasm_c_format_hello_world(
     engine: &Engine,
     module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
     func_name: &str,
 ) -> anyhow::Result<String> {
     // convert param to CString
     let param_name_str = func_name;
     let param_name_cstring: CString = CString::new(param_name_str).unwrap();
     let param_name_cstring_as_bytes: &[u8] = param_name_cstring.to_bytes_with_nul();
-    // Load function an instantiate it
-    let mut linker = Linker::new(&engine);
-    wasi_common::sync::add_to_linker(&mut linker, |state: &mut MyState| &mut state.wasi)?;
-    // store to exchange data with the WASM module
-    let wasi = WasiCtxBuilder::new()
-        .inherit_stdio()
-        .inherit_args()?
-        .build();
-    let mut store = Store::new(&engine, MyState { wasi: wasi });
-    // instantiate module
-    // let instance = Instance::new(&mut store, &module, &[])?;
-    linker.module(&mut store, "", &module)?;
-    let instance: Instance = linker.instantiate(&mut store, &module).unwrap();
+    // instantiate module with the restrictions of the sandbox
+    let (instance, mut store) = create_sandboxed_instance(engine, module, profiler, config)?;
     // allocate shared memory for the parameter
     // allocate some memory within the WASM module
     let offset: u32 = wrapper_wasm_allocate(
@@ -166,7 +904,7 @@ fn wrapper_wasm_c_format_hello_world(
         .get_func(&mut store, "wasm_memory_c_format_hello_world")
         .expect("`wasm_memory_c_format_hello_world` was not an exported function");
     // validate that it corresponds to the parameters and return types we need
-    let func_validated = func_def.typed::<u32, i32>(&store)?;
+    let func_validated = func_def.typed::<u32, u32>(&store)?;
 
     // prepare handing over CString as input
     // instantiate memory
@@ -181,65 +919,48 @@ fn wrapper_wasm_c_format_hello_world(
         )
         .unwrap();
     // call function answer
-    let result_offset = func_validated.call(&mut store, offset)?;
-    if result_offset == 0 {
-        anyhow::bail!("Error: No valid answer received from function")
-    } else {
-        let mut result_offset_position = result_offset;
-        // read answer
-        let mut buffer = [1u8; 1];
-        let mut result_v_u8: Vec<u8> = Vec::new();
-        while buffer[0] != 0u8 {
-            memory.read(
-                &store,
-                result_offset_position.try_into().unwrap(),
-                &mut buffer,
-            )?;
-            result_v_u8.push(buffer[0]);
-            result_offset_position += 1;
-        }
-        // deallocate shared WASM Module memory
-        let dealloc_param_code: i32 =
-            wrapper_wasm_deallocate(instance, &mut store, offset as *const u8).unwrap();
-        if dealloc_param_code != 0 {
-            println!("Error: Could not deallocate shared WASM module memory for parameter");
-        }
-        let dealloc_return_code: i32 =
-            wrapper_wasm_deallocate(instance, &mut store, result_offset as *const u8).unwrap();
-        if dealloc_return_code != 0 {
-            println!("Error: Could not deallocate shared WASM module memory for result");
-        }
-        // convert answer
-        let c_str: &CStr = unsafe { CStr::from_ptr(result_v_u8.as_ptr() as *const i8) };
-        let result_str: &str = c_str.to_str().unwrap();
-        Ok(result_str.to_string())
+    let call_start: Instant = Instant::now();
+    let result_offset = func_validated.call(&mut store, offset);
+    // deallocate shared WASM Module memory
+    let dealloc_param_code: i32 =
+        wrapper_wasm_deallocate(instance, &mut store, offset as *const u8).unwrap();
+    if dealloc_param_code != 0 {
+        println!("Error: Could not deallocate shared WASM module memory for parameter");
     }
+    // read answer
+    let result_v_u8: anyhow::Result<Vec<u8>> = result_offset
+        .and_then(|result_offset| read_wasm_result(instance, &mut store, &memory, result_offset));
+    record_call(
+        &store,
+        "wasm_memory_c_format_hello_world",
+        call_start,
+        param_name_cstring_as_bytes.len(),
+        &result_v_u8,
+    );
+    let result_v_u8: Vec<u8> = result_v_u8?;
+    // convert answer
+    let c_str: &CStr = CStr::from_bytes_with_nul(&result_v_u8)?;
+    let result_str: &str = c_str.to_str().unwrap();
+    Ok(result_str.to_string())
 }
 
 /// Wrapper around the function format_hello_world (Rust ABI) of the WASM Module. This is needed as the standardization of the component model and webassembly interface types is still work-in-progress
 /// # Arguments (note the function `format_hello_world` of the WASM module itself has just one parameter: `func_name`. The other parameters are just to initialize the runtime environment)
 /// * `engine` - wasmtime engine to use for the store
 /// * `module` - module containing the WASM function
+/// * `profiler` - profiler to record the call of the WASM function
+/// * `config` - sandbox configuration applied to the instance
 /// * `func_name` - Parameter `name` for the function
 /// returns the result of the function `format_hello_world`
 fn wrapper_wasm_rust_format_hello_world(
     engine: &Engine,
     module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
     func_name: String,
 ) -> anyhow::Result<String> {
-    // Load function an instantiate it
-    let mut linker = Linker::new(&engine);
-    wasi_common::sync::add_to_linker(&mut linker, |state: &mut MyState| &mut state.wasi)?;
-    // store to exchange data with the WASM module
-    let wasi = WasiCtxBuilder::new()
-        .inherit_stdio()
-        .inherit_args()?
-        .build();
-    let mut store = Store::new(&engine, MyState { wasi: wasi });
-    // instantiate module
-    // let instance = Instance::new(&mut store, &module, &[])?;
-    linker.module(&mut store, "", &module)?;
-    let instance: Instance = linker.instantiate(&mut store, &module).unwrap();
+    // instantiate module with the restrictions of the sandbox
+    let (instance, mut store) = create_sandboxed_instance(engine, module, profiler, config)?;
     // get the function
     let func_def = instance
         .get_func(&mut store, "wasm_memory_rust_format_hello_world")
@@ -270,191 +991,898 @@ fn wrapper_wasm_rust_format_hello_world(
         )
         .unwrap();
     // call function answer
-    let result_offset = func_validated.call(&mut store, (offset, length))?;
-    if result_offset == 0 {
-        anyhow::bail!("Error: No valid answer received from function")
-    } else {
-        let mut result_offset_position = result_offset;
-        // read answer from memory: these are two values: offset and length of the return string
-        // read metadata (offset and length of the sring)
-        // note: WebAssembly is by default 32 bit
-        let mut ptr_buffer = [0u8; (u32::BITS / 8) as usize];
-        let mut len_buffer = [0u8; (u32::BITS / 8) as usize];
-        memory.read(
-            &store,
-            result_offset_position.try_into().unwrap(),
-            &mut ptr_buffer,
-        )?;
-        result_offset_position += (u32::BITS / 8) as u32;
-        memory.read(
-            &store,
-            result_offset_position.try_into().unwrap(),
-            &mut len_buffer,
-        )?;
-        let result_ptr = u32::from_le_bytes(ptr_buffer);
-        let result_len = u32::from_le_bytes(len_buffer);
-        // read the string
-        let mut result_vec: Vec<u8> = vec![0; result_len as usize];
-        let mut result_str_buffer = result_vec.as_mut_slice();
-        memory.read(
-            &store,
-            result_ptr.try_into().unwrap(),
-            &mut result_str_buffer,
-        )?;
-        // deallocate shared WASM Module memory
-        let dealloc_param_code: i32 =
-            wrapper_wasm_deallocate(instance, &mut store, offset as *const u8).unwrap();
-        if dealloc_param_code != 0 {
-            println!("Error: Could not deallocate shared WASM module memory for parameter");
-        }
-        let dealloc_return_meta_code: i32 =
-            wrapper_wasm_deallocate(instance, &mut store, result_offset as *const u8).unwrap();
-        if dealloc_return_meta_code != 0 {
-            println!("Error: Could not deallocate shared WASM module memory for return metadata");
-        }
-        let dealloc_return_data_code: i32 =
-            wrapper_wasm_deallocate(instance, &mut store, result_ptr as *const u8).unwrap();
-        if dealloc_return_data_code != 0 {
-            println!("Error: Could not deallocate shared WASM module memory for return data");
-        }
-        let result_str: String = String::from_utf8_lossy(&result_str_buffer).into_owned();
-        Ok(result_str.to_string())
+    let call_start: Instant = Instant::now();
+    let result_offset = func_validated.call(&mut store, (offset, length));
+    // deallocate shared WASM Module memory
+    let dealloc_param_code: i32 =
+        wrapper_wasm_deallocate(instance, &mut store, offset as *const u8).unwrap();
+    if dealloc_param_code != 0 {
+        println!("Error: Could not deallocate shared WASM module memory for parameter");
     }
+    // read the string
+    let result_str_buffer: anyhow::Result<Vec<u8>> = result_offset
+        .and_then(|result_offset| read_wasm_result(instance, &mut store, &memory, result_offset));
+    record_call(
+        &store,
+        "wasm_memory_rust_format_hello_world",
+        call_start,
+        param_name_string_as_bytes.len(),
+        &result_str_buffer,
+    );
+    let result_str_buffer: Vec<u8> = result_str_buffer?;
+    let result_str: String = String::from_utf8_lossy(&result_str_buffer).into_owned();
+    Ok(result_str.to_string())
 }
 
 /// Wrapper around the function process_data_arrow (Use Arrow for cross-programming language data serialization) of the WASM Module.
 /// # Arguments (note the function `process_data_arrow` of the WASM module itself expects to have the Arrow data exchanged in the module memory. The Arrow data is generated in this application through the functions create_arrow_example_meta_data (instructing the function what to do with the data) and create_arrow_example_data (containing the data to be processed)
 /// * `engine` - wasmtime engine to use for the store
 /// * `module` - module containing the WASM function
+/// * `profiler` - profiler to record the call of the WASM function
+/// * `config` - sandbox configuration applied to the instance
+/// * `example_batch` - data to be processed, e.g. create_arrow_example_data
+/// * `command` - command of the meta data, ie "test" to process the data or "validate" to validate it
+/// * `dry_run` - true if the module should only validate the data without processing it (see wasm_set_dry_run)
+/// * `cardinality_threshold` - string columns with a share of distinct values below the threshold are dictionary encoded before the call (see auto_dictionary_encode). The data is passed unchanged if it is None
 /// returns the result of the function `format_hello_world`
-fn wrapper_wasm_process_data_arrow(engine: &Engine, module: &Module) -> anyhow::Result<String> {
-    // Load function an instantiate it
-    let mut linker = Linker::new(&engine);
-    wasi_common::sync::add_to_linker(&mut linker, |state: &mut MyState| &mut state.wasi)?;
-    // store to exchange data with the WASM module
-    let wasi = WasiCtxBuilder::new()
-        .inherit_stdio()
-        .inherit_args()?
-        .build();
-    let mut store = Store::new(&engine, MyState { wasi: wasi });
-    // instantiate module
-    // let instance = Instance::new(&mut store, &module, &[])?;
-    linker.module(&mut store, "", &module)?;
-    let instance: Instance = linker.instantiate(&mut store, &module).unwrap();
+#[allow(clippy::too_many_arguments)]
+fn wrapper_wasm_process_data_arrow(
+    engine: &Engine,
+    module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+    example_batch: &RecordBatch,
+    command: &str,
+    dry_run: bool,
+    cardinality_threshold: Option<f64>,
+) -> anyhow::Result<String> {
+    // instantiate module with the restrictions of the sandbox
+    let (instance, mut store) = create_sandboxed_instance(engine, module, profiler, config)?;
+    call_wasm_process_data_arrow(
+        instance,
+        &mut store,
+        example_batch,
+        command,
+        dry_run,
+        cardinality_threshold,
+    )
+}
+
+/// Calls the function process_data_arrow of an existing instance of the WASM module, e.g. an instance of a pool
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+/// * `example_batch` - data to be processed, e.g. create_arrow_example_data
+/// * `command` - command of the meta data, ie "test" to process the data or "validate" to validate it
+/// * `dry_run` - true if the module should only validate the data without processing it. The dry-run mode is enabled before the call and disabled again after it
+/// * `cardinality_threshold` - string columns with a share of distinct values below the threshold are dictionary encoded before the call (see auto_dictionary_encode). The data is passed unchanged if it is None
+///
+/// returns the result of the function. Returns an error if the verdicts of the command "validate" are not of type Boolean
+fn call_wasm_process_data_arrow(
+    instance: Instance,
+    store: &mut Store<MyState>,
+    example_batch: &RecordBatch,
+    command: &str,
+    dry_run: bool,
+    cardinality_threshold: Option<f64>,
+) -> anyhow::Result<String> {
+    let result_arrow_ipc: Vec<u8> = call_wasm_process_data_arrow_ipc(
+        instance,
+        store,
+        example_batch,
+        command,
+        dry_run,
+        cardinality_threshold,
+    )?;
+    // check correctness of returned Arrow IPC data
+    println!("Displaying Arrow answer from Module");
+    let stream_reader = StreamReader::try_new(result_arrow_ipc.as_slice(), None).unwrap();
+
+    for item in stream_reader {
+        let result_batch: RecordBatch = item.unwrap();
+        if command == "validate" && !dry_run {
+            check_validation_flags(&result_batch)?;
+        }
+        print_batches(&[result_batch]).unwrap();
+    }
+    Ok("".to_string())
+}
+
+/// Calls the function process_data_arrow of an existing instance of the WASM module without displaying the result, e.g. to measure its latency
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+/// * `example_batch` - data to be processed, e.g. create_arrow_example_data
+/// * `command` - command of the meta data, ie "test" to process the data or "validate" to validate it
+/// * `dry_run` - true if the module should only validate the data without processing it. The dry-run mode is enabled before the call and disabled again after it
+/// * `cardinality_threshold` - string columns with a share of distinct values below the threshold are dictionary encoded before the call (see auto_dictionary_encode). The data is passed unchanged if it is None
+///
+/// returns the result data of the function in Arrow IPC format. If the module exports wasm_memory_process_data_arrow_checked, it is called instead to detect data corrupted while writing it to the module memory
+fn call_wasm_process_data_arrow_ipc(
+    instance: Instance,
+    store: &mut Store<MyState>,
+    example_batch: &RecordBatch,
+    command: &str,
+    dry_run: bool,
+    cardinality_threshold: Option<f64>,
+) -> anyhow::Result<Vec<u8>> {
+    if dry_run {
+        wrapper_wasm_set_dry_run(instance, &mut *store, true)?;
+    }
+
+    // prepare handing Arrow data
+    let serialized_meta_data = create_arrow_example_meta_data(command);
+    // dictionary encode string columns with few distinct values to reduce the size of the data, if requested
+    let serialized_data = match cardinality_threshold {
+        Some(cardinality_threshold) => serialize_arrow_batch(&auto_dictionary_encode(
+            example_batch,
+            cardinality_threshold,
+        )),
+        None => serialize_arrow_batch(example_batch),
+    };
+    let result_arrow_ipc: anyhow::Result<Vec<u8>> = call_wasm_process_data_arrow_serialized(
+        instance,
+        store,
+        &serialized_meta_data,
+        &serialized_data,
+    );
+    // reset the dry-run mode, so that it does not apply to other calls of the instance (e.g. of a pool)
+    if dry_run {
+        wrapper_wasm_set_dry_run(instance, &mut *store, false)?;
+    }
+    result_arrow_ipc
+}
+
+/// Calls the function process_data_arrow of an existing instance of the WASM module with meta data and data that are already serialized, e.g. to replay a recorded call
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+/// * `serialized_meta_data` - meta data in Arrow IPC format, e.g. create_arrow_example_meta_data
+/// * `serialized_data` - data in Arrow IPC format
+///
+/// returns the result data of the function in Arrow IPC format. If the module exports wasm_memory_process_data_arrow_checked, it is called instead to detect data corrupted while writing it to the module memory. The call is recorded if recording is enabled (see global_recorder)
+fn call_wasm_process_data_arrow_serialized(
+    instance: Instance,
+    store: &mut Store<MyState>,
+    serialized_meta_data: &[u8],
+    serialized_data: &[u8],
+) -> anyhow::Result<Vec<u8>> {
     // get the function
     let func_def = instance
-        .get_func(&mut store, "wasm_memory_process_data_arrow")
+        .get_func(&mut *store, "wasm_memory_process_data_arrow")
         .expect("`wasm_memory_process_data_arrow` was not an exported function");
     // validate that it corresponds to the parameters and return types we need
-    let func_validated = func_def.typed::<(u32, u32, u32, u32), u32>(&store)?;
+    let func_validated = func_def.typed::<(u32, u32, u32, u32), u32>(&*store)?;
+    // variant of the function that verifies the checksums of the meta data and data, if supported by the module
+    let func_checked = instance
+        .get_typed_func::<(u32, u32, u32, u32, u32, u32), u32>(
+            &mut *store,
+            "wasm_memory_process_data_arrow_checked",
+        )
+        .ok();
+    let func_name: &str = if func_checked.is_some() {
+        "wasm_memory_process_data_arrow_checked"
+    } else {
+        "wasm_memory_process_data_arrow"
+    };
 
     // prepare handing Arrow data
-    let serialized_meta_data = create_arrow_example_meta_data();
     let serialized_meta_data_size = serialized_meta_data.len();
-    let serialized_data = create_arrow_example_data();
     let serialized_data_size = serialized_data.len();
+    if let Some(recorder) = global_recorder() {
+        if let Err(e) = recorder.record(func_name, serialized_meta_data, serialized_data) {
+            tracing::warn!("Could not record the call of {func_name}: {e}");
+        }
+    }
 
     // instantiate memory
     let memory = instance
-        .get_memory(&mut store, "memory")
+        .get_memory(&mut *store, "memory")
         .ok_or(anyhow::format_err!("failed to find `memory` export"))?;
 
-    // allocate some memory within the WASM module for metadata
-    let offset_meta_data: u32 =
-        wrapper_wasm_allocate(instance, &mut store, serialized_meta_data_size as u32).unwrap()
-            as u32;
+    // allocate some memory within the WASM module for metadata and data at once
+    let offsets: Vec<u32> = wrapper_wasm_allocate_batch(
+        instance,
+        &mut *store,
+        &memory,
+        &[
+            serialized_meta_data_size as u32,
+            serialized_data_size as u32,
+        ],
+    )?;
+    let offset_meta_data: u32 = offsets[0];
+    let offset_data: u32 = offsets[1];
     memory
         .write(
-            &mut store,
+            &mut *store,
             offset_meta_data.try_into().unwrap(),
-            serialized_meta_data.as_slice(),
+            serialized_meta_data,
         )
         .unwrap();
-    // allocate some memory within the WASM module for data
-    let offset_data: u32 =
-        wrapper_wasm_allocate(instance, &mut store, serialized_data_size as u32).unwrap() as u32;
     memory
         .write(
-            &mut store,
+            &mut *store,
             offset_data.try_into().unwrap(),
-            serialized_data.as_slice(),
+            serialized_data,
         )
         .unwrap();
+    // checksums of the written meta data and data
+    let meta_data_crc: u32 = crc32fast::hash(serialized_meta_data);
+    let data_crc: u32 = crc32fast::hash(serialized_data);
     // call function answer
-    let result_offset = func_validated.call(
-        &mut store,
-        (
-            offset_meta_data,
-            serialized_meta_data_size as u32,
-            offset_data,
-            serialized_data_size as u32,
+    let call_start: Instant = Instant::now();
+    let result_offset = match func_checked {
+        Some(func_checked) => func_checked.call(
+            &mut *store,
+            (
+                offset_meta_data,
+                serialized_meta_data_size as u32,
+                meta_data_crc,
+                offset_data,
+                serialized_data_size as u32,
+                data_crc,
+            ),
         ),
-    )?;
+        None => func_validated.call(
+            &mut *store,
+            (
+                offset_meta_data,
+                serialized_meta_data_size as u32,
+                offset_data,
+                serialized_data_size as u32,
+            ),
+        ),
+    };
     // deallocate shared WASM Module memory
     let dealloc_meta_data_code: i32 =
-        wrapper_wasm_deallocate(instance, &mut store, offset_meta_data as *const u8).unwrap();
+        wrapper_wasm_deallocate(instance, &mut *store, offset_meta_data as *const u8).unwrap();
     if dealloc_meta_data_code != 0 {
         println!("Error: Could not deallocate shared WASM module memory for meta data");
     }
     let dealloc_data_code: i32 =
-        wrapper_wasm_deallocate(instance, &mut store, offset_data as *const u8).unwrap();
+        wrapper_wasm_deallocate(instance, &mut *store, offset_data as *const u8).unwrap();
     if dealloc_data_code != 0 {
         println!("Error: Could not deallocate shared WASM module memory for data");
     }
-    if result_offset == 0 {
-        anyhow::bail!("Error: No valid answer received from function")
-    } else {
-        let mut result_offset_position = result_offset;
-        // read answer from memory: these are two values: offset of the processed data and size of the processed data in Arrow IPC format
-        // read metadata (offset and size of the Arrow IPC data)
-        // note: WebAssembly is by default 32 bit
-        let mut ptr_buffer = [0u8; (u32::BITS / 8) as usize];
-        let mut len_buffer = [0u8; (u32::BITS / 8) as usize];
-        memory.read(
-            &store,
-            result_offset_position.try_into().unwrap(),
-            &mut ptr_buffer,
-        )?;
-        result_offset_position += (u32::BITS / 8) as u32;
-        memory.read(
-            &store,
-            result_offset_position.try_into().unwrap(),
-            &mut len_buffer,
-        )?;
-        let result_ptr = u32::from_le_bytes(ptr_buffer);
-        let result_len = u32::from_le_bytes(len_buffer);
-        // read the Arrow IPC data
-        let mut result_arrow_ipc: Vec<u8> = vec![0; result_len as usize];
-        let mut result_arrow_ipc_buffer = result_arrow_ipc.as_mut_slice();
-        memory.read(
-            &store,
-            result_ptr.try_into().unwrap(),
-            &mut result_arrow_ipc_buffer,
-        )?;
-        let dealloc_return_meta_code: i32 =
-            wrapper_wasm_deallocate(instance, &mut store, result_offset as *const u8).unwrap();
-        if dealloc_return_meta_code != 0 {
-            println!("Error: Could not deallocate shared WASM module memory for return metadata");
-        }
-        let dealloc_return_data_code: i32 =
-            wrapper_wasm_deallocate(instance, &mut store, result_ptr as *const u8).unwrap();
-        if dealloc_return_data_code != 0 {
-            println!("Error: Could not deallocate shared WASM module memory for return data");
-        }
-        // check correctness of returned Arrow IPC data
-        println!("Displaying Arrow answer from Module");
-        let stream_reader = StreamReader::try_new(result_arrow_ipc.as_slice(), None).unwrap();
+    // read the Arrow IPC data
+    let result_arrow_ipc: anyhow::Result<Vec<u8>> = result_offset
+        .and_then(|result_offset| read_wasm_result(instance, &mut *store, &memory, result_offset));
+    record_call(
+        store,
+        func_name,
+        call_start,
+        serialized_meta_data_size + serialized_data_size,
+        &result_arrow_ipc,
+    );
+    result_arrow_ipc
+}
 
-        for item in stream_reader {
-            print_batches(&[item.unwrap()]).unwrap();
+/// Checks that the verdicts returned by the command "validate" of the function process_data_arrow survived the round trip through Arrow IPC as Boolean fields
+/// # Arguments
+/// * `result_batch` - result of the command "validate"
+///
+/// returns an error if a verdict is missing or not of type Boolean
+fn check_validation_flags(result_batch: &RecordBatch) -> anyhow::Result<()> {
+    for flag in ["score_valid", "content_valid", "id_valid", "all_valid"] {
+        let data_type: DataType = result_batch
+            .schema()
+            .field_with_name(flag)?
+            .data_type()
+            .clone();
+        if data_type != DataType::Boolean {
+            anyhow::bail!("Verdict '{flag}' has type {data_type} instead of Boolean");
         }
     }
-    Ok("".to_string())
+    Ok(())
 }
 
-/// Wrapper around the allocate function of the WASM module to allocate shared WASM memory. Allocate some memory for the application to write data for the module
-/// Note: It is up to the application (and not the WASM module) to provide enough pages, so the module does not run out of memory
-/// # Arguments
+/// Wrapper around a function of the WASM Module that processes one input of Arrow data, e.g. process_tagged_docs_arrow or process_financial_arrow
+/// # Arguments (note the function of the WASM module itself expects to have the Arrow data exchanged in the module memory)
+/// * `engine` - wasmtime engine to use for the store
+/// * `module` - module containing the WASM function
+/// * `profiler` - profiler to record the call of the WASM function
+/// * `config` - sandbox configuration applied to the instance
+/// * `func_name` - name of the exported function of the WASM module, e.g. wasm_memory_process_tagged_docs_arrow
+/// * `serialized_data` - data to be processed in Arrow IPC format
+///
+/// returns the result of the function
+fn wrapper_wasm_process_single_arrow(
+    engine: &Engine,
+    module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+    func_name: &str,
+    serialized_data: Vec<u8>,
+) -> anyhow::Result<String> {
+    let result_arrow_ipc: Vec<u8> = wrapper_wasm_call_single_buffer(
+        engine,
+        module,
+        profiler,
+        config,
+        func_name,
+        &serialized_data,
+    )?;
+    println!("Displaying Arrow answer from Module");
+    let stream_reader = StreamReader::try_new(result_arrow_ipc.as_slice(), None).unwrap();
+
+    for item in stream_reader {
+        print_batches(&[item.unwrap()]).unwrap();
+    }
+    Ok("".to_string())
+}
+
+/// Wrapper around a function of the WASM Module that takes one buffer in the module memory as input and returns a WasmResult, e.g. process_tagged_docs_arrow
+/// # Arguments (note the function of the WASM module itself expects to have the data exchanged in the module memory)
+/// * `engine` - wasmtime engine to use for the store
+/// * `module` - module containing the WASM function
+/// * `profiler` - profiler to record the call of the WASM function
+/// * `config` - sandbox configuration applied to the instance
+/// * `func_name` - name of the exported function of the WASM module, e.g. wasm_memory_process_tagged_docs_arrow
+/// * `input_data` - data to hand over to the function
+///
+/// returns the result data of the function
+fn wrapper_wasm_call_single_buffer(
+    engine: &Engine,
+    module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+    func_name: &str,
+    input_data: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    // instantiate module with the restrictions of the sandbox
+    let (instance, mut store) = create_sandboxed_instance(engine, module, profiler, config)?;
+    // get the function
+    let func_def = instance
+        .get_func(&mut store, func_name)
+        .ok_or(anyhow::format_err!(
+            "`{func_name}` was not an exported function"
+        ))?;
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<(u32, u32), u32>(&store)?;
+
+    // prepare handing data
+    let input_data_size = input_data.len();
+    if let Some(recorder) = global_recorder() {
+        if let Err(e) = recorder.record(func_name, &[], input_data) {
+            tracing::warn!("Could not record the call of {func_name}: {e}");
+        }
+    }
+
+    // instantiate memory
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or(anyhow::format_err!("failed to find `memory` export"))?;
+
+    // allocate some memory within the WASM module for data
+    let offset_data: u32 =
+        wrapper_wasm_allocate(instance, &mut store, input_data_size as u32).unwrap() as u32;
+    memory
+        .write(&mut store, offset_data.try_into().unwrap(), input_data)
+        .unwrap();
+    // call function
+    let call_start: Instant = Instant::now();
+    let result_offset = func_validated.call(&mut store, (offset_data, input_data_size as u32));
+    // deallocate shared WASM Module memory
+    let dealloc_data_code: i32 =
+        wrapper_wasm_deallocate(instance, &mut store, offset_data as *const u8).unwrap();
+    if dealloc_data_code != 0 {
+        println!("Error: Could not deallocate shared WASM module memory for data");
+    }
+    // read the result data
+    let result_data: anyhow::Result<Vec<u8>> = result_offset
+        .and_then(|result_offset| read_wasm_result(instance, &mut store, &memory, result_offset));
+    record_call(&store, func_name, call_start, input_data_size, &result_data);
+    result_data
+}
+
+/// Wrapper around the function process_csv_file of the WASM Module. The module can only read the file if the sandbox configuration allows filesystem access and preopens the directory of the file
+/// # Arguments (note the function of the WASM module itself expects to have the filename exchanged in the module memory)
+/// * `engine` - wasmtime engine to use for the store
+/// * `module` - module containing the WASM function
+/// * `profiler` - profiler to record the call of the WASM function
+/// * `config` - sandbox configuration applied to the instance
+/// * `filename` - name of the CSV file relative to a preopened directory
+/// * `has_header` - true if the first line of the file contains the field names
+///
+/// returns the result of the function
+fn wrapper_wasm_process_csv_file(
+    engine: &Engine,
+    module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+    filename: &str,
+    has_header: bool,
+) -> anyhow::Result<String> {
+    // instantiate module with the restrictions of the sandbox
+    let (instance, mut store) = create_sandboxed_instance(engine, module, profiler, config)?;
+    // get the function
+    let func_def = instance
+        .get_func(&mut store, "wasm_process_csv_file")
+        .ok_or(anyhow::format_err!(
+            "`wasm_process_csv_file` was not an exported function"
+        ))?;
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<(u32, u32, u32), u32>(&store)?;
+    // instantiate memory
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or(anyhow::format_err!("failed to find `memory` export"))?;
+    // allocate some memory within the WASM module for the filename
+    let filename_len: u32 = filename.len() as u32;
+    let offset_filename: u32 = wrapper_wasm_allocate(instance, &mut store, filename_len)? as u32;
+    memory.write(&mut store, offset_filename as usize, filename.as_bytes())?;
+    // call function
+    let call_start: Instant = Instant::now();
+    let result_offset = func_validated.call(
+        &mut store,
+        (offset_filename, filename_len, u32::from(has_header)),
+    );
+    // deallocate shared WASM Module memory
+    let dealloc_filename_code: i32 =
+        wrapper_wasm_deallocate(instance, &mut store, offset_filename as *const u8)?;
+    if dealloc_filename_code != 0 {
+        println!("Error: Could not deallocate shared WASM module memory for filename");
+    }
+    // read the Arrow IPC data
+    let result_arrow_ipc: anyhow::Result<Vec<u8>> = result_offset
+        .and_then(|result_offset| read_wasm_result(instance, &mut store, &memory, result_offset));
+    record_call(
+        &store,
+        "wasm_process_csv_file",
+        call_start,
+        filename.len(),
+        &result_arrow_ipc,
+    );
+    let result_arrow_ipc: Vec<u8> = result_arrow_ipc?;
+    println!("Displaying Arrow answer from Module");
+    let stream_reader = StreamReader::try_new(result_arrow_ipc.as_slice(), None)?;
+    for item in stream_reader {
+        print_batches(&[item?])?;
+    }
+    Ok("".to_string())
+}
+
+/// Wrapper around the function process_and_write_parquet of the WASM Module. The module writes the result to a Parquet file in the preopened directory "." that is read back to verify it. This requires a sandbox configuration that allows filesystem access
+/// # Arguments (note the function of the WASM module itself expects to have the data and the output path exchanged in the module memory)
+/// * `engine` - wasmtime engine to use for the store
+/// * `module` - module containing the WASM function
+/// * `profiler` - profiler to record the call of the WASM function
+/// * `config` - sandbox configuration applied to the instance
+/// * `batch` - data to process
+/// * `output_path` - path of the Parquet file relative to the preopened directory "."
+///
+/// returns the result read back from the Parquet file
+fn wrapper_wasm_process_and_write_parquet(
+    engine: &Engine,
+    module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+    batch: &RecordBatch,
+    output_path: &str,
+) -> anyhow::Result<Vec<RecordBatch>> {
+    // directory on the host in which the module writes the file
+    let host_dir: &PathBuf = config
+        .preopened_dirs
+        .iter()
+        .find(|(guest_path, _)| guest_path == ".")
+        .map(|(_, host_path)| host_path)
+        .filter(|_| config.allow_filesystem)
+        .ok_or(anyhow::format_err!(
+            "Sandbox configuration does not allow writing to a preopened directory \".\""
+        ))?;
+    // instantiate module with the restrictions of the sandbox
+    let (instance, mut store) = create_sandboxed_instance(engine, module, profiler, config)?;
+    // get the function
+    let func_def = instance
+        .get_func(&mut store, "wasm_memory_process_and_write_parquet")
+        .ok_or(anyhow::format_err!(
+            "`wasm_memory_process_and_write_parquet` was not an exported function"
+        ))?;
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<(u32, u32, u32, u32), i32>(&store)?;
+    // instantiate memory
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or(anyhow::format_err!("failed to find `memory` export"))?;
+    // allocate some memory within the WASM module for the data and the output path
+    let serialized_data: Vec<u8> = serialize_arrow_batch(batch);
+    let data_len: u32 = serialized_data.len() as u32;
+    let offset_data: u32 = wrapper_wasm_allocate(instance, &mut store, data_len)? as u32;
+    memory.write(&mut store, offset_data as usize, &serialized_data)?;
+    let output_path_len: u32 = output_path.len() as u32;
+    let offset_output_path: u32 =
+        wrapper_wasm_allocate(instance, &mut store, output_path_len)? as u32;
+    memory.write(
+        &mut store,
+        offset_output_path as usize,
+        output_path.as_bytes(),
+    )?;
+    // call function
+    let call_start: Instant = Instant::now();
+    let return_code = func_validated.call(
+        &mut store,
+        (offset_data, data_len, offset_output_path, output_path_len),
+    );
+    // deallocate shared WASM Module memory
+    let dealloc_data_code: i32 =
+        wrapper_wasm_deallocate(instance, &mut store, offset_data as *const u8)?;
+    if dealloc_data_code != 0 {
+        println!("Error: Could not deallocate shared WASM module memory for data");
+    }
+    let dealloc_output_path_code: i32 =
+        wrapper_wasm_deallocate(instance, &mut store, offset_output_path as *const u8)?;
+    if dealloc_output_path_code != 0 {
+        println!("Error: Could not deallocate shared WASM module memory for output path");
+    }
+    let return_code: i32 = return_code?;
+    store.data().profiler.record(
+        "wasm_memory_process_and_write_parquet",
+        call_start,
+        serialized_data.len(),
+        0,
+        return_code == 0,
+    );
+    match return_code {
+        0 => (),
+        -1 => anyhow::bail!(
+            "Error: Could not write Parquet file: {}",
+            wrapper_wasm_last_error(instance, &mut store, &memory)?.unwrap_or_default()
+        ),
+        _ => anyhow::bail!(
+            "Error: Could not process data: {}",
+            wrapper_wasm_last_error(instance, &mut store, &memory)?.unwrap_or_default()
+        ),
+    }
+    // verify the file written by the module
+    let file: File = File::open(host_dir.join(output_path))?;
+    let parquet_reader: ParquetRecordBatchReader =
+        ParquetRecordBatchReaderBuilder::try_new(file)?.build()?;
+    let result_batches: Vec<RecordBatch> =
+        parquet_reader.collect::<Result<Vec<RecordBatch>, ArrowError>>()?;
+    println!("Displaying Parquet file written by Module");
+    print_batches(&result_batches)?;
+    Ok(result_batches)
+}
+
+/// Wrapper around the function stream_process_arrow of the WASM Module. The module writes the result as Arrow IPC stream to WASI stdout, which is captured in a buffer of the host and deserialized after the call
+/// # Arguments (note the function of the WASM module itself expects to have the data exchanged in the module memory)
+/// * `engine` - wasmtime engine to use for the store
+/// * `module` - module containing the WASM function
+/// * `profiler` - profiler to record the call of the WASM function
+/// * `config` - sandbox configuration applied to the instance
+/// * `batch` - data to process
+/// * `chunk_rows` - maximum number of rows of a record batch written by the module
+///
+/// returns the record batches read from the captured stdout
+fn wrapper_wasm_stream_process_arrow(
+    engine: &Engine,
+    module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+    batch: &RecordBatch,
+    chunk_rows: u32,
+) -> anyhow::Result<Vec<RecordBatch>> {
+    // instantiate module with the restrictions of the sandbox
+    let (instance, mut store) = create_sandboxed_instance(engine, module, profiler, config)?;
+    // capture stdout of the instance
+    let stdout_buffer: Arc<RwLock<Vec<u8>>> = Arc::new(RwLock::new(Vec::new()));
+    store
+        .data()
+        .wasi
+        .set_stdout(Box::new(WritePipe::from_shared(stdout_buffer.clone())));
+    // get the function
+    let func_def = instance
+        .get_func(&mut store, "wasm_stream_process_arrow")
+        .ok_or(anyhow::format_err!(
+            "`wasm_stream_process_arrow` was not an exported function"
+        ))?;
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<(u32, u32, u32), i32>(&store)?;
+    // instantiate memory
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or(anyhow::format_err!("failed to find `memory` export"))?;
+    // allocate some memory within the WASM module for the data
+    let serialized_data: Vec<u8> = serialize_arrow_batch(batch);
+    let data_len: u32 = serialized_data.len() as u32;
+    let offset_data: u32 = wrapper_wasm_allocate(instance, &mut store, data_len)? as u32;
+    memory.write(&mut store, offset_data as usize, &serialized_data)?;
+    // call function
+    let call_start: Instant = Instant::now();
+    let return_code = func_validated.call(&mut store, (offset_data, data_len, chunk_rows));
+    // deallocate shared WASM Module memory
+    let dealloc_data_code: i32 =
+        wrapper_wasm_deallocate(instance, &mut store, offset_data as *const u8)?;
+    if dealloc_data_code != 0 {
+        println!("Error: Could not deallocate shared WASM module memory for data");
+    }
+    let return_code: i32 = return_code?;
+    let stdout_data: Vec<u8> = stdout_buffer
+        .read()
+        .map_err(|e| anyhow::format_err!("Captured stdout is not readable: {e}"))?
+        .clone();
+    store.data().profiler.record(
+        "wasm_stream_process_arrow",
+        call_start,
+        serialized_data.len(),
+        stdout_data.len(),
+        return_code == 0,
+    );
+    match return_code {
+        0 => (),
+        -1 => anyhow::bail!(
+            "Error: Could not write result to stdout: {}",
+            wrapper_wasm_last_error(instance, &mut store, &memory)?.unwrap_or_default()
+        ),
+        _ => anyhow::bail!(
+            "Error: Could not process data: {}",
+            wrapper_wasm_last_error(instance, &mut store, &memory)?.unwrap_or_default()
+        ),
+    }
+    // deserialize the stream written by the module
+    let stream_reader = StreamReader::try_new(stdout_data.as_slice(), None)?;
+    let result_batches: Vec<RecordBatch> =
+        stream_reader.collect::<Result<Vec<RecordBatch>, ArrowError>>()?;
+    println!(
+        "Displaying stream written by Module to stdout ({} record batches)",
+        result_batches.len()
+    );
+    print_batches(&result_batches)?;
+    Ok(result_batches)
+}
+
+/// Wrapper around the function wasm_get_build_info of the WASM Module
+/// # Arguments (note the function `wasm_get_build_info` of the WASM module itself has no parameters. The parameters are just to initialize the runtime environment)
+/// * `engine` - wasmtime engine to use for the store
+/// * `module` - module containing the WASM function
+/// * `config` - sandbox configuration applied to the instance
+///
+/// returns the build information as JSON object. The call is not recorded by the profiler of the application
+fn wrapper_wasm_get_build_info(
+    engine: &Engine,
+    module: &Module,
+    config: &SandboxConfig,
+) -> anyhow::Result<String> {
+    let profiler: Arc<ExecutionProfiler> = Arc::new(ExecutionProfiler::default());
+    // instantiate module with the restrictions of the sandbox
+    let (instance, mut store) = create_sandboxed_instance(engine, module, &profiler, config)?;
+    let func_validated = instance.get_typed_func::<(), u32>(&mut store, "wasm_get_build_info")?;
+    // instantiate memory
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or(anyhow::format_err!("failed to find `memory` export"))?;
+    // the build information is a C string owned by the module, so it is not deallocated
+    let build_info_offset: u32 = func_validated.call(&mut store, ())?;
+    let build_info_data: &[u8] = memory
+        .data(&store)
+        .get(build_info_offset as usize..)
+        .ok_or(anyhow::format_err!(
+            "Build information is outside of the WASM module memory"
+        ))?;
+    Ok(CStr::from_bytes_until_nul(build_info_data)?
+        .to_str()?
+        .to_string())
+}
+
+/// Wrapper around the function wasm_version of the WASM Module
+/// # Arguments (note the function `wasm_version` of the WASM module itself has no parameters. The parameters are just to initialize the runtime environment)
+/// * `engine` - wasmtime engine to use for the store
+/// * `module` - module containing the WASM function
+/// * `profiler` - profiler to record the call of the WASM function
+/// * `config` - sandbox configuration applied to the instance
+///
+/// returns the version of the module
+fn wrapper_wasm_version(
+    engine: &Engine,
+    module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+) -> anyhow::Result<String> {
+    // instantiate module with the restrictions of the sandbox
+    let (instance, mut store) = create_sandboxed_instance(engine, module, profiler, config)?;
+    // get the function
+    let func_def = instance
+        .get_func(&mut store, "wasm_version")
+        .ok_or(anyhow::format_err!(
+            "`wasm_version` was not an exported function"
+        ))?;
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<(), u32>(&store)?;
+    // instantiate memory
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or(anyhow::format_err!("failed to find `memory` export"))?;
+    // call function
+    let call_start: Instant = Instant::now();
+    let result_data: anyhow::Result<Vec<u8>> = func_validated
+        .call(&mut store, ())
+        .and_then(|result_offset| read_wasm_result(instance, &mut store, &memory, result_offset));
+    record_call(&store, "wasm_version", call_start, 0, &result_data);
+    Ok(String::from_utf8(result_data?)?)
+}
+
+/// Records a call of a function of the WASM module in the profiler of the store
+/// # Arguments
+/// * `store` - store of the instance
+/// * `function` - name of the function of the WASM module
+/// * `start` - time the call started
+/// * `input_bytes` - size of the data handed over to the function
+/// * `result` - result data of the function
+fn record_call(
+    store: &Store<MyState>,
+    function: &str,
+    start: Instant,
+    input_bytes: usize,
+    result: &anyhow::Result<Vec<u8>>,
+) {
+    let output_bytes: usize = result.as_ref().map_or(0, |result_data| result_data.len());
+    store
+        .data()
+        .profiler
+        .record(function, start, input_bytes, output_bytes, result.is_ok());
+}
+
+/// Reads the result of a function of the WASM module. Results are returned as pointer to a WasmResult in the WASM module memory with the layout (little endian): status (i32) at byte 0, data_ptr (u32) at byte 4, data_len (u32) at byte 8
+/// The WasmResult and the result data are deallocated after reading
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+/// * `memory` - memory of the instance
+/// * `result_offset` - pointer to the WasmResult returned by the function
+///
+/// returns the result data. If the status is non-zero, an error containing the last error of the WASM module is returned
+fn read_wasm_result(
+    instance: Instance,
+    store: &mut Store<MyState>,
+    memory: &Memory,
+    result_offset: u32,
+) -> anyhow::Result<Vec<u8>> {
+    if result_offset == 0 {
+        anyhow::bail!("Error: No valid answer received from function")
+    }
+    // read the WasmResult
+    // note: WebAssembly is by default 32 bit
+    let mut wasm_result_buffer = [0u8; 3 * (u32::BITS / 8) as usize];
+    memory.read(&*store, result_offset as usize, &mut wasm_result_buffer)?;
+    let status = i32::from_le_bytes(wasm_result_buffer[0..4].try_into()?);
+    let data_ptr = u32::from_le_bytes(wasm_result_buffer[4..8].try_into()?);
+    let data_len = u32::from_le_bytes(wasm_result_buffer[8..12].try_into()?);
+    let dealloc_return_meta_code: i32 =
+        wrapper_wasm_deallocate(instance, &mut *store, result_offset as *const u8)?;
+    if dealloc_return_meta_code != 0 {
+        println!("Error: Could not deallocate shared WASM module memory for return metadata");
+    }
+    if status != 0 {
+        let last_error: String = wrapper_wasm_last_error(instance, &mut *store, memory)?
+            .unwrap_or("unknown error".to_string());
+        // the application can retry with less data if the module is out of memory
+        if status == WASM_RESULT_ERROR_OUT_OF_MEMORY {
+            return Err(OutOfMemoryError(last_error).into());
+        }
+        if status == WASM_RESULT_ERROR_OUTPUT_TOO_LARGE {
+            return Err(OutputTooLargeError(last_error).into());
+        }
+        anyhow::bail!("Error: Function of WASM module failed with status {status}: {last_error}")
+    }
+    // read the data
+    let mut result_data: Vec<u8> = vec![0; data_len as usize];
+    memory.read(&*store, data_ptr as usize, &mut result_data)?;
+    let dealloc_return_data_code: i32 =
+        wrapper_wasm_deallocate(instance, &mut *store, data_ptr as *const u8)?;
+    if dealloc_return_data_code != 0 {
+        println!("Error: Could not deallocate shared WASM module memory for return data");
+    }
+    Ok(result_data)
+}
+
+/// Wrapper around the function wasm_last_error of the WASM module to fetch the last error that occurred in the module
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+/// * `memory` - memory of the instance
+///
+/// returns the last error. It is None if no error occurred
+fn wrapper_wasm_last_error(
+    instance: Instance,
+    store: &mut Store<MyState>,
+    memory: &Memory,
+) -> anyhow::Result<Option<String>> {
+    // get the function
+    let func_def = instance
+        .get_func(&mut *store, "wasm_last_error")
+        .expect("`wasm_last_error` was not an exported function");
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<(), u32>(&*store)?;
+    // call function
+    let result_offset = func_validated.call(&mut *store, ())?;
+    if result_offset == 0 {
+        return Ok(None);
+    }
+    let error_message: Vec<u8> = read_wasm_result(instance, &mut *store, memory, result_offset)?;
+    Ok(Some(String::from_utf8_lossy(&error_message).into_owned()))
+}
+
+/// Adds the functions that the application provides to the WASM modules to the linker
+/// * `host_log(level: i32, msg_ptr: u32, msg_len: u32)` - logs a UTF-8 message in the WASM module memory via the logger of the application. Levels: 0 = error, 1 = warn, 2 = info, 3 = debug, 4 = trace
+/// * `host_kv_get(key_ptr: u32, key_len: u32, val_ptr: u32, val_max_len: u32) -> i32` - writes the value of a key of the key-value store of the store (see MyState) to the WASM module memory. Returns the size of the value (0 or more) if it has been written, -1 if the key does not exist and -2 if the value is larger than val_max_len
+/// * `host_kv_set(key_ptr: u32, key_len: u32, val_ptr: u32, val_len: u32) -> i32` - sets the value of a key of the key-value store of the store. Returns 0 if the value has been set
+/// # Arguments
+/// * `linker` - linker used to instantiate the WASM modules
+fn add_host_functions_to_linker(linker: &mut Linker<MyState>) -> anyhow::Result<()> {
+    linker.func_wrap(
+        "env",
+        "host_log",
+        |mut caller: Caller<'_, MyState>, level: i32, msg_ptr: u32, msg_len: u32| {
+            // read the message from the memory of the WASM module calling the function
+            let memory = match caller.get_export("memory") {
+                Some(Extern::Memory(memory)) => memory,
+                _ => anyhow::bail!("failed to find `memory` export"),
+            };
+            let mut msg_buffer: Vec<u8> = vec![0; msg_len as usize];
+            memory.read(&caller, msg_ptr as usize, &mut msg_buffer)?;
+            let msg = String::from_utf8_lossy(&msg_buffer);
+            match level {
+                0 => tracing::event!(target: "wasm_module", Level::ERROR, "{msg}"),
+                1 => tracing::event!(target: "wasm_module", Level::WARN, "{msg}"),
+                2 => tracing::event!(target: "wasm_module", Level::INFO, "{msg}"),
+                3 => tracing::event!(target: "wasm_module", Level::DEBUG, "{msg}"),
+                _ => tracing::event!(target: "wasm_module", Level::TRACE, "{msg}"),
+            }
+            Ok(())
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "host_kv_get",
+        |mut caller: Caller<'_, MyState>,
+         key_ptr: u32,
+         key_len: u32,
+         val_ptr: u32,
+         val_max_len: u32|
+         -> anyhow::Result<i32> {
+            let memory = match caller.get_export("memory") {
+                Some(Extern::Memory(memory)) => memory,
+                _ => anyhow::bail!("failed to find `memory` export"),
+            };
+            let mut key: Vec<u8> = vec![0; key_len as usize];
+            memory.read(&caller, key_ptr as usize, &mut key)?;
+            let Some(value) = caller.data().kv_store.get(&key).cloned() else {
+                return Ok(HOST_KV_KEY_NOT_FOUND);
+            };
+            if value.len() > val_max_len as usize {
+                return Ok(HOST_KV_BUFFER_TOO_SMALL);
+            }
+            memory.write(&mut caller, val_ptr as usize, &value)?;
+            Ok(value.len() as i32)
+        },
+    )?;
+    linker.func_wrap(
+        "env",
+        "host_kv_set",
+        |mut caller: Caller<'_, MyState>,
+         key_ptr: u32,
+         key_len: u32,
+         val_ptr: u32,
+         val_len: u32|
+         -> anyhow::Result<i32> {
+            let memory = match caller.get_export("memory") {
+                Some(Extern::Memory(memory)) => memory,
+                _ => anyhow::bail!("failed to find `memory` export"),
+            };
+            let mut key: Vec<u8> = vec![0; key_len as usize];
+            memory.read(&caller, key_ptr as usize, &mut key)?;
+            let mut value: Vec<u8> = vec![0; val_len as usize];
+            memory.read(&caller, val_ptr as usize, &mut value)?;
+            caller.data_mut().kv_store.insert(key, value);
+            Ok(0)
+        },
+    )?;
+    Ok(())
+}
+
+/// Wrapper around the allocate function of the WASM module to allocate shared WASM memory. Allocate some memory for the application to write data for the module
+/// Note: It is up to the application (and not the WASM module) to provide enough pages, so the module does not run out of memory
+/// # Arguments
 /// * `size` - size of memory to allocaten
 /// returns a pointer to the allocated memory area
 fn wrapper_wasm_allocate(
@@ -475,6 +1903,200 @@ fn wrapper_wasm_allocate(
     Ok(result as *const u8)
 }
 
+/// Wrapper around the allocate_batch function of the WASM module to allocate several areas of shared WASM memory with one call of the module instead of one call per area
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+/// * `memory` - memory of the instance
+/// * `sizes` - sizes of the memory areas to allocate
+///
+/// returns the pointers to the allocated memory areas in the order of the sizes
+fn wrapper_wasm_allocate_batch(
+    instance: Instance,
+    store: &mut Store<MyState>,
+    memory: &Memory,
+    sizes: &[u32],
+) -> anyhow::Result<Vec<u32>> {
+    // get the function
+    let func_def = instance
+        .get_func(&mut *store, "wasm_allocate_batch")
+        .expect("`wasm_allocate_batch` was not an exported function");
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<(u32, u32), u32>(&*store)?;
+    // hand over the sizes
+    let sizes_bytes: Vec<u8> = sizes.iter().flat_map(|size| size.to_le_bytes()).collect();
+    let offset_sizes: u32 =
+        wrapper_wasm_allocate(instance, &mut *store, sizes_bytes.len() as u32)? as u32;
+    memory.write(&mut *store, offset_sizes as usize, &sizes_bytes)?;
+    // call function
+    let offset_ptrs: u32 = func_validated.call(&mut *store, (offset_sizes, sizes.len() as u32))?;
+    wrapper_wasm_deallocate(instance, &mut *store, offset_sizes as *const u8)?;
+    if offset_ptrs == 0 {
+        return Err(OutOfMemoryError(format!(
+            "Could not allocate {} areas of shared WASM module memory",
+            sizes.len()
+        ))
+        .into());
+    }
+    // read the pointers
+    let mut ptrs_bytes: Vec<u8> = vec![0; sizes.len() * 4];
+    memory.read(&*store, offset_ptrs as usize, &mut ptrs_bytes)?;
+    wrapper_wasm_deallocate(instance, &mut *store, offset_ptrs as *const u8)?;
+    Ok(ptrs_bytes
+        .chunks_exact(4)
+        .map(|ptr_bytes| u32::from_le_bytes(ptr_bytes.try_into().unwrap()))
+        .collect())
+}
+
+/// Wrapper around the health check function of the WASM module. It checks that the instance is still healthy, e.g. before it is reused from a pool
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+///
+/// returns 0 if the instance is healthy and a negative code otherwise (-100 if memory has been leaked)
+fn wrapper_wasm_health_check(
+    instance: Instance,
+    mut store: impl AsContextMut<Data = MyState>,
+) -> anyhow::Result<i32> {
+    // get the function
+    let func_def = instance
+        .get_func(&mut store, "wasm_health_check")
+        .expect("`wasm_health_check` was not an exported function");
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<(), i32>(&store)?;
+    // call function
+    let result = func_validated.call(&mut store, ())?;
+    Ok(result)
+}
+
+/// Wrapper around the telemetry function of the WASM module to fetch all telemetry of an instance with one call
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+///
+/// returns the telemetry
+fn wrapper_wasm_get_telemetry(
+    instance: Instance,
+    store: &mut Store<MyState>,
+) -> anyhow::Result<ModuleTelemetry> {
+    // get the function
+    let func_def = instance
+        .get_func(&mut *store, "wasm_get_telemetry")
+        .expect("`wasm_get_telemetry` was not an exported function");
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<(), u32>(&*store)?;
+    // call function
+    let telemetry_offset: u32 = func_validated.call(&mut *store, ())?;
+    anyhow::ensure!(
+        telemetry_offset != 0,
+        "WASM module could not allocate memory for the telemetry"
+    );
+    let memory = instance
+        .get_memory(&mut *store, "memory")
+        .ok_or(anyhow::format_err!("failed to find `memory` export"))?;
+    let mut telemetry_bytes: Vec<u8> = vec![0; TELEMETRY_SIZE];
+    memory.read(&*store, telemetry_offset as usize, &mut telemetry_bytes)?;
+    let dealloc_telemetry_code: i32 =
+        wrapper_wasm_deallocate(instance, &mut *store, telemetry_offset as *const u8)?;
+    if dealloc_telemetry_code != 0 {
+        println!("Error: Could not deallocate shared WASM module memory for telemetry");
+    }
+    ModuleTelemetry::from_bytes(&telemetry_bytes)
+}
+
+/// Wrapper around the init function of the WASM module. It installs a panic hook in the module, so that the panic message of a trap can be fetched via wasm_last_error
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+fn wrapper_wasm_init(
+    instance: Instance,
+    mut store: impl AsContextMut<Data = MyState>,
+) -> anyhow::Result<()> {
+    // get the function
+    let func_def = instance
+        .get_func(&mut store, "wasm_init")
+        .expect("`wasm_init` was not an exported function");
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<(), ()>(&store)?;
+    // call function
+    func_validated.call(&mut store, ())?;
+    Ok(())
+}
+
+/// Wrapper around the function config_set of the WASM module. It sets a value of the configuration of the instance, e.g. a threshold of the processing
+/// # Arguments (note the function `wasm_config_set` of the WASM module itself expects to have the key and value exchanged in the module memory)
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+/// * `key` - key of the configuration, e.g. score_threshold
+/// * `value` - value of the configuration
+fn wrapper_wasm_config_set(
+    instance: Instance,
+    store: &mut Store<MyState>,
+    key: &str,
+    value: &str,
+) -> anyhow::Result<()> {
+    // get the function
+    let func_def = instance
+        .get_func(&mut *store, "wasm_config_set")
+        .ok_or(anyhow::format_err!(
+            "`wasm_config_set` was not an exported function"
+        ))?;
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<(u32, u32, u32, u32), i32>(&*store)?;
+    // instantiate memory
+    let memory = instance
+        .get_memory(&mut *store, "memory")
+        .ok_or(anyhow::format_err!("failed to find `memory` export"))?;
+    // allocate some memory within the WASM module for the key and the value
+    let key_len: u32 = key.len() as u32;
+    let offset_key: u32 = wrapper_wasm_allocate(instance, &mut *store, key_len)? as u32;
+    memory.write(&mut *store, offset_key as usize, key.as_bytes())?;
+    let value_len: u32 = value.len() as u32;
+    let offset_value: u32 = wrapper_wasm_allocate(instance, &mut *store, value_len)? as u32;
+    memory.write(&mut *store, offset_value as usize, value.as_bytes())?;
+    // call function
+    let return_code =
+        func_validated.call(&mut *store, (offset_key, key_len, offset_value, value_len));
+    // deallocate shared WASM Module memory
+    for (offset, name) in [(offset_key, "key"), (offset_value, "value")] {
+        if wrapper_wasm_deallocate(instance, &mut *store, offset as *const u8)? != 0 {
+            println!("Error: Could not deallocate shared WASM module memory for {name}");
+        }
+    }
+    if return_code? != 0 {
+        anyhow::bail!(
+            "Error: Could not set configuration '{key}': {}",
+            wrapper_wasm_last_error(instance, store, &memory)?.unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// Wrapper around the warm-up function of the WASM module. It allocates, writes and deallocates buffers of typical sizes, so that the first call of a new instance is not slower than the following ones
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+/// * `typical_input_size` - typical size of the data handed over to the module
+/// * `typical_output_size` - typical size of the result data
+///
+/// returns 0 if the warm-up succeeded and a negative code otherwise
+fn wrapper_wasm_warmup(
+    instance: Instance,
+    mut store: impl AsContextMut<Data = MyState>,
+    typical_input_size: u32,
+    typical_output_size: u32,
+) -> anyhow::Result<i32> {
+    // get the function
+    let func_def = instance
+        .get_func(&mut store, "wasm_warmup")
+        .expect("`wasm_warmup` was not an exported function");
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<(u32, u32), i32>(&store)?;
+    // call function
+    let result = func_validated.call(&mut store, (typical_input_size, typical_output_size))?;
+    Ok(result)
+}
+
 ///  Wrapper around the deallocate function of the WASM module to deallocate shared WASM memory. Deallocates existing memory for the purpose of the application
 /// # Arguments
 /// * `ptr` - mutuable pointer to the memory to deallocate
@@ -495,22 +2117,165 @@ fn wrapper_wasm_deallocate(
     Ok(result)
 }
 
+///  Wrapper around the set_dry_run function of the WASM module to enable or disable the dry-run mode of process_data_arrow
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+/// * `enabled` - true to only validate the data in following calls of process_data_arrow, false to process it
+///
+/// returns an error if the function is not exported by the module or the call failed
+fn wrapper_wasm_set_dry_run(
+    instance: Instance,
+    mut store: impl AsContextMut<Data = MyState>,
+    enabled: bool,
+) -> anyhow::Result<()> {
+    // get the function
+    let func_def = instance
+        .get_func(&mut store, "wasm_set_dry_run")
+        .ok_or(anyhow::format_err!(
+            "`wasm_set_dry_run` was not an exported function"
+        ))?;
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<u32, ()>(&store)?;
+    // call function
+    func_validated.call(&mut store, enabled as u32)?;
+    Ok(())
+}
+
+/// Wrapper around the set_tenant_id function of the WASM module to set the tenant of the following calls
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+/// * `tenant_id` - ID of the tenant. Memory allocated for one tenant cannot be validated or deallocated by another tenant
+///
+/// returns an error if the function is not exported by the module or the call failed
+fn wrapper_wasm_set_tenant_id(
+    instance: Instance,
+    mut store: impl AsContextMut<Data = MyState>,
+    tenant_id: u32,
+) -> anyhow::Result<()> {
+    // get the function
+    let func_def =
+        instance
+            .get_func(&mut store, "wasm_set_tenant_id")
+            .ok_or(anyhow::format_err!(
+                "`wasm_set_tenant_id` was not an exported function"
+            ))?;
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<u32, ()>(&store)?;
+    // call function
+    func_validated.call(&mut store, tenant_id)?;
+    Ok(())
+}
+
+/// Wrapper around the set_null_handling function of the WASM module to set how null values in the data of process_data_arrow are handled
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+/// * `mode` - 0 to reject data with null values, 1 to replace them by defaults, 2 (NULL_HANDLING_SKIP) to skip rows with null values
+///
+/// returns an error if the function is not exported by the module or the mode is unknown
+fn wrapper_wasm_set_null_handling(
+    instance: Instance,
+    store: &mut Store<MyState>,
+    mode: u32,
+) -> anyhow::Result<()> {
+    // get the function
+    let func_def = instance
+        .get_func(&mut *store, "wasm_set_null_handling")
+        .ok_or(anyhow::format_err!(
+            "`wasm_set_null_handling` was not an exported function"
+        ))?;
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<u32, i32>(&*store)?;
+    // call function
+    if func_validated.call(&mut *store, mode)? != 0 {
+        let memory = instance
+            .get_memory(&mut *store, "memory")
+            .ok_or(anyhow::format_err!("failed to find `memory` export"))?;
+        anyhow::bail!(
+            "Error: Could not set null handling: {}",
+            wrapper_wasm_last_error(instance, store, &memory)?.unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// Wrapper around the get_last_processing_duration_ns function of the WASM module to fetch the duration of the processing of the last call measured by the module
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+///
+/// returns the duration in nanoseconds
+fn wrapper_wasm_get_last_processing_duration_ns(
+    instance: Instance,
+    store: &mut Store<MyState>,
+) -> anyhow::Result<u64> {
+    // get the function
+    let func_def = instance
+        .get_func(&mut *store, "wasm_get_last_processing_duration_ns")
+        .ok_or(anyhow::format_err!(
+            "`wasm_get_last_processing_duration_ns` was not an exported function"
+        ))?;
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<(), u64>(&*store)?;
+    // call function
+    func_validated.call(&mut *store, ())
+}
+
+/// Wrapper around the set_max_output_bytes function of the WASM module to limit the size of its results
+/// # Arguments
+/// * `instance` - instance of the WASM module
+/// * `store` - store of the instance
+/// * `limit` - maximum size of a result in bytes, 0 removes the limit
+///
+/// returns an error if the function is not exported by the module
+fn wrapper_wasm_set_max_output_bytes(
+    instance: Instance,
+    store: &mut Store<MyState>,
+    limit: u32,
+) -> anyhow::Result<()> {
+    // get the function
+    let func_def = instance
+        .get_func(&mut *store, "wasm_set_max_output_bytes")
+        .ok_or(anyhow::format_err!(
+            "`wasm_set_max_output_bytes` was not an exported function"
+        ))?;
+    // validate that it corresponds to the parameters and return types we need
+    let func_validated = func_def.typed::<u32, ()>(&*store)?;
+    // call function
+    func_validated.call(&mut *store, limit)?;
+    Ok(())
+}
+
 /// Create example data
 /// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.77}
-/// returns a binary representation of the data in Arrow IPC format
-fn create_arrow_example_data() -> Vec<u8> {
+/// The schema metadata {source: "wasm-app", version: "1.0.0", created_at: <Unix timestamp>} describes the provenance of the data
+/// returns the data as record batch
+fn create_arrow_example_data() -> RecordBatch {
     // define schema
-    let schema = Schema::new(vec![
-        Field::new("id", DataType::UInt64, false),
-        Field::new("content", DataType::Utf8, false),
-        Field::new("title", DataType::Utf8, false),
-        Field::new(
-            "date",
-            DataType::Timestamp(TimeUnit::Second, Some("+00:00".to_string().into())),
-            false,
+    let metadata: HashMap<String, String> = HashMap::from([
+        ("source".to_string(), "wasm-app".to_string()),
+        ("version".to_string(), "1.0.0".to_string()),
+        (
+            "created_at".to_string(),
+            OffsetDateTime::now_utc().unix_timestamp().to_string(),
         ),
-        Field::new("score", DataType::Float64, false),
     ]);
+    let schema = Schema::new_with_metadata(
+        vec![
+            Field::new("id", DataType::UInt64, false),
+            Field::new("content", DataType::Utf8, false),
+            Field::new("title", DataType::Utf8, false),
+            Field::new(
+                "date",
+                DataType::Timestamp(TimeUnit::Second, Some("+00:00".to_string().into())),
+                false,
+            ),
+            Field::new("score", DataType::Float64, false),
+        ],
+        metadata,
+    );
     let ids = UInt64Array::from(vec![1]);
     let contents = StringArray::from(vec!["this is a test"]);
     let titles = StringArray::from(vec!["test"]);
@@ -521,7 +2286,7 @@ fn create_arrow_example_data() -> Vec<u8> {
     let scores = Float64Array::from(vec![1.123456f64]);
 
     // build a record batch
-    let batch = RecordBatch::try_new(
+    RecordBatch::try_new(
         Arc::new(schema.clone()),
         vec![
             Arc::new(ids),
@@ -531,6 +2296,148 @@ fn create_arrow_example_data() -> Vec<u8> {
             Arc::new(scores),
         ],
     )
+    .unwrap()
+}
+
+/// Converts a greeting returned by the function rust_format_hello_world of module 1 into a document of the example data, so that module 2 can process it
+/// {id: 1, content: <greeting>, title: "greeting", date:"2022-01-01T12:00:00Z", score: 1.123456}
+/// # Arguments
+/// * `greeting` - greeting as UTF-8 string
+///
+/// returns a binary representation of the document in Arrow IPC format
+fn greeting_to_document(greeting: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+    let greeting: String = String::from_utf8(greeting)?;
+    let example_batch: RecordBatch = create_arrow_example_data();
+    let mut columns: Vec<ArrayRef> = example_batch.columns().to_vec();
+    columns[1] = Arc::new(StringArray::from(vec![greeting]));
+    columns[2] = Arc::new(StringArray::from(vec!["greeting"]));
+    let batch: RecordBatch = RecordBatch::try_new(example_batch.schema(), columns)?;
+    Ok(serialize_arrow_batch(&batch))
+}
+
+/// Create example data with a second row without score (null), e.g. from an upstream system that does not enforce non-nullable fields
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456}, {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: null}
+/// returns the data as record batch
+fn create_arrow_example_data_with_null_row() -> RecordBatch {
+    let example_batch: RecordBatch = repeat_rows(&create_arrow_example_data(), 2);
+    let schema = example_batch.schema();
+    let score_index: usize = schema.index_of("score").unwrap();
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields[score_index] = fields[score_index].clone().with_nullable(true);
+    let mut columns: Vec<ArrayRef> = example_batch.columns().to_vec();
+    columns[score_index] = Arc::new(Float64Array::from(vec![Some(1.123456f64), None]));
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .unwrap()
+}
+
+/// Create example data with strings with 64-bit offsets (LargeUtf8), e.g. for very large documents
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.77}
+/// returns the data as record batch
+fn create_arrow_example_large_utf8_data() -> RecordBatch {
+    let example_batch: RecordBatch = create_arrow_example_data();
+    let mut fields: Vec<Field> = Vec::with_capacity(example_batch.num_columns());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(example_batch.num_columns());
+    for (field, column) in example_batch
+        .schema()
+        .fields()
+        .iter()
+        .zip(example_batch.columns())
+    {
+        if field.data_type() == &DataType::Utf8 {
+            fields.push(field.as_ref().clone().with_data_type(DataType::LargeUtf8));
+            columns.push(arrow::compute::cast(column, &DataType::LargeUtf8).unwrap());
+        } else {
+            fields.push(field.as_ref().clone());
+            columns.push(column.clone());
+        }
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap()
+}
+
+/// Create example data with scores with half precision (Float16), e.g. to reduce the size of the data if the precision of Float64 is not needed
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456 rounded to Float16}
+/// returns the data as record batch
+fn create_arrow_example_data_f16() -> RecordBatch {
+    let example_batch: RecordBatch = create_arrow_example_data();
+    let schema = example_batch.schema();
+    let score_index: usize = schema.index_of("score").unwrap();
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields[score_index] = fields[score_index]
+        .clone()
+        .with_data_type(DataType::Float16);
+    let mut columns: Vec<ArrayRef> = example_batch.columns().to_vec();
+    columns[score_index] = Arc::new(Float16Array::from_iter_values([f16::from_f64(1.123456f64)]));
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .unwrap()
+}
+
+/// Repeats the first row of a record batch, e.g. to measure the size of larger data
+/// # Arguments
+/// * `batch` - record batch with at least one row
+/// * `num_rows` - number of rows of the result
+///
+/// returns the record batch with num_rows copies of the first row
+fn repeat_rows(batch: &RecordBatch, num_rows: usize) -> RecordBatch {
+    let indices = UInt32Array::from(vec![0; num_rows]);
+    let columns: Vec<ArrayRef> = batch
+        .columns()
+        .iter()
+        .map(|column| arrow::compute::take(column, &indices, None).unwrap())
+        .collect();
+    RecordBatch::try_new(batch.schema(), columns).unwrap()
+}
+
+/// Serializes a record batch
+/// # Arguments
+/// * `batch` - record batch to serialize
+///
+/// returns a binary representation of the record batch in Arrow IPC format
+fn serialize_arrow_batch(batch: &RecordBatch) -> Vec<u8> {
+    let buffer: Vec<u8> = Vec::new();
+
+    let mut stream_writer = StreamWriter::try_new(buffer, &batch.schema()).unwrap();
+    stream_writer.write(batch).unwrap();
+
+    stream_writer.into_inner().unwrap()
+}
+
+/// Create example documents with multiple tags
+/// {id: 1, title: "test", tags: ["rust", "wasm", "arrow"]}, {id: 2, title: "untagged", tags: []}, {id: 3, title: "test2", tags: ["arrow"]}
+/// returns a binary representation of the data in Arrow IPC format
+fn create_arrow_example_tagged_docs_data() -> Vec<u8> {
+    // define schema
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "tags",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            false,
+        ),
+    ]);
+    let ids = UInt64Array::from(vec![1, 2, 3]);
+    let titles = StringArray::from(vec!["test", "untagged", "test2"]);
+    let mut tags_builder = ListBuilder::new(StringBuilder::new());
+    tags_builder.values().append_value("rust");
+    tags_builder.values().append_value("wasm");
+    tags_builder.values().append_value("arrow");
+    tags_builder.append(true);
+    tags_builder.append(true);
+    tags_builder.values().append_value("arrow");
+    tags_builder.append(true);
+    let tags = tags_builder.finish();
+
+    // build a record batch
+    let batch = RecordBatch::try_new(
+        Arc::new(schema.clone()),
+        vec![Arc::new(ids), Arc::new(titles), Arc::new(tags)],
+    )
     .unwrap();
     // serialize it
     let buffer: Vec<u8> = Vec::new();
@@ -538,34 +2445,110 @@ fn create_arrow_example_data() -> Vec<u8> {
     let mut stream_writer = StreamWriter::try_new(buffer, &schema).unwrap();
     stream_writer.write(&batch).unwrap();
 
-    let serialized_batch = stream_writer.into_inner().unwrap();
-    return serialized_batch;
+    stream_writer.into_inner().unwrap()
 }
 
-/// Create example meta-data, ie commands for the module on what to do with the data
-/// A simple commmand structure {command: "test", config: {filename: "test.txt"}}
+/// Create example financial data with exact decimal prices
+/// {id: 1, price: 19.990000, quantity: 3}, {id: 2, price: 0.000001, quantity: 1000000}, {id: 3, price: 1234567.123456, quantity: 2}
 /// returns a binary representation of the data in Arrow IPC format
-fn create_arrow_example_meta_data() -> Vec<u8> {
+fn create_arrow_example_financial_data() -> Vec<u8> {
     // define schema
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("price", DataType::Decimal128(18, 6), false),
+        Field::new("quantity", DataType::Int64, false),
+    ]);
+    let ids = UInt64Array::from(vec![1, 2, 3]);
+    // raw values of the decimals, e.g. 19990000 with scale 6 is 19.990000
+    let prices = Decimal128Array::from(vec![19_990_000, 1, 1_234_567_123_456])
+        .with_precision_and_scale(18, 6)
+        .unwrap();
+    let quantities = Int64Array::from(vec![3, 1_000_000, 2]);
+
+    // build a record batch
+    let batch = RecordBatch::try_new(
+        Arc::new(schema.clone()),
+        vec![Arc::new(ids), Arc::new(prices), Arc::new(quantities)],
+    )
+    .unwrap();
+    // serialize it
+    let buffer: Vec<u8> = Vec::new();
+
+    let mut stream_writer = StreamWriter::try_new(buffer, &schema).unwrap();
+    stream_writer.write(&batch).unwrap();
+
+    stream_writer.into_inner().unwrap()
+}
+
+/// Create example meta-data like create_arrow_example_meta_data, but with the config as Struct flattened to one field per key (config.filename, config.encoding, config.max_length, config.tenant) for applications without support of nested types
+/// # Arguments
+/// * `command` - command for the module, e.g. "test" or "validate"
+///
+/// returns a binary representation of the data in Arrow IPC format
+fn create_arrow_example_meta_data_flattened(command: &str) -> Vec<u8> {
+    let config = StructArray::from(
+        [
+            ("filename", "test.txt"),
+            ("encoding", "utf-8"),
+            ("max_length", "1024"),
+            ("tenant", "example"),
+        ]
+        .into_iter()
+        .map(|(key, value)| {
+            (
+                Arc::new(Field::new(key, DataType::Utf8, false)),
+                Arc::new(StringArray::from(vec![value])) as ArrayRef,
+            )
+        })
+        .collect::<Vec<(Arc<Field>, ArrayRef)>>(),
+    );
     let schema = Schema::new(vec![
         Field::new("command", DataType::Utf8, false),
-        Field::new(
-            "config",
-            DataType::Struct(arrow::datatypes::Fields::from(vec![Field::new(
-                "filename",
-                DataType::Utf8,
-                false,
-            )])),
-            false,
-        ),
+        Field::new("config", config.data_type().clone(), false),
     ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(StringArray::from(vec![command])), Arc::new(config)],
+    )
+    .unwrap();
+    serialize_arrow_batch(&flatten_struct_column(&batch, "config").unwrap())
+}
+
+/// Create example meta-data, ie commands for the module on what to do with the data
+/// A simple commmand structure {command: "test", config: {filename: "test.txt", encoding: "utf-8", max_length: "1024", tenant: "example"}}. The config is a map, so that new keys do not change the schema
+/// # Arguments
+/// * `command` - command for the module, e.g. "test" or "validate"
+///
+/// returns a binary representation of the data in Arrow IPC format
+fn create_arrow_example_meta_data(command: &str) -> Vec<u8> {
     // define one data item
-    let command = StringArray::from(vec!["test"]);
+    let command = StringArray::from(vec![command]);
 
-    let config = StructArray::from(vec![(
-        Arc::new(Field::new("filename", DataType::Utf8, false)),
-        Arc::new(StringArray::from(vec!["test.txt"])) as ArrayRef,
-    )]);
+    let mut config_builder = MapBuilder::new(
+        Some(MapFieldNames {
+            entry: "entry".to_string(),
+            key: "key".to_string(),
+            value: "value".to_string(),
+        }),
+        StringBuilder::new(),
+        StringBuilder::new(),
+    );
+    for (key, value) in [
+        ("filename", "test.txt"),
+        ("encoding", "utf-8"),
+        ("max_length", "1024"),
+        ("tenant", "example"),
+    ] {
+        config_builder.keys().append_value(key);
+        config_builder.values().append_value(value);
+    }
+    config_builder.append(true).unwrap();
+    let config = config_builder.finish();
+    // define schema
+    let schema = Schema::new(vec![
+        Field::new("command", DataType::Utf8, false),
+        Field::new("config", config.data_type().clone(), false),
+    ]);
     // build a record batch
     let batch = RecordBatch::try_new(
         Arc::new(schema.clone()),
@@ -0,0 +1,90 @@
+//! Pipelines of functions of several WASM modules, where the result of one function is the input of the next one
+use std::sync::Arc;
+
+use wasmtime::Engine;
+use wasmtime::Module;
+
+use crate::profiler::ExecutionProfiler;
+use crate::sandbox::SandboxConfig;
+use crate::wrapper_wasm_call_single_buffer;
+
+/// Transformation of the result of a stage before it is handed over to the next stage, e.g. to convert a string into Arrow IPC data
+pub type StageTransform = Arc<dyn Fn(Vec<u8>) -> anyhow::Result<Vec<u8>> + Send>;
+
+/// Stage of a pipeline, ie a function of a WASM module that takes one buffer in the module memory as input and returns a WasmResult
+pub struct PipelineStage {
+    /// module containing the function
+    pub module: Arc<Module>,
+    /// name of the exported function, e.g. wasm_memory_rust_format_hello_world
+    pub function: String,
+    /// optional transformation of the result of the function before it is handed over to the next stage
+    pub transform: Option<StageTransform>,
+}
+
+/// Pipeline running the functions of its stages in sequence. Each stage runs on a new sandboxed instance of its module
+pub struct ModulePipeline {
+    /// stages in the order of execution
+    pub stages: Vec<PipelineStage>,
+    /// profiler to record the calls of the WASM functions
+    profiler: Arc<ExecutionProfiler>,
+    /// sandbox configuration applied to the instances
+    config: SandboxConfig,
+}
+
+impl ModulePipeline {
+    /// Creates a pipeline without stages
+    /// # Arguments
+    /// * `profiler` - profiler to record the calls of the WASM functions
+    /// * `config` - sandbox configuration applied to the instances
+    ///
+    /// returns the pipeline
+    pub fn new(profiler: &Arc<ExecutionProfiler>, config: &SandboxConfig) -> ModulePipeline {
+        ModulePipeline {
+            stages: Vec::new(),
+            profiler: Arc::clone(profiler),
+            config: config.clone(),
+        }
+    }
+
+    /// Appends a stage to the pipeline
+    /// # Arguments
+    /// * `stage` - stage to run after the existing stages
+    ///
+    /// returns the pipeline
+    pub fn with_stage(mut self, stage: PipelineStage) -> ModulePipeline {
+        self.stages.push(stage);
+        self
+    }
+
+    /// Runs the stages in sequence. The input of the first stage is the initial input, the input of each further stage is the (transformed) result of the previous stage
+    /// # Arguments
+    /// * `engine` - engine the modules were compiled with
+    /// * `initial_input` - input of the first stage
+    ///
+    /// returns the (transformed) result of the last stage. Returns an error naming the stage if a function or transformation failed
+    pub fn run(&self, engine: &Engine, initial_input: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        let mut data: Vec<u8> = initial_input;
+        for (index, stage) in self.stages.iter().enumerate() {
+            data = wrapper_wasm_call_single_buffer(
+                engine,
+                &stage.module,
+                &self.profiler,
+                &self.config,
+                &stage.function,
+                &data,
+            )
+            .map_err(|e| {
+                anyhow::format_err!("Stage {index} ({}) of pipeline failed: {e}", stage.function)
+            })?;
+            if let Some(transform) = &stage.transform {
+                data = transform(data).map_err(|e| {
+                    anyhow::format_err!(
+                        "Transformation of the result of stage {index} ({}) failed: {e}",
+                        stage.function
+                    )
+                })?;
+            }
+        }
+        Ok(data)
+    }
+}
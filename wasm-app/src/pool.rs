@@ -0,0 +1,218 @@
+//! Pool of instances of a WASM module that can be reused across calls
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use wasmtime::Engine;
+use wasmtime::Instance;
+use wasmtime::Module;
+use wasmtime::Store;
+
+use crate::profiler::ExecutionProfiler;
+use crate::sandbox::{create_sandboxed_instance, reset_call_limits, SandboxConfig};
+use crate::{wrapper_wasm_health_check, wrapper_wasm_set_tenant_id, wrapper_wasm_warmup, MyState};
+
+/// Interval in which idle instances are checked via the health check of the module
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Typical size of the data handed over to a module, used to warm up new instances
+pub const WARMUP_INPUT_SIZE: u32 = 64 * 1024;
+
+/// Typical size of the result data of a module, used to warm up new instances
+pub const WARMUP_OUTPUT_SIZE: u32 = 64 * 1024;
+
+/// Tenant of requests for which no tenant is given
+pub const DEFAULT_TENANT_ID: u32 = 0;
+
+/// Instance of a WASM module with its own store
+pub struct PooledInstance {
+    pub store: Store<MyState>,
+    pub instance: Instance,
+    /// time of the last successful health check (or the instantiation)
+    last_health_check: Instant,
+}
+
+/// Pool of instances of a WASM module. Instances are acquired for a call and released afterwards, so that they do not need to be instantiated for every call
+pub struct InstancePool {
+    engine: Engine,
+    module: Module,
+    profiler: Arc<ExecutionProfiler>,
+    config: SandboxConfig,
+    idle: Vec<PooledInstance>,
+}
+
+impl InstancePool {
+    /// Creates a pool with instances of a module
+    /// # Arguments
+    /// * `engine` - wasmtime engine to use for the stores
+    /// * `module` - module to instantiate
+    /// * `profiler` - profiler to record calls of the WASM functions
+    /// * `config` - sandbox configuration applied to all instances
+    /// * `size` - number of instances to create
+    ///
+    /// returns the pool
+    pub fn new(
+        engine: &Engine,
+        module: &Module,
+        profiler: &Arc<ExecutionProfiler>,
+        config: &SandboxConfig,
+        size: usize,
+    ) -> anyhow::Result<InstancePool> {
+        let mut pool = InstancePool {
+            engine: engine.clone(),
+            module: module.clone(),
+            profiler: Arc::clone(profiler),
+            config: config.clone(),
+            idle: Vec::with_capacity(size),
+        };
+        for _ in 0..size {
+            let pooled_instance: PooledInstance = pool.instantiate()?;
+            pool.idle.push(pooled_instance);
+        }
+        Ok(pool)
+    }
+
+    /// Acquires an idle instance or creates a new one if no instance is idle for the default tenant
+    ///
+    /// returns the instance with the fuel and deadline of the sandbox configuration. It should be released after use
+    pub fn acquire(&mut self) -> anyhow::Result<PooledInstance> {
+        self.acquire_for_tenant(DEFAULT_TENANT_ID)
+    }
+
+    /// Acquires an idle instance or creates a new one if no instance is idle for a request of a tenant. Memory allocated in the module for one tenant cannot be validated or deallocated by another tenant
+    /// # Arguments
+    /// * `tenant_id` - ID of the tenant of the request
+    ///
+    /// returns the instance with the fuel and deadline of the sandbox configuration and the tenant set, if the module supports tenants. It should be released after use
+    pub fn acquire_for_tenant(&mut self, tenant_id: u32) -> anyhow::Result<PooledInstance> {
+        let mut pooled_instance: PooledInstance = match self.idle.pop() {
+            Some(mut pooled_instance) => {
+                // fuel and deadline apply per call, not per instance
+                reset_call_limits(&mut pooled_instance.store, &self.config)?;
+                pooled_instance
+            }
+            None => self.instantiate()?,
+        };
+        // the tenant applies per request, not per instance
+        if pooled_instance
+            .instance
+            .get_func(&mut pooled_instance.store, "wasm_set_tenant_id")
+            .is_some()
+        {
+            wrapper_wasm_set_tenant_id(
+                pooled_instance.instance,
+                &mut pooled_instance.store,
+                tenant_id,
+            )?;
+        }
+        Ok(pooled_instance)
+    }
+
+    /// Releases an instance, so that it can be acquired again
+    /// # Arguments
+    /// * `pooled_instance` - instance acquired from the pool
+    pub fn release(&mut self, pooled_instance: PooledInstance) {
+        self.idle.push(pooled_instance);
+    }
+
+    /// Replaces an acquired instance that crashed (e.g. trapped) with a new instance. The state of a crashed instance is undefined, so it must not be released to the pool
+    /// # Arguments
+    /// * `crashed_instance` - instance acquired from the pool that crashed
+    ///
+    /// returns the new instance. It should be released after use, so that the pool keeps its capacity
+    pub fn replace(&mut self, crashed_instance: PooledInstance) -> anyhow::Result<PooledInstance> {
+        drop(crashed_instance);
+        self.instantiate()
+    }
+
+    /// Number of idle instances
+    ///
+    /// returns the number of idle instances
+    pub fn idle_count(&self) -> usize {
+        self.idle.len()
+    }
+
+    /// Runs the health check of the module on idle instances that have not been checked within the interval and replaces unhealthy instances with new ones
+    /// # Arguments
+    /// * `interval` - instances checked within this interval are skipped, e.g. HEALTH_CHECK_INTERVAL
+    ///
+    /// returns the number of replaced instances
+    pub fn check_idle_instances(&mut self, interval: Duration) -> anyhow::Result<usize> {
+        let mut replaced: usize = 0;
+        for i in 0..self.idle.len() {
+            let pooled_instance: &mut PooledInstance = &mut self.idle[i];
+            if pooled_instance.last_health_check.elapsed() < interval {
+                continue;
+            }
+            let health_check_code: anyhow::Result<i32> =
+                wrapper_wasm_health_check(pooled_instance.instance, &mut pooled_instance.store);
+            match health_check_code {
+                Ok(0) => pooled_instance.last_health_check = Instant::now(),
+                health_check_code => {
+                    tracing::warn!(
+                        "Replacing instance of WASM module that failed the health check: {:?}",
+                        health_check_code
+                    );
+                    self.idle[i] = self.instantiate()?;
+                    replaced += 1;
+                }
+            }
+        }
+        Ok(replaced)
+    }
+
+    /// Creates a new instance of the module with its own store. The instance is warmed up before it is made available
+    ///
+    /// returns the instance
+    fn instantiate(&self) -> anyhow::Result<PooledInstance> {
+        let (instance, mut store) =
+            create_sandboxed_instance(&self.engine, &self.module, &self.profiler, &self.config)?;
+        warm_up_instance(instance, &mut store, &self.config)?;
+        Ok(PooledInstance {
+            store,
+            instance,
+            last_health_check: Instant::now(),
+        })
+    }
+}
+
+/// Warms up a new instance via the function wasm_warmup of the module with WARMUP_INPUT_SIZE and WARMUP_OUTPUT_SIZE. Modules that do not export wasm_warmup are not warmed up
+/// # Arguments
+/// * `instance` - new instance of the module
+/// * `store` - store of the instance. Its fuel and deadline are reset afterwards, so that the warm-up does not count towards the first call
+/// * `config` - sandbox configuration applied to the instance
+///
+/// returns an error if the warm-up failed
+pub fn warm_up_instance(
+    instance: Instance,
+    store: &mut Store<MyState>,
+    config: &SandboxConfig,
+) -> anyhow::Result<()> {
+    if instance.get_func(&mut *store, "wasm_warmup").is_none() {
+        return Ok(());
+    }
+    let warmup_code: i32 =
+        wrapper_wasm_warmup(instance, &mut *store, WARMUP_INPUT_SIZE, WARMUP_OUTPUT_SIZE)?;
+    if warmup_code != 0 {
+        anyhow::bail!("Warm-up of instance of WASM module failed with code {warmup_code}");
+    }
+    reset_call_limits(store, config)
+}
+
+/// Starts a thread that checks the idle instances of a pool every HEALTH_CHECK_INTERVAL and replaces unhealthy ones
+/// # Arguments
+/// * `pool` - pool to check
+///
+/// returns the handle of the thread
+pub fn spawn_health_checks(pool: Arc<Mutex<InstancePool>>) -> JoinHandle<()> {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(HEALTH_CHECK_INTERVAL);
+        if let Err(e) = pool
+            .lock()
+            .unwrap()
+            .check_idle_instances(HEALTH_CHECK_INTERVAL)
+        {
+            tracing::error!("Health check of idle instances failed: {e}");
+        }
+    })
+}
@@ -0,0 +1,163 @@
+//! Profiling of calls to functions of WASM modules
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use arrow::array::{Float64Array, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use arrow::util::pretty::print_batches;
+
+/// Record of one call to a function of a WASM module
+pub struct CallRecord {
+    /// name of the function of the WASM module
+    pub function: String,
+    /// time the call started
+    pub start: Instant,
+    /// duration of the call including the exchange of data via the WASM module memory
+    pub duration: Duration,
+    /// size of the data handed over to the function
+    pub input_bytes: usize,
+    /// size of the data returned by the function
+    pub output_bytes: usize,
+    /// true if the function returned successfully
+    pub success: bool,
+}
+
+/// Collects records of calls to functions of WASM modules, e.g. to compare their latency. It can be shared between stores (see MyState)
+#[derive(Default)]
+pub struct ExecutionProfiler {
+    pub records: Mutex<Vec<CallRecord>>,
+}
+
+impl ExecutionProfiler {
+    /// Records a call to a function of a WASM module that started at `start` and ends now
+    /// # Arguments
+    /// * `function` - name of the function of the WASM module
+    /// * `start` - time the call started
+    /// * `input_bytes` - size of the data handed over to the function
+    /// * `output_bytes` - size of the data returned by the function
+    /// * `success` - true if the function returned successfully
+    pub fn record(
+        &self,
+        function: &str,
+        start: Instant,
+        input_bytes: usize,
+        output_bytes: usize,
+        success: bool,
+    ) {
+        let call_record = CallRecord {
+            function: function.to_string(),
+            start,
+            duration: start.elapsed(),
+            input_bytes,
+            output_bytes,
+            success,
+        };
+        self.records.lock().unwrap().push(call_record);
+    }
+
+    /// Summarizes the records per function (in order of the first call)
+    ///
+    /// returns a record batch with the schema {function: Utf8, calls: UInt64, failures: UInt64, p50_ms: Float64, p95_ms: Float64, p99_ms: Float64, throughput_mb_s: Float64, calls_per_s: Float64}. The throughput is based on the input and output bytes of all calls of a function, the calls per second on the time from the start of the first to the end of the last call
+    pub fn to_arrow_batch(&self) -> RecordBatch {
+        let records = self.records.lock().unwrap();
+        // group by function, keeping the order of the first call
+        let mut functions: Vec<&str> = Vec::new();
+        for call_record in records.iter() {
+            if !functions.contains(&call_record.function.as_str()) {
+                functions.push(&call_record.function);
+            }
+        }
+        let mut calls: Vec<u64> = Vec::new();
+        let mut failures: Vec<u64> = Vec::new();
+        let mut p50s: Vec<f64> = Vec::new();
+        let mut p95s: Vec<f64> = Vec::new();
+        let mut p99s: Vec<f64> = Vec::new();
+        let mut throughputs: Vec<f64> = Vec::new();
+        let mut calls_per_s: Vec<f64> = Vec::new();
+        for function in functions.iter() {
+            let function_records: Vec<&CallRecord> = records
+                .iter()
+                .filter(|call_record| call_record.function == *function)
+                .collect();
+            let mut durations: Vec<Duration> = function_records
+                .iter()
+                .map(|call_record| call_record.duration)
+                .collect();
+            durations.sort();
+            let total_duration: Duration = durations.iter().sum();
+            let total_bytes: usize = function_records
+                .iter()
+                .map(|call_record| call_record.input_bytes + call_record.output_bytes)
+                .sum();
+            calls.push(function_records.len() as u64);
+            failures.push(
+                function_records
+                    .iter()
+                    .filter(|call_record| !call_record.success)
+                    .count() as u64,
+            );
+            p50s.push(percentile_ms(&durations, 50.0));
+            p95s.push(percentile_ms(&durations, 95.0));
+            p99s.push(percentile_ms(&durations, 99.0));
+            throughputs.push(total_bytes as f64 / 1_000_000.0 / total_duration.as_secs_f64());
+            // window from the start of the first call to the end of the last call
+            let first_start: Instant = function_records
+                .iter()
+                .map(|call_record| call_record.start)
+                .min()
+                .unwrap();
+            let last_end: Instant = function_records
+                .iter()
+                .map(|call_record| call_record.start + call_record.duration)
+                .max()
+                .unwrap();
+            calls_per_s
+                .push(function_records.len() as f64 / (last_end - first_start).as_secs_f64());
+        }
+        // define schema
+        let schema = Schema::new(vec![
+            Field::new("function", DataType::Utf8, false),
+            Field::new("calls", DataType::UInt64, false),
+            Field::new("failures", DataType::UInt64, false),
+            Field::new("p50_ms", DataType::Float64, false),
+            Field::new("p95_ms", DataType::Float64, false),
+            Field::new("p99_ms", DataType::Float64, false),
+            Field::new("throughput_mb_s", DataType::Float64, false),
+            Field::new("calls_per_s", DataType::Float64, false),
+        ]);
+        // build a record batch
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(StringArray::from(functions)),
+                Arc::new(UInt64Array::from(calls)),
+                Arc::new(UInt64Array::from(failures)),
+                Arc::new(Float64Array::from(p50s)),
+                Arc::new(Float64Array::from(p95s)),
+                Arc::new(Float64Array::from(p99s)),
+                Arc::new(Float64Array::from(throughputs)),
+                Arc::new(Float64Array::from(calls_per_s)),
+            ],
+        )
+        .unwrap()
+    }
+
+    /// Prints the latency (p50/p95/p99), throughput and calls per second per function
+    pub fn print_summary(&self) {
+        println!("Execution profile of WASM functions");
+        print_batches(&[self.to_arrow_batch()]).unwrap();
+    }
+}
+
+/// Determines a percentile of durations using the nearest-rank method
+/// # Arguments
+/// * `sorted_durations` - durations sorted ascending. It must not be empty
+/// * `percentile` - percentile to determine, e.g. 95.0
+///
+/// returns the percentile in milliseconds
+fn percentile_ms(sorted_durations: &[Duration], percentile: f64) -> f64 {
+    let rank: usize = (percentile / 100.0 * sorted_durations.len() as f64).ceil() as usize;
+    sorted_durations[rank.clamp(1, sorted_durations.len()) - 1].as_secs_f64() * 1000.0
+}
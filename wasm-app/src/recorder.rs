@@ -0,0 +1,174 @@
+//! Recording of the inputs of calls to functions of WASM modules and their replay, e.g. to reproduce an issue of production with the exact same data
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use serde::{Deserialize, Serialize};
+use wasmtime::Engine;
+use wasmtime::Module;
+
+use crate::profiler::ExecutionProfiler;
+use crate::sandbox::{create_sandboxed_instance, SandboxConfig};
+use crate::{call_wasm_process_data_arrow_serialized, wrapper_wasm_call_single_buffer};
+
+/// Environment variable that enables the recording of calls if it is set to 1
+const RECORD_ENV: &str = "WASM_RECORD";
+
+/// Environment variable with the path of the file of the recording
+const RECORD_PATH_ENV: &str = "WASM_RECORD_PATH";
+
+/// Path of the file of the recording if WASM_RECORD_PATH is not set
+const DEFAULT_RECORD_PATH: &str = "wasm_recording.jsonl";
+
+// Global variable with the recorder of the application. It is created on first use and is None if the recording is not enabled
+static RECORDER: OnceLock<Option<CallRecorder>> = OnceLock::new();
+
+/// Inputs of one call to a function of a WASM module
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedCall {
+    /// name of the function of the WASM module
+    pub function: String,
+    /// meta data in Arrow IPC format. It is empty for functions with only one input, e.g. wasm_memory_process_tagged_docs_arrow
+    pub meta_ipc: Vec<u8>,
+    /// data in Arrow IPC format
+    pub data_ipc: Vec<u8>,
+    /// time of the call in milliseconds since the UNIX epoch
+    pub timestamp: u64,
+}
+
+/// Records the inputs of calls to functions of WASM modules. Each call is appended to the file of the recording as one line of JSON, so that the calls before a crash of the application are not lost
+pub struct CallRecorder {
+    /// calls recorded so far
+    pub calls: Mutex<Vec<RecordedCall>>,
+    /// path of the file of the recording
+    pub output_path: PathBuf,
+}
+
+impl CallRecorder {
+    /// Creates a recorder with an empty recording
+    /// # Arguments
+    /// * `output_path` - path of the file of the recording. An existing file is overwritten
+    ///
+    /// returns the recorder
+    pub fn new(output_path: &Path) -> anyhow::Result<CallRecorder> {
+        File::create(output_path)?;
+        Ok(CallRecorder {
+            calls: Mutex::new(Vec::new()),
+            output_path: output_path.to_path_buf(),
+        })
+    }
+
+    /// Creates a recorder if the recording is enabled by the environment variable WASM_RECORD=1. The recording is written to the path in WASM_RECORD_PATH (default: wasm_recording.jsonl)
+    ///
+    /// returns the recorder. It is None if the recording is not enabled
+    pub fn from_env() -> anyhow::Result<Option<CallRecorder>> {
+        if std::env::var(RECORD_ENV).as_deref() != Ok("1") {
+            return Ok(None);
+        }
+        let output_path: PathBuf = std::env::var_os(RECORD_PATH_ENV)
+            .map(PathBuf::from)
+            .unwrap_or(PathBuf::from(DEFAULT_RECORD_PATH));
+        Ok(Some(CallRecorder::new(&output_path)?))
+    }
+
+    /// Records a call and appends it to the file of the recording
+    /// # Arguments
+    /// * `function` - name of the function of the WASM module
+    /// * `meta_ipc` - meta data in Arrow IPC format. Empty for functions with only one input
+    /// * `data_ipc` - data in Arrow IPC format
+    pub fn record(&self, function: &str, meta_ipc: &[u8], data_ipc: &[u8]) -> anyhow::Result<()> {
+        let timestamp: u64 = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+        let call = RecordedCall {
+            function: function.to_string(),
+            meta_ipc: meta_ipc.to_vec(),
+            data_ipc: data_ipc.to_vec(),
+            timestamp,
+        };
+        // the lock also keeps the lines of concurrent calls apart
+        let mut calls = self.calls.lock().unwrap();
+        let mut file: File = OpenOptions::new().append(true).open(&self.output_path)?;
+        let mut line: Vec<u8> = serde_json::to_vec(&call)?;
+        line.push(b'\n');
+        file.write_all(&line)?;
+        calls.push(call);
+        Ok(())
+    }
+}
+
+/// Returns the recorder of the application, see CallRecorder::from_env
+///
+/// returns the recorder. It is None if the recording is not enabled or the file of the recording cannot be created
+pub fn global_recorder() -> Option<&'static CallRecorder> {
+    RECORDER
+        .get_or_init(|| {
+            CallRecorder::from_env().unwrap_or_else(|e| {
+                tracing::warn!("Could not create the recording of calls: {e}");
+                None
+            })
+        })
+        .as_ref()
+}
+
+/// Reads the calls of a recording
+/// # Arguments
+/// * `path` - path of the file of the recording
+///
+/// returns the calls in the order they have been recorded
+pub fn read_recording(path: &Path) -> anyhow::Result<Vec<RecordedCall>> {
+    let mut calls: Vec<RecordedCall> = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line: String = line?;
+        if !line.is_empty() {
+            calls.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(calls)
+}
+
+/// Replays the calls of a recording, each in a new instance of the module. Settings of the recorded instances (e.g. wasm_set_dry_run) are not part of the recording, only the sandbox configuration applies
+/// # Arguments
+/// * `engine` - wasmtime engine to use for the stores
+/// * `module` - module containing the recorded functions
+/// * `profiler` - profiler to record the calls of the WASM functions
+/// * `config` - sandbox configuration applied to the instances
+/// * `path` - path of the file of the recording
+///
+/// returns the results of all calls in the order of the recording
+pub fn replay_recording(
+    engine: &Engine,
+    module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+    path: &Path,
+) -> anyhow::Result<Vec<RecordBatch>> {
+    let mut result_batches: Vec<RecordBatch> = Vec::new();
+    for call in read_recording(path)? {
+        let result_arrow_ipc: Vec<u8> = if call.meta_ipc.is_empty() {
+            wrapper_wasm_call_single_buffer(
+                engine,
+                module,
+                profiler,
+                config,
+                &call.function,
+                &call.data_ipc,
+            )?
+        } else {
+            let (instance, mut store) =
+                create_sandboxed_instance(engine, module, profiler, config)?;
+            call_wasm_process_data_arrow_serialized(
+                instance,
+                &mut store,
+                &call.meta_ipc,
+                &call.data_ipc,
+            )?
+        };
+        for batch in StreamReader::try_new(result_arrow_ipc.as_slice(), None)? {
+            result_batches.push(batch?);
+        }
+    }
+    Ok(result_batches)
+}
@@ -0,0 +1,114 @@
+//! Calls of WASM functions on pooled instances that recover from crashes (traps) of the module, e.g. a panic due to a failed unwrap
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use wasmtime::Instance;
+use wasmtime::Memory;
+use wasmtime::Store;
+use wasmtime::Trap;
+use wasmtime::WasmBacktrace;
+
+use crate::pool::{InstancePool, PooledInstance};
+use crate::{wrapper_wasm_last_error, MyState};
+
+/// Error returned by the SafeModuleRunner if the module crashed
+#[derive(Debug)]
+pub enum WasmError {
+    /// the module panicked (trapped) during the call, also on the retry if retried is true
+    ModulePanicked { message: String, retried: bool },
+}
+
+impl fmt::Display for WasmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WasmError::ModulePanicked { message, retried } => write!(
+                f,
+                "WASM module panicked{}: {message}",
+                if *retried { " (also on retry)" } else { "" }
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WasmError {}
+
+/// Runs calls on instances of a pool. Instances that crash are replaced by new instances, so that a single bad input does not reduce the capacity of the pool
+pub struct SafeModuleRunner {
+    pool: Arc<Mutex<InstancePool>>,
+    /// if true, a call that crashed is retried once on the new instance
+    retry: bool,
+}
+
+impl SafeModuleRunner {
+    /// Creates a runner for the instances of a pool
+    /// # Arguments
+    /// * `pool` - pool to acquire the instances from
+    /// * `retry` - if true, a call that crashed is retried once on the new instance
+    ///
+    /// returns the runner
+    pub fn new(pool: Arc<Mutex<InstancePool>>, retry: bool) -> SafeModuleRunner {
+        SafeModuleRunner { pool, retry }
+    }
+
+    /// Runs a call on an instance of the pool. If the module traps, the instance is replaced by a new instance and the call is retried once if configured
+    /// # Arguments
+    /// * `call` - call of one or more functions of the instance, e.g. call_wasm_process_data_arrow
+    ///
+    /// returns the result of the call. Returns WasmError::ModulePanicked if the module trapped (on the retry)
+    pub fn call<T>(
+        &self,
+        call: impl Fn(Instance, &mut Store<MyState>) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let mut pooled_instance: PooledInstance = self.pool.lock().unwrap().acquire()?;
+        let mut retried: bool = false;
+        loop {
+            let result: anyhow::Result<T> =
+                call(pooled_instance.instance, &mut pooled_instance.store);
+            let (trap, backtrace): (&Trap, Option<&WasmBacktrace>) = match &result {
+                Err(e) if e.downcast_ref::<Trap>().is_some() => (
+                    e.downcast_ref::<Trap>().unwrap(),
+                    e.downcast_ref::<WasmBacktrace>(),
+                ),
+                _ => {
+                    self.pool.lock().unwrap().release(pooled_instance);
+                    return result;
+                }
+            };
+            // a panic traps via an unreachable instruction, the panic hook of the module stores the panic message as last error before
+            let message: String = match trap {
+                Trap::UnreachableCodeReached => {
+                    match panic_message(pooled_instance.instance, &mut pooled_instance.store) {
+                        Some(panic_message) => format!("{trap}: {panic_message}"),
+                        None => trap.to_string(),
+                    }
+                }
+                _ => trap.to_string(),
+            };
+            match backtrace {
+                Some(backtrace) => tracing::error!("WASM module trapped: {message}\n{backtrace}"),
+                None => tracing::error!("WASM module trapped: {message}"),
+            }
+            pooled_instance = self.pool.lock().unwrap().replace(pooled_instance)?;
+            if retried || !self.retry {
+                self.pool.lock().unwrap().release(pooled_instance);
+                return Err(WasmError::ModulePanicked { message, retried }.into());
+            }
+            tracing::warn!("Retrying call that trapped on a new instance of the WASM module");
+            retried = true;
+        }
+    }
+}
+
+/// Fetches the panic message of an instance that trapped. Modules that export wasm_init store it as last error before the trap
+/// # Arguments
+/// * `instance` - instance that trapped
+/// * `store` - store of the instance
+///
+/// returns the panic message. It is None if the module did not store it or it cannot be fetched from the crashed instance
+fn panic_message(instance: Instance, store: &mut Store<MyState>) -> Option<String> {
+    instance.get_func(&mut *store, "wasm_init")?;
+    let memory: Memory = instance.get_memory(&mut *store, "memory")?;
+    wrapper_wasm_last_error(instance, store, &memory)
+        .ok()
+        .flatten()
+}
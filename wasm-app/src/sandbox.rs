@@ -0,0 +1,181 @@
+//! Security policy (sandbox) applied to all instances of WASM modules
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+use wasi_common::sync::WasiCtxBuilder;
+use wasi_common::sync::{ambient_authority, Dir};
+use wasmtime::Config;
+use wasmtime::Engine;
+use wasmtime::Instance;
+use wasmtime::Linker;
+use wasmtime::Module;
+use wasmtime::Store;
+use wasmtime::StoreLimitsBuilder;
+
+use crate::profiler::ExecutionProfiler;
+use crate::{
+    add_host_functions_to_linker, wrapper_wasm_config_set, wrapper_wasm_init,
+    wrapper_wasm_set_max_output_bytes, MyState,
+};
+
+/// Size of a page of WASM memory in bytes
+const WASM_PAGE_SIZE: usize = 65536;
+
+/// Interval in which the epoch of the engine is incremented to enforce timeouts
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// Restrictions applied to instances of WASM modules
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct SandboxConfig {
+    /// maximum number of pages (64 KiB) of memory of an instance
+    pub max_memory_pages: u32,
+    /// fuel (roughly number of WASM instructions) an instance may consume per call. No limit if None
+    pub fuel_budget: Option<u64>,
+    /// time in milliseconds after which a call is interrupted. No limit if None
+    pub timeout_ms: Option<u64>,
+    /// if false, the module has no access to the filesystem, even if preopened_dirs are configured
+    pub allow_filesystem: bool,
+    /// directories the module can access as (path in the module, path on the host), e.g. (".", "../../test-data")
+    pub preopened_dirs: Vec<(String, PathBuf)>,
+    /// if false, the module has no access to the network. Note: the application currently hands over no sockets to modules, so they have no network access in any case
+    pub allow_network: bool,
+    /// configuration (key, value) set via wasm_config_set after the instantiation, e.g. ("score_threshold", "0.5"). It is ignored for modules that do not export wasm_config_set
+    pub module_config: BTreeMap<String, String>,
+    /// maximum size in bytes of a result of a module, so that the application does not run out of memory reading it. No limit if None. It is ignored for modules that do not export wasm_set_max_output_bytes
+    pub max_output_bytes: Option<u32>,
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        SandboxConfig {
+            max_memory_pages: 16384,
+            fuel_budget: None,
+            timeout_ms: None,
+            allow_filesystem: false,
+            preopened_dirs: Vec::new(),
+            allow_network: false,
+            module_config: BTreeMap::new(),
+            max_output_bytes: None,
+        }
+    }
+}
+
+impl SandboxConfig {
+    /// Loads the configuration from a TOML file
+    /// # Arguments
+    /// * `path` - path to the file, e.g. sandbox.toml. Missing keys are set to their default
+    ///
+    /// returns the configuration
+    pub fn from_file(path: &Path) -> anyhow::Result<SandboxConfig> {
+        let content: String = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&content)?)
+    }
+}
+
+/// Creates an engine that supports the restrictions of the configuration (fuel and timeouts)
+/// # Arguments
+/// * `config` - sandbox configuration used for all instances created with the engine
+///
+/// returns the engine
+pub fn create_engine(config: &SandboxConfig) -> anyhow::Result<Engine> {
+    let mut engine_config = Config::new();
+    engine_config.consume_fuel(config.fuel_budget.is_some());
+    engine_config.epoch_interruption(config.timeout_ms.is_some());
+    let engine = Engine::new(&engine_config)?;
+    if config.timeout_ms.is_some() {
+        // the epoch is incremented regularly, calls are interrupted once their deadline (in epochs) is reached
+        let ticker_engine: Engine = engine.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(EPOCH_TICK);
+            ticker_engine.increment_epoch();
+        });
+    }
+    Ok(engine)
+}
+
+/// Creates an instance of a module with its own store and applies all restrictions of the configuration
+/// # Arguments
+/// * `engine` - engine created with create_engine for the same configuration
+/// * `module` - module to instantiate
+/// * `profiler` - profiler to record the calls of the WASM functions
+/// * `config` - sandbox configuration
+///
+/// returns the instance and its store
+pub fn create_sandboxed_instance(
+    engine: &Engine,
+    module: &Module,
+    profiler: &Arc<ExecutionProfiler>,
+    config: &SandboxConfig,
+) -> anyhow::Result<(Instance, Store<MyState>)> {
+    let mut linker = Linker::new(engine);
+    wasi_common::sync::add_to_linker(&mut linker, |state: &mut MyState| &mut state.wasi)?;
+    add_host_functions_to_linker(&mut linker)?;
+    // store to exchange data with the WASM module
+    let mut wasi_builder = WasiCtxBuilder::new();
+    wasi_builder.inherit_stdio().inherit_args()?;
+    if config.allow_filesystem {
+        for (guest_path, host_path) in &config.preopened_dirs {
+            let dir: Dir = Dir::open_ambient_dir(host_path, ambient_authority())?;
+            wasi_builder.preopened_dir(dir, guest_path)?;
+        }
+    }
+    let wasi = wasi_builder.build();
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(config.max_memory_pages as usize * WASM_PAGE_SIZE)
+        .build();
+    let mut store = Store::new(
+        engine,
+        MyState {
+            wasi,
+            profiler: Arc::clone(profiler),
+            limits,
+            kv_store: HashMap::new(),
+        },
+    );
+    store.limiter(|state: &mut MyState| &mut state.limits);
+    reset_call_limits(&mut store, config)?;
+    let instance: Instance = linker.instantiate(&mut store, module)?;
+    // modules that export wasm_init provide the panic message of a trap via wasm_last_error
+    if instance.get_func(&mut store, "wasm_init").is_some() {
+        wrapper_wasm_init(instance, &mut store)?;
+        reset_call_limits(&mut store, config)?;
+    }
+    // the configuration of the deployment is set once, before the first call
+    if !config.module_config.is_empty()
+        && instance.get_func(&mut store, "wasm_config_set").is_some()
+    {
+        for (key, value) in &config.module_config {
+            wrapper_wasm_config_set(instance, &mut store, key, value)?;
+        }
+        reset_call_limits(&mut store, config)?;
+    }
+    if let Some(max_output_bytes) = config.max_output_bytes {
+        if instance
+            .get_func(&mut store, "wasm_set_max_output_bytes")
+            .is_some()
+        {
+            wrapper_wasm_set_max_output_bytes(instance, &mut store, max_output_bytes)?;
+            reset_call_limits(&mut store, config)?;
+        }
+    }
+    Ok((instance, store))
+}
+
+/// Resets the fuel and the deadline of a store, e.g. before an instance is reused for another call
+/// # Arguments
+/// * `store` - store of the instance
+/// * `config` - sandbox configuration
+pub fn reset_call_limits(store: &mut Store<MyState>, config: &SandboxConfig) -> anyhow::Result<()> {
+    if let Some(fuel_budget) = config.fuel_budget {
+        store.set_fuel(fuel_budget)?;
+    }
+    if let Some(timeout_ms) = config.timeout_ms {
+        let epoch_tick_ms: u64 = EPOCH_TICK.as_millis() as u64;
+        store.set_epoch_deadline(timeout_ms.div_ceil(epoch_tick_ms).max(1));
+    }
+    Ok(())
+}
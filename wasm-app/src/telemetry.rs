@@ -0,0 +1,79 @@
+//! Telemetry of an instance of a WASM module (memory, calls and cache) fetched via wasm_get_telemetry, e.g. for monitoring
+use std::fmt;
+
+/// Size of the telemetry of version 1 in bytes
+pub const TELEMETRY_SIZE: usize = 64;
+
+/// Telemetry of an instance of a WASM module, see wasm_get_telemetry of wasm-module2 for the layout
+#[derive(Debug, Clone, Copy)]
+pub struct ModuleTelemetry {
+    /// version of the layout
+    pub version: u32,
+    /// memory areas currently allocated
+    pub live_allocs: u32,
+    /// total size of the memory areas currently allocated in bytes
+    pub live_bytes: u64,
+    /// largest total size of the allocated memory areas since the instantiation in bytes
+    pub peak_bytes: u64,
+    /// calls of wasm_memory_process_data_arrow
+    pub total_calls: u64,
+    /// calls of wasm_memory_process_data_arrow that returned a non-zero status
+    pub failed_calls: u64,
+    /// total duration of the calls of wasm_memory_process_data_arrow in nanoseconds
+    pub total_processing_ns: u64,
+    /// calls answered from the cache of the module
+    pub cache_hits: u64,
+    /// calls not answered from the cache of the module
+    pub cache_misses: u64,
+}
+
+impl ModuleTelemetry {
+    /// Reads the telemetry from the bytes returned by wasm_get_telemetry
+    /// # Arguments
+    /// * `bytes` - telemetry in little endian
+    ///
+    /// returns the telemetry. Returns an error if the bytes are too short or the version is not supported
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<ModuleTelemetry> {
+        anyhow::ensure!(
+            bytes.len() >= TELEMETRY_SIZE,
+            "Telemetry has {} bytes, expected at least {TELEMETRY_SIZE} bytes",
+            bytes.len()
+        );
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+        let version: u32 = u32_at(0);
+        // later versions only add fields at the end
+        anyhow::ensure!(version >= 1, "Telemetry version {version} is not supported");
+        Ok(ModuleTelemetry {
+            version,
+            live_allocs: u32_at(4),
+            live_bytes: u64_at(8),
+            peak_bytes: u64_at(16),
+            total_calls: u64_at(24),
+            failed_calls: u64_at(32),
+            total_processing_ns: u64_at(40),
+            cache_hits: u64_at(48),
+            cache_misses: u64_at(56),
+        })
+    }
+}
+
+impl fmt::Display for ModuleTelemetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "version {}: {} memory areas allocated ({} bytes, peak {} bytes), {} calls ({} failed, {} ns), cache {} hits / {} misses",
+            self.version,
+            self.live_allocs,
+            self.live_bytes,
+            self.peak_bytes,
+            self.total_calls,
+            self.failed_calls,
+            self.total_processing_ns,
+            self.cache_hits,
+            self.cache_misses
+        )
+    }
+}
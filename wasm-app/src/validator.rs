@@ -0,0 +1,254 @@
+//! Validation of the data before and of the result after calls of wasm_memory_process_data_arrow on the host, so that invalid data is reported with the reason instead of an opaque trap of the module
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::datatypes::{DataType, Field, Float64Type, SchemaRef};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use wasmtime::Engine;
+use wasmtime::Module;
+
+use crate::call_wasm_process_data_arrow_ipc;
+use crate::profiler::ExecutionProfiler;
+use crate::sandbox::{create_sandboxed_instance, SandboxConfig};
+
+/// Stage of a call in which the validation failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationStage {
+    /// the data handed over to the module
+    Pre,
+    /// the result returned by the module
+    Post,
+}
+
+/// Error returned by the ValidatedArrowCaller if the data or the result is not valid
+#[derive(Debug)]
+pub struct ValidationError {
+    /// stage in which the validation failed
+    pub stage: ValidationStage,
+    /// name of the field that is not valid. It is empty if the record batch as a whole is not valid, e.g. its number of rows
+    pub field: String,
+    /// description why the field is not valid
+    pub reason: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stage: &str = match self.stage {
+            ValidationStage::Pre => "data",
+            ValidationStage::Post => "result",
+        };
+        if self.field.is_empty() {
+            write!(f, "Validation of the {stage} failed: {}", self.reason)
+        } else {
+            write!(
+                f,
+                "Validation of the {stage} failed for field '{}': {}",
+                self.field, self.reason
+            )
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Calls wasm_memory_process_data_arrow with the command "test" only with valid data and checks its result
+pub struct ValidatedArrowCaller {
+    /// schema the data must have. Fields that are not nullable must not contain null values
+    input_schema: SchemaRef,
+    /// schema the result must have. The meta data of the schema is not checked
+    output_schema: SchemaRef,
+    /// allowed number of rows of the data
+    row_range: RangeInclusive<usize>,
+    /// plausible values of numeric fields of the data as (name of the field, range)
+    numeric_ranges: Vec<(String, RangeInclusive<f64>)>,
+}
+
+impl ValidatedArrowCaller {
+    /// Creates a caller that validates the data and the result against the expected schemas
+    /// # Arguments
+    /// * `input_schema` - schema the data must have, e.g. the schema of create_arrow_example_data
+    /// * `output_schema` - schema the result must have
+    /// * `row_range` - allowed number of rows of the data, e.g. the row limits of the module
+    ///
+    /// returns the caller
+    pub fn new(
+        input_schema: SchemaRef,
+        output_schema: SchemaRef,
+        row_range: RangeInclusive<usize>,
+    ) -> ValidatedArrowCaller {
+        ValidatedArrowCaller {
+            input_schema,
+            output_schema,
+            row_range,
+            numeric_ranges: Vec::new(),
+        }
+    }
+
+    /// Adds a plausible range of the values of a numeric field of the data
+    /// # Arguments
+    /// * `field` - name of the numeric field
+    /// * `range` - plausible values (inclusive)
+    ///
+    /// returns the caller
+    pub fn with_numeric_range(
+        mut self,
+        field: &str,
+        range: RangeInclusive<f64>,
+    ) -> ValidatedArrowCaller {
+        self.numeric_ranges.push((field.to_string(), range));
+        self
+    }
+
+    /// Validates the data, processes it in a new instance of the module and validates the result
+    /// # Arguments
+    /// * `engine` - wasmtime engine to use for the store
+    /// * `module` - module containing the WASM function
+    /// * `profiler` - profiler to record the call of the WASM function
+    /// * `config` - sandbox configuration applied to the instance
+    /// * `batch` - data to process
+    ///
+    /// returns the result. Returns a ValidationError if the data or the result is not valid. The module is not called with data that is not valid
+    pub fn call(
+        &self,
+        engine: &Engine,
+        module: &Module,
+        profiler: &Arc<ExecutionProfiler>,
+        config: &SandboxConfig,
+        batch: &RecordBatch,
+    ) -> anyhow::Result<Vec<RecordBatch>> {
+        self.validate_input(batch)?;
+        let (instance, mut store) = create_sandboxed_instance(engine, module, profiler, config)?;
+        let result_arrow_ipc: Vec<u8> =
+            call_wasm_process_data_arrow_ipc(instance, &mut store, batch, "test", false, None)?;
+        let result_batches: Vec<RecordBatch> =
+            StreamReader::try_new(result_arrow_ipc.as_slice(), None)?
+                .collect::<Result<Vec<RecordBatch>, _>>()?;
+        for result_batch in &result_batches {
+            self.validate_output(result_batch)?;
+        }
+        Ok(result_batches)
+    }
+
+    /// Validates the data before the call: schema, number of rows, null values and plausible numeric values
+    /// # Arguments
+    /// * `batch` - data to process
+    ///
+    /// returns the first ValidationError (stage Pre) found
+    pub fn validate_input(&self, batch: &RecordBatch) -> Result<(), ValidationError> {
+        let error = |field: &str, reason: String| ValidationError {
+            stage: ValidationStage::Pre,
+            field: field.to_string(),
+            reason,
+        };
+        check_fields(batch, &self.input_schema, ValidationStage::Pre)?;
+        if !self.row_range.contains(&batch.num_rows()) {
+            return Err(error(
+                "",
+                format!(
+                    "{} rows, expected {} to {} rows",
+                    batch.num_rows(),
+                    self.row_range.start(),
+                    self.row_range.end()
+                ),
+            ));
+        }
+        for field in self.input_schema.fields() {
+            // all fields exist after check_fields
+            let column: &ArrayRef = batch.column_by_name(field.name()).unwrap();
+            if !field.is_nullable() && column.null_count() > 0 {
+                return Err(error(
+                    field.name(),
+                    format!(
+                        "{} null values in a field that is not nullable",
+                        column.null_count()
+                    ),
+                ));
+            }
+        }
+        for (name, range) in &self.numeric_ranges {
+            let column: &ArrayRef = batch
+                .column_by_name(name)
+                .ok_or(error(name, "field not found".to_string()))?;
+            if !column.data_type().is_numeric() {
+                return Err(error(
+                    name,
+                    format!("type {} is not numeric", column.data_type()),
+                ));
+            }
+            let values: ArrayRef = arrow::compute::cast(column, &DataType::Float64)
+                .map_err(|e| error(name, e.to_string()))?;
+            if let Some(value) = values
+                .as_primitive::<Float64Type>()
+                .iter()
+                .flatten()
+                .find(|value| !range.contains(value))
+            {
+                return Err(error(
+                    name,
+                    format!(
+                        "value {value} is not plausible, expected {} to {}",
+                        range.start(),
+                        range.end()
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates a record batch of the result after the call against the expected schema
+    /// # Arguments
+    /// * `batch` - record batch of the result
+    ///
+    /// returns the first ValidationError (stage Post) found
+    pub fn validate_output(&self, batch: &RecordBatch) -> Result<(), ValidationError> {
+        check_fields(batch, &self.output_schema, ValidationStage::Post)
+    }
+}
+
+/// Checks that a record batch has exactly the fields of a schema with the same types
+/// # Arguments
+/// * `batch` - record batch to check
+/// * `schema` - expected schema
+/// * `stage` - stage of the call reported in the error
+///
+/// returns a ValidationError for the first field that is missing, not expected or of another type
+fn check_fields(
+    batch: &RecordBatch,
+    schema: &SchemaRef,
+    stage: ValidationStage,
+) -> Result<(), ValidationError> {
+    let error = |field: &str, reason: String| ValidationError {
+        stage,
+        field: field.to_string(),
+        reason,
+    };
+    for field in schema.fields() {
+        let Some(column) = batch.column_by_name(field.name()) else {
+            return Err(error(field.name(), "field not found".to_string()));
+        };
+        if column.data_type() != field.data_type() {
+            return Err(error(
+                field.name(),
+                format!(
+                    "type {}, expected {}",
+                    column.data_type(),
+                    field.data_type()
+                ),
+            ));
+        }
+    }
+    let batch_schema: SchemaRef = batch.schema();
+    if let Some(field) = batch_schema
+        .fields()
+        .iter()
+        .map(|field| field.as_ref())
+        .find(|field: &&Field| schema.field_with_name(field.name()).is_err())
+    {
+        return Err(error(field.name(), "field not expected".to_string()));
+    }
+    Ok(())
+}
@@ -0,0 +1,162 @@
+//! Tests of the estimation of duplicate documents across calls of wasm_memory_process_data_arrow with a Bloom filter (wasm_init_bloom_filter) of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{AsArray, Float64Array, StringArray, TimestampSecondArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Module, Store, TypedFunc};
+
+mod common;
+use common::{call_arrow_function_on_instance, instantiate, meta_data, module_path, serialize};
+
+/// Example data of wasm-app
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456}
+///
+/// returns the data in Arrow IPC format
+fn example_data() -> Vec<u8> {
+    serialize(&example_batch())
+}
+
+/// Example data of wasm-app as record batch, see example_data
+///
+/// returns the data as record batch
+fn example_batch() -> RecordBatch {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap()
+}
+
+/// Calls wasm_memory_process_data_arrow with the example data
+/// # Arguments
+/// * `store` - store of the instance
+/// * `instance` - instance of the module
+///
+/// returns the value of the field is_duplicate_estimate of the result
+fn is_duplicate_estimate(store: &mut Store<WasiCtx>, instance: Instance) -> bool {
+    let result: Vec<u8> = call_arrow_function_on_instance(
+        store,
+        instance,
+        "wasm_memory_process_data_arrow",
+        &[&meta_data(), &example_data()],
+    )
+    .unwrap();
+    let batch: RecordBatch = StreamReader::try_new(result.as_slice(), None)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    batch
+        .column_by_name("is_duplicate_estimate")
+        .unwrap()
+        .as_boolean()
+        .value(0)
+}
+
+#[test]
+fn documents_are_estimated_as_duplicates_until_the_filter_is_reset() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let init_bloom_filter: TypedFunc<(u32, u32), i32> = instance
+        .get_typed_func(&mut store, "wasm_init_bloom_filter")
+        .unwrap();
+    let reset_bloom_filter: TypedFunc<(), ()> = instance
+        .get_typed_func(&mut store, "wasm_reset_bloom_filter")
+        .unwrap();
+    assert_eq!(init_bloom_filter.call(&mut store, (1000, 10)).unwrap(), 0);
+    assert!(!is_duplicate_estimate(&mut store, instance));
+    // the id has been added by the first call
+    assert!(is_duplicate_estimate(&mut store, instance));
+    reset_bloom_filter.call(&mut store, ()).unwrap();
+    assert!(!is_duplicate_estimate(&mut store, instance));
+}
+
+#[test]
+fn ids_of_failed_calls_are_not_added_to_the_filter() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let init_bloom_filter: TypedFunc<(u32, u32), i32> = instance
+        .get_typed_func(&mut store, "wasm_init_bloom_filter")
+        .unwrap();
+    let set_row_limits: TypedFunc<(u32, u32), i32> = instance
+        .get_typed_func(&mut store, "wasm_set_row_limits")
+        .unwrap();
+    assert_eq!(init_bloom_filter.call(&mut store, (1000, 10)).unwrap(), 0);
+    // the call fails after the duplicates have been skipped, because the data has too few rows
+    assert_eq!(set_row_limits.call(&mut store, (2, 10)).unwrap(), 0);
+    assert!(call_arrow_function_on_instance(
+        &mut store,
+        instance,
+        "wasm_memory_process_data_arrow",
+        &[&meta_data(), &example_data()],
+    )
+    .is_err());
+    // the retry is not estimated as duplicate
+    assert_eq!(set_row_limits.call(&mut store, (1, 10_000)).unwrap(), 0);
+    assert!(!is_duplicate_estimate(&mut store, instance));
+    assert!(is_duplicate_estimate(&mut store, instance));
+}
+
+#[test]
+fn data_without_rows_is_rejected() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let init_bloom_filter: TypedFunc<(u32, u32), i32> = instance
+        .get_typed_func(&mut store, "wasm_init_bloom_filter")
+        .unwrap();
+    assert_eq!(init_bloom_filter.call(&mut store, (1000, 10)).unwrap(), 0);
+    // only batches emptied by skipping duplicates are not validated
+    let error = call_arrow_function_on_instance(
+        &mut store,
+        instance,
+        "wasm_memory_process_data_arrow",
+        &[&meta_data(), &serialize(&example_batch().slice(0, 0))],
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("status -2"), "{error}");
+}
+
+#[test]
+fn invalid_parameters_are_rejected() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let init_bloom_filter: TypedFunc<(u32, u32), i32> = instance
+        .get_typed_func(&mut store, "wasm_init_bloom_filter")
+        .unwrap();
+    assert_eq!(init_bloom_filter.call(&mut store, (0, 10)).unwrap(), -1);
+    assert_eq!(init_bloom_filter.call(&mut store, (1000, 0)).unwrap(), -1);
+    assert_eq!(
+        init_bloom_filter.call(&mut store, (1000, 1000)).unwrap(),
+        -1
+    );
+}
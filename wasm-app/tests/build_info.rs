@@ -0,0 +1,42 @@
+//! Tests of the build information (wasm_get_build_info) of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::ffi::CStr;
+
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Memory, Module, Store, TypedFunc};
+
+mod common;
+use common::{instantiate, module_path};
+
+#[test]
+fn build_info_is_a_json_object() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let memory: Memory = instance.get_memory(&mut store, "memory").unwrap();
+    let get_build_info: TypedFunc<(), u32> = instance
+        .get_typed_func(&mut store, "wasm_get_build_info")
+        .unwrap();
+    let build_info_ptr: u32 = get_build_info.call(&mut store, ()).unwrap();
+    let build_info: serde_json::Value = serde_json::from_str(
+        CStr::from_bytes_until_nul(&memory.data(&store)[build_info_ptr as usize..])
+            .unwrap()
+            .to_str()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(build_info["crate_name"], "wasm_module2");
+    assert_eq!(build_info["version"], "0.1.0");
+    for key in [
+        "git_sha",
+        "build_date",
+        "rustc_version",
+        "target",
+        "opt_level",
+    ] {
+        assert!(build_info[key].is_string(), "{key}");
+    }
+    // the build information is owned by the module and stays the same
+    assert_eq!(get_build_info.call(&mut store, ()).unwrap(), build_info_ptr);
+}
@@ -0,0 +1,154 @@
+//! Tests of the verification of the checksums of the inputs of wasm_memory_process_data_arrow_checked of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{AsArray, Float64Array, StringArray, TimestampSecondArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Memory, Module, Store, TypedFunc};
+
+mod common;
+use common::{instantiate, meta_data, module_path, serialize};
+
+/// Parameters of wasm_memory_process_data_arrow_checked: position, size and CRC32 of the meta data and of the data
+type ProcessDataArrowCheckedParams = (u32, u32, u32, u32, u32, u32);
+
+/// Example data of wasm-app
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456}
+///
+/// returns the data in Arrow IPC format
+fn example_data() -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Calls wasm_memory_process_data_arrow_checked with the meta data of the command "test" and the example data
+/// # Arguments
+/// * `store` - store of the instance
+/// * `instance` - instance of the module
+/// * `data_crc` - CRC32 of the data passed to the module
+///
+/// returns the status and the result data
+fn process_data_arrow_checked(
+    mut store: &mut Store<WasiCtx>,
+    instance: Instance,
+    data_crc: u32,
+) -> (i32, Vec<u8>) {
+    let memory: Memory = instance.get_memory(&mut store, "memory").unwrap();
+    let allocate: TypedFunc<u32, u32> = instance
+        .get_typed_func(&mut store, "wasm_allocate")
+        .unwrap();
+    let process_data_arrow_checked: TypedFunc<ProcessDataArrowCheckedParams, u32> = instance
+        .get_typed_func(&mut store, "wasm_memory_process_data_arrow_checked")
+        .unwrap();
+    let meta_data: Vec<u8> = meta_data();
+    let data: Vec<u8> = example_data();
+    let mut params: Vec<u32> = Vec::with_capacity(4);
+    for input in [meta_data.as_slice(), data.as_slice()] {
+        let input_ptr: u32 = allocate.call(&mut store, input.len() as u32).unwrap();
+        memory.write(&mut store, input_ptr as usize, input).unwrap();
+        params.push(input_ptr);
+        params.push(input.len() as u32);
+    }
+    let result_ptr: u32 = process_data_arrow_checked
+        .call(
+            &mut store,
+            (
+                params[0],
+                params[1],
+                crc32fast::hash(&meta_data),
+                params[2],
+                params[3],
+                data_crc,
+            ),
+        )
+        .unwrap();
+    // WasmResult: status at byte 0, data_ptr at byte 4, data_len at byte 8
+    let mut wasm_result = [0u8; 12];
+    memory
+        .read(&store, result_ptr as usize, &mut wasm_result)
+        .unwrap();
+    let status: i32 = i32::from_le_bytes(wasm_result[0..4].try_into().unwrap());
+    let result_data_ptr: u32 = u32::from_le_bytes(wasm_result[4..8].try_into().unwrap());
+    let result_data_len: u32 = u32::from_le_bytes(wasm_result[8..12].try_into().unwrap());
+    let mut result_data: Vec<u8> = vec![0u8; result_data_len as usize];
+    memory
+        .read(&store, result_data_ptr as usize, &mut result_data)
+        .unwrap();
+    (status, result_data)
+}
+
+#[test]
+fn data_with_matching_checksums_is_processed() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let (status, result) =
+        process_data_arrow_checked(&mut store, instance, crc32fast::hash(&example_data()));
+    assert_eq!(status, 0);
+    let batch: RecordBatch = StreamReader::try_new(result.as_slice(), None)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        batch
+            .column_by_name("content")
+            .unwrap()
+            .as_string::<i32>()
+            .value(0),
+        "this is a test2"
+    );
+}
+
+#[test]
+fn data_with_a_mismatching_checksum_is_rejected() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let (status, _) =
+        process_data_arrow_checked(&mut store, instance, !crc32fast::hash(&example_data()));
+    assert_eq!(status, -20);
+}
+
+#[test]
+fn injected_errors_fail_checked_calls() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let set_error_injection_rate: TypedFunc<u32, i32> = instance
+        .get_typed_func(&mut store, "wasm_set_error_injection_rate")
+        .unwrap();
+    // the checked call is processed like wasm_memory_process_data_arrow, including the error injection
+    assert_eq!(set_error_injection_rate.call(&mut store, 1000).unwrap(), 0);
+    let (status, _) =
+        process_data_arrow_checked(&mut store, instance, crc32fast::hash(&example_data()));
+    assert_eq!(status, -2);
+}
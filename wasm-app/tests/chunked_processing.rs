@@ -0,0 +1,161 @@
+//! Tests of the processing of data split into several chunks (wasm_memory_process_chunked_arrow) of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{Float64Array, StringArray, TimestampSecondArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Memory, Module, Store, TypedFunc};
+
+mod common;
+use common::{
+    call_arrow_function_on_instance, instantiate, meta_data, module_path, process_data_arrow,
+    serialize,
+};
+
+/// Example data of wasm-app
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456}
+///
+/// returns the data in Arrow IPC format
+fn example_data() -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Description of chunks
+/// # Arguments
+/// * `data_ptrs` - positions of the chunks in the module memory
+/// * `data_sizes` - sizes of the chunks
+///
+/// returns the description in Arrow IPC format
+fn chunk_meta(data_ptrs: Vec<u64>, data_sizes: Vec<u64>) -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("data_ptr", DataType::UInt64, false),
+        Field::new("data_size", DataType::UInt64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(data_ptrs)),
+            Arc::new(UInt64Array::from(data_sizes)),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Writes a chunk into memory allocated in the module
+/// # Arguments
+/// * `store` - store of the instance
+/// * `instance` - instance of the module
+/// * `chunk` - chunk in Arrow IPC format
+///
+/// returns the position of the chunk in the module memory
+fn write_chunk(store: &mut Store<WasiCtx>, instance: Instance, chunk: &[u8]) -> u64 {
+    let memory: Memory = instance.get_memory(&mut *store, "memory").unwrap();
+    let allocate: TypedFunc<u32, u32> = instance
+        .get_typed_func(&mut *store, "wasm_allocate")
+        .unwrap();
+    let chunk_ptr: u32 = allocate.call(&mut *store, chunk.len() as u32).unwrap();
+    memory
+        .write(&mut *store, chunk_ptr as usize, chunk)
+        .unwrap();
+    chunk_ptr as u64
+}
+
+#[test]
+fn results_of_the_chunks_are_merged() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let chunk: Vec<u8> = example_data();
+    let data_ptrs: Vec<u64> = (0..3)
+        .map(|_| write_chunk(&mut store, instance, &chunk))
+        .collect();
+    let result: Vec<u8> = call_arrow_function_on_instance(
+        &mut store,
+        instance,
+        "wasm_memory_process_chunked_arrow",
+        &[&chunk_meta(data_ptrs, vec![chunk.len() as u64; 3])],
+    )
+    .unwrap();
+    let batches: Vec<RecordBatch> = StreamReader::try_new(result.as_slice(), None)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(batches.len(), 1);
+    assert_eq!(batches[0].num_rows(), 3);
+    // each chunk is processed like the data of wasm_memory_process_data_arrow
+    let single_result: Vec<u8> = process_data_arrow(&path, &meta_data(), &chunk).unwrap();
+    let single_batch: RecordBatch = StreamReader::try_new(single_result.as_slice(), None)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(batches[0].schema(), single_batch.schema());
+    for i in 0..3 {
+        assert_eq!(batches[0].slice(i, 1), single_batch);
+    }
+}
+
+#[test]
+fn chunks_without_allocated_memory_are_rejected() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let chunk: Vec<u8> = example_data();
+    let data_ptr: u64 = write_chunk(&mut store, instance, &chunk);
+    let error = call_arrow_function_on_instance(
+        &mut store,
+        instance,
+        "wasm_memory_process_chunked_arrow",
+        &[&chunk_meta(
+            vec![data_ptr, data_ptr + 8],
+            vec![chunk.len() as u64; 2],
+        )],
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("status -1"), "{error}");
+}
+
+#[test]
+fn descriptions_without_chunks_are_rejected() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let error = call_arrow_function_on_instance(
+        &mut store,
+        instance,
+        "wasm_memory_process_chunked_arrow",
+        &[&chunk_meta(vec![], vec![])],
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("status -2"), "{error}");
+}
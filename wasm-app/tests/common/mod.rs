@@ -0,0 +1,254 @@
+//! Helpers of the integration tests that call functions of wasm-module2
+//! The module needs to be built before (see README.md). module_path panics if it has not been built, so that the tests fail instead of passing without testing anything
+// each test file uses only some of the helpers
+#![allow(dead_code)]
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::array::{Array, MapBuilder, MapFieldNames, StringArray, StringBuilder};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+
+use wasi_common::sync::{ambient_authority, Dir, WasiCtxBuilder};
+use wasi_common::WasiCtx;
+use wasmtime::{Caller, Engine, Func, Instance, Linker, Memory, Module, Store, Val};
+
+/// Path of wasm-module2 built for WASI in release mode
+///
+/// returns the path of the module. Panics if the module has not been built
+pub fn module_path() -> PathBuf {
+    ["wasm32-wasip1", "wasm32-wasi"]
+        .iter()
+        .map(|target| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../wasm-module2/target")
+                .join(target)
+                .join("release/wasm_module2.wasm")
+        })
+        .find(|path| path.exists())
+        .expect("wasm-module2 has not been built, run cargo build --release --target wasm32-wasip1 in wasm-module2 (see README.md)")
+}
+
+/// Serializes a record batch in Arrow IPC format
+/// # Arguments
+/// * `batch` - record batch to serialize
+///
+/// returns the record batch in Arrow IPC format
+pub fn serialize(batch: &RecordBatch) -> Vec<u8> {
+    let mut stream_writer = StreamWriter::try_new(Vec::new(), &batch.schema()).unwrap();
+    stream_writer.write(batch).unwrap();
+    stream_writer.into_inner().unwrap()
+}
+
+/// Meta data of the command "test"
+///
+/// returns the meta data in Arrow IPC format
+pub fn meta_data() -> Vec<u8> {
+    let mut config_builder = MapBuilder::new(
+        Some(MapFieldNames {
+            entry: "entry".to_string(),
+            key: "key".to_string(),
+            value: "value".to_string(),
+        }),
+        StringBuilder::new(),
+        StringBuilder::new(),
+    );
+    config_builder.keys().append_value("filename");
+    config_builder.values().append_value("test.txt");
+    config_builder.append(true).unwrap();
+    let config = config_builder.finish();
+    let schema = Schema::new(vec![
+        Field::new("command", DataType::Utf8, false),
+        Field::new("config", config.data_type().clone(), false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(StringArray::from(vec!["test"])), Arc::new(config)],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Calls wasm_memory_process_data_arrow of a new instance of the module
+/// # Arguments
+/// * `path` - path of the module
+/// * `meta_data` - meta data in Arrow IPC format
+/// * `data` - data in Arrow IPC format
+///
+/// returns the result data in Arrow IPC format
+pub fn process_data_arrow(
+    path: &PathBuf,
+    meta_data: &[u8],
+    data: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    let engine: Engine = Engine::default();
+    let module: Module = Module::from_file(&engine, path)?;
+    process_data_arrow_with_module(&engine, &module, meta_data, data)
+}
+
+/// Calls wasm_memory_process_data_arrow of a new instance of a compiled module
+/// # Arguments
+/// * `engine` - engine the module has been compiled with
+/// * `module` - compiled module
+/// * `meta_data` - meta data in Arrow IPC format
+/// * `data` - data in Arrow IPC format
+///
+/// returns the result data in Arrow IPC format
+pub fn process_data_arrow_with_module(
+    engine: &Engine,
+    module: &Module,
+    meta_data: &[u8],
+    data: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    call_arrow_function_with_module(
+        engine,
+        module,
+        "wasm_memory_process_data_arrow",
+        &[meta_data, data],
+    )
+}
+
+/// Calls a function of a new instance of the module that expects its inputs in the module memory and returns a WasmResult
+/// # Arguments
+/// * `path` - path of the module
+/// * `function_name` - name of the exported function
+/// * `inputs` - inputs of the function, e.g. data in Arrow IPC format. Each input is passed as position and size
+///
+/// returns the result data
+pub fn call_arrow_function(
+    path: &PathBuf,
+    function_name: &str,
+    inputs: &[&[u8]],
+) -> anyhow::Result<Vec<u8>> {
+    let engine: Engine = Engine::default();
+    let module: Module = Module::from_file(&engine, path)?;
+    call_arrow_function_with_module(&engine, &module, function_name, inputs)
+}
+
+/// Calls a function of a new instance of a compiled module that expects its inputs in the module memory and returns a WasmResult
+/// # Arguments
+/// * `engine` - engine the module has been compiled with
+/// * `module` - compiled module
+/// * `function_name` - name of the exported function
+/// * `inputs` - inputs of the function. Each input is passed as position and size
+///
+/// returns the result data
+pub fn call_arrow_function_with_module(
+    engine: &Engine,
+    module: &Module,
+    function_name: &str,
+    inputs: &[&[u8]],
+) -> anyhow::Result<Vec<u8>> {
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(engine, module)?;
+    call_arrow_function_on_instance(&mut store, instance, function_name, inputs)
+}
+
+/// Creates a new instance of a compiled module without access to the filesystem, e.g. to change settings of the instance before calling a function
+/// # Arguments
+/// * `engine` - engine the module has been compiled with
+/// * `module` - compiled module
+///
+/// returns the store and the instance
+pub fn instantiate(engine: &Engine, module: &Module) -> anyhow::Result<(Store<WasiCtx>, Instance)> {
+    instantiate_with_preopened_dirs(engine, module, &[])
+}
+
+/// Creates a new instance of a compiled module with access to directories of the host, like the sandbox of the application with allow_filesystem
+/// # Arguments
+/// * `engine` - engine the module has been compiled with
+/// * `module` - compiled module
+/// * `preopened_dirs` - directories the module can access as path in the module and path on the host
+///
+/// returns the store and the instance
+pub fn instantiate_with_preopened_dirs(
+    engine: &Engine,
+    module: &Module,
+    preopened_dirs: &[(&str, &Path)],
+) -> anyhow::Result<(Store<WasiCtx>, Instance)> {
+    let mut linker: Linker<WasiCtx> = Linker::new(engine);
+    wasi_common::sync::add_to_linker(&mut linker, |wasi: &mut WasiCtx| wasi)?;
+    // messages of the module are not relevant for the tests
+    linker.func_wrap(
+        "env",
+        "host_log",
+        |_caller: Caller<'_, WasiCtx>, _level: i32, _msg_ptr: u32, _msg_len: u32| {},
+    )?;
+    // the key-value store of the application is empty in the tests
+    linker.func_wrap(
+        "env",
+        "host_kv_get",
+        |_caller: Caller<'_, WasiCtx>,
+         _key_ptr: u32,
+         _key_len: u32,
+         _val_ptr: u32,
+         _val_max_len: u32|
+         -> i32 { -1 },
+    )?;
+    linker.func_wrap(
+        "env",
+        "host_kv_set",
+        |_caller: Caller<'_, WasiCtx>,
+         _key_ptr: u32,
+         _key_len: u32,
+         _val_ptr: u32,
+         _val_len: u32|
+         -> i32 { 0 },
+    )?;
+    let mut wasi_builder = WasiCtxBuilder::new();
+    for (guest_path, host_path) in preopened_dirs {
+        let dir: Dir = Dir::open_ambient_dir(host_path, ambient_authority())?;
+        wasi_builder.preopened_dir(dir, guest_path)?;
+    }
+    let mut store: Store<WasiCtx> = Store::new(engine, wasi_builder.build());
+    let instance: Instance = linker.instantiate(&mut store, module)?;
+    Ok((store, instance))
+}
+
+/// Calls a function of an instance that expects its inputs in the module memory and returns a WasmResult
+/// # Arguments
+/// * `store` - store of the instance
+/// * `instance` - instance of the module
+/// * `function_name` - name of the exported function
+/// * `inputs` - inputs of the function. Each input is passed as position and size
+///
+/// returns the result data
+pub fn call_arrow_function_on_instance(
+    mut store: &mut Store<WasiCtx>,
+    instance: Instance,
+    function_name: &str,
+    inputs: &[&[u8]],
+) -> anyhow::Result<Vec<u8>> {
+    let memory: Memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or(anyhow::format_err!("failed to find `memory` export"))?;
+    let allocate = instance.get_typed_func::<u32, u32>(&mut store, "wasm_allocate")?;
+    let function: Func =
+        instance
+            .get_func(&mut store, function_name)
+            .ok_or(anyhow::format_err!(
+                "`{function_name}` was not an exported function"
+            ))?;
+    let mut params: Vec<Val> = Vec::with_capacity(inputs.len() * 2);
+    for input in inputs {
+        let input_ptr: u32 = allocate.call(&mut store, input.len() as u32)?;
+        memory.write(&mut store, input_ptr as usize, input)?;
+        params.push(Val::I32(input_ptr as i32));
+        params.push(Val::I32(input.len() as i32));
+    }
+    let mut results: [Val; 1] = [Val::I32(0)];
+    function.call(&mut store, &params, &mut results)?;
+    let result_ptr: u32 = results[0].i32().ok_or(anyhow::format_err!(
+        "`{function_name}` did not return a pointer"
+    ))? as u32;
+    // WasmResult: status at byte 0, data_ptr at byte 4, data_len at byte 8
+    let mut wasm_result = [0u8; 12];
+    memory.read(&store, result_ptr as usize, &mut wasm_result)?;
+    let status: i32 = i32::from_le_bytes(wasm_result[0..4].try_into()?);
+    anyhow::ensure!(status == 0, "{function_name} returned status {status}");
+    let result_data_ptr: u32 = u32::from_le_bytes(wasm_result[4..8].try_into()?);
+    let result_data_len: u32 = u32::from_le_bytes(wasm_result[8..12].try_into()?);
+    let mut result_data: Vec<u8> = vec![0u8; result_data_len as usize];
+    memory.read(&store, result_data_ptr as usize, &mut result_data)?;
+    Ok(result_data)
+}
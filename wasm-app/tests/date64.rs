@@ -0,0 +1,153 @@
+//! Tests of the field date of type Date64 (milliseconds since the UNIX epoch) of wasm_memory_combine_datetime_arrow and wasm_memory_process_data_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, Date32Array, Date64Array, Float64Array, StringArray,
+    Time64NanosecondArray, UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit, TimestampNanosecondType};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+
+mod common;
+use common::{call_arrow_function, meta_data, module_path, process_data_arrow, serialize};
+
+/// 2022-01-01 in days since the UNIX epoch
+const EXAMPLE_DATE_DAYS: i32 = 18_993;
+
+/// 12:00:00 in nanoseconds since midnight
+const EXAMPLE_TIME_OF_DAY_NS: i64 = 43_200_000_000_000;
+
+/// Data with an id, a date and a time of day
+/// # Arguments
+/// * `dates` - column of the date
+///
+/// returns the data in Arrow IPC format
+fn example_data(dates: ArrayRef) -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("date", dates.data_type().clone(), false),
+        Field::new("time_of_day", DataType::Time64(TimeUnit::Nanosecond), false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            dates,
+            Arc::new(Time64NanosecondArray::from(vec![EXAMPLE_TIME_OF_DAY_NS])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Data expected by the command "test" with the date of type Date64
+/// # Arguments
+/// * `date` - date in milliseconds since the UNIX epoch
+///
+/// returns the data in Arrow IPC format
+fn example_document(date: i64) -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("date", DataType::Date64, false),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            Arc::new(Date64Array::from(vec![date])),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Combines the date and time of day of the example data
+/// # Arguments
+/// * `dates` - column of the date
+///
+/// returns the timestamp in nanoseconds and the schema metadata date_source_type of the result
+fn combine(dates: ArrayRef) -> (i64, Option<String>) {
+    let path = module_path();
+    let result: Vec<u8> = call_arrow_function(
+        &path,
+        "wasm_memory_combine_datetime_arrow",
+        &[&example_data(dates)],
+    )
+    .unwrap();
+    let stream_reader = StreamReader::try_new(result.as_slice(), None).unwrap();
+    let date_source_type: Option<String> = stream_reader
+        .schema()
+        .metadata()
+        .get("date_source_type")
+        .cloned();
+    let batch: RecordBatch = stream_reader.into_iter().next().unwrap().unwrap();
+    let timestamps = batch
+        .column_by_name("timestamp")
+        .unwrap()
+        .as_primitive::<TimestampNanosecondType>();
+    assert!(!timestamps.is_null(0));
+    (timestamps.value(0), date_source_type)
+}
+
+#[test]
+fn date32_is_combined_without_conversion() {
+    let dates: ArrayRef = Arc::new(Date32Array::from(vec![EXAMPLE_DATE_DAYS]));
+    let (timestamp, date_source_type) = combine(dates);
+    assert_eq!(
+        timestamp,
+        EXAMPLE_DATE_DAYS as i64 * 86_400_000_000_000 + EXAMPLE_TIME_OF_DAY_NS
+    );
+    assert_eq!(date_source_type, None);
+}
+
+#[test]
+fn date64_is_truncated_to_days() {
+    // 2022-01-01T08:00:00Z, the time of day of the date is truncated
+    let dates: ArrayRef = Arc::new(Date64Array::from(vec![
+        EXAMPLE_DATE_DAYS as i64 * 86_400_000 + 28_800_000,
+    ]));
+    let (timestamp, date_source_type) = combine(dates);
+    assert_eq!(
+        timestamp,
+        EXAMPLE_DATE_DAYS as i64 * 86_400_000_000_000 + EXAMPLE_TIME_OF_DAY_NS
+    );
+    assert_eq!(date_source_type.as_deref(), Some("date64"));
+}
+
+#[test]
+fn date64_is_processed_as_date() {
+    let path = module_path();
+    // 2022-01-01T08:00:00Z, the time of day of the date is truncated
+    let result: Vec<u8> = process_data_arrow(
+        &path,
+        &meta_data(),
+        &example_document(EXAMPLE_DATE_DAYS as i64 * 86_400_000 + 28_800_000),
+    )
+    .unwrap();
+    let stream_reader = StreamReader::try_new(result.as_slice(), None).unwrap();
+    assert_eq!(
+        stream_reader
+            .schema()
+            .metadata()
+            .get("date_source_type")
+            .map(String::as_str),
+        Some("date64")
+    );
+    let batch: RecordBatch = stream_reader.into_iter().next().unwrap().unwrap();
+    assert_eq!(
+        batch
+            .column_by_name("content")
+            .unwrap()
+            .as_string::<i32>()
+            .value(0),
+        "this is a test2"
+    );
+}
@@ -0,0 +1,97 @@
+//! Tests of the coercion of scores of type Decimal128 to Float64 by wasm_memory_process_data_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, Decimal128Array, Float64Array, StringArray, TimestampSecondArray, UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Module, Store, TypedFunc};
+
+mod common;
+use common::{call_arrow_function_on_instance, instantiate, meta_data, module_path, serialize};
+
+/// Example data of wasm-app with a given score
+/// # Arguments
+/// * `scores` - column of the score with the value 1.123456
+///
+/// returns the data in Arrow IPC format
+fn example_data(scores: ArrayRef) -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", scores.data_type().clone(), false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            scores,
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+#[test]
+fn decimal_scores_are_coerced_and_counted() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let get_coercion_count: TypedFunc<(), u64> = instance
+        .get_typed_func(&mut store, "wasm_get_coercion_count")
+        .unwrap();
+    assert_eq!(get_coercion_count.call(&mut store, ()).unwrap(), 0);
+    // the processing fails if the coerced score is not 1.123456
+    let decimal_scores: ArrayRef = Arc::new(
+        Decimal128Array::from(vec![1_123_456i128])
+            .with_precision_and_scale(18, 6)
+            .unwrap(),
+    );
+    let result: Vec<u8> = call_arrow_function_on_instance(
+        &mut store,
+        instance,
+        "wasm_memory_process_data_arrow",
+        &[&meta_data(), &example_data(decimal_scores)],
+    )
+    .unwrap();
+    let stream_reader = StreamReader::try_new(result.as_slice(), None).unwrap();
+    assert_eq!(
+        stream_reader
+            .schema()
+            .metadata()
+            .get("score_precision_loss")
+            .map(String::as_str),
+        Some("true")
+    );
+    assert_eq!(get_coercion_count.call(&mut store, ()).unwrap(), 1);
+    // scores of type Float64 are not coerced
+    let float_scores: ArrayRef = Arc::new(Float64Array::from(vec![1.123456f64]));
+    let result: Vec<u8> = call_arrow_function_on_instance(
+        &mut store,
+        instance,
+        "wasm_memory_process_data_arrow",
+        &[&meta_data(), &example_data(float_scores)],
+    )
+    .unwrap();
+    let stream_reader = StreamReader::try_new(result.as_slice(), None).unwrap();
+    assert!(!stream_reader
+        .schema()
+        .metadata()
+        .contains_key("score_precision_loss"));
+    assert_eq!(get_coercion_count.call(&mut store, ()).unwrap(), 1);
+}
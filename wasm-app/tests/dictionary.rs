@@ -0,0 +1,94 @@
+//! Tests of the decoding of dictionary encoded fields by wasm_memory_process_data_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, DictionaryArray, Float64Array, StringArray, TimestampSecondArray, UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Int32Type, Schema, TimeUnit};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Module, Store};
+
+mod common;
+use common::{call_arrow_function_on_instance, instantiate, meta_data, module_path, serialize};
+
+/// Example data of wasm-app with given columns for content and title
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456}
+/// # Arguments
+/// * `contents` - column of the content
+/// * `titles` - column of the title
+///
+/// returns the data in Arrow IPC format
+fn example_data(contents: ArrayRef, titles: ArrayRef) -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", contents.data_type().clone(), false),
+        Field::new("title", titles.data_type().clone(), false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            contents,
+            titles,
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Calls wasm_memory_process_data_arrow
+/// # Arguments
+/// * `store` - store of the instance
+/// * `instance` - instance of the module
+/// * `data` - data in Arrow IPC format
+///
+/// returns the result
+fn process(store: &mut Store<WasiCtx>, instance: Instance, data: &[u8]) -> Vec<RecordBatch> {
+    let result: Vec<u8> = call_arrow_function_on_instance(
+        store,
+        instance,
+        "wasm_memory_process_data_arrow",
+        &[&meta_data(), data],
+    )
+    .unwrap();
+    StreamReader::try_new(result.as_slice(), None)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+#[test]
+fn dictionary_encoded_strings_are_processed_like_strings() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let expected_result: Vec<RecordBatch> = process(
+        &mut store,
+        instance,
+        &example_data(
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+        ),
+    );
+    let contents: DictionaryArray<Int32Type> = vec!["this is a test"].into_iter().collect();
+    let titles: DictionaryArray<Int32Type> = vec!["test"].into_iter().collect();
+    let result: Vec<RecordBatch> = process(
+        &mut store,
+        instance,
+        &example_data(Arc::new(contents), Arc::new(titles)),
+    );
+    assert_eq!(result, expected_result);
+}
@@ -0,0 +1,103 @@
+//! Tests of the injection of errors into wasm_memory_process_data_arrow of wasm-module2 (wasm_set_error_injection_rate), so that the error handling of the application can be tested
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{Float64Array, StringArray, TimestampSecondArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Module, Store, TypedFunc};
+
+mod common;
+use common::{call_arrow_function_on_instance, instantiate, meta_data, module_path, serialize};
+
+/// Example data of wasm-app
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456}
+///
+/// returns the data in Arrow IPC format
+fn example_data() -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Calls wasm_memory_process_data_arrow with the example data
+/// # Arguments
+/// * `store` - store of the instance
+/// * `instance` - instance of the module
+///
+/// returns the result data. Returns an error if the status of the result is non-zero
+fn process_data_arrow(store: &mut Store<WasiCtx>, instance: Instance) -> anyhow::Result<Vec<u8>> {
+    call_arrow_function_on_instance(
+        store,
+        instance,
+        "wasm_memory_process_data_arrow",
+        &[&meta_data(), &example_data()],
+    )
+}
+
+#[test]
+fn injected_errors_fail_every_call() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let set_error_injection_rate: TypedFunc<u32, i32> = instance
+        .get_typed_func(&mut store, "wasm_set_error_injection_rate")
+        .unwrap();
+    let clear_error_injection: TypedFunc<(), ()> = instance
+        .get_typed_func(&mut store, "wasm_clear_error_injection")
+        .unwrap();
+    // more than every call cannot fail
+    assert_eq!(set_error_injection_rate.call(&mut store, 1001).unwrap(), -1);
+    assert_eq!(set_error_injection_rate.call(&mut store, 1000).unwrap(), 0);
+    for _ in 0..10 {
+        // the injected error is returned like other errors of the processing
+        let error = process_data_arrow(&mut store, instance).unwrap_err();
+        assert!(error.to_string().contains("status -2"), "{error}");
+        let last_error: Vec<u8> =
+            call_arrow_function_on_instance(&mut store, instance, "wasm_last_error", &[]).unwrap();
+        assert_eq!(
+            String::from_utf8(last_error).unwrap(),
+            "Injected error for testing"
+        );
+    }
+    clear_error_injection.call(&mut store, ()).unwrap();
+    process_data_arrow(&mut store, instance).unwrap();
+}
+
+#[test]
+fn no_errors_are_injected_with_a_rate_of_zero() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let set_error_injection_rate: TypedFunc<u32, i32> = instance
+        .get_typed_func(&mut store, "wasm_set_error_injection_rate")
+        .unwrap();
+    assert_eq!(set_error_injection_rate.call(&mut store, 0).unwrap(), 0);
+    for _ in 0..10 {
+        process_data_arrow(&mut store, instance).unwrap();
+    }
+}
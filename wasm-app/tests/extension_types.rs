@@ -0,0 +1,149 @@
+//! Tests of the semantic validation of fields annotated with Arrow extension types by wasm_memory_process_data_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{AsArray, Float64Array, StringArray, TimestampSecondArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit, UInt64Type};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Module, Store, TypedFunc};
+
+mod common;
+use common::{call_arrow_function_on_instance, instantiate, meta_data, module_path, serialize};
+
+/// Example data of wasm-app with the field content annotated as email and the field title annotated as json
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456}
+///
+/// returns the data in Arrow IPC format
+fn annotated_example_data() -> Vec<u8> {
+    serialize(&annotated_example_batch())
+}
+
+/// Example data of wasm-app with the field content annotated as email and the field title annotated as json
+///
+/// returns the record batch
+fn annotated_example_batch() -> RecordBatch {
+    let extension_type =
+        |name: &str| HashMap::from([("ARROW:extension:name".to_string(), name.to_string())]);
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false).with_metadata(extension_type("email")),
+        Field::new("title", DataType::Utf8, false).with_metadata(extension_type("json")),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false).with_metadata(extension_type("unknown")),
+    ]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap()
+}
+
+/// Fetches the report of the extension types of the last call
+/// # Arguments
+/// * `store` - store of the instance
+/// * `instance` - instance of the module
+///
+/// returns the report
+fn extension_type_report(store: &mut Store<WasiCtx>, instance: Instance) -> RecordBatch {
+    let report: Vec<u8> =
+        call_arrow_function_on_instance(store, instance, "wasm_memory_extension_type_report", &[])
+            .unwrap();
+    StreamReader::try_new(report.as_slice(), None)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn annotated_fields_are_reported() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    call_arrow_function_on_instance(
+        &mut store,
+        instance,
+        "wasm_memory_process_data_arrow",
+        &[&meta_data(), &annotated_example_data()],
+    )
+    .unwrap();
+    let batch: RecordBatch = extension_type_report(&mut store, instance);
+    // fields with unknown extension types are not reported
+    assert_eq!(batch.num_rows(), 2);
+    let fields = batch.column_by_name("field").unwrap().as_string::<i32>();
+    let extension_types = batch
+        .column_by_name("extension_type")
+        .unwrap()
+        .as_string::<i32>();
+    let valid_counts = batch
+        .column_by_name("valid_count")
+        .unwrap()
+        .as_primitive::<UInt64Type>();
+    let invalid_counts = batch
+        .column_by_name("invalid_count")
+        .unwrap()
+        .as_primitive::<UInt64Type>();
+    assert_eq!(fields.value(0), "content");
+    assert_eq!(extension_types.value(0), "email");
+    assert_eq!(valid_counts.value(0), 0);
+    assert_eq!(invalid_counts.value(0), 1);
+    assert_eq!(fields.value(1), "title");
+    assert_eq!(extension_types.value(1), "json");
+    assert_eq!(valid_counts.value(1), 0);
+    assert_eq!(invalid_counts.value(1), 1);
+}
+
+#[test]
+fn report_of_cached_results_is_restored() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let set_cache_ttl_ms: TypedFunc<u32, ()> = instance
+        .get_typed_func(&mut store, "wasm_set_cache_ttl_ms")
+        .unwrap();
+    set_cache_ttl_ms.call(&mut store, 60_000).unwrap();
+    let annotated_batch: RecordBatch = annotated_example_batch();
+    let fields: Vec<Field> = annotated_batch
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.as_ref().clone().with_metadata(HashMap::new()))
+        .collect();
+    let batch: RecordBatch = annotated_batch
+        .with_schema(Arc::new(Schema::new(fields)))
+        .unwrap();
+    for (data, reported_fields) in [
+        (annotated_example_data(), 2),
+        (serialize(&batch), 0),
+        // answered from the cache
+        (annotated_example_data(), 2),
+    ] {
+        call_arrow_function_on_instance(
+            &mut store,
+            instance,
+            "wasm_memory_process_data_arrow",
+            &[&meta_data(), &data],
+        )
+        .unwrap();
+        assert_eq!(
+            extension_type_report(&mut store, instance).num_rows(),
+            reported_fields
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! Tests of the access of wasm_process_csv_file of wasm-module2 to the filesystem, which is only possible if the application preopens a directory (allow_filesystem of the sandbox configuration)
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::path::PathBuf;
+
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Memory, Module, Store, TypedFunc};
+
+mod common;
+use common::{instantiate, instantiate_with_preopened_dirs, module_path};
+
+/// Calls wasm_process_csv_file with the file documents.csv, which contains the data expected by the command "test"
+/// # Arguments
+/// * `store` - store of the instance
+/// * `instance` - instance of the module
+///
+/// returns the status of the result
+fn process_csv_file(mut store: &mut Store<WasiCtx>, instance: Instance) -> i32 {
+    let memory: Memory = instance.get_memory(&mut store, "memory").unwrap();
+    let allocate: TypedFunc<u32, u32> = instance
+        .get_typed_func(&mut store, "wasm_allocate")
+        .unwrap();
+    let process_csv_file: TypedFunc<(u32, u32, u32), u32> = instance
+        .get_typed_func(&mut store, "wasm_process_csv_file")
+        .unwrap();
+    let filename: &[u8] = b"documents.csv";
+    let filename_ptr: u32 = allocate.call(&mut store, filename.len() as u32).unwrap();
+    memory
+        .write(&mut store, filename_ptr as usize, filename)
+        .unwrap();
+    let result_ptr: u32 = process_csv_file
+        .call(&mut store, (filename_ptr, filename.len() as u32, 1))
+        .unwrap();
+    // WasmResult: status at byte 0, data_ptr at byte 4, data_len at byte 8
+    let mut wasm_result = [0u8; 12];
+    memory
+        .read(&store, result_ptr as usize, &mut wasm_result)
+        .unwrap();
+    i32::from_le_bytes(wasm_result[0..4].try_into().unwrap())
+}
+
+#[test]
+fn filesystem_access_is_denied_without_preopened_directories() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    // the file cannot be opened
+    assert_eq!(process_csv_file(&mut store, instance), -2);
+}
+
+#[test]
+fn filesystem_access_is_allowed_in_preopened_directories() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let test_data: PathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-data");
+    let (mut store, instance): (Store<WasiCtx>, Instance) =
+        instantiate_with_preopened_dirs(&engine, &module, &[(".", &test_data)]).unwrap();
+    assert_eq!(process_csv_file(&mut store, instance), 0);
+}
@@ -0,0 +1,143 @@
+//! Tests of the command "filter" of wasm_memory_process_data_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, AsArray, Float64Array, MapBuilder, MapFieldNames, StringArray, StringBuilder,
+    TimestampSecondArray, UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit, UInt64Type};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+
+mod common;
+use common::{module_path, process_data_arrow, serialize};
+
+/// Example data with three documents with the ids 1, 2, 3 and the scores 0.5, 1.0, 2.0
+///
+/// returns the data in Arrow IPC format
+fn example_data() -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1, 2, 3])),
+            Arc::new(StringArray::from(vec!["first", "second", "third"])),
+            Arc::new(StringArray::from(vec!["test", "test", "test"])),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400; 3]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![0.5, 1.0, 2.0])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Meta data of the command "filter"
+/// # Arguments
+/// * `field` - field to compare
+/// * `op` - comparison operator
+/// * `value` - value to compare with
+///
+/// returns the meta data in Arrow IPC format
+fn filter_meta_data(field: &str, op: &str, value: &str) -> Vec<u8> {
+    let mut config_builder = MapBuilder::new(
+        Some(MapFieldNames {
+            entry: "entry".to_string(),
+            key: "key".to_string(),
+            value: "value".to_string(),
+        }),
+        StringBuilder::new(),
+        StringBuilder::new(),
+    );
+    for (key, config_value) in [
+        ("filename", "test.txt"),
+        ("field", field),
+        ("op", op),
+        ("value", value),
+    ] {
+        config_builder.keys().append_value(key);
+        config_builder.values().append_value(config_value);
+    }
+    config_builder.append(true).unwrap();
+    let config = config_builder.finish();
+    let schema = Schema::new(vec![
+        Field::new("command", DataType::Utf8, false),
+        Field::new("config", config.data_type().clone(), false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from(vec!["filter"])),
+            Arc::new(config),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Filters the example data
+/// # Arguments
+/// * `field` - field to compare
+/// * `op` - comparison operator
+/// * `value` - value to compare with
+///
+/// returns the ids of the filtered documents
+fn filtered_ids(field: &str, op: &str, value: &str) -> anyhow::Result<Vec<u64>> {
+    let path = module_path();
+    process_data_arrow(&path, &filter_meta_data(field, op, value), &example_data()).map(|result| {
+        StreamReader::try_new(result.as_slice(), None)
+            .unwrap()
+            .flat_map(|batch| {
+                let batch: RecordBatch = batch.unwrap();
+                let ids = batch
+                    .column_by_name("id")
+                    .unwrap()
+                    .as_primitive::<UInt64Type>();
+                (0..ids.len()).map(|i| ids.value(i)).collect::<Vec<u64>>()
+            })
+            .collect()
+    })
+}
+
+#[test]
+fn comparison_operators_filter_rows() {
+    for (op, expected_ids) in [
+        ("gt", vec![3]),
+        ("lt", vec![1]),
+        ("eq", vec![2]),
+        ("ge", vec![2, 3]),
+        ("le", vec![1, 2]),
+    ] {
+        let ids = filtered_ids("score", op, "1.0");
+        assert_eq!(ids.unwrap(), expected_ids, "operator {op}");
+    }
+}
+
+#[test]
+fn value_is_cast_to_type_of_field() {
+    let ids = filtered_ids("id", "ge", "2");
+    assert_eq!(ids.unwrap(), vec![2, 3]);
+}
+
+#[test]
+fn value_of_incompatible_type_is_rejected() {
+    let ids = filtered_ids("score", "gt", "high");
+    assert!(ids.is_err());
+}
+
+#[test]
+fn unknown_operator_is_rejected() {
+    let ids = filtered_ids("score", "ne", "1.0");
+    assert!(ids.is_err());
+}
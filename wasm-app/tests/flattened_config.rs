@@ -0,0 +1,81 @@
+//! Tests of the meta data of wasm_memory_process_data_arrow of wasm-module2 with the config flattened to one field per key (config.<key>) for applications without support of nested types
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, Float64Array, StringArray, TimestampSecondArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+
+mod common;
+use common::{meta_data, module_path, process_data_arrow, serialize};
+
+/// Example data of wasm-app
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456}
+///
+/// returns the data in Arrow IPC format
+fn example_data() -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Meta data of the command "test" with the config flattened to the fields config.<key>
+/// # Arguments
+/// * `config` - keys and values of the config
+///
+/// returns the meta data in Arrow IPC format
+fn flattened_meta_data(config: &[(&str, &str)]) -> Vec<u8> {
+    let mut fields: Vec<Field> = vec![Field::new("command", DataType::Utf8, false)];
+    let mut columns: Vec<ArrayRef> = vec![Arc::new(StringArray::from(vec!["test"]))];
+    for (key, value) in config {
+        fields.push(Field::new(format!("config.{key}"), DataType::Utf8, false));
+        columns.push(Arc::new(StringArray::from(vec![*value])));
+    }
+    serialize(&RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap())
+}
+
+#[test]
+fn flattened_config_is_processed_like_nested_config() {
+    let path = module_path();
+    let nested_result: Vec<u8> = process_data_arrow(&path, &meta_data(), &example_data()).unwrap();
+    let flattened_result: Vec<u8> = process_data_arrow(
+        &path,
+        &flattened_meta_data(&[("filename", "test.txt")]),
+        &example_data(),
+    )
+    .unwrap();
+    assert_eq!(flattened_result, nested_result);
+}
+
+#[test]
+fn flattened_config_values_are_applied() {
+    let path = module_path();
+    // the content of the example data has more than 5 characters
+    let result = process_data_arrow(
+        &path,
+        &flattened_meta_data(&[("filename", "test.txt"), ("max_length", "5")]),
+        &example_data(),
+    );
+    assert!(result.is_err());
+}
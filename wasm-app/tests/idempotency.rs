@@ -0,0 +1,95 @@
+//! Tests that wasm_memory_process_data_arrow of wasm-module2 returns the same result for the same data, e.g. so that results can be cached or deduplicated
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{Float64Array, StringArray, TimestampSecondArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use arrow::util::pretty::pretty_format_batches;
+use wasmtime::{Engine, Module};
+
+mod common;
+use common::{meta_data, module_path, process_data_arrow_with_module, serialize};
+
+/// Example data of wasm-app
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456}
+///
+/// returns the data in Arrow IPC format
+fn example_data() -> Vec<u8> {
+    let metadata: HashMap<String, String> = HashMap::from([
+        ("source".to_string(), "wasm-app".to_string()),
+        ("version".to_string(), "1.0.0".to_string()),
+        ("created_at".to_string(), "1641038400".to_string()),
+    ]);
+    let schema = Schema::new_with_metadata(
+        vec![
+            Field::new("id", DataType::UInt64, false),
+            Field::new("content", DataType::Utf8, false),
+            Field::new("title", DataType::Utf8, false),
+            Field::new(
+                "date",
+                DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+                false,
+            ),
+            Field::new("score", DataType::Float64, false),
+        ],
+        metadata,
+    );
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Processes the same data twice, each time with a new instance of the module, and compares the results
+/// # Arguments
+/// * `engine` - engine the module has been compiled with
+/// * `module` - compiled wasm-module2
+/// * `input` - data in Arrow IPC format processed with the command "test"
+///
+/// returns true if both results contain the same record batches, ie they are equal and have the same canonical string form
+fn test_module_idempotency(
+    engine: &Engine,
+    module: &Module,
+    input: Vec<u8>,
+) -> anyhow::Result<bool> {
+    let first_result: Vec<u8> =
+        process_data_arrow_with_module(engine, module, &meta_data(), &input)?;
+    let second_result: Vec<u8> =
+        process_data_arrow_with_module(engine, module, &meta_data(), &input)?;
+    let first_batches: Vec<RecordBatch> =
+        StreamReader::try_new(first_result.as_slice(), None)?.collect::<Result<_, _>>()?;
+    let second_batches: Vec<RecordBatch> =
+        StreamReader::try_new(second_result.as_slice(), None)?.collect::<Result<_, _>>()?;
+    if first_batches.len() != second_batches.len() {
+        return Ok(false);
+    }
+    for (first_batch, second_batch) in first_batches.iter().zip(&second_batches) {
+        if first_batch != second_batch
+            || pretty_format_batches(std::slice::from_ref(first_batch))?.to_string()
+                != pretty_format_batches(std::slice::from_ref(second_batch))?.to_string()
+        {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+#[test]
+fn example_data_is_processed_idempotently() {
+    let path = module_path();
+    let engine: Engine = Engine::default();
+    let module: Module = Module::from_file(&engine, path).unwrap();
+    assert!(test_module_idempotency(&engine, &module, example_data()).unwrap());
+}
@@ -0,0 +1,84 @@
+//! Tests of the peak readings per device of IoT sensors with the time of day as Time32(Second) (wasm_memory_process_iot_timeseries_arrow) of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{AsArray, Float32Array, Time32SecondArray, UInt64Array};
+use arrow::datatypes::{
+    DataType, Field, Float32Type, Schema, Time32SecondType, TimeUnit, UInt64Type,
+};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+
+mod common;
+use common::{call_arrow_function, module_path, serialize};
+
+/// Readings of IoT sensors
+/// # Arguments
+/// * `device_ids` - ids of the devices
+/// * `reading_times` - times of day of the readings in seconds since midnight
+/// * `sensor_values` - values of the readings
+///
+/// returns the readings in Arrow IPC format
+fn readings(device_ids: Vec<u64>, reading_times: Vec<i32>, sensor_values: Vec<f32>) -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("device_id", DataType::UInt64, false),
+        Field::new("reading_time", DataType::Time32(TimeUnit::Second), false),
+        Field::new("sensor_value", DataType::Float32, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(device_ids)),
+            Arc::new(Time32SecondArray::from(reading_times)),
+            Arc::new(Float32Array::from(sensor_values)),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+#[test]
+fn peak_reading_per_device() {
+    let path = module_path();
+    let data: Vec<u8> = readings(
+        vec![2, 1, 2, 1, 2],
+        vec![0, 3_600, 43_200, 86_399, 50_000],
+        vec![1.5, 20.0, 7.25, 19.0, 7.25],
+    );
+    let result: Vec<u8> =
+        call_arrow_function(&path, "wasm_memory_process_iot_timeseries_arrow", &[&data]).unwrap();
+    let batch: RecordBatch = StreamReader::try_new(result.as_slice(), None)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        batch.schema().field(2).data_type(),
+        &DataType::Time32(TimeUnit::Second)
+    );
+    // devices in the order they first occur, the first reading with the peak value is kept
+    assert_eq!(
+        batch.column(0).as_primitive::<UInt64Type>().values(),
+        &[2, 1]
+    );
+    assert_eq!(
+        batch.column(1).as_primitive::<Float32Type>().values(),
+        &[7.25, 20.0]
+    );
+    assert_eq!(
+        batch.column(2).as_primitive::<Time32SecondType>().values(),
+        &[43_200, 3_600]
+    );
+}
+
+#[test]
+fn reading_times_after_midnight_are_rejected() {
+    let path = module_path();
+    for reading_time in [-1, 86_400] {
+        let data: Vec<u8> = readings(vec![1], vec![reading_time], vec![1.0]);
+        assert!(
+            call_arrow_function(&path, "wasm_memory_process_iot_timeseries_arrow", &[&data])
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,74 @@
+//! Tests of the extraction of values from a field with JSON documents (wasm_memory_parse_json_field_arrow) of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{AsArray, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+
+mod common;
+use common::{call_arrow_function, module_path, serialize};
+
+/// Documents with metadata as JSON
+/// # Arguments
+/// * `metadata` - metadata of the documents as JSON
+///
+/// returns the documents in Arrow IPC format
+fn documents(metadata: Vec<Option<&str>>) -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("metadata", DataType::Utf8, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from_iter_values(1..=metadata.len() as u64)),
+            Arc::new(StringArray::from(metadata)),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+#[test]
+fn values_of_the_path_are_extracted() {
+    let path = module_path();
+    let data: Vec<u8> = documents(vec![
+        Some(r#"{"author": {"name": "Alice"}}"#),
+        Some(r#"{"author": {"id": 7}}"#),
+        Some("not json"),
+        None,
+        Some(r#"{"author": {"name": 42}}"#),
+        Some(r#"{"author": {"name": null}}"#),
+    ]);
+    let result: Vec<u8> = call_arrow_function(
+        &path,
+        "wasm_memory_parse_json_field_arrow",
+        &[&data, b"metadata", b"author.name", b"author_name"],
+    )
+    .unwrap();
+    let batch: RecordBatch = StreamReader::try_new(result.as_slice(), None)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(batch.num_columns(), 3);
+    assert_eq!(batch.schema().field(2).name(), "author_name");
+    assert_eq!(
+        batch.column(2).as_string::<i32>(),
+        &StringArray::from(vec![Some("Alice"), None, None, None, Some("42"), None])
+    );
+}
+
+#[test]
+fn existing_fields_are_not_overwritten() {
+    let path = module_path();
+    let data: Vec<u8> = documents(vec![Some(r#"{"id": 2}"#)]);
+    assert!(call_arrow_function(
+        &path,
+        "wasm_memory_parse_json_field_arrow",
+        &[&data, b"metadata", b"id", b"id"],
+    )
+    .is_err());
+}
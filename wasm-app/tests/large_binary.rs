@@ -0,0 +1,113 @@
+//! Tests of binary payloads (field attachment) with 32-bit (Binary) and 64-bit (LargeBinary) offsets in wasm_memory_process_data_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, AsArray, BinaryBuilder, Float64Array, LargeBinaryBuilder, StringArray,
+    TimestampSecondArray, UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Module, Store};
+
+mod common;
+use common::{call_arrow_function_on_instance, instantiate, meta_data, module_path, serialize};
+
+/// Example data of wasm-app with an attachment
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456, attachment: ...}
+/// # Arguments
+/// * `attachments` - column of the attachment with one value
+///
+/// returns the data in Arrow IPC format
+fn example_data(attachments: ArrayRef) -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+        Field::new("attachment", attachments.data_type().clone(), true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+            attachments,
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Calls wasm_memory_process_data_arrow with the example data and an attachment
+/// # Arguments
+/// * `store` - store of the instance
+/// * `instance` - instance of the module
+/// * `attachments` - column of the attachment with one value
+///
+/// returns the result
+fn process_attachment(
+    store: &mut Store<WasiCtx>,
+    instance: Instance,
+    attachments: ArrayRef,
+) -> RecordBatch {
+    let result: Vec<u8> = call_arrow_function_on_instance(
+        store,
+        instance,
+        "wasm_memory_process_data_arrow",
+        &[&meta_data(), &example_data(attachments)],
+    )
+    .unwrap();
+    StreamReader::try_new(result.as_slice(), None)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn large_binary_attachments_are_returned_as_large_binary() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    // the module processes one document per call, so each value is sent in its own call
+    let values: [&[u8]; 3] = [b"", &[0x00, 0xff, 0x7f, 0x80], b"media file"];
+    for value in values {
+        let mut builder = LargeBinaryBuilder::new();
+        builder.append_value(value);
+        let batch: RecordBatch =
+            process_attachment(&mut store, instance, Arc::new(builder.finish()));
+        let attachments = batch.column_by_name("attachment").unwrap();
+        assert_eq!(attachments.data_type(), &DataType::LargeBinary);
+        assert_eq!(attachments.as_binary::<i64>().value(0), value);
+    }
+}
+
+#[test]
+fn binary_attachments_are_returned_as_binary() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let mut builder = BinaryBuilder::new();
+    builder.append_value([0x00, 0xff, 0x7f, 0x80]);
+    let batch: RecordBatch = process_attachment(&mut store, instance, Arc::new(builder.finish()));
+    let attachments = batch.column_by_name("attachment").unwrap();
+    assert_eq!(attachments.data_type(), &DataType::Binary);
+    assert_eq!(
+        attachments.as_binary::<i32>().value(0),
+        [0x00, 0xff, 0x7f, 0x80]
+    );
+}
@@ -0,0 +1,85 @@
+//! Tests of strings with 64-bit offsets (LargeUtf8) in the data of wasm_memory_process_data_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, AsArray, Float64Array, LargeStringArray, StringArray, TimestampSecondArray,
+    UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+
+mod common;
+use common::{meta_data, module_path, process_data_arrow, serialize};
+
+/// Data expected by the command "test"
+/// # Arguments
+/// * `string_data_type` - type of the fields content and title, ie Utf8 or LargeUtf8
+///
+/// returns the data in Arrow IPC format
+fn data(string_data_type: DataType) -> Vec<u8> {
+    let strings = |value: &str| -> ArrayRef {
+        match string_data_type {
+            DataType::LargeUtf8 => Arc::new(LargeStringArray::from(vec![value])),
+            _ => Arc::new(StringArray::from(vec![value])),
+        }
+    };
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", string_data_type.clone(), false),
+        Field::new("title", string_data_type.clone(), false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            strings("this is a test"),
+            strings("test"),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Deserializes the result of the module
+/// # Arguments
+/// * `result_data` - result data in Arrow IPC format
+///
+/// returns the first record batch of the result
+fn deserialize(result_data: &[u8]) -> RecordBatch {
+    StreamReader::try_new(result_data, None)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn large_utf8_is_returned_as_large_utf8() {
+    let path = module_path();
+    let result: RecordBatch =
+        deserialize(&process_data_arrow(&path, &meta_data(), &data(DataType::LargeUtf8)).unwrap());
+    let content = result.column_by_name("content").unwrap();
+    assert_eq!(content.data_type(), &DataType::LargeUtf8);
+    assert_eq!(content.as_string::<i64>().value(0), "this is a test2");
+}
+
+#[test]
+fn utf8_is_returned_as_utf8() {
+    let path = module_path();
+    let result: RecordBatch =
+        deserialize(&process_data_arrow(&path, &meta_data(), &data(DataType::Utf8)).unwrap());
+    let content = result.column_by_name("content").unwrap();
+    assert_eq!(content.data_type(), &DataType::Utf8);
+    assert_eq!(content.as_string::<i32>().value(0), "this is a test2");
+}
@@ -0,0 +1,275 @@
+//! Property-based tests of the memory management (wasm_allocate, wasm_deallocate, wasm_validate_pointer) of the WASM modules
+//! The modules need to be built before (see README.md). Tests of modules that have not been built fail
+use std::path::PathBuf;
+
+use proptest::prelude::*;
+use proptest::test_runner::{Config, RngAlgorithm, TestCaseError, TestRng, TestRunner};
+
+use wasi_common::sync::WasiCtxBuilder;
+use wasi_common::WasiCtx;
+use wasmtime::{Caller, Engine, Instance, Linker, Memory, Module, Store, TypedFunc};
+
+/// Seed of the random sequences of operations, so that failures can be reproduced
+const SEED: [u8; 32] = *b"rust-wasm-dynamic-module-study!!";
+
+/// Number of random sequences of operations per module
+const CASES: u32 = 32;
+
+/// Maximum size of a memory area allocated by an operation
+const MAX_SIZE: u32 = 1_048_576;
+
+/// Return code of wasm_deallocate if the memory has been deallocated
+const DEALLOCATE_SUCCESS: i32 = 0;
+
+/// Return code of wasm_deallocate if the memory has already been deallocated
+const DEALLOCATE_ALREADY_FREED: i32 = -2;
+
+/// Return code of wasm_deallocate if the memory has not been allocated. The slab of wasm-module1 (feature slab) also returns it for memory that has already been deallocated
+const DEALLOCATE_NOT_ALLOCATED: i32 = -1;
+
+/// Maximum number of memory areas allocated at the same time with the slab of wasm-module1 (feature slab)
+const SLAB_CAPACITY: u32 = 256;
+
+/// Operation on the memory management of a module
+#[derive(Clone, Debug)]
+enum Op {
+    Allocate,
+    Deallocate,
+    Validate,
+}
+
+/// Memory management functions of an instance of a module
+struct MemoryManagement {
+    store: Store<WasiCtx>,
+    memory: Memory,
+    allocate: TypedFunc<u32, u32>,
+    deallocate: TypedFunc<u32, i32>,
+    validate_pointer: TypedFunc<u32, u32>,
+    allocated_bytes: TypedFunc<(), u32>,
+}
+
+impl MemoryManagement {
+    /// Instantiates a module and fetches its memory management functions
+    /// # Arguments
+    /// * `engine` - engine the module was compiled with
+    /// * `module` - module to instantiate
+    ///
+    /// returns the memory management functions of the new instance
+    fn new(engine: &Engine, module: &Module) -> anyhow::Result<MemoryManagement> {
+        let mut linker: Linker<WasiCtx> = Linker::new(engine);
+        wasi_common::sync::add_to_linker(&mut linker, |wasi: &mut WasiCtx| wasi)?;
+        // messages of the module are not relevant for the tests
+        linker.func_wrap(
+            "env",
+            "host_log",
+            |_caller: Caller<'_, WasiCtx>, _level: i32, _msg_ptr: u32, _msg_len: u32| {},
+        )?;
+        let mut store: Store<WasiCtx> = Store::new(engine, WasiCtxBuilder::new().build());
+        let instance: Instance = linker.instantiate(&mut store, module)?;
+        let memory: Memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or(anyhow::format_err!("failed to find `memory` export"))?;
+        Ok(MemoryManagement {
+            allocate: instance.get_typed_func(&mut store, "wasm_allocate")?,
+            deallocate: instance.get_typed_func(&mut store, "wasm_deallocate")?,
+            validate_pointer: instance.get_typed_func(&mut store, "wasm_validate_pointer")?,
+            allocated_bytes: instance.get_typed_func(&mut store, "wasm_allocated_bytes")?,
+            store,
+            memory,
+        })
+    }
+}
+
+/// Path of a module built for WASI in release mode
+/// # Arguments
+/// * `module_dir` - directory of the module, e.g. wasm-module1
+/// * `module_file` - file of the module, e.g. wasm_module1.wasm
+///
+/// returns the path of the module. Panics if the module has not been built
+fn module_path(module_dir: &str, module_file: &str) -> PathBuf {
+    ["wasm32-wasip1", "wasm32-wasi"]
+        .iter()
+        .map(|target| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join(module_dir)
+                .join("target")
+                .join(target)
+                .join("release")
+                .join(module_file)
+        })
+        .find(|path| path.exists())
+        .unwrap_or_else(|| {
+            panic!("{module_file} has not been built, run cargo build --release --target wasm32-wasip1 in {module_dir} (see README.md)")
+        })
+}
+
+/// Path of wasm-module1 built with the memory management based on a slab (feature slab) for browser targets without WASI in release mode
+///
+/// returns the path of the module. Panics if the module has not been built
+fn slab_module_path() -> PathBuf {
+    let path: PathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../wasm-module1/target/wasm32-unknown-unknown/release/wasm_module1.wasm");
+    assert!(
+        path.exists(),
+        "wasm_module1.wasm has not been built with the feature slab, run cargo build --release --target wasm32-unknown-unknown --features slab in wasm-module1 (see README.md)"
+    );
+    path
+}
+
+/// Strategy generating a sequence of operations. The size is the size of the memory area to allocate or selects the pointer to deallocate or validate
+///
+/// returns the strategy
+fn ops_strategy() -> impl Strategy<Value = Vec<(u32, Op)>> {
+    prop::collection::vec(
+        (
+            1..=MAX_SIZE,
+            prop_oneof![Just(Op::Allocate), Just(Op::Deallocate), Just(Op::Validate)],
+        ),
+        1..64,
+    )
+}
+
+/// Runs a sequence of operations on a new instance of a module and checks the invariants of the memory management after each operation
+/// # Arguments
+/// * `engine` - engine the module was compiled with
+/// * `module` - module to test
+/// * `ops` - sequence of operations
+/// * `already_freed_code` - return code of wasm_deallocate for memory that has already been deallocated
+///
+/// returns an error describing the first violated invariant
+fn check_ops(
+    engine: &Engine,
+    module: &Module,
+    ops: &[(u32, Op)],
+    already_freed_code: i32,
+) -> Result<(), TestCaseError> {
+    let mut mm: MemoryManagement =
+        MemoryManagement::new(engine, module).map_err(|e| TestCaseError::fail(e.to_string()))?;
+    // pointers with their size that are allocated
+    let mut live: Vec<(u32, u32)> = Vec::new();
+    // pointers that have been deallocated
+    let mut freed: Vec<u32> = Vec::new();
+    for (size, op) in ops {
+        match op {
+            Op::Allocate => {
+                let ptr: u32 = mm
+                    .allocate
+                    .call(&mut mm.store, *size)
+                    .map_err(|e| TestCaseError::fail(e.to_string()))?;
+                prop_assert_ne!(ptr, 0, "allocation of {} bytes failed", size);
+                freed.retain(|freed_ptr| *freed_ptr != ptr);
+                live.push((ptr, *size));
+            }
+            Op::Deallocate | Op::Validate if live.is_empty() && freed.is_empty() => {}
+            Op::Deallocate => {
+                // the size selects a live or a freed pointer
+                let index: usize = *size as usize % (live.len() + freed.len());
+                let ptr: u32 = if index < live.len() {
+                    live[index].0
+                } else {
+                    freed[index - live.len()]
+                };
+                let code: i32 = mm
+                    .deallocate
+                    .call(&mut mm.store, ptr)
+                    .map_err(|e| TestCaseError::fail(e.to_string()))?;
+                if index < live.len() {
+                    // (2) deallocating a live pointer succeeds
+                    prop_assert_eq!(code, DEALLOCATE_SUCCESS);
+                    live.remove(index);
+                    freed.push(ptr);
+                } else {
+                    // (3) deallocating a freed pointer is reported as such
+                    prop_assert_eq!(code, already_freed_code);
+                }
+            }
+            Op::Validate => {
+                let index: usize = *size as usize % (live.len() + freed.len());
+                let (ptr, expected_size): (u32, u32) = if index < live.len() {
+                    live[index]
+                } else {
+                    (freed[index - live.len()], 0)
+                };
+                // (1) validating a live pointer returns its size, a freed pointer is invalid
+                let validated_size: u32 = mm
+                    .validate_pointer
+                    .call(&mut mm.store, ptr)
+                    .map_err(|e| TestCaseError::fail(e.to_string()))?;
+                prop_assert_eq!(validated_size, expected_size);
+            }
+        }
+        // (4) the tracked memory areas fit into the linear memory
+        let allocated_bytes: u32 = mm
+            .allocated_bytes
+            .call(&mut mm.store, ())
+            .map_err(|e| TestCaseError::fail(e.to_string()))?;
+        prop_assert_eq!(
+            allocated_bytes as u64,
+            live.iter().map(|(_, size)| *size as u64).sum::<u64>()
+        );
+        prop_assert!(allocated_bytes as usize <= mm.memory.data_size(&mm.store));
+    }
+    Ok(())
+}
+
+/// Runs random sequences of operations on a module
+/// # Arguments
+/// * `path` - path of the module
+/// * `already_freed_code` - return code of wasm_deallocate for memory that has already been deallocated
+fn run_memory_management_proptest(path: PathBuf, already_freed_code: i32) {
+    let engine: Engine = Engine::default();
+    let module: Module = Module::from_file(&engine, path).unwrap();
+    let mut runner: TestRunner = TestRunner::new_with_rng(
+        Config {
+            cases: CASES,
+            ..Config::default()
+        },
+        TestRng::from_seed(RngAlgorithm::ChaCha, &SEED),
+    );
+    runner
+        .run(&ops_strategy(), |ops| {
+            check_ops(&engine, &module, &ops, already_freed_code)
+        })
+        .unwrap();
+}
+
+#[test]
+fn memory_management_module1() {
+    run_memory_management_proptest(
+        module_path("wasm-module1", "wasm_module1.wasm"),
+        DEALLOCATE_ALREADY_FREED,
+    );
+}
+
+#[test]
+fn memory_management_module2() {
+    run_memory_management_proptest(
+        module_path("wasm-module2", "wasm_module2.wasm"),
+        DEALLOCATE_ALREADY_FREED,
+    );
+}
+
+#[test]
+fn memory_management_module1_slab() {
+    run_memory_management_proptest(slab_module_path(), DEALLOCATE_NOT_ALLOCATED);
+}
+
+#[test]
+fn slab_rejects_allocations_beyond_its_capacity() {
+    let engine: Engine = Engine::default();
+    let module: Module = Module::from_file(&engine, slab_module_path()).unwrap();
+    let mut mm: MemoryManagement = MemoryManagement::new(&engine, &module).unwrap();
+    let ptrs: Vec<u32> = (0..SLAB_CAPACITY)
+        .map(|_| mm.allocate.call(&mut mm.store, 16).unwrap())
+        .collect();
+    assert!(ptrs.iter().all(|ptr| *ptr != 0));
+    // all entries of the slab are in use
+    assert_eq!(mm.allocate.call(&mut mm.store, 16).unwrap(), 0);
+    // a deallocated entry can be used again
+    assert_eq!(
+        mm.deallocate.call(&mut mm.store, ptrs[0]).unwrap(),
+        DEALLOCATE_SUCCESS
+    );
+    assert_ne!(mm.allocate.call(&mut mm.store, 16).unwrap(), 0);
+}
@@ -0,0 +1,46 @@
+//! Tests of the normalization of text fields with wasm_memory_normalize_text_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{Array, AsArray, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+
+mod common;
+use common::{call_arrow_function, module_path, serialize};
+
+#[test]
+fn text_is_trimmed_lowercased_and_whitespace_collapsed() {
+    let path = module_path();
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1, 2])),
+            Arc::new(StringArray::from(vec![Some("  HELLO\tWORLD  "), None])),
+        ],
+    )
+    .unwrap();
+    let opts: &str =
+        r#"{"trim": true, "lowercase": true, "nfc": true, "collapse_whitespace": true}"#;
+    let result_data: Vec<u8> = call_arrow_function(
+        &path,
+        "wasm_memory_normalize_text_arrow",
+        &[&serialize(&batch), opts.as_bytes()],
+    )
+    .unwrap();
+    let result_batches: Vec<RecordBatch> = StreamReader::try_new(result_data.as_slice(), None)
+        .unwrap()
+        .collect::<Result<Vec<RecordBatch>, _>>()
+        .unwrap();
+    let contents: &StringArray = result_batches[0]
+        .column_by_name("content")
+        .unwrap()
+        .as_string::<i32>();
+    assert_eq!(contents.value(0), "hello world");
+    assert!(contents.is_null(1));
+}
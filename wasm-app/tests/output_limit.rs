@@ -0,0 +1,103 @@
+//! Tests of the limit of the size of results (wasm_set_max_output_bytes) of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{Float64Array, StringArray, TimestampSecondArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Module, Store, TypedFunc};
+
+mod common;
+use common::{call_arrow_function_on_instance, instantiate, meta_data, module_path, serialize};
+
+/// Example data of wasm-app
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456}
+///
+/// returns the data in Arrow IPC format
+fn example_data() -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+#[test]
+fn results_exceeding_the_limit_are_rejected() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let set_max_output_bytes: TypedFunc<u32, ()> = instance
+        .get_typed_func(&mut store, "wasm_set_max_output_bytes")
+        .unwrap();
+    let result_size: usize = call_arrow_function_on_instance(
+        &mut store,
+        instance,
+        "wasm_memory_process_data_arrow",
+        &[&meta_data(), &example_data()],
+    )
+    .unwrap()
+    .len();
+    set_max_output_bytes
+        .call(&mut store, result_size as u32 - 1)
+        .unwrap();
+    let error = call_arrow_function_on_instance(
+        &mut store,
+        instance,
+        "wasm_memory_process_data_arrow",
+        &[&meta_data(), &example_data()],
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("status -40"), "{error}");
+    // the error message is shorter than the limit
+    let last_error: Vec<u8> =
+        call_arrow_function_on_instance(&mut store, instance, "wasm_last_error", &[]).unwrap();
+    assert_eq!(
+        String::from_utf8(last_error).unwrap(),
+        format!(
+            "Result size {result_size} exceeds limit {}",
+            result_size - 1
+        )
+    );
+    // results of the size of the limit are accepted
+    set_max_output_bytes
+        .call(&mut store, result_size as u32)
+        .unwrap();
+    call_arrow_function_on_instance(
+        &mut store,
+        instance,
+        "wasm_memory_process_data_arrow",
+        &[&meta_data(), &example_data()],
+    )
+    .unwrap();
+    // 0 removes the limit
+    set_max_output_bytes.call(&mut store, 0).unwrap();
+    call_arrow_function_on_instance(
+        &mut store,
+        instance,
+        "wasm_memory_process_data_arrow",
+        &[&meta_data(), &example_data()],
+    )
+    .unwrap();
+}
@@ -0,0 +1,114 @@
+//! Tests that data read back from Parquet is processed by wasm_memory_process_data_arrow of wasm-module2 like the original data in Arrow IPC format. Parquet is written and read by the application, only the processing is done by the module
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use arrow::array::{Float64Array, StringArray, TimestampSecondArray, UInt64Array};
+use arrow::datatypes::SchemaRef;
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use parquet::arrow::arrow_writer::ArrowWriter;
+
+mod common;
+use common::{meta_data, module_path, process_data_arrow, serialize};
+
+/// Number of rows read from the Parquet file per record batch
+const PARQUET_BATCH_SIZE: usize = 1024;
+
+/// Example data of wasm-app (see create_arrow_example_data) with a fixed creation time
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456}
+///
+/// returns the data as record batch
+fn example_batch() -> RecordBatch {
+    let metadata: HashMap<String, String> = HashMap::from([
+        ("source".to_string(), "wasm-app".to_string()),
+        ("version".to_string(), "1.0.0".to_string()),
+        ("created_at".to_string(), "1641038400".to_string()),
+    ]);
+    let schema = Schema::new_with_metadata(
+        vec![
+            Field::new("id", DataType::UInt64, false),
+            Field::new("content", DataType::Utf8, false),
+            Field::new("title", DataType::Utf8, false),
+            Field::new(
+                "date",
+                DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+                false,
+            ),
+            Field::new("score", DataType::Float64, false),
+        ],
+        metadata,
+    );
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap()
+}
+
+/// Processes data with wasm_memory_process_data_arrow
+/// # Arguments
+/// * `path` - path of the module
+/// * `batch` - data
+///
+/// returns the result
+fn process(path: &PathBuf, batch: &RecordBatch) -> Vec<RecordBatch> {
+    let result: Vec<u8> = process_data_arrow(path, &meta_data(), &serialize(batch)).unwrap();
+    StreamReader::try_new(result.as_slice(), None)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+#[test]
+fn data_read_back_from_parquet_is_processed_like_the_original_data() {
+    let path = module_path();
+    let original_batch: RecordBatch = example_batch();
+    // write the data to a Parquet file and read it back
+    let parquet_path: PathBuf = std::env::temp_dir().join(format!(
+        "wasm-app-round-trip-{}.parquet",
+        std::process::id()
+    ));
+    let mut parquet_writer = ArrowWriter::try_new(
+        File::create(&parquet_path).unwrap(),
+        original_batch.schema(),
+        None,
+    )
+    .unwrap();
+    parquet_writer.write(&original_batch).unwrap();
+    parquet_writer.close().unwrap();
+    let parquet_reader_builder =
+        ParquetRecordBatchReaderBuilder::try_new(File::open(&parquet_path).unwrap()).unwrap();
+    // the record batches read from Parquet do not contain the schema metadata, e.g. the provenance of the data, but the schema of the file does
+    let parquet_schema: SchemaRef = parquet_reader_builder.schema().clone();
+    let parquet_reader: ParquetRecordBatchReader = parquet_reader_builder
+        .with_batch_size(PARQUET_BATCH_SIZE)
+        .build()
+        .unwrap();
+    let recovered_batches: Vec<RecordBatch> = parquet_reader.collect::<Result<_, _>>().unwrap();
+    std::fs::remove_file(&parquet_path).unwrap();
+    assert_eq!(recovered_batches.len(), 1);
+    let recovered_batch: RecordBatch = recovered_batches
+        .into_iter()
+        .next()
+        .unwrap()
+        .with_schema(parquet_schema)
+        .unwrap();
+    // the schema, including the timezone of the date, survives the round trip
+    assert_eq!(recovered_batch, original_batch);
+    assert_eq!(
+        process(&path, &recovered_batch),
+        process(&path, &original_batch)
+    );
+}
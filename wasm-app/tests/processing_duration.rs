@@ -0,0 +1,80 @@
+//! Tests of the duration of the processing measured by wasm-module2 (wasm_get_last_processing_duration_ns)
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use arrow::array::{Float64Array, StringArray, TimestampSecondArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Module, Store, TypedFunc};
+
+mod common;
+use common::{call_arrow_function_on_instance, instantiate, meta_data, module_path, serialize};
+
+/// Example data of wasm-app
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456}
+///
+/// returns the data in Arrow IPC format
+fn example_data() -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+#[test]
+fn processing_duration_is_part_of_the_call_duration() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let get_last_processing_duration_ns: TypedFunc<(), u64> = instance
+        .get_typed_func(&mut store, "wasm_get_last_processing_duration_ns")
+        .unwrap();
+    assert_eq!(
+        get_last_processing_duration_ns
+            .call(&mut store, ())
+            .unwrap(),
+        0
+    );
+    let call_start: Instant = Instant::now();
+    call_arrow_function_on_instance(
+        &mut store,
+        instance,
+        "wasm_memory_process_data_arrow",
+        &[&meta_data(), &example_data()],
+    )
+    .unwrap();
+    let call_duration: Duration = call_start.elapsed();
+    let processing_duration: Duration = Duration::from_nanos(
+        get_last_processing_duration_ns
+            .call(&mut store, ())
+            .unwrap(),
+    );
+    assert!(processing_duration > Duration::ZERO);
+    assert!(
+        processing_duration < call_duration,
+        "{processing_duration:?} >= {call_duration:?}"
+    );
+}
@@ -0,0 +1,95 @@
+//! Tests of the recording (WASM_RECORD=1) and replay (--replay) of calls to functions of wasm-module2 by the application
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::Arc;
+
+use arrow::array::{StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use arrow::util::pretty::pretty_format_batches;
+
+mod common;
+use common::{module_path, serialize};
+
+/// Runs the application in the directory of the crate, so that it finds its sandbox configuration
+/// # Arguments
+/// * `args` - arguments of the command line
+/// * `envs` - environment variables as (name, value)
+///
+/// returns the output of the application. Panics if it fails
+fn run_app(args: &[&str], envs: &[(&str, &Path)]) -> Output {
+    let output: Output = Command::new(env!("CARGO_BIN_EXE_wasm-app"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(args)
+        .envs(envs.iter().copied())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+#[test]
+fn replayed_call_returns_recorded_result() {
+    let path = module_path();
+    let dir: PathBuf = std::env::temp_dir().join(format!("wasm-app-replay-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input_path: PathBuf = dir.join("input.arrow");
+    let output_path: PathBuf = dir.join("output.arrow");
+    let recording_path: PathBuf = dir.join("recording.jsonl");
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("category", DataType::Utf8, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1, 2, 3, 4])),
+            Arc::new(StringArray::from(vec!["a", "b", "a", "c"])),
+        ],
+    )
+    .unwrap();
+    std::fs::write(&input_path, serialize(&batch)).unwrap();
+    // record
+    run_app(
+        &[
+            "call",
+            path.to_str().unwrap(),
+            "wasm_memory_column_cardinality_arrow",
+            "--input",
+            input_path.to_str().unwrap(),
+            "--output",
+            output_path.to_str().unwrap(),
+        ],
+        &[
+            ("WASM_RECORD", Path::new("1")),
+            ("WASM_RECORD_PATH", &recording_path),
+        ],
+    );
+    // replay
+    let replay_output: Output = run_app(
+        &[
+            "--replay",
+            recording_path.to_str().unwrap(),
+            "--replay-module",
+            path.to_str().unwrap(),
+        ],
+        &[],
+    );
+    let recorded_batches: Vec<RecordBatch> =
+        StreamReader::try_new(std::fs::read(&output_path).unwrap().as_slice(), None)
+            .unwrap()
+            .collect::<Result<Vec<RecordBatch>, _>>()
+            .unwrap();
+    let expected: String = pretty_format_batches(&recorded_batches)
+        .unwrap()
+        .to_string();
+    let replayed: String = String::from_utf8(replay_output.stdout).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(replayed.trim_end(), expected.trim_end());
+}
@@ -0,0 +1,167 @@
+//! Tests of the rolling correlation between two numeric fields by wasm_memory_rolling_correlation_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{Array, AsArray, Float64Array};
+use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Memory, Module, Store, TypedFunc};
+
+mod common;
+use common::{instantiate, module_path, serialize};
+
+/// Parameters of wasm_memory_rolling_correlation_arrow: position and size of the data, of the name of the first field and of the name of the second field, and the window size
+type RollingCorrelationParams = (u32, u32, u32, u32, u32, u32, u32);
+
+/// Calls wasm_memory_rolling_correlation_arrow of a new instance of the module with the fields a and b
+/// # Arguments
+/// * `data` - data in Arrow IPC format
+/// * `window` - number of rows of a window
+///
+/// returns the status and the result data
+fn rolling_correlation(data: &[u8], window: u32) -> (i32, Vec<u8>) {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let memory: Memory = instance.get_memory(&mut store, "memory").unwrap();
+    let allocate: TypedFunc<u32, u32> = instance
+        .get_typed_func(&mut store, "wasm_allocate")
+        .unwrap();
+    let rolling_correlation: TypedFunc<RollingCorrelationParams, u32> = instance
+        .get_typed_func(&mut store, "wasm_memory_rolling_correlation_arrow")
+        .unwrap();
+    let mut params: Vec<u32> = Vec::with_capacity(6);
+    for input in [data, b"a", b"b"] {
+        let input_ptr: u32 = allocate.call(&mut store, input.len() as u32).unwrap();
+        memory.write(&mut store, input_ptr as usize, input).unwrap();
+        params.push(input_ptr);
+        params.push(input.len() as u32);
+    }
+    let result_ptr: u32 = rolling_correlation
+        .call(
+            &mut store,
+            (
+                params[0], params[1], params[2], params[3], params[4], params[5], window,
+            ),
+        )
+        .unwrap();
+    // WasmResult: status at byte 0, data_ptr at byte 4, data_len at byte 8
+    let mut wasm_result = [0u8; 12];
+    memory
+        .read(&store, result_ptr as usize, &mut wasm_result)
+        .unwrap();
+    let status: i32 = i32::from_le_bytes(wasm_result[0..4].try_into().unwrap());
+    let result_data_ptr: u32 = u32::from_le_bytes(wasm_result[4..8].try_into().unwrap());
+    let result_data_len: u32 = u32::from_le_bytes(wasm_result[8..12].try_into().unwrap());
+    let mut result_data: Vec<u8> = vec![0u8; result_data_len as usize];
+    memory
+        .read(&store, result_data_ptr as usize, &mut result_data)
+        .unwrap();
+    (status, result_data)
+}
+
+/// Data with two Float64 fields
+/// # Arguments
+/// * `a` - values of the field a
+/// * `b` - values of the field b
+///
+/// returns the data in Arrow IPC format
+fn data(a: Vec<Option<f64>>, b: Vec<Option<f64>>) -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("a", DataType::Float64, true),
+        Field::new("b", DataType::Float64, true),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Float64Array::from(a)),
+            Arc::new(Float64Array::from(b)),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+#[test]
+fn correlation_is_computed_over_the_window() {
+    let (status, result) = rolling_correlation(
+        &data(
+            vec![
+                Some(1.0),
+                Some(2.0),
+                Some(3.0),
+                Some(4.0),
+                Some(3.0),
+                None,
+                Some(5.0),
+                Some(5.0),
+                Some(5.0),
+            ],
+            vec![
+                Some(2.0),
+                Some(4.0),
+                Some(6.0),
+                Some(1.0),
+                Some(5.0),
+                Some(1.0),
+                Some(2.0),
+                Some(3.0),
+                Some(4.0),
+            ],
+        ),
+        3,
+    );
+    assert_eq!(status, 0);
+    let batch: RecordBatch = StreamReader::try_new(result.as_slice(), None)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(batch.schema().field(2).name(), "rolling_correlation");
+    let correlation: &Float64Array = batch.column(2).as_primitive::<Float64Type>();
+    // incomplete windows
+    assert!(correlation.is_null(0));
+    assert!(correlation.is_null(1));
+    // perfectly correlated window
+    assert!((correlation.value(2) - 1.0).abs() < 1e-9);
+    // {2, 3, 4} and {4, 6, 1}: covariance -1.5, variances 1 and 6.333...
+    assert!((correlation.value(3) + 1.5 / (19.0f64 / 3.0).sqrt()).abs() < 1e-9);
+    // {3, 4, 3} and {6, 1, 5}
+    assert!((correlation.value(4) + 0.9819805060619656).abs() < 1e-9);
+    // windows containing the null value
+    assert!(correlation.is_null(5));
+    assert!(correlation.is_null(6));
+    assert!(correlation.is_null(7));
+    // the field a is constant
+    assert!(correlation.is_null(8));
+}
+
+#[test]
+fn correlation_of_values_of_small_magnitude_is_computed() {
+    let (status, result) = rolling_correlation(
+        &data(
+            vec![Some(1e-7), Some(2e-7), Some(3e-7)],
+            vec![Some(3e-9), Some(2e-9), Some(1e-9)],
+        ),
+        3,
+    );
+    assert_eq!(status, 0);
+    let batch: RecordBatch = StreamReader::try_new(result.as_slice(), None)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let correlation: &Float64Array = batch.column(2).as_primitive::<Float64Type>();
+    // small values are not constant
+    assert!(correlation.is_valid(2));
+    assert!((correlation.value(2) + 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn windows_of_a_single_row_are_rejected() {
+    let (status, _) = rolling_correlation(&data(vec![Some(1.0)], vec![Some(1.0)]), 1);
+    assert_ne!(status, 0);
+}
@@ -0,0 +1,101 @@
+//! Tests of the rolling statistics of all numeric fields by wasm_memory_rolling_stats_all_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{Array, AsArray, Float64Array, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Memory, Module, Store, TypedFunc};
+
+mod common;
+use common::{instantiate, module_path, serialize};
+
+/// Calls wasm_memory_rolling_stats_all_arrow of a new instance of the module
+/// # Arguments
+/// * `data` - data in Arrow IPC format
+/// * `window_size` - number of rows of a window
+///
+/// returns the result data
+fn rolling_stats_all(data: &[u8], window_size: u32) -> RecordBatch {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let memory: Memory = instance.get_memory(&mut store, "memory").unwrap();
+    let allocate: TypedFunc<u32, u32> = instance
+        .get_typed_func(&mut store, "wasm_allocate")
+        .unwrap();
+    let rolling_stats_all: TypedFunc<(u32, u32, u32), u32> = instance
+        .get_typed_func(&mut store, "wasm_memory_rolling_stats_all_arrow")
+        .unwrap();
+    let data_ptr: u32 = allocate.call(&mut store, data.len() as u32).unwrap();
+    memory.write(&mut store, data_ptr as usize, data).unwrap();
+    let result_ptr: u32 = rolling_stats_all
+        .call(&mut store, (data_ptr, data.len() as u32, window_size))
+        .unwrap();
+    // WasmResult: status at byte 0, data_ptr at byte 4, data_len at byte 8
+    let mut wasm_result = [0u8; 12];
+    memory
+        .read(&store, result_ptr as usize, &mut wasm_result)
+        .unwrap();
+    assert_eq!(i32::from_le_bytes(wasm_result[0..4].try_into().unwrap()), 0);
+    let result_data_ptr: u32 = u32::from_le_bytes(wasm_result[4..8].try_into().unwrap());
+    let result_data_len: u32 = u32::from_le_bytes(wasm_result[8..12].try_into().unwrap());
+    let mut result_data: Vec<u8> = vec![0u8; result_data_len as usize];
+    memory
+        .read(&store, result_data_ptr as usize, &mut result_data)
+        .unwrap();
+    StreamReader::try_new(result_data.as_slice(), None)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn statistics_are_computed_for_float64_and_int64_fields() {
+    let schema = Schema::new(vec![
+        Field::new("name", DataType::Utf8, false),
+        Field::new("price", DataType::Float64, false),
+        Field::new("volume", DataType::Int64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from(vec!["a", "b", "c", "d"])),
+            Arc::new(Float64Array::from(vec![1.0, 3.0, 2.0, 6.0])),
+            Arc::new(Int64Array::from(vec![10, 20, 30, 40])),
+        ],
+    )
+    .unwrap();
+    let result = rolling_stats_all(&serialize(&batch), 2);
+    // 3 fields of the data and 4 statistics for each of the 2 numeric fields
+    assert_eq!(result.num_columns(), 11);
+    assert!(result.column_by_name("name_rolling_mean").is_none());
+    let statistic = |name: &str| {
+        result
+            .column_by_name(name)
+            .unwrap()
+            .as_primitive::<Float64Type>()
+            .clone()
+    };
+    // the first row has insufficient history
+    for name in [
+        "price_rolling_mean",
+        "price_rolling_min",
+        "price_rolling_max",
+        "price_rolling_std",
+    ] {
+        assert!(statistic(name).is_null(0), "{name}");
+    }
+    // window of the last row: 2.0, 6.0
+    assert_eq!(statistic("price_rolling_mean").value(3), 4.0);
+    assert_eq!(statistic("price_rolling_min").value(3), 2.0);
+    assert_eq!(statistic("price_rolling_max").value(3), 6.0);
+    assert_eq!(statistic("price_rolling_std").value(3), 2.0);
+    // window of the second row: 10, 20
+    assert_eq!(statistic("volume_rolling_mean").value(1), 15.0);
+    assert_eq!(statistic("volume_rolling_std").value(1), 5.0);
+}
@@ -0,0 +1,117 @@
+//! Tests of the rolling sum and mean over a numeric field by wasm_memory_rolling_window_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{AsArray, Float64Array};
+use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Memory, Module, Store, TypedFunc};
+
+mod common;
+use common::{instantiate, module_path, serialize};
+
+/// Parameters of wasm_memory_rolling_window_arrow: position and size of the data and of the name of the field, the window size and position and size of the window function
+type RollingWindowParams = (u32, u32, u32, u32, u32, u32, u32);
+
+/// Calls wasm_memory_rolling_window_arrow of a new instance of the module with the field value
+/// # Arguments
+/// * `values` - values of the field value
+/// * `window_size` - number of rows of a window
+/// * `function` - window function, ie "sum" or "mean"
+///
+/// returns the field windowed_result of the result data
+fn rolling_window(values: Vec<Option<f64>>, window_size: u32, function: &str) -> Float64Array {
+    let path = module_path();
+    let schema = Schema::new(vec![Field::new("value", DataType::Float64, true)]);
+    let batch =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(Float64Array::from(values))]).unwrap();
+    let data: Vec<u8> = serialize(&batch);
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let memory: Memory = instance.get_memory(&mut store, "memory").unwrap();
+    let allocate: TypedFunc<u32, u32> = instance
+        .get_typed_func(&mut store, "wasm_allocate")
+        .unwrap();
+    let rolling_window: TypedFunc<RollingWindowParams, u32> = instance
+        .get_typed_func(&mut store, "wasm_memory_rolling_window_arrow")
+        .unwrap();
+    let mut params: Vec<u32> = Vec::with_capacity(6);
+    for input in [data.as_slice(), b"value", function.as_bytes()] {
+        let input_ptr: u32 = allocate.call(&mut store, input.len() as u32).unwrap();
+        memory.write(&mut store, input_ptr as usize, input).unwrap();
+        params.push(input_ptr);
+        params.push(input.len() as u32);
+    }
+    let result_ptr: u32 = rolling_window
+        .call(
+            &mut store,
+            (
+                params[0],
+                params[1],
+                params[2],
+                params[3],
+                window_size,
+                params[4],
+                params[5],
+            ),
+        )
+        .unwrap();
+    // WasmResult: status at byte 0, data_ptr at byte 4, data_len at byte 8
+    let mut wasm_result = [0u8; 12];
+    memory
+        .read(&store, result_ptr as usize, &mut wasm_result)
+        .unwrap();
+    assert_eq!(i32::from_le_bytes(wasm_result[0..4].try_into().unwrap()), 0);
+    let result_data_ptr: u32 = u32::from_le_bytes(wasm_result[4..8].try_into().unwrap());
+    let result_data_len: u32 = u32::from_le_bytes(wasm_result[8..12].try_into().unwrap());
+    let mut result_data: Vec<u8> = vec![0u8; result_data_len as usize];
+    memory
+        .read(&store, result_data_ptr as usize, &mut result_data)
+        .unwrap();
+    let result: RecordBatch = StreamReader::try_new(result_data.as_slice(), None)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.schema().field(1).name(), "windowed_result");
+    result.column(1).as_primitive::<Float64Type>().clone()
+}
+
+#[test]
+fn sum_is_computed_over_the_window() {
+    let sum = rolling_window(
+        vec![Some(1.0), Some(2.0), Some(3.0), None, Some(4.0), Some(5.0)],
+        2,
+        "sum",
+    );
+    assert_eq!(
+        sum,
+        Float64Array::from(vec![None, Some(3.0), Some(5.0), None, None, Some(9.0)])
+    );
+}
+
+#[test]
+fn non_finite_values_only_affect_their_windows() {
+    for non_finite in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        let mean = rolling_window(
+            vec![
+                Some(1.0),
+                Some(2.0),
+                Some(non_finite),
+                Some(4.0),
+                Some(6.0),
+                Some(8.0),
+            ],
+            2,
+            "mean",
+        );
+        assert_eq!(
+            mean,
+            Float64Array::from(vec![None, Some(1.5), None, None, Some(5.0), Some(7.0)]),
+            "{non_finite}"
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//! Tests of the run-end encoding of results enabled with wasm_set_use_run_encoding of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{Array, Float64Array, RunArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Int32Type, Schema};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use wasmtime::{Engine, Module};
+
+mod common;
+use common::{call_arrow_function_on_instance, instantiate, module_path, serialize};
+
+/// Number of rows of the data
+const ROWS: usize = 10000;
+
+/// Number of consecutive rows with the same score
+const RUN_LENGTH: usize = 1000;
+
+#[test]
+fn repetitive_scores_are_run_end_encoded() {
+    let path = module_path();
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    // the score alternates between two values every RUN_LENGTH rows
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from_iter_values(1..=ROWS as u64)),
+            Arc::new(Float64Array::from_iter_values((0..ROWS).map(|i| {
+                if (i / RUN_LENGTH).is_multiple_of(2) {
+                    0.5
+                } else {
+                    1.0
+                }
+            }))),
+        ],
+    )
+    .unwrap();
+    let engine: Engine = Engine::default();
+    let module: Module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance) = instantiate(&engine, &module).unwrap();
+    instance
+        .get_typed_func::<(u32, u32), ()>(&mut store, "wasm_set_use_run_encoding")
+        .unwrap()
+        .call(&mut store, (1, 100))
+        .unwrap();
+    let result_data: Vec<u8> = call_arrow_function_on_instance(
+        &mut store,
+        instance,
+        "wasm_memory_project_arrow",
+        &[&serialize(&batch), b"id,score"],
+    )
+    .unwrap();
+    let result_batches: Vec<RecordBatch> = StreamReader::try_new(result_data.as_slice(), None)
+        .unwrap()
+        .collect::<Result<Vec<RecordBatch>, _>>()
+        .unwrap();
+    // ids have no runs and are not encoded
+    assert_eq!(
+        result_batches[0].column_by_name("id").unwrap().data_type(),
+        &DataType::UInt64
+    );
+    let scores: &RunArray<Int32Type> = result_batches[0]
+        .column_by_name("score")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<RunArray<Int32Type>>()
+        .unwrap();
+    assert_eq!(scores.len(), ROWS);
+    assert_eq!(scores.values().len(), ROWS / RUN_LENGTH);
+    assert_eq!(scores.run_ends().values().len(), ROWS / RUN_LENGTH);
+}
@@ -0,0 +1,80 @@
+//! Tests of the propagation of the schema metadata through wasm_memory_process_data_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{Float64Array, StringArray, TimestampSecondArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+
+mod common;
+use common::{meta_data, module_path, process_data_arrow, serialize};
+
+/// Data expected by the command "test" with schema metadata
+/// # Arguments
+/// * `metadata` - schema metadata of the data
+///
+/// returns the data in Arrow IPC format
+fn data(metadata: HashMap<String, String>) -> Vec<u8> {
+    let schema = Schema::new_with_metadata(
+        vec![
+            Field::new("id", DataType::UInt64, false),
+            Field::new("content", DataType::Utf8, false),
+            Field::new("title", DataType::Utf8, false),
+            Field::new(
+                "date",
+                DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+                false,
+            ),
+            Field::new("score", DataType::Float64, false),
+        ],
+        metadata,
+    );
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+#[test]
+fn schema_metadata_survives_round_trip() {
+    let path = module_path();
+    let metadata: HashMap<String, String> = HashMap::from([
+        ("source".to_string(), "wasm-app".to_string()),
+        ("version".to_string(), "1.0.0".to_string()),
+        ("created_at".to_string(), "1641038400".to_string()),
+    ]);
+    let result_data: Vec<u8> =
+        process_data_arrow(&path, &meta_data(), &data(metadata.clone())).unwrap();
+    let stream_reader = StreamReader::try_new(result_data.as_slice(), None).unwrap();
+    let result_schema = stream_reader.schema();
+    let result_metadata: &HashMap<String, String> = result_schema.metadata();
+    // the metadata of the application is kept and the provenance of the processing is added
+    for (key, value) in &metadata {
+        assert_eq!(
+            result_metadata.get(key),
+            Some(value),
+            "metadata '{key}' was not propagated"
+        );
+    }
+    assert_eq!(
+        result_metadata.get("processed_by").map(String::as_str),
+        Some("wasm-module2")
+    );
+    assert_eq!(
+        result_metadata
+            .get("processing_version")
+            .map(String::as_str),
+        Some("1.0.0")
+    );
+}
@@ -0,0 +1,79 @@
+//! Tests of strings as views (Utf8View) in the data of wasm_memory_process_data_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, Float64Array, StringArray, StringViewBuilder, TimestampSecondArray, UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+
+mod common;
+use common::{meta_data, module_path, process_data_arrow, serialize};
+
+/// Data expected by the command "test"
+/// # Arguments
+/// * `string_data_type` - type of the fields content and title, ie Utf8 or Utf8View
+///
+/// returns the data in Arrow IPC format
+fn data(string_data_type: DataType) -> Vec<u8> {
+    let strings = |value: &str| -> ArrayRef {
+        match string_data_type {
+            DataType::Utf8View => {
+                let mut builder = StringViewBuilder::new();
+                builder.append_value(value);
+                Arc::new(builder.finish())
+            }
+            _ => Arc::new(StringArray::from(vec![value])),
+        }
+    };
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", string_data_type.clone(), false),
+        Field::new("title", string_data_type.clone(), false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            // longer than 12 bytes, ie not inlined in the view
+            strings("this is a test"),
+            // up to 12 bytes, ie inlined in the view
+            strings("test"),
+            // 2022-01-01T12:00:00Z
+            Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Deserializes the result of the module
+/// # Arguments
+/// * `result_data` - result data in Arrow IPC format
+///
+/// returns the record batches of the result
+fn deserialize(result_data: &[u8]) -> Vec<RecordBatch> {
+    StreamReader::try_new(result_data, None)
+        .unwrap()
+        .collect::<Result<Vec<RecordBatch>, _>>()
+        .unwrap()
+}
+
+#[test]
+fn utf8_view_is_processed_like_utf8() {
+    let path = module_path();
+    let result_utf8: Vec<u8> =
+        process_data_arrow(&path, &meta_data(), &data(DataType::Utf8)).unwrap();
+    let result_utf8_view: Vec<u8> =
+        process_data_arrow(&path, &meta_data(), &data(DataType::Utf8View)).unwrap();
+    assert_eq!(deserialize(&result_utf8_view), deserialize(&result_utf8));
+}
@@ -0,0 +1,54 @@
+//! Tests of the isolation of the memory areas of tenants (wasm_set_tenant_id) of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Module, Store, TypedFunc};
+
+mod common;
+use common::{instantiate, module_path};
+
+/// Return code of wasm_deallocate if the memory has been deallocated
+const DEALLOCATE_SUCCESS: i32 = 0;
+
+/// Return code of wasm_deallocate if the memory has never been allocated (for the current tenant)
+const DEALLOCATE_NOT_ALLOCATED: i32 = -1;
+
+/// Size of the memory area allocated by a tenant
+const SIZE: u32 = 1024;
+
+#[test]
+fn tenants_cannot_free_allocations_of_other_tenants() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    let set_tenant_id: TypedFunc<u32, ()> = instance
+        .get_typed_func(&mut store, "wasm_set_tenant_id")
+        .unwrap();
+    let allocate: TypedFunc<u32, u32> = instance
+        .get_typed_func(&mut store, "wasm_allocate")
+        .unwrap();
+    let deallocate: TypedFunc<u32, i32> = instance
+        .get_typed_func(&mut store, "wasm_deallocate")
+        .unwrap();
+    let validate_pointer: TypedFunc<u32, u32> = instance
+        .get_typed_func(&mut store, "wasm_validate_pointer")
+        .unwrap();
+    // tenant 1 allocates memory
+    set_tenant_id.call(&mut store, 1).unwrap();
+    let ptr: u32 = allocate.call(&mut store, SIZE).unwrap();
+    assert_eq!(validate_pointer.call(&mut store, ptr).unwrap(), SIZE);
+    // tenant 2 can neither validate nor free it
+    set_tenant_id.call(&mut store, 2).unwrap();
+    assert_eq!(validate_pointer.call(&mut store, ptr).unwrap(), 0);
+    assert_eq!(
+        deallocate.call(&mut store, ptr).unwrap(),
+        DEALLOCATE_NOT_ALLOCATED
+    );
+    // tenant 1 still owns it
+    set_tenant_id.call(&mut store, 1).unwrap();
+    assert_eq!(validate_pointer.call(&mut store, ptr).unwrap(), SIZE);
+    assert_eq!(
+        deallocate.call(&mut store, ptr).unwrap(),
+        DEALLOCATE_SUCCESS
+    );
+}
@@ -0,0 +1,98 @@
+//! Tests of the normalization of the precision of the field date by wasm_memory_process_data_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, Float64Array, StringArray, TimestampMicrosecondArray, TimestampMillisecondArray,
+    TimestampNanosecondArray, TimestampSecondArray, UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+
+mod common;
+use common::{meta_data, module_path, process_data_arrow, serialize};
+
+/// 2022-01-01T12:00:00Z in seconds since the UNIX epoch
+const EXAMPLE_DATE_SECONDS: i64 = 1_641_038_400;
+
+/// Example data of wasm-app with the date 2022-01-01T12:00:00Z in a given precision
+/// # Arguments
+/// * `dates` - column of the date with the value 2022-01-01T12:00:00Z
+///
+/// returns the data in Arrow IPC format
+fn example_data(dates: ArrayRef) -> Vec<u8> {
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new("date", dates.data_type().clone(), false),
+        Field::new("score", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(vec![1])),
+            Arc::new(StringArray::from(vec!["this is a test"])),
+            Arc::new(StringArray::from(vec!["test"])),
+            dates,
+            Arc::new(Float64Array::from(vec![1.123456f64])),
+        ],
+    )
+    .unwrap();
+    serialize(&batch)
+}
+
+/// Processes the example data with the command "test" and returns the schema metadata original_timestamp_precision of the result
+/// # Arguments
+/// * `dates` - column of the date with the value 2022-01-01T12:00:00Z
+///
+/// returns the value of original_timestamp_precision. It is None if the result does not contain it
+fn original_timestamp_precision(dates: ArrayRef) -> Option<String> {
+    let path = module_path();
+    // the processing fails if the normalized date is not 2022-01-01T12:00:00Z
+    let result: Vec<u8> = process_data_arrow(&path, &meta_data(), &example_data(dates)).unwrap();
+    let stream_reader = StreamReader::try_new(result.as_slice(), None).unwrap();
+    stream_reader
+        .schema()
+        .metadata()
+        .get("original_timestamp_precision")
+        .cloned()
+}
+
+#[test]
+fn date_in_seconds_is_processed_without_normalization() {
+    let dates: ArrayRef =
+        Arc::new(TimestampSecondArray::from(vec![EXAMPLE_DATE_SECONDS]).with_timezone("+00:00"));
+    let precision = original_timestamp_precision(dates);
+    assert_eq!(precision, None);
+}
+
+#[test]
+fn date_in_milliseconds_is_normalized() {
+    let dates: ArrayRef = Arc::new(
+        TimestampMillisecondArray::from(vec![EXAMPLE_DATE_SECONDS * 1_000]).with_timezone("UTC"),
+    );
+    let precision = original_timestamp_precision(dates);
+    assert_eq!(precision.as_deref(), Some("millisecond"));
+}
+
+#[test]
+fn date_in_microseconds_is_normalized() {
+    let dates: ArrayRef = Arc::new(
+        TimestampMicrosecondArray::from(vec![EXAMPLE_DATE_SECONDS * 1_000_000])
+            .with_timezone("+00:00"),
+    );
+    let precision = original_timestamp_precision(dates);
+    assert_eq!(precision.as_deref(), Some("microsecond"));
+}
+
+#[test]
+fn date_in_nanoseconds_is_normalized() {
+    let dates: ArrayRef = Arc::new(
+        TimestampNanosecondArray::from(vec![EXAMPLE_DATE_SECONDS * 1_000_000_000])
+            .with_timezone("+00:00"),
+    );
+    let precision = original_timestamp_precision(dates);
+    assert_eq!(precision.as_deref(), Some("nanosecond"));
+}
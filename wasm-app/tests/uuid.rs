@@ -0,0 +1,84 @@
+//! Tests of the validation of UUIDs stored as FixedSizeBinary(16) by wasm_memory_validate_uuids_arrow of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{Array, AsArray, FixedSizeBinaryArray, StringArray};
+use arrow::datatypes::{DataType, Field, Schema, UInt8Type};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+
+mod common;
+use common::{call_arrow_function, module_path, serialize};
+
+/// UUID of version 4 and variant RFC 4122 (f47ac10b-58cc-4372-a567-0e02b2c3d479)
+const UUID_V4: [u8; 16] = [
+    0xf4, 0x7a, 0xc1, 0x0b, 0x58, 0xcc, 0x43, 0x72, 0xa5, 0x67, 0x0e, 0x02, 0xb2, 0xc3, 0xd4, 0x79,
+];
+
+/// UUID of version 7, which is not supported (017f22e2-79b0-7cc3-98c4-dc0c0c07398f)
+const UUID_V7: [u8; 16] = [
+    0x01, 0x7f, 0x22, 0xe2, 0x79, 0xb0, 0x7c, 0xc3, 0x98, 0xc4, 0xdc, 0x0c, 0x0c, 0x07, 0x39, 0x8f,
+];
+
+/// UUID of version 1 and the Microsoft variant (6ba7b810-9dad-11d1-c0b4-00c04fd430c8)
+const UUID_MICROSOFT: [u8; 16] = [
+    0x6b, 0xa7, 0xb8, 0x10, 0x9d, 0xad, 0x11, 0xd1, 0xc0, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8,
+];
+
+#[test]
+fn versions_and_variants_are_validated() {
+    let path = module_path();
+    let uuids = FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+        [
+            Some(UUID_V4.to_vec()),
+            Some(UUID_V7.to_vec()),
+            Some(UUID_MICROSOFT.to_vec()),
+            None,
+        ]
+        .into_iter(),
+        16,
+    )
+    .unwrap();
+    let schema = Schema::new(vec![Field::new(
+        "uuid",
+        DataType::FixedSizeBinary(16),
+        true,
+    )]);
+    let data: Vec<u8> =
+        serialize(&RecordBatch::try_new(Arc::new(schema), vec![Arc::new(uuids)]).unwrap());
+    let result: Vec<u8> =
+        call_arrow_function(&path, "wasm_memory_validate_uuids_arrow", &[&data]).unwrap();
+    let batch: RecordBatch = StreamReader::try_new(result.as_slice(), None)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let versions = batch
+        .column_by_name("version")
+        .unwrap()
+        .as_primitive::<UInt8Type>();
+    let variants = batch.column_by_name("variant").unwrap().as_string::<i32>();
+    let valid = batch.column_by_name("valid").unwrap().as_boolean();
+    assert_eq!(versions.value(0), 4);
+    assert_eq!(variants.value(0), "rfc4122");
+    assert!(valid.value(0));
+    assert_eq!(versions.value(1), 7);
+    assert_eq!(variants.value(1), "rfc4122");
+    assert!(!valid.value(1));
+    assert_eq!(versions.value(2), 1);
+    assert_eq!(variants.value(2), "microsoft");
+    assert!(!valid.value(2));
+    assert!(versions.is_null(3));
+    assert!(variants.is_null(3));
+    assert!(!valid.value(3));
+}
+
+#[test]
+fn uuids_as_strings_are_rejected() {
+    let path = module_path();
+    let schema = Schema::new(vec![Field::new("uuid", DataType::Utf8, false)]);
+    let uuids = StringArray::from(vec!["f47ac10b-58cc-4372-a567-0e02b2c3d479"]);
+    let data: Vec<u8> =
+        serialize(&RecordBatch::try_new(Arc::new(schema), vec![Arc::new(uuids)]).unwrap());
+    assert!(call_arrow_function(&path, "wasm_memory_validate_uuids_arrow", &[&data]).is_err());
+}
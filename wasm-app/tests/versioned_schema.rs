@@ -0,0 +1,165 @@
+//! Tests of the processing of data in different versions of the expected schema (wasm_memory_process_versioned_arrow) of wasm-module2
+//! The module needs to be built before (see README.md). The tests fail if it has not been built
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, AsArray, Float64Array, ListBuilder, StringArray, StringBuilder, TimestampSecondArray,
+    UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit, UInt64Type};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use wasi_common::WasiCtx;
+use wasmtime::{Engine, Instance, Memory, Module, Store, TypedFunc};
+
+mod common;
+use common::{call_arrow_function_on_instance, instantiate, module_path, serialize};
+
+/// Example data of wasm-app with the field content under a given name and additional fields
+/// {id: 1, content: "this is a test", title: "test",date:"2022-01-01T12:00:00Z", score: 1.123456}
+/// # Arguments
+/// * `content_name` - name of the field content
+/// * `additional_fields` - additional fields and their columns
+///
+/// returns the data in Arrow IPC format
+fn example_data(content_name: &str, additional_fields: Vec<(Field, ArrayRef)>) -> Vec<u8> {
+    let mut fields: Vec<Field> = vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new(content_name, DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+    ];
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from(vec![1])),
+        Arc::new(StringArray::from(vec!["this is a test"])),
+        Arc::new(StringArray::from(vec!["test"])),
+        // 2022-01-01T12:00:00Z
+        Arc::new(TimestampSecondArray::from(vec![1_641_038_400]).with_timezone("+00:00")),
+        Arc::new(Float64Array::from(vec![1.123456f64])),
+    ];
+    for (field, column) in additional_fields {
+        fields.push(field);
+        columns.push(column);
+    }
+    let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).unwrap();
+    serialize(&batch)
+}
+
+/// Calls wasm_memory_process_versioned_arrow
+/// # Arguments
+/// * `store` - store of the instance
+/// * `instance` - instance of the module
+/// * `schema_version` - version of the schema of the data
+/// * `data` - data in Arrow IPC format
+///
+/// returns the result. Returns an error with the last error of the module if the status is non-zero
+fn process_versioned(
+    store: &mut Store<WasiCtx>,
+    instance: Instance,
+    schema_version: u32,
+    data: &[u8],
+) -> Result<RecordBatch, String> {
+    let memory: Memory = instance.get_memory(&mut *store, "memory").unwrap();
+    let allocate: TypedFunc<u32, u32> = instance
+        .get_typed_func(&mut *store, "wasm_allocate")
+        .unwrap();
+    let process_versioned_arrow: TypedFunc<(u32, u32, u32), u32> = instance
+        .get_typed_func(&mut *store, "wasm_memory_process_versioned_arrow")
+        .unwrap();
+    let data_ptr: u32 = allocate.call(&mut *store, data.len() as u32).unwrap();
+    memory.write(&mut *store, data_ptr as usize, data).unwrap();
+    let result_ptr: u32 = process_versioned_arrow
+        .call(&mut *store, (schema_version, data_ptr, data.len() as u32))
+        .unwrap();
+    // WasmResult: status at byte 0, data_ptr at byte 4, data_len at byte 8
+    let mut wasm_result = [0u8; 12];
+    memory
+        .read(&*store, result_ptr as usize, &mut wasm_result)
+        .unwrap();
+    if i32::from_le_bytes(wasm_result[0..4].try_into().unwrap()) != 0 {
+        let last_error: Vec<u8> =
+            call_arrow_function_on_instance(store, instance, "wasm_last_error", &[]).unwrap();
+        return Err(String::from_utf8(last_error).unwrap());
+    }
+    let result_data_ptr: u32 = u32::from_le_bytes(wasm_result[4..8].try_into().unwrap());
+    let result_data_len: u32 = u32::from_le_bytes(wasm_result[8..12].try_into().unwrap());
+    let mut result_data: Vec<u8> = vec![0u8; result_data_len as usize];
+    memory
+        .read(&*store, result_data_ptr as usize, &mut result_data)
+        .unwrap();
+    Ok(StreamReader::try_new(result_data.as_slice(), None)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap())
+}
+
+#[test]
+fn all_schema_versions_are_processed() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    // version 1
+    let batch: RecordBatch =
+        process_versioned(&mut store, instance, 1, &example_data("content", vec![])).unwrap();
+    assert_eq!(batch.column(0).as_primitive::<UInt64Type>().value(0), 1);
+    // version 2
+    let mut tags_builder = ListBuilder::new(StringBuilder::new());
+    tags_builder.values().append_value("news");
+    tags_builder.values().append_value("sports");
+    tags_builder.append(true);
+    let tags: ArrayRef = Arc::new(tags_builder.finish());
+    let tags_field = Field::new("tags", tags.data_type().clone(), true);
+    let batch: RecordBatch = process_versioned(
+        &mut store,
+        instance,
+        2,
+        &example_data("content", vec![(tags_field, tags)]),
+    )
+    .unwrap();
+    assert_eq!(
+        batch
+            .column_by_name("first_tag")
+            .unwrap()
+            .as_string::<i32>()
+            .value(0),
+        "news"
+    );
+    // version 3
+    let batch: RecordBatch =
+        process_versioned(&mut store, instance, 3, &example_data("body", vec![])).unwrap();
+    assert_eq!(
+        batch
+            .column_by_name("content")
+            .unwrap()
+            .as_string::<i32>()
+            .value(0),
+        "this is a test2"
+    );
+}
+
+#[test]
+fn unknown_schema_versions_are_rejected() {
+    let path = module_path();
+    let engine = Engine::default();
+    let module = Module::from_file(&engine, &path).unwrap();
+    let (mut store, instance): (Store<WasiCtx>, Instance) = instantiate(&engine, &module).unwrap();
+    for schema_version in [0, 4] {
+        assert_eq!(
+            process_versioned(
+                &mut store,
+                instance,
+                schema_version,
+                &example_data("content", vec![])
+            )
+            .unwrap_err(),
+            format!("Unknown schema version {schema_version}")
+        );
+    }
+}
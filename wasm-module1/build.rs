@@ -0,0 +1,67 @@
+//! Provides information about the build to the module as environment variables at compile time (see wasm_get_build_info)
+use std::env;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() {
+    let rustc: String = env::var("RUSTC").unwrap_or("rustc".to_string());
+    let rustc_version: String = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map_or("unknown".to_string(), |version| version.trim().to_string());
+    // reproducible builds set the build date via SOURCE_DATE_EPOCH
+    let build_timestamp: u64 = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs())
+        });
+    println!("cargo:rustc-env=BUILD_RUSTC_VERSION={rustc_version}");
+    println!(
+        "cargo:rustc-env=BUILD_DATE={}",
+        format_utc_date(build_timestamp)
+    );
+    println!(
+        "cargo:rustc-env=BUILD_TARGET={}",
+        env::var("TARGET").unwrap_or_default()
+    );
+    println!(
+        "cargo:rustc-env=BUILD_OPT_LEVEL={}",
+        env::var("OPT_LEVEL").unwrap_or_default()
+    );
+}
+
+/// Formats a UNIX timestamp as UTC date and time in RFC 3339 format
+/// # Arguments
+/// * `timestamp` - seconds since the UNIX epoch
+///
+/// returns the date and time, e.g. 2022-01-01T12:00:00Z
+fn format_utc_date(timestamp: u64) -> String {
+    let days: i64 = (timestamp / 86_400) as i64;
+    let seconds_of_day: u64 = timestamp % 86_400;
+    // conversion of days to the civil calendar, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z: i64 = days + 719_468;
+    let era: i64 = z.div_euclid(146_097);
+    let day_of_era: i64 = z - era * 146_097;
+    let year_of_era: i64 =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year: i64 = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index: i64 = (5 * day_of_year + 2) / 153;
+    let day: i64 = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month: i64 = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year: i64 = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60
+    )
+}
@@ -0,0 +1,232 @@
+//! Fuzz target for the memory management (wasm_allocate, wasm_deallocate, wasm_validate_pointer) of wasm-module1
+//! The module needs to be built before (see README.md). The input is a sequence of operations of 5 bytes each: an opcode followed by its argument (u32, little endian)
+//! * 0 - wasm_allocate(argument)
+//! * 1 - wasm_deallocate of a pointer returned by wasm_allocate before (selected by the argument), e.g. to deallocate it twice
+//! * 2 - wasm_deallocate(argument)
+//! * 3 - wasm_validate_pointer of a pointer returned by wasm_allocate before (selected by the argument)
+//! * 4 - wasm_validate_pointer(argument)
+//!
+//! After each operation the statistics of wasm_get_alloc_stats are compared to the expected counts. A trap of the module is a failure
+#![no_main]
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use libfuzzer_sys::fuzz_target;
+use wasi_common::sync::WasiCtxBuilder;
+use wasi_common::WasiCtx;
+use wasmtime::{
+    Caller, Engine, Instance, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder,
+    TypedFunc,
+};
+
+/// Maximum size of the memory of the module, so that large allocations fail (gracefully) instead of exhausting the memory of the fuzzer
+const MAX_MEMORY_SIZE: usize = 64 * 1024 * 1024;
+
+/// Size of an operation of the input
+const OP_SIZE: usize = 5;
+
+/// State of the store of the module
+struct FuzzState {
+    wasi: WasiCtx,
+    limits: StoreLimits,
+}
+
+/// Memory management functions of an instance of the module
+struct MemoryManagement {
+    store: Store<FuzzState>,
+    memory: Memory,
+    allocate: TypedFunc<u32, u32>,
+    deallocate: TypedFunc<u32, i32>,
+    validate_pointer: TypedFunc<u32, u32>,
+    get_alloc_stats: TypedFunc<(), u32>,
+}
+
+/// Counts of the memory management expected from the operations of the input
+#[derive(Default)]
+struct ExpectedStats {
+    allocations: u32,
+    deallocations: u32,
+    failed_deallocations: u32,
+    /// allocated pointers with their size
+    live: HashMap<u32, u32>,
+}
+
+/// Compiles the module once for all inputs
+///
+/// returns the engine and the module
+fn engine_and_module() -> &'static (Engine, Module) {
+    static ENGINE_AND_MODULE: OnceLock<(Engine, Module)> = OnceLock::new();
+    ENGINE_AND_MODULE.get_or_init(|| {
+        let path: PathBuf = ["wasm32-wasip1", "wasm32-wasi"]
+            .iter()
+            .map(|target| {
+                PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                    .join("../target")
+                    .join(target)
+                    .join("release/wasm_module1.wasm")
+            })
+            .find(|path| path.exists())
+            .expect("wasm_module1.wasm has not been built, see README.md");
+        let engine: Engine = Engine::default();
+        let module: Module = Module::from_file(&engine, path).unwrap();
+        (engine, module)
+    })
+}
+
+impl MemoryManagement {
+    /// Instantiates the module and fetches its memory management functions
+    ///
+    /// returns the memory management functions of the new instance
+    fn new() -> MemoryManagement {
+        let (engine, module) = engine_and_module();
+        let mut linker: Linker<FuzzState> = Linker::new(engine);
+        wasi_common::sync::add_to_linker(&mut linker, |state: &mut FuzzState| &mut state.wasi)
+            .unwrap();
+        // messages of the module are not relevant for the fuzzer
+        linker
+            .func_wrap(
+                "env",
+                "host_log",
+                |_caller: Caller<'_, FuzzState>, _level: i32, _msg_ptr: u32, _msg_len: u32| {},
+            )
+            .unwrap();
+        let mut store: Store<FuzzState> = Store::new(
+            engine,
+            FuzzState {
+                wasi: WasiCtxBuilder::new().build(),
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(MAX_MEMORY_SIZE)
+                    .build(),
+            },
+        );
+        store.limiter(|state: &mut FuzzState| &mut state.limits);
+        let instance: Instance = linker.instantiate(&mut store, module).unwrap();
+        MemoryManagement {
+            memory: instance.get_memory(&mut store, "memory").unwrap(),
+            allocate: instance
+                .get_typed_func(&mut store, "wasm_allocate")
+                .unwrap(),
+            deallocate: instance
+                .get_typed_func(&mut store, "wasm_deallocate")
+                .unwrap(),
+            validate_pointer: instance
+                .get_typed_func(&mut store, "wasm_validate_pointer")
+                .unwrap(),
+            get_alloc_stats: instance
+                .get_typed_func(&mut store, "wasm_get_alloc_stats")
+                .unwrap(),
+            store,
+        }
+    }
+
+    /// Calls wasm_deallocate and checks the return code against the expected counts
+    /// # Arguments
+    /// * `ptr` - pointer to deallocate
+    /// * `expected` - expected counts, updated by the deallocation
+    fn deallocate(&mut self, ptr: u32, expected: &mut ExpectedStats) {
+        let code: i32 = self
+            .deallocate
+            .call(&mut self.store, ptr)
+            .unwrap_or_else(|e| panic!("wasm_deallocate({ptr}) trapped: {e}"));
+        if expected.live.remove(&ptr).is_some() {
+            assert_eq!(code, 0, "wasm_deallocate({ptr}) of allocated memory failed");
+            expected.deallocations += 1;
+        } else {
+            assert!(
+                code == -1 || code == -2,
+                "wasm_deallocate({ptr}) of memory that is not allocated returned {code}"
+            );
+            expected.failed_deallocations += 1;
+        }
+    }
+
+    /// Calls wasm_validate_pointer and checks the size against the expected allocated pointers
+    /// # Arguments
+    /// * `ptr` - pointer to validate
+    /// * `expected` - expected counts
+    fn validate_pointer(&mut self, ptr: u32, expected: &ExpectedStats) {
+        let size: u32 = self
+            .validate_pointer
+            .call(&mut self.store, ptr)
+            .unwrap_or_else(|e| panic!("wasm_validate_pointer({ptr}) trapped: {e}"));
+        assert_eq!(
+            size,
+            expected.live.get(&ptr).copied().unwrap_or(0),
+            "wasm_validate_pointer({ptr}) returned a wrong size"
+        );
+    }
+
+    /// Calls wasm_get_alloc_stats and compares the statistics with the expected counts
+    /// # Arguments
+    /// * `expected` - expected counts
+    fn check_alloc_stats(&mut self, expected: &ExpectedStats) {
+        let stats_ptr: u32 = self
+            .get_alloc_stats
+            .call(&mut self.store, ())
+            .unwrap_or_else(|e| panic!("wasm_get_alloc_stats() trapped: {e}"));
+        let mut stats_buffer = [0u8; 16];
+        self.memory
+            .read(&self.store, stats_ptr as usize, &mut stats_buffer)
+            .unwrap();
+        let stats: Vec<u32> = stats_buffer
+            .chunks_exact(4)
+            .map(|x| u32::from_le_bytes(x.try_into().unwrap()))
+            .collect();
+        assert_eq!(
+            stats,
+            vec![
+                expected.allocations,
+                expected.deallocations,
+                expected.failed_deallocations,
+                expected.live.len() as u32
+            ],
+            "wasm_get_alloc_stats() is not consistent with the expected counts (allocations, deallocations, failed deallocations, allocated memory areas)"
+        );
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut mm: MemoryManagement = MemoryManagement::new();
+    let mut expected: ExpectedStats = ExpectedStats::default();
+    // pointers returned by wasm_allocate, including deallocated ones
+    let mut known_ptrs: Vec<u32> = Vec::new();
+    for op in data.chunks_exact(OP_SIZE) {
+        let argument: u32 = u32::from_le_bytes(op[1..].try_into().unwrap());
+        let known_ptr: Option<u32> = (!known_ptrs.is_empty())
+            .then(|| known_ptrs[argument as usize % known_ptrs.len()]);
+        match op[0] % 5 {
+            0 => {
+                let ptr: u32 = mm
+                    .allocate
+                    .call(&mut mm.store, argument)
+                    .unwrap_or_else(|e| panic!("wasm_allocate({argument}) trapped: {e}"));
+                // a null pointer signals that the memory cannot be allocated
+                if ptr != 0 {
+                    assert!(
+                        expected.live.insert(ptr, argument).is_none(),
+                        "wasm_allocate({argument}) returned the allocated pointer {ptr}"
+                    );
+                    expected.allocations += 1;
+                    if !known_ptrs.contains(&ptr) {
+                        known_ptrs.push(ptr);
+                    }
+                }
+            }
+            1 => {
+                if let Some(ptr) = known_ptr {
+                    mm.deallocate(ptr, &mut expected);
+                }
+            }
+            2 => mm.deallocate(argument, &mut expected),
+            3 => {
+                if let Some(ptr) = known_ptr {
+                    mm.validate_pointer(ptr, &expected);
+                }
+            }
+            _ => mm.validate_pointer(argument, &expected),
+        }
+        mm.check_alloc_stats(&expected);
+    }
+});
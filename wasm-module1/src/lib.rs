@@ -2,10 +2,38 @@ use std::ffi::CString;
 
 use std::cell::Cell;
 use std::cell::RefCell;
+#[cfg(not(feature = "slab"))]
 use std::collections::HashMap;
+#[cfg(not(feature = "slab"))]
+use std::collections::HashSet;
 use std::ffi::CStr;
 use std::mem::ManuallyDrop;
-use std::ptr;
+
+mod build_info;
+#[cfg(feature = "slab")]
+mod slab;
+
+#[cfg(feature = "slab")]
+use slab::live_memory_areas;
+#[cfg(feature = "slab")]
+pub use slab::{allocate, validate_pointer, wasm_allocate, wasm_allocated_bytes, wasm_deallocate};
+
+// Functions provided by the application to the module
+extern "C" {
+    /// Logs a UTF-8 message via the logger of the application
+    /// # Arguments
+    /// * `level` - log level, see HostLogLevel
+    /// * `msg_ptr` - pointer to the message in the WASM module memory
+    /// * `msg_len` - length of the message
+    fn host_log(level: i32, msg_ptr: *const u8, msg_len: u32);
+}
+
+/// Log levels understood by the host function host_log
+enum HostLogLevel {
+    Error = 0,
+    Warn = 1,
+    Debug = 3,
+}
 
 /// A simple function returning a number as this is the most simple and native data type supported by WASM
 /// returns a number
@@ -14,35 +42,117 @@ pub extern "C" fn answer() -> i32 {
     return 42;
 }
 
-// Global variable to keep track of allocated memory
+// Global variable to keep track of allocated memory (see slab for the feature slab)
 // Note: This is really an execption as allocate by the app to the module should have only for parameters
 // Otherwise it would be really bad for performance.
+#[cfg(not(feature = "slab"))]
 thread_local!(
     static MEMORY_AREAS: RefCell<HashMap<*const u8, (usize, ManuallyDrop<Box<[u8]>>)>> =
         RefCell::new(HashMap::new());
 );
 
+// Global variable to keep track of memory that has been deallocated, so that deallocating it again can be reported as such
+// A pointer is removed once the same address is allocated again
+#[cfg(not(feature = "slab"))]
+thread_local!(
+    static FREED_AREAS: RefCell<HashSet<*const u8>> = RefCell::new(HashSet::new());
+);
+
+// Global variable to count the calls of the memory management, see wasm_get_alloc_stats
+thread_local!(
+    static ALLOC_STATS: Cell<AllocStats> = const {
+        Cell::new(AllocStats {
+            allocations: 0,
+            deallocations: 0,
+            failed_deallocations: 0,
+        })
+    };
+);
+
+// Buffer in the WASM module memory to return the statistics of the memory management without allocating memory, see wasm_get_alloc_stats
+thread_local!(
+    static ALLOC_STATS_BUFFER: RefCell<[u32; 4]> = const { RefCell::new([0; 4]) };
+);
+
+// Global variable to keep track of the last error that occurred in the module
+// The application can fetch it via wasm_last_error after a function signaled an error
+thread_local!(
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+);
+
+/// Result of a function of the module returned to the application as pointer. The application reads status first: if it is non-zero, it can fetch details via wasm_last_error. If it is zero, data_ptr and data_len describe the result data
+/// Layout in the WASM module memory (little endian): status at byte 0, data_ptr at byte 4, data_len at byte 8
+#[repr(C)]
+pub struct WasmResult {
+    pub status: i32,
+    pub data_ptr: u32,
+    pub data_len: u32,
+}
+
+impl WasmResult {
+    /// Converts the result to its representation in the WASM module memory
+    ///
+    /// returns the result as bytes
+    fn to_bytes(&self) -> Box<[u8]> {
+        let mut vec_result: Vec<u8> = Vec::new();
+        vec_result.extend_from_slice(&self.status.to_le_bytes());
+        vec_result.extend_from_slice(&self.data_ptr.to_le_bytes());
+        vec_result.extend_from_slice(&self.data_len.to_le_bytes());
+        vec_result.into_boxed_slice()
+    }
+}
+
+/// Counters of the memory management
+#[derive(Clone, Copy)]
+struct AllocStats {
+    /// memory areas allocated for the application or for results
+    allocations: u32,
+    /// memory areas deallocated successfully
+    deallocations: u32,
+    /// calls of wasm_deallocate with memory that has not been allocated or has already been deallocated
+    failed_deallocations: u32,
+}
+
+/// Status of a WasmResult
+enum WasmResultStatus {
+    Success = 0,
+    ErrorInvalidMemory = -1,
+}
+
 enum MemoryAreasReturnCode {
     Success = 0,
     ErrorMemmoryNotAllocated = -1,
+    // the slab does not keep track of deallocated memory
+    #[cfg_attr(feature = "slab", allow(dead_code))]
+    ErrorMemoryAlreadyFreed = -2,
 }
 
 /// Allocate some memory for the application to write data for the module
 /// Note: It is up to the application (and not the WASM module) to provide enough pages, so the module does not run out of memory
 /// # Arguments
 /// * `size` - size of memory to allocaten
-/// returns a pointer to the allocated memory area
+/// returns a pointer to the allocated memory area. Returns a null pointer if the memory cannot be allocated
+#[cfg(not(feature = "slab"))]
 #[no_mangle]
 pub extern "C" fn wasm_allocate(size: u32) -> *const u8 {
+    // zero-sized memory areas would all have the same (dangling) pointer
+    let alloc_size: usize = (size as usize).max(1);
+    // do not abort if the memory cannot be allocated, but let the application handle it
+    let mut alloc_vec: Vec<u8> = Vec::new();
+    if alloc_vec.try_reserve_exact(alloc_size).is_err() {
+        return std::ptr::null();
+    }
     // create a Box with empty memory
-    let alloc_box = ManuallyDrop::new(vec![0u8; size as usize].into_boxed_slice());
+    alloc_vec.resize(alloc_size, 0);
+    let alloc_box = ManuallyDrop::new(alloc_vec.into_boxed_slice());
     return allocate(size as usize, alloc_box);
 }
 
 /// Deallocates existing memory for the purpose of the application
 /// # Arguments
 /// * `ptr` - mutuable pointer to the memory to deallocate
-/// returns a code if it was successful or not
+/// returns a code if it was successful or not. It is -2 if the memory has already been deallocated and -1 if it has never been allocated
+#[cfg(not(feature = "slab"))]
 #[no_mangle]
 pub extern "C" fn wasm_deallocate(ptr: *const u8) -> i32 {
     // check if the ptr exists
@@ -50,30 +160,127 @@ pub extern "C" fn wasm_deallocate(ptr: *const u8) -> i32 {
     MEMORY_AREAS.with(|mem_map| cell.set(mem_map.borrow_mut().remove(&ptr)));
     let memory_area: Option<(usize, ManuallyDrop<Box<[u8]>>)> = cell.into_inner();
     match memory_area {
-        Some(x) => ManuallyDrop::into_inner(x.1), // will then be deleted after function returns
-        None => return MemoryAreasReturnCode::ErrorMemmoryNotAllocated as i32,
+        Some(x) => {
+            FREED_AREAS.with(|freed| freed.borrow_mut().insert(ptr));
+            update_alloc_stats(|stats| stats.deallocations += 1);
+            ManuallyDrop::into_inner(x.1) // will then be deleted after function returns
+        }
+        None if FREED_AREAS.with(|freed| freed.borrow().contains(&ptr)) => {
+            update_alloc_stats(|stats| stats.failed_deallocations += 1);
+            log(
+                HostLogLevel::Warn,
+                &format!("Cannot deallocate memory at {ptr:?} that has already been deallocated"),
+            );
+            return MemoryAreasReturnCode::ErrorMemoryAlreadyFreed as i32;
+        }
+        None => {
+            update_alloc_stats(|stats| stats.failed_deallocations += 1);
+            log(
+                HostLogLevel::Warn,
+                &format!("Cannot deallocate memory at {ptr:?} that has not been allocated"),
+            );
+            return MemoryAreasReturnCode::ErrorMemmoryNotAllocated as i32;
+        }
     };
     // return success
     return MemoryAreasReturnCode::Success as i32;
 }
 
+/// Validates if a pointer has been allocated in this module and not yet deallocated
+/// # Arguments
+/// * `ptr` - pointer to the memory area
+///
+/// returns the size of the memory area. It is 0 if the pointer is invalid
+#[no_mangle]
+pub extern "C" fn wasm_validate_pointer(ptr: *const u8) -> u32 {
+    validate_pointer(ptr) as u32
+}
+
+/// Returns the total size of the memory areas that have been allocated in this module and not yet deallocated
+///
+/// returns the total size in bytes
+#[cfg(not(feature = "slab"))]
+#[no_mangle]
+pub extern "C" fn wasm_allocated_bytes() -> u32 {
+    MEMORY_AREAS.with(|mem_map| mem_map.borrow().values().map(|x| x.0).sum::<usize>() as u32)
+}
+
+/// Returns statistics of the memory management, e.g. to check that the application deallocates all memory. The statistics are returned without allocating memory, so that fetching them does not change them
+///
+/// returns a pointer to 4 counters (u32, little endian) in the WASM module memory: allocations, successful deallocations, failed deallocations (memory not allocated or already deallocated) and memory areas currently allocated. Note: The pointer is owned by the module and valid until the next call of wasm_get_alloc_stats. It must not be deallocated
+#[no_mangle]
+pub extern "C" fn wasm_get_alloc_stats() -> u32 {
+    let stats: AllocStats = ALLOC_STATS.with(|alloc_stats| alloc_stats.get());
+    let live_areas: u32 = live_memory_areas() as u32;
+    ALLOC_STATS_BUFFER.with(|buffer| {
+        *buffer.borrow_mut() = [
+            stats.allocations.to_le(),
+            stats.deallocations.to_le(),
+            stats.failed_deallocations.to_le(),
+            live_areas.to_le(),
+        ];
+        buffer.as_ptr() as u32
+    })
+}
+
+/// Initializes the module. The application should call it once after instantiating the module
+///
+/// Installs a panic hook that stores the panic message as last error before the module traps, so that the application can fetch the reason of the trap via wasm_last_error
+#[no_mangle]
+pub extern "C" fn wasm_init() {
+    std::panic::set_hook(Box::new(|info| {
+        let msg = info.to_string();
+        LAST_ERROR.with(|e| *e.borrow_mut() = Some(msg));
+    }));
+}
+
+/// Returns the last error that occurred in the module
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the error message (a Rust str). Returns 0 if no error occurred. Note: The calling application must signal to the module that the memory can be fred by calling deallocate on the returned pointer and the error message pointer
+#[no_mangle]
+pub extern "C" fn wasm_last_error() -> u32 {
+    match LAST_ERROR.with(|last_error| last_error.borrow().clone()) {
+        Some(error_message) => allocate_result(error_message.into_bytes().into_boxed_slice()),
+        None => 0,
+    }
+}
+
+/// Returns the version of the module
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the version of the module (a Rust str), e.g. 0.1.0. Note: The calling application must signal to the module that the memory can be fred by calling deallocate on the returned pointer and the version pointer
+#[no_mangle]
+pub extern "C" fn wasm_version() -> u32 {
+    allocate_result(
+        env!("CARGO_PKG_VERSION")
+            .as_bytes()
+            .to_vec()
+            .into_boxed_slice(),
+    )
+}
+
 /// A hello world function that takes as input a pointer to a C string in the WASM module memory and outputs a pointer to a C string in the WASM module memory containing a greeting
 /// # Arguments
 /// * `name` - pointer to a c string containing a name to greet
-/// Returns a pointer to a C string. Note: The calling application must signal to the module that the memory can be fred by calling deallocate on the returned pointer
+/// Returns a pointer to a WasmResult in the WASM module memory containing the greeting as C string. Note: The calling application must signal to the module that the memory can be fred by calling deallocate on the returned pointer and the C string pointer
 ///
 #[no_mangle]
-pub extern "C" fn wasm_memory_c_format_hello_world(name: *const i8) -> *const u8 {
+pub extern "C" fn wasm_memory_c_format_hello_world(name: *const i8) -> u32 {
     // validate pointer
     let expected_size: usize = validate_pointer(name as *const u8);
     if expected_size == 0 {
-        return ptr::null();
+        return allocate_error(
+            WasmResultStatus::ErrorInvalidMemory,
+            "Parameter name is not a valid pointer".to_string(),
+        );
     }; // return if no valid allocated memory was provided
        // convert parameter to Rust
     let c_str: &CStr = unsafe { CStr::from_ptr(name) };
     // check valid memory representation
     if c_str.to_bytes_with_nul().len() != expected_size {
-        return ptr::null();
+        return allocate_error(
+            WasmResultStatus::ErrorInvalidMemory,
+            "Parameter name does not match the allocated memory".to_string(),
+        );
     }; // return if allocated memory does not match expected memory
     let name_str: &str = c_str.to_str().unwrap();
     // execute the real native function
@@ -84,24 +291,23 @@ pub extern "C" fn wasm_memory_c_format_hello_world(name: *const i8) -> *const u8
         .unwrap()
         .into_bytes_with_nul()
         .into_boxed_slice();
-    let result_cstring_len: usize = result_cstring.len();
-    let allocated_cstring: ManuallyDrop<Box<[u8]>> = ManuallyDrop::new(result_cstring);
-
-    let return_u8: *const u8 = allocate(result_cstring_len, allocated_cstring);
-    return return_u8;
+    return allocate_result(result_cstring);
 }
 
 /// A hello world function that takes as input a pointer (offset, length) in the WASM module memory containing the name (in Rust str format)
 /// # Arguments
 /// * `offset` - position of the start of the Rust str
 /// * `length` - length of the Rust str
-/// Returns an offset in the WASM module memory where an offset and length of the result greeting (a Rust str) are stored
+/// Returns a pointer to a WasmResult in the WASM module memory containing the greeting (a Rust str)
 #[no_mangle]
 pub extern "C" fn wasm_memory_rust_format_hello_world(offset: *mut u32, length: u32) -> u32 {
     // validate pointer
     let expected_size_param: usize = validate_pointer(offset as *const u8);
     if (expected_size_param == 0) | (expected_size_param != length as usize) {
-        return 0;
+        return allocate_error(
+            WasmResultStatus::ErrorInvalidMemory,
+            "Parameter name is not a valid pointer".to_string(),
+        );
     }; // return if no valid allocated memory was provided
 
     // fetch from WASM module memory
@@ -118,31 +324,15 @@ pub extern "C" fn wasm_memory_rust_format_hello_world(offset: *mut u32, length:
     let result_string: Box<[u8]> = format_hello_world(&name_str)
         .into_bytes()
         .into_boxed_slice();
-    let result_string_len: usize = result_string.len();
-    let allocated_result_string: ManuallyDrop<Box<[u8]>> = ManuallyDrop::new(result_string);
-    // return position of WASM memory where we can find a offset, length pair
-    let string_ptr = allocate(result_string_len, allocated_result_string);
-    // prepare metadata
-    let mut vec_meta: Vec<u8> = Vec::new();
-    let string_ptr_array: [u8; (usize::BITS / 8) as usize] = (string_ptr as usize).to_le_bytes();
-    let length_array: [u8; (usize::BITS / 8) as usize] = result_string_len.to_le_bytes();
-    for byte in string_ptr_array {
-        vec_meta.push(byte);
-    }
-    for byte in length_array {
-        vec_meta.push(byte);
-    }
-    let str_meta: Box<[u8]> = vec_meta.into_boxed_slice();
-    let str_meta_len: usize = str_meta.len();
-    let str_meta_ptr = allocate(str_meta_len, ManuallyDrop::new(str_meta));
     // the reason is that Rust only support one return value. Although it can be a tuple, this is translated by wasm to one return type and not multi-value
-    return str_meta_ptr as u32;
+    return allocate_result(result_string);
 }
 
 /// Validates if a pointer has been properly allocated in this module
 /// # Arguments
 /// * `ptr` - pointer
 /// returns the size of the allocated memory area. It is 0 if the pointer is invalid
+#[cfg(not(feature = "slab"))]
 pub fn validate_pointer(ptr: *const u8) -> usize {
     let cell: Cell<usize> = Cell::new(0);
     MEMORY_AREAS.with(|mem_map| match mem_map.borrow().get(&ptr) {
@@ -158,17 +348,91 @@ pub fn validate_pointer(ptr: *const u8) -> usize {
 /// # Arguments
 /// * `size` - size of memory to allocaten
 /// returns a pointer to the allocated memory area
+#[cfg(not(feature = "slab"))]
 pub fn allocate(size: usize, alloc_box: ManuallyDrop<Box<[u8]>>) -> *const u8 {
     let result_ptr: *const u8 = alloc_box.as_ptr();
+    // the address is in use again
+    FREED_AREAS.with(|freed| freed.borrow_mut().remove(&result_ptr));
+    update_alloc_stats(|stats| stats.allocations += 1);
     // save allocated memory to avoid it is cleaned up after function exits
     MEMORY_AREAS.with(|mem_map| mem_map.borrow_mut().insert(result_ptr, (size, alloc_box)));
     return result_ptr;
 }
 
+/// Returns the number of memory areas currently allocated
+///
+/// returns the number of memory areas
+#[cfg(not(feature = "slab"))]
+fn live_memory_areas() -> usize {
+    MEMORY_AREAS.with(|mem_map| mem_map.borrow().len())
+}
+
+/// Allocates the result of a function so that the application can read it and release it after reading
+/// # Arguments
+/// * `result` - result data
+///
+/// returns a pointer to a WasmResult describing the result data
+fn allocate_result(result: Box<[u8]>) -> u32 {
+    let result_len: usize = result.len();
+    let result_ptr: *const u8 = allocate(result_len, ManuallyDrop::new(result));
+    allocate_wasm_result(WasmResult {
+        status: WasmResultStatus::Success as i32,
+        data_ptr: result_ptr as u32,
+        data_len: result_len as u32,
+    })
+}
+
+/// Allocates the result of a function that failed so that the application can read it and release it after reading
+/// # Arguments
+/// * `status` - status describing the failure
+/// * `error_message` - description of the error that the application can fetch via wasm_last_error
+///
+/// returns a pointer to a WasmResult with the status
+fn allocate_error(status: WasmResultStatus, error_message: String) -> u32 {
+    log(HostLogLevel::Error, &error_message);
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(error_message));
+    allocate_wasm_result(WasmResult {
+        status: status as i32,
+        data_ptr: 0,
+        data_len: 0,
+    })
+}
+
+/// Allocates a WasmResult so that the application can read it and release it after reading
+/// # Arguments
+/// * `wasm_result` - result of a function
+///
+/// returns a pointer to the WasmResult
+fn allocate_wasm_result(wasm_result: WasmResult) -> u32 {
+    let wasm_result_bytes: Box<[u8]> = wasm_result.to_bytes();
+    let wasm_result_len: usize = wasm_result_bytes.len();
+    allocate(wasm_result_len, ManuallyDrop::new(wasm_result_bytes)) as u32
+}
+
 /// The native hello_world function in rust
 /// # Arguments
 /// * `name` - a str containing the name to greet
 /// Returns a string with the greeting
 fn format_hello_world(name: &str) -> String {
+    log(HostLogLevel::Debug, &format!("Greeting {name}"));
     return format!("Hello World, {name}!");
 }
+
+/// Logs a message via the host function host_log of the application
+/// # Arguments
+/// * `level` - log level
+/// * `message` - message to log
+fn log(level: HostLogLevel, message: &str) {
+    unsafe { host_log(level as i32, message.as_ptr(), message.len() as u32) };
+}
+
+/// Updates the statistics of the memory management
+/// # Arguments
+/// * `update` - update of the counters
+fn update_alloc_stats(update: impl FnOnce(&mut AllocStats)) {
+    ALLOC_STATS.with(|alloc_stats| {
+        let mut stats: AllocStats = alloc_stats.get();
+        update(&mut stats);
+        alloc_stats.set(stats);
+    });
+}
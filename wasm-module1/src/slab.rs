@@ -0,0 +1,171 @@
+//! Memory management with a fixed-capacity slab (feature slab), e.g. for browser targets without WASI (wasm32-unknown-unknown). The exported functions behave like the ones based on MEMORY_AREAS, but only rely on core and alloc
+extern crate alloc;
+
+use alloc::alloc::{alloc_zeroed, dealloc, Layout};
+use alloc::boxed::Box;
+use core::cell::UnsafeCell;
+use core::mem::ManuallyDrop;
+
+use crate::{log, update_alloc_stats, HostLogLevel, MemoryAreasReturnCode};
+
+/// Maximum number of memory areas allocated at the same time
+const SLAB_CAPACITY: usize = 256;
+
+/// Tag of the memory areas. The module does not distinguish owners of memory areas
+const SLAB_TAG: u32 = 0;
+
+/// Memory area of the slab
+#[derive(Clone, Copy)]
+struct SlabEntry {
+    /// pointer to the memory area
+    ptr: *mut u8,
+    /// size of the memory area requested
+    size: usize,
+    /// owner of the memory area
+    tag: u32,
+}
+
+/// Entries of the slab. A free entry is None
+struct Slab(UnsafeCell<[Option<SlabEntry>; SLAB_CAPACITY]>);
+
+// WASM modules are single-threaded, so the slab is never accessed concurrently
+unsafe impl Sync for Slab {}
+
+// Global variable to keep track of allocated memory. A static instead of thread_local, so that the slab only relies on core
+static SLAB: Slab = Slab(UnsafeCell::new([None; SLAB_CAPACITY]));
+
+/// Allocate some memory for the application to write data for the module
+/// Note: It is up to the application (and not the WASM module) to provide enough pages, so the module does not run out of memory
+/// # Arguments
+/// * `size` - size of memory to allocate
+///
+/// returns a pointer to the allocated memory area. Returns a null pointer if the memory cannot be allocated or all 256 entries of the slab are in use
+#[no_mangle]
+pub extern "C" fn wasm_allocate(size: u32) -> *const u8 {
+    slab_allocate(size as usize)
+}
+
+/// Deallocates existing memory for the purpose of the application
+/// # Arguments
+/// * `ptr` - mutuable pointer to the memory to deallocate
+///
+/// returns a code if it was successful or not. It is -1 if the memory has not been allocated or has already been deallocated (the slab does not keep track of deallocated memory)
+#[no_mangle]
+pub extern "C" fn wasm_deallocate(ptr: *const u8) -> i32 {
+    let entry: Option<SlabEntry> = with_slab(|slab| {
+        slab.iter_mut()
+            .find(
+                |entry| matches!(entry, Some(x) if core::ptr::eq(x.ptr, ptr) && x.tag == SLAB_TAG),
+            )
+            .and_then(|entry| entry.take())
+    });
+    match entry {
+        Some(x) => {
+            update_alloc_stats(|stats| stats.deallocations += 1);
+            // free the memory with the same layout
+            unsafe { dealloc(x.ptr, slab_layout(x.size)) };
+            MemoryAreasReturnCode::Success as i32
+        }
+        None => {
+            update_alloc_stats(|stats| stats.failed_deallocations += 1);
+            log(
+                HostLogLevel::Warn,
+                &format!("Cannot deallocate memory at {ptr:?} that has not been allocated"),
+            );
+            MemoryAreasReturnCode::ErrorMemmoryNotAllocated as i32
+        }
+    }
+}
+
+/// Returns the total size of the memory areas that have been allocated in this module and not yet deallocated
+///
+/// returns the total size in bytes
+#[no_mangle]
+pub extern "C" fn wasm_allocated_bytes() -> u32 {
+    with_slab(|slab| slab.iter().flatten().map(|x| x.size).sum::<usize>() as u32)
+}
+
+/// Validates if a pointer has been properly allocated in this module
+/// # Arguments
+/// * `ptr` - pointer
+///
+/// returns the size of the allocated memory area. It is 0 if the pointer is invalid
+pub fn validate_pointer(ptr: *const u8) -> usize {
+    with_slab(|slab| {
+        slab.iter()
+            .flatten()
+            .find(|x| core::ptr::eq(x.ptr, ptr) && x.tag == SLAB_TAG)
+            .map_or(0, |x| x.size)
+    })
+}
+
+/// Allocates memory in the slab and copies data into it, e.g. to return data to the calling application of the module
+/// # Arguments
+/// * `size` - size of memory to allocate
+/// * `alloc_box` - data to copy, it is dropped afterwards
+///
+/// returns a pointer to the allocated memory area. Returns a null pointer if the memory cannot be allocated or all entries of the slab are in use
+pub fn allocate(size: usize, alloc_box: ManuallyDrop<Box<[u8]>>) -> *const u8 {
+    let data: Box<[u8]> = ManuallyDrop::into_inner(alloc_box);
+    let result_ptr: *const u8 = slab_allocate(size);
+    if !result_ptr.is_null() {
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                data.as_ptr(),
+                result_ptr as *mut u8,
+                size.min(data.len()),
+            )
+        };
+    }
+    result_ptr
+}
+
+/// Returns the number of memory areas currently allocated
+///
+/// returns the number of used entries of the slab
+pub(crate) fn live_memory_areas() -> usize {
+    with_slab(|slab| slab.iter().flatten().count())
+}
+
+/// Allocates zero-initialized memory in a free entry of the slab
+/// # Arguments
+/// * `size` - size of memory to allocate
+///
+/// returns a pointer to the allocated memory area. Returns a null pointer if the memory cannot be allocated or all entries of the slab are in use
+fn slab_allocate(size: usize) -> *const u8 {
+    with_slab(|slab| {
+        let Some(entry) = slab.iter_mut().find(|entry| entry.is_none()) else {
+            return core::ptr::null();
+        };
+        let ptr: *mut u8 = unsafe { alloc_zeroed(slab_layout(size)) };
+        if ptr.is_null() {
+            return core::ptr::null();
+        }
+        *entry = Some(SlabEntry {
+            ptr,
+            size,
+            tag: SLAB_TAG,
+        });
+        update_alloc_stats(|stats| stats.allocations += 1);
+        ptr as *const u8
+    })
+}
+
+/// Layout of a memory area of the slab
+/// # Arguments
+/// * `size` - size of the memory area
+///
+/// returns the layout. Zero-sized memory areas are allocated with one byte, because the allocator does not support zero-sized allocations
+fn slab_layout(size: usize) -> Layout {
+    Layout::array::<u8>(size.max(1)).unwrap()
+}
+
+/// Accesses the entries of the slab
+/// # Arguments
+/// * `f` - function applied to the entries
+///
+/// returns the result of the function
+fn with_slab<R>(f: impl FnOnce(&mut [Option<SlabEntry>; SLAB_CAPACITY]) -> R) -> R {
+    // the module is single-threaded and f does not access the slab again, so the reference is unique
+    f(unsafe { &mut *SLAB.0.get() })
+}
@@ -0,0 +1,121 @@
+//! Aggregation of a numeric field of data in Arrow IPC format
+use std::sync::Arc;
+
+use arrow::array::{Array, Float64Array, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Aggregates a numeric field of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `agg_spec_offset` - position of the start of the aggregation specification as JSON, e.g. {"function": "sum", "field": "score"}. Supported functions are sum, min, max, mean and count
+/// * `agg_spec_size` - size of the aggregation specification
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the result data in Arrow IPC format. The result has one row with the schema {field: Utf8, function: Utf8, result: Float64, count: UInt64}. If the aggregation failed, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_aggregate_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    agg_spec_offset: *mut u32,
+    agg_spec_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - aggregation specification
+    let input_vec_agg_spec: Vec<u8> = match read_shared_memory(agg_spec_offset, agg_spec_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match aggregate_arrow(&input_vec_data, &input_vec_agg_spec) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Parses the aggregation specification, aggregates the data and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `agg_spec` - aggregation specification as JSON
+///
+/// returns the result in Arrow IPC format
+fn aggregate_arrow(serialized_data: &[u8], agg_spec: &[u8]) -> Result<Vec<u8>, String> {
+    let agg_spec: serde_json::Value = serde_json::from_slice(agg_spec)
+        .map_err(|e| format!("Invalid aggregation specification: {e}"))?;
+    let function: &str = agg_spec["function"]
+        .as_str()
+        .ok_or("Aggregation specification has no \"function\"")?;
+    let field: &str = agg_spec["field"]
+        .as_str()
+        .ok_or("Aggregation specification has no \"field\"")?;
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let result_batch: RecordBatch = aggregate(&batch, field, function)?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Aggregates a numeric field of a record batch. Null values are ignored
+/// # Arguments
+/// * `batch` - record batch containing the field
+/// * `field` - name of the field to aggregate
+/// * `function` - aggregation function (sum, min, max, mean or count)
+///
+/// returns a record batch with one row containing the result of the aggregation
+fn aggregate(batch: &RecordBatch, field: &str, function: &str) -> Result<RecordBatch, String> {
+    let column_index: usize = batch
+        .schema()
+        .index_of(field)
+        .map_err(|_| format!("Field '{field}' not found in schema"))?;
+    let column = batch.column(column_index);
+    // Float64 is aggregated directly, other numeric types are casted
+    let values: Float64Array = match column.data_type() {
+        DataType::Float64 => arrow::array::as_primitive_array::<Float64Type>(column).clone(),
+        data_type if data_type.is_numeric() => {
+            let casted_column =
+                arrow::compute::cast(column, &DataType::Float64).map_err(|e| e.to_string())?;
+            arrow::array::as_primitive_array::<Float64Type>(&casted_column).clone()
+        }
+        data_type => {
+            return Err(format!(
+                "Field '{field}' has non-numeric type {data_type} and cannot be aggregated"
+            ))
+        }
+    };
+    let count: u64 = (values.len() - values.null_count()) as u64;
+    let result: Option<f64> = match function {
+        "sum" => arrow::compute::sum(&values),
+        "min" => arrow::compute::min(&values),
+        "max" => arrow::compute::max(&values),
+        "mean" => match count {
+            0 => None,
+            _ => Some(values.iter().flatten().sum::<f64>() / count as f64),
+        },
+        "count" => Some(count as f64),
+        _ => return Err(format!("Unknown aggregation function '{function}'")),
+    };
+    // define schema
+    let schema = Schema::new(vec![
+        Field::new("field", DataType::Utf8, false),
+        Field::new("function", DataType::Utf8, false),
+        Field::new("result", DataType::Float64, true),
+        Field::new("count", DataType::UInt64, false),
+    ]);
+    // build a record batch
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from(vec![field])),
+            Arc::new(StringArray::from(vec![function])),
+            Arc::new(Float64Array::from(vec![result])),
+            Arc::new(UInt64Array::from(vec![count])),
+        ],
+    )
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,83 @@
+//! Resolution of fields by their name or an alias in the field metadata, so that the module tolerates renamed fields (e.g. body instead of content) if the application declares the expected name as alias
+use std::sync::Arc;
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::{Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+/// Key of the field metadata containing the name the module expects for a renamed field
+const ALIAS_METADATA_KEY: &str = "alias";
+
+/// Finds a field of a record batch by its name or, if no field has the name, by the alias in its field metadata (e.g. {"alias": "content"} for a field body)
+/// # Arguments
+/// * `batch` - record batch
+/// * `name` - name of the field expected by the module
+///
+/// returns the index and the column of the field. It is None if no field has the name or alias
+pub(crate) fn resolve_field_by_name_or_alias<'a>(
+    batch: &'a RecordBatch,
+    name: &str,
+) -> Option<(usize, &'a ArrayRef)> {
+    let schema = batch.schema_ref();
+    schema
+        .index_of(name)
+        .ok()
+        .or_else(|| {
+            schema.fields().iter().position(|field| {
+                field.metadata().get(ALIAS_METADATA_KEY).map(String::as_str) == Some(name)
+            })
+        })
+        .map(|index| (index, batch.column(index)))
+}
+
+/// Renames the fields of a record batch that have one of the names of the expected schema as alias to that name. Other fields are not changed
+/// # Arguments
+/// * `batch` - record batch of data
+/// * `expected_schema` - schema expected by the module
+///
+/// returns the record batch with the fields named as expected
+pub(crate) fn rename_aliased_fields(
+    batch: &RecordBatch,
+    expected_schema: &Schema,
+) -> Result<RecordBatch, String> {
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    for expected_field in expected_schema.fields() {
+        if let Some((index, _)) = resolve_field_by_name_or_alias(batch, expected_field.name()) {
+            fields[index] = fields[index].clone().with_name(expected_field.name());
+        }
+    }
+    let columns: Vec<ArrayRef> = batch.columns().to_vec();
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Declares the name expected by the module as alias of a field, e.g. for data of an older or newer version of the expected schema
+/// # Arguments
+/// * `batch` - record batch of data
+/// * `name` - name of the field in the data, e.g. body
+/// * `alias` - name of the field expected by the module, e.g. content
+///
+/// returns the record batch with the alias in the field metadata. Returns an error if the field is missing
+pub(crate) fn with_alias(
+    batch: &RecordBatch,
+    name: &str,
+    alias: &str,
+) -> Result<RecordBatch, String> {
+    let schema = batch.schema();
+    let index: usize = schema
+        .index_of(name)
+        .map_err(|_| format!("Field '{name}' not found in schema"))?;
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    let mut metadata = fields[index].metadata().clone();
+    metadata.insert(ALIAS_METADATA_KEY.to_string(), alias.to_string());
+    fields[index] = fields[index].clone().with_metadata(metadata);
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        batch.columns().to_vec(),
+    )
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,110 @@
+//! Binary payloads of documents (field attachment) in the data of wasm_memory_process_data_arrow, e.g. media files. Payloads that exceed 2 GB in total need 64-bit offsets (LargeBinary) instead of 32-bit offsets (Binary)
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, GenericBinaryArray, GenericBinaryBuilder, OffsetSizeTrait,
+};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::{log, HostLogLevel};
+
+/// Name of the optional field of the data with the binary payload of a document
+pub(crate) const ATTACHMENT_FIELD: &str = "attachment";
+
+/// Removes the optional field attachment from a record batch, so that the other fields can be processed as usual
+/// # Arguments
+/// * `batch` - record batch of data
+///
+/// returns the record batch without the field attachment and the attachments. Returns an error if the field attachment is neither of type Binary nor LargeBinary
+pub(crate) fn split_attachment(
+    batch: &RecordBatch,
+) -> Result<(RecordBatch, Option<ArrayRef>), String> {
+    let Ok(attachment_index) = batch.schema().index_of(ATTACHMENT_FIELD) else {
+        return Ok((batch.clone(), None));
+    };
+    let attachments: ArrayRef = batch.column(attachment_index).clone();
+    if !matches!(
+        attachments.data_type(),
+        DataType::Binary | DataType::LargeBinary
+    ) {
+        return Err(format!(
+            "Field '{ATTACHMENT_FIELD}' has type {} instead of Binary or LargeBinary",
+            attachments.data_type()
+        ));
+    }
+    let mut batch: RecordBatch = batch.clone();
+    batch.remove_column(attachment_index);
+    Ok((batch, Some(attachments)))
+}
+
+/// Field of the result with the processed attachments
+/// # Arguments
+/// * `attachments` - attachments of the data
+///
+/// returns the field attachment with the type of the attachments (Binary or LargeBinary). It is None if the data has no attachments
+pub(crate) fn attachment_result_field(attachments: Option<&ArrayRef>) -> Option<Field> {
+    attachments
+        .map(|attachments| Field::new(ATTACHMENT_FIELD, attachments.data_type().clone(), true))
+}
+
+/// Appends the field attachment with the processed attachments to the result. The attachments keep the type of the data (Binary or LargeBinary)
+/// # Arguments
+/// * `batch` - result
+/// * `attachments` - attachments of the data, one per row of the result. If None, the result is returned unchanged
+///
+/// returns the result with the additional field attachment. Returns an error if the number of attachments differs from the number of rows of the result
+pub(crate) fn append_attachment_column(
+    batch: RecordBatch,
+    attachments: Option<&ArrayRef>,
+) -> Result<RecordBatch, String> {
+    let (Some(attachments), Some(attachment_field)) =
+        (attachments, attachment_result_field(attachments))
+    else {
+        return Ok(batch);
+    };
+    let processed_attachments: ArrayRef = match attachments.data_type() {
+        DataType::LargeBinary => Arc::new(process_attachments(attachments.as_binary::<i64>())),
+        _ => Arc::new(process_attachments(attachments.as_binary::<i32>())),
+    };
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields.push(attachment_field);
+    let mut columns: Vec<ArrayRef> = batch.columns().to_vec();
+    columns.push(processed_attachments);
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Processes the attachments value by value. The offsets of the result have the same size as the offsets of the attachments
+/// # Arguments
+/// * `attachments` - attachments with 32-bit (Binary) or 64-bit (LargeBinary) offsets
+///
+/// returns the processed attachments
+fn process_attachments<O: OffsetSizeTrait>(
+    attachments: &GenericBinaryArray<O>,
+) -> GenericBinaryArray<O> {
+    let mut builder: GenericBinaryBuilder<O> =
+        GenericBinaryBuilder::with_capacity(attachments.len(), attachments.value_data().len());
+    let mut attachment_bytes: usize = 0;
+    for i in 0..attachments.len() {
+        if attachments.is_null(i) {
+            builder.append_null();
+        } else {
+            let attachment: &[u8] = attachments.value(i);
+            attachment_bytes += attachment.len();
+            builder.append_value(attachment);
+        }
+    }
+    log(
+        HostLogLevel::Debug,
+        &format!(
+            "Processed {} attachments with {attachment_bytes} bytes",
+            attachments.len()
+        ),
+    );
+    builder.finish()
+}
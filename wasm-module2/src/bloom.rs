@@ -0,0 +1,143 @@
+//! Estimation of duplicate documents across calls of wasm_memory_process_data_arrow with a Bloom filter of the ids seen by the instance
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, BooleanArray};
+use arrow::compute::{cast, filter_record_batch};
+use arrow::datatypes::{DataType, Field, Schema, UInt64Type};
+use arrow::record_batch::RecordBatch;
+
+use fastbloom::BloomFilter;
+
+use crate::set_last_error;
+use crate::validate::column;
+
+// Global variable with the Bloom filter of the ids processed by the instance. Duplicates are not estimated if it is None. The application creates it via wasm_init_bloom_filter
+thread_local!(
+    static BLOOM: RefCell<Option<BloomFilter>> = const { RefCell::new(None) };
+);
+
+/// Creates a new Bloom filter of the ids processed by wasm_memory_process_data_arrow. Rows of the data whose id is (probably) contained in the filter are skipped in all following calls of the instance and the ids of the other rows are added to it when the call succeeded, so that the data of a failed call can be retried. The result has an additional field is_duplicate_estimate of type Boolean. Note: Cached results (see wasm_set_cache_ttl_ms) are not used while the filter exists
+/// # Arguments
+/// * `expected_items` - number of ids expected to be added to the filter, must be positive
+/// * `false_positive_rate_per_mille` - probability in per mille (1 to 999) that an id is wrongly estimated to be a duplicate after expected_items ids have been added
+///
+/// returns 0 if the filter has been created. Returns -1 if a parameter is out of range, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_init_bloom_filter(
+    expected_items: u32,
+    false_positive_rate_per_mille: u32,
+) -> i32 {
+    if expected_items == 0 {
+        set_last_error(
+            "The expected number of items of the Bloom filter must be positive".to_string(),
+        );
+        return -1;
+    }
+    if !(1..=999).contains(&false_positive_rate_per_mille) {
+        set_last_error(format!(
+            "False positive rate of {false_positive_rate_per_mille} per mille of the Bloom filter is not between 1 and 999"
+        ));
+        return -1;
+    }
+    let bloom: BloomFilter =
+        BloomFilter::with_false_pos(f64::from(false_positive_rate_per_mille) / 1000.0)
+            .expected_items(expected_items as usize);
+    BLOOM.with(|filter| *filter.borrow_mut() = Some(bloom));
+    0
+}
+
+/// Removes all ids from the Bloom filter created by wasm_init_bloom_filter, so that all ids are processed again. It has no effect if there is no filter
+#[no_mangle]
+pub extern "C" fn wasm_reset_bloom_filter() {
+    BLOOM.with(|filter| {
+        if let Some(bloom) = filter.borrow_mut().as_mut() {
+            bloom.clear();
+        }
+    });
+}
+
+/// Checks if duplicates are estimated by a Bloom filter (see wasm_init_bloom_filter)
+///
+/// returns true if the filter exists
+pub(crate) fn bloom_filter_enabled() -> bool {
+    BLOOM.with(|filter| filter.borrow().is_some())
+}
+
+/// Skips the rows of a record batch whose id is (probably) contained in the Bloom filter or has been seen before in the same call. The filter is not changed, see add_processed_ids. Rows with a null id are kept. The record batch is returned unchanged if there is no filter
+/// # Arguments
+/// * `batch` - record batch of data with the field id
+/// * `processed_ids` - ids of the rows kept before in the same call. The ids of the kept rows are added
+///
+/// returns the record batch without duplicates and the number of skipped rows. Returns an error if the field id is missing or cannot be cast to UInt64
+pub(crate) fn skip_duplicates(
+    batch: &RecordBatch,
+    processed_ids: &mut HashSet<u64>,
+) -> Result<(RecordBatch, u64), String> {
+    BLOOM.with(|filter| {
+        let filter = filter.borrow();
+        let Some(bloom) = filter.as_ref() else {
+            return Ok((batch.clone(), 0));
+        };
+        let ids: ArrayRef =
+            cast(column(batch, "id")?, &DataType::UInt64).map_err(|e| e.to_string())?;
+        // insert returns false if the id has been kept before in the same call
+        let keep: BooleanArray = ids
+            .as_primitive::<UInt64Type>()
+            .iter()
+            .map(|id| Some(id.is_none_or(|id| !bloom.contains(&id) && processed_ids.insert(id))))
+            .collect();
+        let skipped: u64 = (keep.len() - keep.true_count()) as u64;
+        let batch: RecordBatch = filter_record_batch(batch, &keep).map_err(|e| e.to_string())?;
+        Ok((batch, skipped))
+    })
+}
+
+/// Adds the ids of the rows processed by a successful call to the Bloom filter. It has no effect if there is no filter
+/// # Arguments
+/// * `processed_ids` - ids of the rows kept by skip_duplicates
+pub(crate) fn add_processed_ids(processed_ids: &HashSet<u64>) {
+    BLOOM.with(|filter| {
+        if let Some(bloom) = filter.borrow_mut().as_mut() {
+            for id in processed_ids {
+                bloom.insert(id);
+            }
+        }
+    });
+}
+
+/// Field of the result of wasm_memory_process_data_arrow that is true if rows have been skipped as duplicates
+///
+/// returns the field is_duplicate_estimate (Boolean). It is None if there is no Bloom filter (see wasm_init_bloom_filter)
+pub(crate) fn duplicate_result_field() -> Option<Field> {
+    bloom_filter_enabled().then(|| Field::new("is_duplicate_estimate", DataType::Boolean, false))
+}
+
+/// Appends the field is_duplicate_estimate (Boolean) to the result of wasm_memory_process_data_arrow if there is a Bloom filter (see wasm_init_bloom_filter)
+/// # Arguments
+/// * `batch` - result record batch
+/// * `duplicate_count` - number of rows of the data skipped as duplicates
+///
+/// returns the record batch with the additional field, which is true if rows have been skipped. It is returned unchanged if there is no filter
+pub(crate) fn append_duplicate_column(
+    batch: RecordBatch,
+    duplicate_count: u64,
+) -> Result<RecordBatch, String> {
+    let Some(duplicate_field) = duplicate_result_field() else {
+        return Ok(batch);
+    };
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields.push(duplicate_field);
+    let mut columns: Vec<ArrayRef> = batch.columns().to_vec();
+    columns.push(Arc::new(BooleanArray::from(vec![
+        duplicate_count > 0;
+        batch.num_rows()
+    ])));
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,35 @@
+//! Information about the build of the module, e.g. to find out which build of the module runs in production
+use std::ffi::CString;
+
+// Global variable with the build information as C string, so that the pointer returned by wasm_get_build_info stays valid
+thread_local!(
+    static BUILD_INFO: CString = CString::new(build_info_json()).unwrap();
+);
+
+/// Returns information about the build of the module
+///
+/// Returns a pointer to a C string (UTF-8) in the WASM module memory containing the build information as JSON object with the keys crate_name, version, git_sha, build_date (UTC, RFC 3339), rustc_version, target and opt_level, e.g. {"crate_name": "wasm_module2", "version": "0.1.0", ...}. git_sha is "unknown" if the environment variable GIT_SHA was not set at compile time. Note: The memory is owned by the module, the calling application must not deallocate it
+#[no_mangle]
+pub extern "C" fn wasm_get_build_info() -> u32 {
+    BUILD_INFO.with(|build_info| build_info.as_ptr() as u32)
+}
+
+/// Creates the build information from environment variables set at compile time (see build.rs)
+///
+/// returns the build information as JSON object
+fn build_info_json() -> String {
+    let fields: [(&str, &str); 7] = [
+        ("crate_name", &env!("CARGO_PKG_NAME").replace('-', "_")),
+        ("version", env!("CARGO_PKG_VERSION")),
+        ("git_sha", option_env!("GIT_SHA").unwrap_or("unknown")),
+        ("build_date", env!("BUILD_DATE")),
+        ("rustc_version", env!("BUILD_RUSTC_VERSION")),
+        ("target", env!("BUILD_TARGET")),
+        ("opt_level", env!("BUILD_OPT_LEVEL")),
+    ];
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("\"{key}\": \"{}\"", value.escape_default()))
+        .collect();
+    format!("{{{}}}", fields.join(", "))
+}
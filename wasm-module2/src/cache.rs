@@ -0,0 +1,165 @@
+//! Cache of the results of wasm_memory_process_data_arrow, so that identical inputs are not processed again within a configurable time to live (TTL)
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use sha2::{Digest, Sha256};
+
+use crate::extension::ExtensionTypeCount;
+
+/// Cached result with the report of the extension types if the call changed it (see wasm_memory_extension_type_report) and the time it has been cached
+type CachedResult = (Vec<u8>, Option<Vec<ExtensionTypeCount>>, Instant);
+
+// Global variable with the cached results by the SHA-256 hash of the inputs
+thread_local!(
+    static RESULT_CACHE: RefCell<HashMap<[u8; 32], CachedResult>> = RefCell::new(HashMap::new());
+);
+
+// Global variable with the time to live of cached results. The cache is disabled if it is None. The application sets it via wasm_set_cache_ttl_ms
+thread_local!(
+    static CACHE_TTL: Cell<Option<Duration>> = const { Cell::new(None) };
+);
+
+// Global variable with the statistics of the cache
+thread_local!(
+    static CACHE_STATS: Cell<CacheStats> = const {
+        Cell::new(CacheStats {
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        })
+    };
+);
+
+// Buffer in the WASM module memory to return the statistics of the cache without allocating memory, see wasm_get_cache_stats
+thread_local!(
+    static CACHE_STATS_BUFFER: RefCell<[u64; 3]> = const { RefCell::new([0; 3]) };
+);
+
+/// Counters of the cache
+#[derive(Clone, Copy)]
+struct CacheStats {
+    /// calls answered with a cached result
+    hits: u64,
+    /// calls without a valid cached result
+    misses: u64,
+    /// cached results removed because they expired
+    evictions: u64,
+}
+
+/// Sets the time to live of the results of wasm_memory_process_data_arrow in the cache. It applies to all following calls of the instance (default: 0)
+/// # Arguments
+/// * `ttl` - time to live in milliseconds. 0 disables the cache and removes all cached results
+#[no_mangle]
+pub extern "C" fn wasm_set_cache_ttl_ms(ttl: u32) {
+    if ttl == 0 {
+        CACHE_TTL.with(|cache_ttl| cache_ttl.set(None));
+        RESULT_CACHE.with(|result_cache| result_cache.borrow_mut().clear());
+    } else {
+        CACHE_TTL.with(|cache_ttl| cache_ttl.set(Some(Duration::from_millis(ttl as u64))));
+    }
+}
+
+/// Returns statistics of the cache, e.g. to check if the time to live fits the calls of the application. The statistics are returned without allocating memory, so that fetching them does not change them
+///
+/// returns a pointer to 3 counters (u64, little endian) in the WASM module memory: hits, misses and evictions of expired results. Note: The pointer is owned by the module and valid until the next call of wasm_get_cache_stats. It must not be deallocated
+#[no_mangle]
+pub extern "C" fn wasm_get_cache_stats() -> u32 {
+    let stats: CacheStats = CACHE_STATS.with(|cache_stats| cache_stats.get());
+    CACHE_STATS_BUFFER.with(|buffer| {
+        *buffer.borrow_mut() = [
+            stats.hits.to_le(),
+            stats.misses.to_le(),
+            stats.evictions.to_le(),
+        ];
+        buffer.as_ptr() as u32
+    })
+}
+
+/// Returns the hits and misses of the cache, e.g. for wasm_get_telemetry
+///
+/// returns the number of calls answered from the cache and the number of calls without a valid cached result
+pub(crate) fn cache_hits_misses() -> (u64, u64) {
+    let stats: CacheStats = CACHE_STATS.with(|cache_stats| cache_stats.get());
+    (stats.hits, stats.misses)
+}
+
+/// Checks if the cache is enabled, e.g. to read inputs of the key only if needed
+///
+/// returns true if the time to live of cached results has been set
+pub(crate) fn cache_enabled() -> bool {
+    CACHE_TTL.with(|cache_ttl| cache_ttl.get()).is_some()
+}
+
+/// Computes the key of the inputs of a call in the cache
+/// # Arguments
+/// * `inputs` - inputs of the call, e.g. the settings, the meta data and the data
+///
+/// returns the SHA-256 hash of the inputs. It is None if the cache is disabled
+pub(crate) fn cache_key(inputs: &[&[u8]]) -> Option<[u8; 32]> {
+    CACHE_TTL.with(|cache_ttl| cache_ttl.get())?;
+    let mut hasher = Sha256::new();
+    for input in inputs {
+        // the length separates the inputs, so that different splits of the same bytes have different keys
+        hasher.update((input.len() as u64).to_le_bytes());
+        hasher.update(input);
+    }
+    Some(hasher.finalize().into())
+}
+
+/// Fetches a cached result. Expired results are removed from the cache if no valid result is cached
+/// # Arguments
+/// * `key` - key of the inputs computed by cache_key
+///
+/// returns the cached result and the report of the extension types if the call changed it. It is None if no result is cached for the key or it has expired
+pub(crate) fn cached_result(key: &[u8; 32]) -> Option<(Vec<u8>, Option<Vec<ExtensionTypeCount>>)> {
+    let ttl: Duration = CACHE_TTL.with(|cache_ttl| cache_ttl.get())?;
+    let result: Option<(Vec<u8>, Option<Vec<ExtensionTypeCount>>)> =
+        RESULT_CACHE.with(|result_cache| {
+            result_cache
+                .borrow()
+                .get(key)
+                .filter(|(_, _, cached_at)| cached_at.elapsed() < ttl)
+                .map(|(result, report, _)| (result.clone(), report.clone()))
+        });
+    if result.is_some() {
+        update_cache_stats(|stats| stats.hits += 1);
+        return result;
+    }
+    // expired results are only removed on a miss, so that the module does not need a timer
+    let evictions: usize = RESULT_CACHE.with(|result_cache| {
+        let mut result_cache = result_cache.borrow_mut();
+        let cached_results: usize = result_cache.len();
+        result_cache.retain(|_, (_, _, cached_at)| cached_at.elapsed() < ttl);
+        cached_results - result_cache.len()
+    });
+    update_cache_stats(|stats| {
+        stats.misses += 1;
+        stats.evictions += evictions as u64;
+    });
+    None
+}
+
+/// Caches a result
+/// # Arguments
+/// * `key` - key of the inputs computed by cache_key
+/// * `result` - result of the call
+/// * `report` - report of the extension types if the call changed it, so that it can be restored if the result is reused
+pub(crate) fn cache_result(key: [u8; 32], result: &[u8], report: Option<Vec<ExtensionTypeCount>>) {
+    RESULT_CACHE.with(|result_cache| {
+        result_cache
+            .borrow_mut()
+            .insert(key, (result.to_vec(), report, Instant::now()))
+    });
+}
+
+/// Updates the statistics of the cache
+/// # Arguments
+/// * `update` - function that changes the counters
+fn update_cache_stats(update: impl FnOnce(&mut CacheStats)) {
+    CACHE_STATS.with(|cache_stats| {
+        let mut stats: CacheStats = cache_stats.get();
+        update(&mut stats);
+        cache_stats.set(stats);
+    });
+}
@@ -0,0 +1,124 @@
+//! Estimation of the number of distinct values of the columns of data in Arrow IPC format, e.g. to decide whether to apply dictionary encoding or partitioning
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::BuildHasherDefault;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayData, ArrayRef, AsArray, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use hyperloglogplus::{HyperLogLog, HyperLogLogPlus};
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Precision of the HyperLogLog estimation, ie 2^14 registers with a standard error of about 0.8%
+const HYPERLOGLOG_PRECISION: u8 = 14;
+
+/// Estimates the number of distinct values of the string and numeric columns of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing one row per string (Utf8, LargeUtf8 or Utf8View) and numeric field of the data in Arrow IPC format with the schema {column: Utf8, approx_distinct: UInt64, exact_null_count: UInt64, data_type: Utf8}. The distinct values of strings are estimated with HyperLogLog (precision 14, about 0.8% error), the distinct values of numbers are counted exactly by their bits (e.g. 0.0 and -0.0 are distinct). Null values are not counted as distinct values. Fields of other types, including decimals, are not included
+#[no_mangle]
+pub extern "C" fn wasm_memory_column_cardinality_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match column_cardinality_arrow(&input_vec_data) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, estimates the distinct values of its columns and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+///
+/// returns the number of distinct values of each string and numeric field in Arrow IPC format
+fn column_cardinality_arrow(serialized_data: &[u8]) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let mut columns: Vec<String> = Vec::new();
+    let mut approx_distincts: Vec<u64> = Vec::new();
+    let mut null_counts: Vec<u64> = Vec::new();
+    let mut data_types: Vec<String> = Vec::new();
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        let approx_distinct: u64 = match field.data_type() {
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+                estimate_distinct_strings(column)?
+            }
+            data_type => match data_type.primitive_width() {
+                Some(width) if data_type.is_numeric() && width <= 8 => {
+                    count_distinct_numbers(&column.to_data(), width)
+                }
+                _ => continue,
+            },
+        };
+        columns.push(field.name().clone());
+        approx_distincts.push(approx_distinct);
+        null_counts.push(column.null_count() as u64);
+        data_types.push(field.data_type().to_string());
+    }
+    let schema = Schema::new(vec![
+        Field::new("column", DataType::Utf8, false),
+        Field::new("approx_distinct", DataType::UInt64, false),
+        Field::new("exact_null_count", DataType::UInt64, false),
+        Field::new("data_type", DataType::Utf8, false),
+    ]);
+    let result_batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from(columns)),
+            Arc::new(UInt64Array::from(approx_distincts)),
+            Arc::new(UInt64Array::from(null_counts)),
+            Arc::new(StringArray::from(data_types)),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Estimates the number of distinct non-null strings of a column with HyperLogLog
+/// # Arguments
+/// * `column` - column of type Utf8, LargeUtf8 or Utf8View
+///
+/// returns the estimated number of distinct strings
+fn estimate_distinct_strings(column: &ArrayRef) -> Result<u64, String> {
+    // strings with 64-bit offsets or as views are estimated as Utf8
+    let strings: ArrayRef =
+        arrow::compute::cast(column, &DataType::Utf8).map_err(|e| e.to_string())?;
+    // a hasher with fixed keys, so that the estimation of the same data is always the same
+    let mut hyperloglog: HyperLogLogPlus<str, BuildHasherDefault<DefaultHasher>> =
+        HyperLogLogPlus::new(HYPERLOGLOG_PRECISION, BuildHasherDefault::default())
+            .map_err(|e| e.to_string())?;
+    for value in strings.as_string::<i32>().iter().flatten() {
+        hyperloglog.insert(value);
+    }
+    Ok(hyperloglog.count().round() as u64)
+}
+
+/// Counts the number of distinct non-null numbers of a column exactly by their bits
+/// # Arguments
+/// * `data` - data of a column of a numeric type
+/// * `width` - size of a value of the type in bytes, at most 8
+///
+/// returns the number of distinct numbers
+fn count_distinct_numbers(data: &ArrayData, width: usize) -> u64 {
+    let values: &[u8] = &data.buffers()[0].as_slice()[data.offset() * width..];
+    let mut distinct_values: HashSet<u64> = HashSet::new();
+    for i in (0..data.len()).filter(|i| data.is_valid(*i)) {
+        // the bits of a value are zero-extended to 64 bits
+        let mut bits = [0u8; 8];
+        bits[..width].copy_from_slice(&values[i * width..(i + 1) * width]);
+        distinct_values.insert(u64::from_le_bytes(bits));
+    }
+    distinct_values.len() as u64
+}
@@ -0,0 +1,65 @@
+//! Verification of checksums (CRC32) of data in the WASM module memory, e.g. to detect data corrupted by a resize of the memory while the application writes it
+use crate::error_injection::inject_error;
+use crate::telemetry::{CallTelemetry, ProcessingTimer};
+use crate::{
+    allocate_error, allocate_error_invalid_memory, process_data_arrow_pipeline, read_shared_memory,
+    try_read_shared_memory, WasmResultStatus,
+};
+
+/// Processes data in Arrow IPC format from the WASM module memory like wasm_memory_process_data_arrow (including the error injection, the pinned schema, the cache, the dry-run mode and the coercion) after verifying the checksums of the meta data and the data
+/// # Arguments
+/// * `meta_data_offset` - position of the start of the meta data ("command") in Arrow IPC format
+/// * `meta_data_size` - size of the meta data in Arrow IPC format
+/// * `meta_data_crc` - CRC32 of the meta data computed by the application
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `data_crc` - CRC32 of the data computed by the application
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the result data in Arrow IPC format, see wasm_memory_process_data_arrow. If the checksum of the meta data or the data does not match, the data is not processed and the status is -20, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_process_data_arrow_checked(
+    meta_data_offset: *mut u32,
+    meta_data_size: u32,
+    meta_data_crc: u32,
+    data_offset: *mut u32,
+    data_size: u32,
+    data_crc: u32,
+) -> u32 {
+    // the call is recorded for wasm_get_telemetry when the function returns
+    let mut call_telemetry: CallTelemetry = CallTelemetry::start();
+    // the injected error is counted as failed call
+    if let Some(injected_error) = inject_error() {
+        return injected_error;
+    }
+    // fetch from WASM module memory - meta data
+    let input_vec_meta_data: Vec<u8> = match read_shared_memory(meta_data_offset, meta_data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - data. The application can retry smaller data if there is no memory for the copy
+    let input_vec_data: Vec<u8> = match try_read_shared_memory(data_offset, data_size) {
+        Ok(Some(x)) => x,
+        Ok(None) => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+        Err(error_message) => {
+            return allocate_error(WasmResultStatus::ErrorOutOfMemory, error_message)
+        }
+    };
+    // the duration of the processing is recorded for wasm_get_last_processing_duration_ns when the function returns
+    let _processing_timer: ProcessingTimer = ProcessingTimer::start();
+    for (name, input, expected_crc) in [
+        ("meta data", &input_vec_meta_data, meta_data_crc),
+        ("data", &input_vec_data, data_crc),
+    ] {
+        let crc: u32 = crc32fast::hash(input);
+        if crc != expected_crc {
+            return allocate_error(
+                WasmResultStatus::ErrorChecksum,
+                format!(
+                    "Checksum of the {name} does not match: expected {expected_crc:#010x}, found {crc:#010x}"
+                ),
+            );
+        }
+    }
+    // the verified data is processed by the same pipeline as in wasm_memory_process_data_arrow
+    process_data_arrow_pipeline(&input_vec_meta_data, &input_vec_data, &mut call_telemetry)
+}
@@ -0,0 +1,112 @@
+//! Processing of data split into several chunks in Arrow IPC format, e.g. if the application cannot provide the data in one contiguous memory area
+use arrow::array::{Array, AsArray, UInt64Array};
+use arrow::datatypes::{DataType, UInt64Type};
+use arrow::record_batch::RecordBatch;
+
+use crate::config_store::max_content_length;
+use crate::telemetry::ProcessingTimer;
+use crate::validate::column;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, process_command_data_arrow,
+    read_arrow_batch, read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Processes chunks of data in Arrow IPC format from the WASM module memory like the command "test" of wasm_memory_process_data_arrow
+/// # Arguments
+/// * `chunk_meta_offset` - position of the start of the description of the chunks in Arrow IPC format with the schema {data_ptr: UInt64, data_size: UInt64}. Each row describes one chunk of data in Arrow IPC format allocated in the WASM module memory
+/// * `chunk_meta_size` - size of the description of the chunks in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the results of all chunks in the order of the chunks merged into one stream in Arrow IPC format. If a chunk is not valid allocated memory, the status is ErrorInvalidMemory. If the description is not valid, describes no chunks or a chunk cannot be processed, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_process_chunked_arrow(
+    chunk_meta_offset: *mut u32,
+    chunk_meta_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - description of the chunks
+    let input_vec_chunk_meta: Vec<u8> = match read_shared_memory(chunk_meta_offset, chunk_meta_size)
+    {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // the duration of the processing is recorded for wasm_get_last_processing_duration_ns when the function returns
+    let _processing_timer: ProcessingTimer = ProcessingTimer::start();
+    let chunks: Vec<(u64, u64)> = match read_chunks(&input_vec_chunk_meta) {
+        Ok(x) => x,
+        Err(error_message) => {
+            return allocate_error(WasmResultStatus::ErrorProcessing, error_message)
+        }
+    };
+    // fetch from WASM module memory - chunks
+    let mut input_vec_chunks: Vec<Vec<u8>> = Vec::with_capacity(chunks.len());
+    for (data_ptr, data_size) in chunks {
+        let (Ok(data_ptr), Ok(data_size)) = (usize::try_from(data_ptr), u32::try_from(data_size))
+        else {
+            return allocate_error_invalid_memory();
+        };
+        match read_shared_memory(data_ptr as *mut u32, data_size) {
+            Some(x) => input_vec_chunks.push(x),
+            None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+        }
+    }
+    match process_chunked_arrow(&input_vec_chunks) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the description of the chunks
+/// # Arguments
+/// * `serialized_chunk_meta` - description of the chunks in Arrow IPC format
+///
+/// returns the position and size of each chunk. Returns an error if a field is missing, has another type or contains null values or if there are no chunks
+fn read_chunks(serialized_chunk_meta: &[u8]) -> Result<Vec<(u64, u64)>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_chunk_meta).map_err(|e| e.to_string())?;
+    let data_ptrs = column(&batch, "data_ptr")?;
+    let data_sizes = column(&batch, "data_size")?;
+    for (name, data_type) in [
+        ("data_ptr", data_ptrs.data_type()),
+        ("data_size", data_sizes.data_type()),
+    ] {
+        if data_type != &DataType::UInt64 {
+            return Err(format!(
+                "Field '{name}' has type {data_type} instead of {}",
+                DataType::UInt64
+            ));
+        }
+    }
+    if data_ptrs.null_count() > 0 || data_sizes.null_count() > 0 {
+        return Err("The description of the chunks contains null values".to_string());
+    }
+    if batch.num_rows() == 0 {
+        return Err("The description of the chunks contains no chunks".to_string());
+    }
+    let data_ptrs: &UInt64Array = data_ptrs.as_primitive::<UInt64Type>();
+    let data_sizes: &UInt64Array = data_sizes.as_primitive::<UInt64Type>();
+    Ok(data_ptrs
+        .values()
+        .iter()
+        .copied()
+        .zip(data_sizes.values().iter().copied())
+        .collect())
+}
+
+/// Processes each chunk independently and merges the results
+/// # Arguments
+/// * `input_vec_chunks` - chunks of data in Arrow IPC format
+///
+/// returns the merged results in Arrow IPC format. Returns an error if a chunk cannot be processed or the results of the chunks have different schemas
+fn process_chunked_arrow(input_vec_chunks: &[Vec<u8>]) -> Result<Vec<u8>, String> {
+    let max_length: Option<usize> = max_content_length(None)?;
+    let mut result_batches: Vec<RecordBatch> = Vec::with_capacity(input_vec_chunks.len());
+    for (i, input_vec_chunk) in input_vec_chunks.iter().enumerate() {
+        let serialized_result: Vec<u8> =
+            process_command_data_arrow("test", max_length, None, None, input_vec_chunk)
+                .map_err(|e| format!("Chunk {i} cannot be processed: {e}"))?;
+        result_batches.push(read_arrow_batch(&serialized_result).map_err(|e| e.to_string())?);
+    }
+    // there is at least one chunk, see read_chunks
+    let result_batch: RecordBatch =
+        arrow::compute::concat_batches(&result_batches[0].schema(), &result_batches)
+            .map_err(|e| format!("The results of the chunks cannot be merged: {e}"))?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
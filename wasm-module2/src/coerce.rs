@@ -0,0 +1,152 @@
+//! Coercion of data in Arrow format to an expected schema, so that the module tolerates compatible changes of the schema by the application (e.g. Int32 instead of UInt64)
+use std::sync::Arc;
+
+use arrow::array::ArrayRef;
+use arrow::compute::CastOptions;
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+
+use crate::{log, normalize_tz, HostLogLevel};
+
+/// Coerces the fields of a record batch to the types of the fields with the same name in the expected schema. Only safe widenings are applied, ie Int8/Int16/Int32 to Int64, signed and unsigned integers to UInt64 (failing for negative values), Float16/Float32 to Float64 and Date32 to timestamps with a precision of second. Dictionary encoded strings are decoded to Utf8. Fields that are not part of the expected schema are not changed
+/// # Arguments
+/// * `batch` - record batch to coerce
+/// * `expected_schema` - schema expected by the module
+///
+/// returns the coerced record batch. Returns an error if a field has a type that cannot be coerced to the expected type
+pub(crate) fn coerce_batch(
+    batch: &RecordBatch,
+    expected_schema: &Schema,
+) -> Result<RecordBatch, String> {
+    let mut fields: Vec<Field> = Vec::with_capacity(batch.num_columns());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(batch.num_columns());
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        let expected_data_type: &DataType = match expected_schema.field_with_name(field.name()) {
+            Ok(expected_field) => expected_field.data_type(),
+            Err(_) => field.data_type(),
+        };
+        if is_same_data_type(field.data_type(), expected_data_type) {
+            fields.push(field.as_ref().clone());
+            columns.push(column.clone());
+            continue;
+        }
+        if !can_coerce(field.data_type(), expected_data_type) {
+            return Err(format!(
+                "Field '{}' has type {} that cannot be coerced to the expected type {expected_data_type}",
+                field.name(),
+                field.data_type()
+            ));
+        }
+        log(
+            HostLogLevel::Warn,
+            &format!(
+                "Coercing field '{}' from type {} to the expected type {expected_data_type}",
+                field.name(),
+                field.data_type()
+            ),
+        );
+        // do not convert values that do not fit (e.g. negative values to UInt64) to null, but fail
+        let cast_options: CastOptions = CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+        let coerced_column: ArrayRef =
+            arrow::compute::cast_with_options(column, expected_data_type, &cast_options)
+                .map_err(|e| format!("Field '{}' cannot be coerced: {e}", field.name()))?;
+        fields.push(
+            field
+                .as_ref()
+                .clone()
+                .with_data_type(expected_data_type.clone()),
+        );
+        columns.push(coerced_column);
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(|e| e.to_string())
+}
+
+/// Decodes dictionary encoded fields of a record batch to their value type, e.g. Dictionary(Int32, Utf8) to Utf8. The application may encode fields with repeated values as dictionary to reduce the size of the data
+/// # Arguments
+/// * `batch` - record batch to decode
+///
+/// returns the record batch without dictionary encoded fields. Other fields, the field metadata and the schema metadata are not changed
+pub(crate) fn materialize_dictionaries(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = Vec::with_capacity(batch.num_columns());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(batch.num_columns());
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        match field.data_type() {
+            DataType::Dictionary(_, value_type) => {
+                fields.push(
+                    field
+                        .as_ref()
+                        .clone()
+                        .with_data_type(value_type.as_ref().clone()),
+                );
+                columns.push(
+                    arrow::compute::cast(column, value_type)
+                        .map_err(|e| format!("Field '{}' cannot be decoded: {e}", field.name()))?,
+                );
+            }
+            _ => {
+                fields.push(field.as_ref().clone());
+                columns.push(column.clone());
+            }
+        }
+    }
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Checks if two types are the same. Different representations of the UTC timezone of timestamps are considered the same. Strings with 64-bit offsets (LargeUtf8) are considered the same as the expected Utf8
+/// # Arguments
+/// * `data_type` - type of the data
+/// * `expected_data_type` - expected type
+///
+/// returns true if both types are the same
+fn is_same_data_type(data_type: &DataType, expected_data_type: &DataType) -> bool {
+    match (data_type, expected_data_type) {
+        (
+            DataType::Timestamp(time_unit, Some(tz)),
+            DataType::Timestamp(expected_time_unit, Some(expected_tz)),
+        ) => time_unit == expected_time_unit && normalize_tz(tz) == normalize_tz(expected_tz),
+        (DataType::LargeUtf8, DataType::Utf8) => true,
+        _ => data_type == expected_data_type,
+    }
+}
+
+/// Checks if a type can be safely widened to the expected type
+/// # Arguments
+/// * `data_type` - type of the data
+/// * `expected_data_type` - expected type
+///
+/// returns true if the type can be coerced to the expected type
+fn can_coerce(data_type: &DataType, expected_data_type: &DataType) -> bool {
+    match expected_data_type {
+        DataType::Int64 => matches!(
+            data_type,
+            DataType::Int8 | DataType::Int16 | DataType::Int32
+        ),
+        DataType::UInt64 => matches!(
+            data_type,
+            DataType::Int8
+                | DataType::Int16
+                | DataType::Int32
+                | DataType::Int64
+                | DataType::UInt8
+                | DataType::UInt16
+                | DataType::UInt32
+        ),
+        DataType::Float64 => matches!(data_type, DataType::Float16 | DataType::Float32),
+        // dates without a time of day are processed as timestamps at midnight
+        DataType::Timestamp(TimeUnit::Second, _) => data_type == &DataType::Date32,
+        // dictionary encoded strings are decoded
+        DataType::Utf8 => match data_type {
+            DataType::Dictionary(_, value_type) => value_type.as_ref() == &DataType::Utf8,
+            _ => false,
+        },
+        _ => false,
+    }
+}
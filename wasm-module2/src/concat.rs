@@ -0,0 +1,96 @@
+//! Concatenation of two streams of data in Arrow IPC format, e.g. to merge results from multiple sources
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Concatenates two streams of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `batch1_offset` - position of the start of the first stream in Arrow IPC format
+/// * `batch1_size` - size of the first stream
+/// * `batch2_offset` - position of the start of the second stream in Arrow IPC format
+/// * `batch2_size` - size of the second stream
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing one stream in Arrow IPC format with the rows of the first stream followed by the rows of the second stream. A stream without record batches is treated as an empty table. If the schemas of the streams are not identical, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_concat_arrow(
+    batch1_offset: *mut u32,
+    batch1_size: u32,
+    batch2_offset: *mut u32,
+    batch2_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - first stream
+    let input_vec_batch1: Vec<u8> = match read_shared_memory(batch1_offset, batch1_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - second stream
+    let input_vec_batch2: Vec<u8> = match read_shared_memory(batch2_offset, batch2_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match concat_arrow(&input_vec_batch1, &input_vec_batch2) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes both streams, concatenates them and serializes the result
+/// # Arguments
+/// * `serialized_batch1` - first stream in Arrow IPC format
+/// * `serialized_batch2` - second stream in Arrow IPC format
+///
+/// returns the concatenated data in Arrow IPC format
+fn concat_arrow(serialized_batch1: &[u8], serialized_batch2: &[u8]) -> Result<Vec<u8>, String> {
+    // streams without record batches are read as empty record batches with the schema of the stream
+    let batch1: RecordBatch = read_arrow_batch(serialized_batch1).map_err(|e| e.to_string())?;
+    let batch2: RecordBatch = read_arrow_batch(serialized_batch2).map_err(|e| e.to_string())?;
+    if batch1.schema() != batch2.schema() {
+        return Err(describe_schema_difference(
+            &batch1.schema(),
+            &batch2.schema(),
+        ));
+    }
+    let result_batch: RecordBatch =
+        arrow::compute::concat_batches(&batch1.schema(), &[batch1, batch2])
+            .map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Describes the first difference between two schemas
+/// # Arguments
+/// * `schema1` - schema of the first stream
+/// * `schema2` - schema of the second stream
+///
+/// returns a description of the difference
+pub(crate) fn describe_schema_difference(schema1: &Schema, schema2: &Schema) -> String {
+    let field_difference: Option<String> = schema1
+        .fields()
+        .iter()
+        .zip(schema2.fields().iter())
+        .find(|(field1, field2)| field1 != field2)
+        .map(|(field1, field2)| {
+            format!(
+                "field '{}' ({}, nullable: {}) differs from field '{}' ({}, nullable: {})",
+                field1.name(),
+                field1.data_type(),
+                field1.is_nullable(),
+                field2.name(),
+                field2.data_type(),
+                field2.is_nullable()
+            )
+        });
+    let difference: String = match field_difference {
+        Some(x) => x,
+        None if schema1.fields().len() != schema2.fields().len() => format!(
+            "{} fields differ from {} fields",
+            schema1.fields().len(),
+            schema2.fields().len()
+        ),
+        None => "metadata differs".to_string(),
+    };
+    format!("Schemas of the streams are not identical: {difference}")
+}
@@ -0,0 +1,140 @@
+//! Configuration of wasm_memory_process_data_arrow as key-value pairs (Map) in the meta data, so that new configuration parameters do not require a change of the schema
+use arrow::array::{Array, ArrayRef, AsArray, MapArray, StringArray};
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+
+use crate::validate::column;
+
+/// Encoding of the data supported by the module
+const SUPPORTED_ENCODING: &str = "utf-8";
+
+/// Configuration of the processing of the data
+pub(crate) struct ProcessingConfig {
+    /// value of the key "filename"
+    pub(crate) filename: Option<String>,
+    /// value of the key "max_length", ie the maximum number of characters of the content of a document
+    pub(crate) max_length: Option<usize>,
+    /// value of the key "tenant", ie the tenant whose configuration is read from the key-value store of the application
+    pub(crate) tenant: Option<String>,
+    /// value of the key "field", ie the field compared by the command "filter"
+    pub(crate) filter_field: Option<String>,
+    /// value of the key "op", ie the comparison operator of the command "filter"
+    pub(crate) filter_op: Option<String>,
+    /// value of the key "value", ie the value compared by the command "filter"
+    pub(crate) filter_value: Option<String>,
+}
+
+/// Reads the configuration from a row of the field config of the meta data. Unknown keys are ignored, so that older versions of the module accept configurations of newer applications
+/// # Arguments
+/// * `config` - column of the field config of type Map(Utf8, Utf8) or Struct with one field per key (e.g. reconstructed from flattened fields, see unflatten_batch)
+/// * `row` - row of the meta data
+///
+/// returns the configuration. Returns an error if the field is not of type Map(Utf8, Utf8) or Struct or a value of a known key is not valid
+pub(crate) fn read_config(config: &ArrayRef, row: usize) -> Result<ProcessingConfig, String> {
+    let mut processing_config = ProcessingConfig {
+        filename: None,
+        max_length: None,
+        tenant: None,
+        filter_field: None,
+        filter_op: None,
+        filter_value: None,
+    };
+    if let Some(config) = config.as_struct_opt() {
+        for (field, column) in config.fields().iter().zip(config.columns()) {
+            // values of other types than Utf8, e.g. max_length as integer, are read as string
+            let values: ArrayRef = arrow::compute::cast(column, &DataType::Utf8).map_err(|e| {
+                format!(
+                    "Field 'config.{}' cannot be read as Utf8: {e}",
+                    field.name()
+                )
+            })?;
+            let values: &StringArray = values.as_string::<i32>();
+            if values.is_valid(row) {
+                read_config_entry(&mut processing_config, field.name(), values.value(row))?;
+            }
+        }
+        return Ok(processing_config);
+    }
+    let config: &MapArray = config.as_map_opt().ok_or(format!(
+        "Field 'config' has type {} instead of Map or Struct",
+        config.data_type()
+    ))?;
+    let keys: &StringArray = config
+        .keys()
+        .as_string_opt::<i32>()
+        .ok_or("Keys of the field 'config' are not of type Utf8")?;
+    let values: &StringArray = config
+        .values()
+        .as_string_opt::<i32>()
+        .ok_or("Values of the field 'config' are not of type Utf8")?;
+    let offsets: &[i32] = config.value_offsets();
+    for entry in offsets[row] as usize..offsets[row + 1] as usize {
+        if values.is_null(entry) {
+            continue;
+        }
+        read_config_entry(
+            &mut processing_config,
+            keys.value(entry),
+            values.value(entry),
+        )?;
+    }
+    Ok(processing_config)
+}
+
+/// Applies a key-value pair of the configuration
+/// # Arguments
+/// * `processing_config` - configuration read so far
+/// * `key` - key of the configuration
+/// * `value` - value of the key
+///
+/// returns an error if the value of a known key is not valid
+fn read_config_entry(
+    processing_config: &mut ProcessingConfig,
+    key: &str,
+    value: &str,
+) -> Result<(), String> {
+    match key {
+        "filename" => processing_config.filename = Some(value.to_string()),
+        "encoding" if !value.eq_ignore_ascii_case(SUPPORTED_ENCODING) => {
+            return Err(format!(
+                "Encoding '{value}' is not supported, only {SUPPORTED_ENCODING}"
+            ));
+        }
+        "max_length" => {
+            processing_config.max_length = Some(
+                value
+                    .parse::<usize>()
+                    .map_err(|e| format!("Maximum length '{value}' is not valid: {e}"))?,
+            )
+        }
+        "tenant" => processing_config.tenant = Some(value.to_string()),
+        "field" => processing_config.filter_field = Some(value.to_string()),
+        "op" => processing_config.filter_op = Some(value.to_string()),
+        "value" => processing_config.filter_value = Some(value.to_string()),
+        // unknown keys are ignored for forward compatibility
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Checks that the content of each document of a record batch does not exceed the maximum length of the configuration
+/// # Arguments
+/// * `batch` - record batch of data with the field content
+/// * `max_length` - maximum number of characters of the content
+///
+/// returns an error if the content of a document has more than max_length characters
+pub(crate) fn check_max_length(batch: &RecordBatch, max_length: usize) -> Result<(), String> {
+    // strings with 64-bit offsets, as views or dictionary encoded are checked as Utf8
+    let contents: ArrayRef = arrow::compute::cast(column(batch, "content")?, &DataType::Utf8)
+        .map_err(|e| e.to_string())?;
+    let contents: &StringArray = contents.as_string::<i32>();
+    for i in 0..contents.len() {
+        if contents.is_valid(i) && contents.value(i).chars().count() > max_length {
+            return Err(format!(
+                "Content of row {i} has {} characters, more than the maximum length {max_length}",
+                contents.value(i).chars().count()
+            ));
+        }
+    }
+    Ok(())
+}
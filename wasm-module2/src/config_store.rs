@@ -0,0 +1,239 @@
+//! Key-value configuration of the instance with tuning parameters of the processing that change between deployments, e.g. thresholds. The application sets it once after the instantiation
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::host_kv::host_kv_value;
+use crate::{read_shared_memory, set_last_error, validate_pointer_aligned, MEMORY_ALIGNMENT};
+
+/// Key of the minimum valid score of the command "validate" of wasm_memory_process_data_arrow (default: 0.0)
+const SCORE_THRESHOLD: &str = "score_threshold";
+
+/// Key of the maximum number of characters of the content of a document of wasm_memory_process_data_arrow. It applies if the meta data does not contain the key max_length (default: unlimited)
+const MAX_CONTENT_LENGTH: &str = "max_content_length";
+
+/// Return code of wasm_config_set
+enum ConfigSetReturnCode {
+    Success = 0,
+    ErrorInvalidMemory = -1,
+    ErrorInvalidValue = -2,
+}
+
+/// Return code of wasm_config_get if no value is returned
+enum ConfigGetReturnCode {
+    ErrorKeyNotFound = -1,
+    ErrorInvalidMemory = -2,
+}
+
+// Global variable with the configuration of the instance. The application sets it via wasm_config_set
+thread_local!(
+    static CONFIG: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+);
+
+/// Sets a value of the configuration of the instance. It applies to all following calls of the instance. Values of the keys score_threshold (number) and max_content_length (non-negative integer) are validated, other keys are stored as they are
+/// # Arguments
+/// * `key_offset` - position of the start of the key as UTF-8 string
+/// * `key_size` - size of the key
+/// * `value_offset` - position of the start of the value as UTF-8 string
+/// * `value_size` - size of the value
+///
+/// returns 0 if the value has been set. Returns -1 if no valid memory was provided and -2 if the key or value is not valid UTF-8 or the value is not valid for the key, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_config_set(
+    key_offset: *mut u32,
+    key_size: u32,
+    value_offset: *mut u32,
+    value_size: u32,
+) -> i32 {
+    // fetch from WASM module memory - key
+    let input_vec_key: Vec<u8> = match read_shared_memory(key_offset, key_size) {
+        Some(x) => x,
+        None => {
+            set_last_error("Invalid memory: key has not been allocated".to_string());
+            return ConfigSetReturnCode::ErrorInvalidMemory as i32;
+        }
+    };
+    // fetch from WASM module memory - value
+    let input_vec_value: Vec<u8> = match read_shared_memory(value_offset, value_size) {
+        Some(x) => x,
+        None => {
+            set_last_error("Invalid memory: value has not been allocated".to_string());
+            return ConfigSetReturnCode::ErrorInvalidMemory as i32;
+        }
+    };
+    match config_entry(input_vec_key, input_vec_value) {
+        Ok((key, value)) => {
+            CONFIG.with(|config| config.borrow_mut().insert(key, value));
+            ConfigSetReturnCode::Success as i32
+        }
+        Err(error_message) => {
+            set_last_error(error_message);
+            ConfigSetReturnCode::ErrorInvalidValue as i32
+        }
+    }
+}
+
+/// Gets a value of the configuration of the instance
+/// # Arguments
+/// * `key_offset` - position of the start of the key as UTF-8 string
+/// * `key_size` - size of the key
+/// * `result_offset` - position of the start of memory allocated with wasm_allocate to which the value is written as UTF-8 string
+/// * `result_max_size` - size of the memory of the result. It must not exceed the allocated memory
+///
+/// returns the size of the value in bytes. The value is only written if it is not larger than result_max_size, so that the application can retry with more memory. Returns -1 if the key does not exist and -2 if no valid memory was provided
+#[no_mangle]
+pub extern "C" fn wasm_config_get(
+    key_offset: *mut u32,
+    key_size: u32,
+    result_offset: *mut u32,
+    result_max_size: u32,
+) -> i32 {
+    // fetch from WASM module memory - key
+    let input_vec_key: Vec<u8> = match read_shared_memory(key_offset, key_size) {
+        Some(x) => x,
+        None => return ConfigGetReturnCode::ErrorInvalidMemory as i32, // return if no valid allocated memory was provided
+    };
+    // the result must fit in the memory allocated by the application
+    if validate_pointer_aligned(result_offset as *const u8, MEMORY_ALIGNMENT)
+        < result_max_size as usize
+    {
+        return ConfigGetReturnCode::ErrorInvalidMemory as i32;
+    }
+    let Some(value) = String::from_utf8(input_vec_key)
+        .ok()
+        .and_then(|key| config_value(&key))
+    else {
+        return ConfigGetReturnCode::ErrorKeyNotFound as i32;
+    };
+    if value.len() <= result_max_size as usize {
+        unsafe {
+            std::slice::from_raw_parts_mut(result_offset as *mut u8, value.len())
+                .copy_from_slice(value.as_bytes())
+        };
+    }
+    value.len() as i32
+}
+
+/// Validates a key and value of the configuration
+/// # Arguments
+/// * `key` - key as UTF-8 string
+/// * `value` - value as UTF-8 string
+///
+/// returns the key and value. Returns an error if the key or value is not valid UTF-8 or the value is not valid for the key
+fn config_entry(key: Vec<u8>, value: Vec<u8>) -> Result<(String, String), String> {
+    let key: String = String::from_utf8(key).map_err(|e| format!("Key is not valid UTF-8: {e}"))?;
+    let value: String = String::from_utf8(value)
+        .map_err(|e| format!("Value of the key '{key}' is not valid UTF-8: {e}"))?;
+    match key.as_str() {
+        SCORE_THRESHOLD => {
+            value
+                .parse::<f64>()
+                .map_err(|e| format!("Score threshold '{value}' is not valid: {e}"))?;
+        }
+        MAX_CONTENT_LENGTH => {
+            value
+                .parse::<usize>()
+                .map_err(|e| format!("Maximum content length '{value}' is not valid: {e}"))?;
+        }
+        // other keys are not interpreted by the module
+        _ => {}
+    }
+    Ok((key, value))
+}
+
+/// Returns a value of the configuration of the instance
+/// # Arguments
+/// * `key` - key of the value
+///
+/// returns the value. It is None if the key has not been set
+pub(crate) fn config_value(key: &str) -> Option<String> {
+    CONFIG.with(|config| config.borrow().get(key).cloned())
+}
+
+/// Returns a value of the configuration of a tenant. The value of the tenant in the key-value store of the application (key "tenant/<tenant>/<key>") takes precedence over the configuration of the instance
+/// # Arguments
+/// * `tenant` - tenant of the meta data. Only the configuration of the instance is used if it is None
+/// * `key` - key of the value, e.g. score_threshold
+///
+/// returns the value. It is None if the key has been set neither for the tenant nor for the instance. Returns an error if the value of the tenant is not valid UTF-8 or cannot be read from the application
+pub(crate) fn tenant_config_value(
+    tenant: Option<&str>,
+    key: &str,
+) -> Result<Option<String>, String> {
+    if let Some(tenant) = tenant {
+        if let Some(value) = host_kv_value(&format!("tenant/{tenant}/{key}"))? {
+            return String::from_utf8(value).map(Some).map_err(|e| {
+                format!("Value of the key '{key}' of the tenant '{tenant}' is not valid UTF-8: {e}")
+            });
+        }
+    }
+    Ok(config_value(key))
+}
+
+/// Returns the minimum valid score of the command "validate"
+/// # Arguments
+/// * `tenant` - tenant of the meta data, see tenant_config_value
+///
+/// returns the value of the key score_threshold. It is 0.0 if the key has not been set. Returns an error if the value of the tenant is not a number
+pub(crate) fn score_threshold(tenant: Option<&str>) -> Result<f64, String> {
+    let Some(value) = tenant_config_value(tenant, SCORE_THRESHOLD)? else {
+        return Ok(0.0);
+    };
+    value
+        .parse::<f64>()
+        .map_err(|e| format!("Score threshold '{value}' is not valid: {e}"))
+}
+
+/// Returns the maximum number of characters of the content of a document
+/// # Arguments
+/// * `tenant` - tenant of the meta data, see tenant_config_value
+///
+/// returns the value of the key max_content_length. It is None if the key has not been set. Returns an error if the value of the tenant is not a non-negative integer
+pub(crate) fn max_content_length(tenant: Option<&str>) -> Result<Option<usize>, String> {
+    tenant_config_value(tenant, MAX_CONTENT_LENGTH)?
+        .map(|value| {
+            value
+                .parse::<usize>()
+                .map_err(|e| format!("Maximum content length '{value}' is not valid: {e}"))
+        })
+        .transpose()
+}
+
+/// Returns the whole configuration of the instance, e.g. to include it in the key of cached results
+///
+/// returns the key-value pairs as UTF-8 string sorted by key
+pub(crate) fn config_settings() -> Vec<u8> {
+    CONFIG.with(|config| {
+        config
+            .borrow()
+            .iter()
+            .collect::<BTreeMap<&String, &String>>()
+            .iter()
+            // the lengths separate the entries, so that keys and values may contain any character
+            .map(|(key, value)| format!("{}:{key}{}:{value}", key.len(), value.len()))
+            .collect::<String>()
+            .into_bytes()
+    })
+}
+
+/// Returns the configuration of a tenant in the key-value store of the application, e.g. to include it in the key of cached results
+/// # Arguments
+/// * `tenant` - tenant of the meta data, see tenant_config_value
+///
+/// returns the values of the keys score_threshold and max_content_length of the tenant. It is empty if there is no tenant. Returns an error if a value cannot be read from the application
+pub(crate) fn tenant_config_settings(tenant: Option<&str>) -> Result<Vec<u8>, String> {
+    let mut settings: Vec<u8> = Vec::new();
+    let Some(tenant) = tenant else {
+        return Ok(settings);
+    };
+    for key in [SCORE_THRESHOLD, MAX_CONTENT_LENGTH] {
+        // the lengths separate the values, so that a missing value differs from an empty one
+        match host_kv_value(&format!("tenant/{tenant}/{key}"))? {
+            Some(value) => {
+                settings.extend(format!("{}:", value.len()).into_bytes());
+                settings.extend(value);
+            }
+            None => settings.push(b'-'),
+        }
+    }
+    Ok(settings)
+}
@@ -0,0 +1,164 @@
+//! Execution context of a call (trace ID, span ID and deadline), so that calls of the module can be followed in distributed traces
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use arrow::array::{Array, StringArray, UInt64Array};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+
+use crate::telemetry::ProcessingTimer;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, append_null_handling_column,
+    has_large_utf8, log, process_data_batch, process_data_result, provenance_metadata,
+    read_arrow_batch, read_shared_memory, write_arrow_batch, HostLogLevel, WasmResultStatus,
+};
+
+// Trace ID of the execution context of the current call. It is included in all messages logged during the call
+thread_local!(
+    static TRACE_ID: RefCell<Option<String>> = const { RefCell::new(None) };
+);
+
+/// Execution context handed over by the application with a call
+struct ExecutionContext {
+    /// ID of the trace the call belongs to
+    trace_id: String,
+    /// ID of the span of the call
+    span_id: String,
+    /// time (milliseconds since the Unix epoch) after which the call should not be processed anymore
+    deadline_epoch_ms: u64,
+}
+
+/// Processes data in Arrow IPC format the same way as wasm_memory_process_data_arrow within an execution context
+/// # Arguments
+/// * `ctx_offset` - position of the start of the execution context in Arrow IPC format with the schema {trace_id: Utf8, span_id: Utf8, deadline_epoch_ms: UInt64} and one row
+/// * `ctx_size` - size of the execution context
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the result data in Arrow IPC format. The trace ID is added to the schema metadata of the result ("trace_id"). If the deadline has passed, the data is not processed and the status is ErrorTimeout (-4), see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_process_with_context_arrow(
+    ctx_offset: *mut u32,
+    ctx_size: u32,
+    data_offset: *mut u32,
+    data_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - execution context
+    let input_vec_ctx: Vec<u8> = match read_shared_memory(ctx_offset, ctx_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // the duration of the processing is recorded for wasm_get_last_processing_duration_ns when the function returns
+    let _processing_timer: ProcessingTimer = ProcessingTimer::start();
+    let ctx: ExecutionContext = match read_execution_context(&input_vec_ctx) {
+        Ok(x) => x,
+        Err(error_message) => {
+            return allocate_error(WasmResultStatus::ErrorProcessing, error_message)
+        }
+    };
+    TRACE_ID.with(|trace_id| *trace_id.borrow_mut() = Some(ctx.trace_id.clone()));
+    log(
+        HostLogLevel::Debug,
+        &format!("Processing call of span {}", ctx.span_id),
+    );
+    let result: u32 = if now_epoch_ms() > ctx.deadline_epoch_ms {
+        allocate_error(
+            WasmResultStatus::ErrorTimeout,
+            format!(
+                "Deadline {} (ms since Unix epoch) has passed, data is not processed",
+                ctx.deadline_epoch_ms
+            ),
+        )
+    } else {
+        match process_with_context_arrow(&ctx, &input_vec_data) {
+            Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+            Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+        }
+    };
+    // the execution context is only valid for this call
+    TRACE_ID.with(|trace_id| *trace_id.borrow_mut() = None);
+    result
+}
+
+/// Trace ID of the execution context of the current call
+///
+/// returns the trace ID. It is None outside of a call with an execution context
+pub(crate) fn current_trace_id() -> Option<String> {
+    TRACE_ID.with(|trace_id| trace_id.borrow().clone())
+}
+
+/// Deserializes the execution context
+/// # Arguments
+/// * `serialized_ctx` - execution context in Arrow IPC format
+///
+/// returns the execution context
+fn read_execution_context(serialized_ctx: &[u8]) -> Result<ExecutionContext, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_ctx).map_err(|e| e.to_string())?;
+    if batch.num_rows() != 1 {
+        return Err(format!(
+            "Execution context must have exactly one row, found {} rows",
+            batch.num_rows()
+        ));
+    }
+    let trace_ids: &StringArray = batch
+        .column_by_name("trace_id")
+        .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+        .ok_or("Field 'trace_id' of type Utf8 not found in schema")?;
+    let span_ids: &StringArray = batch
+        .column_by_name("span_id")
+        .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+        .ok_or("Field 'span_id' of type Utf8 not found in schema")?;
+    let deadlines: &UInt64Array = batch
+        .column_by_name("deadline_epoch_ms")
+        .and_then(|c| c.as_any().downcast_ref::<UInt64Array>())
+        .ok_or("Field 'deadline_epoch_ms' of type UInt64 not found in schema")?;
+    if trace_ids.is_null(0) || span_ids.is_null(0) || deadlines.is_null(0) {
+        return Err("Execution context must not contain null values".to_string());
+    }
+    Ok(ExecutionContext {
+        trace_id: trace_ids.value(0).to_string(),
+        span_id: span_ids.value(0).to_string(),
+        deadline_epoch_ms: deadlines.value(0),
+    })
+}
+
+/// Processes the data and adds the trace ID to the result
+/// # Arguments
+/// * `ctx` - execution context
+/// * `serialized_data` - data in Arrow IPC format
+///
+/// returns the result data in Arrow IPC format
+fn process_with_context_arrow(
+    ctx: &ExecutionContext,
+    serialized_data: &[u8],
+) -> Result<Vec<u8>, String> {
+    let stream_reader = StreamReader::try_new(serialized_data, None).map_err(|e| e.to_string())?;
+    let mut metadata: HashMap<String, String> =
+        provenance_metadata(stream_reader.schema().metadata());
+    metadata.insert("trace_id".to_string(), ctx.trace_id.clone());
+    let mut large_utf8: bool = false;
+    let mut null_count: u64 = 0;
+    for item in stream_reader {
+        let batch: RecordBatch = item.map_err(|e| e.to_string())?;
+        large_utf8 |= has_large_utf8(&batch);
+        null_count += process_data_batch(&batch)?;
+    }
+    let result_batch: RecordBatch =
+        append_null_handling_column(process_data_result(large_utf8, metadata), null_count)?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Current time
+///
+/// returns the milliseconds since the Unix epoch
+fn now_epoch_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64)
+}
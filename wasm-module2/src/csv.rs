@@ -0,0 +1,140 @@
+//! Processing of data in CSV files read via the WASI filesystem, e.g. data that does not arrive in Arrow IPC format
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+use std::sync::Arc;
+
+use arrow::csv::reader::Format;
+use arrow::csv::ReaderBuilder;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, append_null_handling_column,
+    coerce_batch, expected_data_schema, has_large_utf8, process_data_batch, process_data_result,
+    provenance_metadata, read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Number of records of a CSV file used to infer its schema
+const CSV_INFER_SCHEMA_MAX_RECORDS: usize = 100;
+
+/// Reads a CSV file from a directory preopened by the application and processes it the same way as wasm_memory_process_data_arrow
+/// # Arguments
+/// * `filename_offset` - position of the start of the name of the file as UTF-8 string, relative to the preopened directory, e.g. "documents.csv"
+/// * `filename_len` - length of the name of the file
+/// * `has_header` - 1 if the first line of the file contains the field names, 0 otherwise
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the result data in Arrow IPC format. The schema of the file is inferred and timestamps without timezone are read as UTC. If the inferred schema does not match the expected schema, the status is ErrorSchemaMismatch (-3), if the file cannot be read, the status is ErrorProcessing (-2), see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_process_csv_file(
+    filename_offset: *mut u32,
+    filename_len: u32,
+    has_header: u32,
+) -> u32 {
+    // fetch from WASM module memory - filename
+    let input_vec_filename: Vec<u8> = match read_shared_memory(filename_offset, filename_len) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match process_csv_file(&input_vec_filename, has_header != 0) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err((status, error_message)) => allocate_error(status, error_message),
+    }
+}
+
+/// Reads the first record batch of a CSV file and processes it
+/// # Arguments
+/// * `filename` - UTF-8 name of the file
+/// * `has_header` - true if the first line of the file contains the field names
+///
+/// returns the result data in Arrow IPC format. Returns the status and description of the error otherwise
+fn process_csv_file(
+    filename: &[u8],
+    has_header: bool,
+) -> Result<Vec<u8>, (WasmResultStatus, String)> {
+    let filename: &str = std::str::from_utf8(filename).map_err(|e| {
+        (
+            WasmResultStatus::ErrorProcessing,
+            format!("Filename is not valid UTF-8: {e}"),
+        )
+    })?;
+    let batch: RecordBatch =
+        read_csv_file(filename, has_header).map_err(|e| (WasmResultStatus::ErrorProcessing, e))?;
+    let batch: RecordBatch =
+        coerce_csv_batch(&batch).map_err(|e| (WasmResultStatus::ErrorSchemaMismatch, e))?;
+    let null_count: u64 =
+        process_data_batch(&batch).map_err(|e| (WasmResultStatus::ErrorProcessing, e))?;
+    let metadata: HashMap<String, String> = provenance_metadata(batch.schema().metadata());
+    let result_batch: RecordBatch = append_null_handling_column(
+        process_data_result(has_large_utf8(&batch), metadata),
+        null_count,
+    )
+    .map_err(|e| (WasmResultStatus::ErrorProcessing, e))?;
+    write_arrow_batch(&result_batch).map_err(|e| (WasmResultStatus::ErrorProcessing, e.to_string()))
+}
+
+/// Reads the first record batch of a CSV file with an inferred schema
+/// # Arguments
+/// * `filename` - name of the file relative to the preopened directory
+/// * `has_header` - true if the first line of the file contains the field names
+///
+/// returns the first record batch. Returns an error if the file cannot be read or contains no records
+fn read_csv_file(filename: &str, has_header: bool) -> Result<RecordBatch, String> {
+    let mut file: File =
+        File::open(filename).map_err(|e| format!("Cannot open CSV file '{filename}': {e}"))?;
+    let (inferred_schema, _) = Format::default()
+        .with_header(has_header)
+        .infer_schema(&mut file, Some(CSV_INFER_SCHEMA_MAX_RECORDS))
+        .map_err(|e| format!("Cannot infer schema of CSV file '{filename}': {e}"))?;
+    file.seek(SeekFrom::Start(0))
+        .map_err(|e| format!("Cannot read CSV file '{filename}': {e}"))?;
+    // CSV has no timezone annotation, timestamps are considered to be in UTC
+    let fields: Vec<Field> = inferred_schema
+        .fields()
+        .iter()
+        .map(|field| match field.data_type() {
+            DataType::Timestamp(time_unit, None) => field
+                .as_ref()
+                .clone()
+                .with_data_type(DataType::Timestamp(*time_unit, Some("+00:00".into()))),
+            _ => field.as_ref().clone(),
+        })
+        .collect();
+    let mut csv_reader = ReaderBuilder::new(Arc::new(Schema::new(fields)))
+        .with_header(has_header)
+        .build(file)
+        .map_err(|e| format!("Cannot read CSV file '{filename}': {e}"))?;
+    match csv_reader.next() {
+        Some(batch) => batch.map_err(|e| format!("Cannot read CSV file '{filename}': {e}")),
+        None => Err(format!("CSV file '{filename}' contains no records")),
+    }
+}
+
+/// Coerces a record batch read from a CSV file to the expected schema. The inferred schema matches the expected schema if it has the same field names and the types can be coerced to the expected types
+/// # Arguments
+/// * `batch` - record batch read from the CSV file
+///
+/// returns the coerced record batch. Returns a description of the mismatch if the schema does not match
+fn coerce_csv_batch(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    let expected_schema: Schema = expected_data_schema();
+    let field_names: Vec<&str> = batch
+        .schema_ref()
+        .fields()
+        .iter()
+        .map(|field| field.name().as_str())
+        .collect();
+    let expected_field_names: Vec<&str> = expected_schema
+        .fields()
+        .iter()
+        .map(|field| field.name().as_str())
+        .collect();
+    if field_names != expected_field_names {
+        return Err(format!(
+            "Schema of the CSV file does not match the expected schema: found fields ({}), expected fields ({})",
+            field_names.join(", "),
+            expected_field_names.join(", ")
+        ));
+    }
+    coerce_batch(batch, &expected_schema)
+        .map_err(|e| format!("Schema of the CSV file does not match the expected schema: {e}"))
+}
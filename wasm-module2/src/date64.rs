@@ -0,0 +1,74 @@
+//! Conversion of dates in milliseconds since the UNIX epoch (Date64) to days since the UNIX epoch (Date32) for producers of data that only output Date64
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, Date32Builder, Date64Array};
+use arrow::datatypes::{DataType, Date64Type, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::alias::resolve_field_by_name_or_alias;
+use crate::{log, HostLogLevel};
+
+/// Key of the schema metadata of the result with the original type of the field date if it has been converted, ie "date64"
+pub(crate) const DATE_SOURCE_TYPE_KEY: &str = "date_source_type";
+
+/// Number of milliseconds of a day
+const MILLISECONDS_PER_DAY: i64 = 86_400_000;
+
+/// Converts a field from Date64 (milliseconds since the UNIX epoch) to Date32 (days since the UNIX epoch). The milliseconds are divided by 86400000, so that the time of day is truncated. Division truncates toward zero, ie a date before the UNIX epoch with a time of day other than midnight is converted to the following day
+/// # Arguments
+/// * `batch` - record batch of data
+/// * `name` - name of the field
+///
+/// returns the record batch with the field of type Date32 and the schema metadata date_source_type set to "date64". Other record batches, e.g. with the field of type Date32, are returned unchanged. Returns an error if a date cannot be represented in days as Date32
+pub(crate) fn convert_date64_to_date32(
+    batch: &RecordBatch,
+    name: &str,
+) -> Result<RecordBatch, String> {
+    let schema = batch.schema();
+    let Ok(index) = schema.index_of(name) else {
+        return Ok(batch.clone());
+    };
+    if schema.field(index).data_type() != &DataType::Date64 {
+        return Ok(batch.clone());
+    }
+    log(
+        HostLogLevel::Debug,
+        &format!("Converting field '{name}' from Date64 to Date32"),
+    );
+    let dates: &Date64Array = batch.column(index).as_primitive::<Date64Type>();
+    let mut days = Date32Builder::with_capacity(dates.len());
+    for i in 0..dates.len() {
+        if dates.is_null(i) {
+            days.append_null();
+            continue;
+        }
+        let day: i32 = i32::try_from(dates.value(i) / MILLISECONDS_PER_DAY).map_err(|_| {
+            format!(
+                "Date {} of row {i} of field '{name}' cannot be represented as Date32",
+                dates.value(i)
+            )
+        })?;
+        days.append_value(day);
+    }
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields[index] = fields[index].clone().with_data_type(DataType::Date32);
+    let mut columns: Vec<ArrayRef> = batch.columns().to_vec();
+    columns[index] = Arc::new(days.finish());
+    let mut metadata = schema.metadata().clone();
+    metadata.insert(DATE_SOURCE_TYPE_KEY.to_string(), "date64".to_string());
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, metadata)),
+        columns,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Returns the original type of the field date if convert_date64_to_date32 changes it
+/// # Arguments
+/// * `batch` - record batch of data
+///
+/// returns "date64" if the field date (or the field with the alias date) has the type Date64. It is None otherwise
+pub(crate) fn date_source_type(batch: &RecordBatch) -> Option<&'static str> {
+    let (_, dates) = resolve_field_by_name_or_alias(batch, "date")?;
+    (dates.data_type() == &DataType::Date64).then_some("date64")
+}
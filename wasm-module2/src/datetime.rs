@@ -0,0 +1,124 @@
+//! Combination of separate date and time fields of data in Arrow IPC format into timestamps, e.g. for IoT or financial data whose date and time come from different sources
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, Date32Array, Time64NanosecondArray, TimestampNanosecondBuilder,
+};
+use arrow::datatypes::{DataType, Date32Type, Field, Schema, Time64NanosecondType, TimeUnit};
+use arrow::record_batch::RecordBatch;
+
+use crate::date64::convert_date64_to_date32;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Number of nanoseconds of a day
+const NANOSECONDS_PER_DAY: i64 = 86_400_000_000_000;
+
+/// Combines the fields date (Date32 or Date64) and time_of_day (Time64(Nanosecond)) of data in Arrow IPC format from the WASM module memory into a timestamp
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the data in Arrow IPC format with the fields date and time_of_day replaced by the field timestamp (Timestamp(Nanosecond, "+00:00")) at the position of date. A date of type Date64 (milliseconds since the UNIX epoch) is truncated to days (toward zero) and the schema metadata of the result contains date_source_type "date64". The timestamp is null if the date or the time of day is null. If a field is missing, has another type or a time of day is not within a day, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_combine_datetime_arrow(data_offset: *mut u32, data_size: u32) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match combine_datetime_arrow(&input_vec_data) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, combines the date and time of day and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+///
+/// returns the data with timestamps in Arrow IPC format
+fn combine_datetime_arrow(serialized_data: &[u8]) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let batch: RecordBatch = convert_date64_to_date32(&batch, "date")?;
+    let schema = batch.schema();
+    let date_index: usize = field_index(&schema, "date", &DataType::Date32)?;
+    let time_index: usize = field_index(
+        &schema,
+        "time_of_day",
+        &DataType::Time64(TimeUnit::Nanosecond),
+    )?;
+    let dates: &Date32Array = batch.column(date_index).as_primitive::<Date32Type>();
+    let times: &Time64NanosecondArray = batch
+        .column(time_index)
+        .as_primitive::<Time64NanosecondType>();
+    let mut timestamps =
+        TimestampNanosecondBuilder::with_capacity(batch.num_rows()).with_timezone("+00:00");
+    for i in 0..batch.num_rows() {
+        if dates.is_null(i) || times.is_null(i) {
+            timestamps.append_null();
+            continue;
+        }
+        let time_value: i64 = times.value(i);
+        if !(0..NANOSECONDS_PER_DAY).contains(&time_value) {
+            return Err(format!(
+                "Time of day {time_value} of row {i} is not between 0 and {NANOSECONDS_PER_DAY} nanoseconds"
+            ));
+        }
+        let timestamp_ns: i64 = (dates.value(i) as i64)
+            .checked_mul(NANOSECONDS_PER_DAY)
+            .and_then(|date_ns| date_ns.checked_add(time_value))
+            .ok_or(format!(
+                "Date {} of row {i} cannot be represented as timestamp in nanoseconds",
+                dates.value(i)
+            ))?;
+        timestamps.append_value(timestamp_ns);
+    }
+    let mut timestamps: Option<ArrayRef> = Some(Arc::new(timestamps.finish()));
+    let mut fields: Vec<Field> = Vec::with_capacity(schema.fields().len() - 1);
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len() - 1);
+    for (i, field) in schema.fields().iter().enumerate() {
+        if i == time_index {
+            continue;
+        }
+        if i == date_index {
+            fields.push(Field::new(
+                "timestamp",
+                DataType::Timestamp(TimeUnit::Nanosecond, Some("+00:00".into())),
+                field.is_nullable() || schema.field(time_index).is_nullable(),
+            ));
+            columns.extend(timestamps.take());
+        } else {
+            fields.push(field.as_ref().clone());
+            columns.push(batch.column(i).clone());
+        }
+    }
+    let result_batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Finds a field of the data
+/// # Arguments
+/// * `schema` - schema of the data
+/// * `name` - name of the field
+/// * `data_type` - expected type of the field
+///
+/// returns the index of the field. Returns an error if the field does not exist or has another type
+fn field_index(schema: &Schema, name: &str, data_type: &DataType) -> Result<usize, String> {
+    let index: usize = schema
+        .index_of(name)
+        .map_err(|_| format!("Field '{name}' not found in schema"))?;
+    if schema.field(index).data_type() != data_type {
+        return Err(format!(
+            "Field '{name}' has type {} instead of {data_type}",
+            schema.field(index).data_type()
+        ));
+    }
+    Ok(index)
+}
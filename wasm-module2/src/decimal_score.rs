@@ -0,0 +1,103 @@
+//! Coercion of scores with a fixed precision (Decimal128) to Float64 for the calculations of wasm_memory_process_data_arrow, e.g. for data of financial systems
+use std::cell::Cell;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, Float64Array};
+use arrow::datatypes::{DataType, Decimal128Type, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::alias::resolve_field_by_name_or_alias;
+use crate::{log, HostLogLevel};
+
+/// Key of the schema metadata of the result that is "true" if scores of type Decimal128 have been coerced to Float64
+pub(crate) const SCORE_PRECISION_LOSS_KEY: &str = "score_precision_loss";
+
+/// Largest integer up to which all integers can be represented exactly as Float64 (2^53)
+const MAX_EXACT_F64_INTEGER: u128 = 1 << 53;
+
+/// Largest power of ten that can be represented exactly as Float64
+const MAX_EXACT_F64_POWER_OF_TEN: u32 = 22;
+
+// Global variable with the number of record batches whose scores have been coerced from Decimal128 to Float64 since the instantiation of the module
+thread_local!(
+    static COERCION_COUNT: Cell<u64> = const { Cell::new(0) };
+);
+
+/// Returns the number of record batches whose field score has been coerced from Decimal128 to Float64 since the instantiation of the module
+///
+/// returns the number of coercions
+#[no_mangle]
+pub extern "C" fn wasm_get_coercion_count() -> u64 {
+    COERCION_COUNT.with(|coercion_count| coercion_count.get())
+}
+
+/// Checks if the field score of a record batch is of type Decimal128, ie precision may be lost when it is processed
+/// # Arguments
+/// * `batch` - record batch of data
+///
+/// returns true if the score is of type Decimal128
+pub(crate) fn has_decimal_score(batch: &RecordBatch) -> bool {
+    resolve_field_by_name_or_alias(batch, "score")
+        .is_some_and(|(_, scores)| matches!(scores.data_type(), DataType::Decimal128(_, _)))
+}
+
+/// Coerces the field score from Decimal128(p, s) to Float64 by dividing the unscaled values by 10^s with integer arithmetic, so that values with up to 15 significant digits keep their decimal representation. Values that are not exactly representable as Float64 lose precision
+/// # Arguments
+/// * `batch` - record batch of data
+///
+/// returns the record batch with the score as Float64. Other record batches are returned unchanged
+pub(crate) fn coerce_decimal_score(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    let Some((score_index, scores)) = resolve_field_by_name_or_alias(batch, "score") else {
+        return Ok(batch.clone());
+    };
+    let DataType::Decimal128(precision, scale) = scores.data_type() else {
+        return Ok(batch.clone());
+    };
+    log(
+        HostLogLevel::Warn,
+        &format!(
+            "Coercing field 'score' from type Decimal128({precision}, {scale}) to Float64, precision may be lost"
+        ),
+    );
+    let scale: i8 = *scale;
+    let coerced_scores: Float64Array = scores
+        .as_primitive::<Decimal128Type>()
+        .unary(|value| decimal_to_f64(value, scale));
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields[score_index] = fields[score_index]
+        .clone()
+        .with_data_type(DataType::Float64);
+    let mut columns: Vec<ArrayRef> = batch.columns().to_vec();
+    columns[score_index] = Arc::new(coerced_scores);
+    let coerced_batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())?;
+    COERCION_COUNT.with(|coercion_count| coercion_count.set(coercion_count.get() + 1));
+    Ok(coerced_batch)
+}
+
+/// Converts an unscaled decimal value to Float64
+/// # Arguments
+/// * `value` - unscaled value
+/// * `scale` - number of digits of the fraction. A negative scale multiplies the value by 10^-scale
+///
+/// returns the value divided by 10^scale
+fn decimal_to_f64(value: i128, scale: i8) -> f64 {
+    if scale <= 0 {
+        return value as f64 * 10f64.powi(-i32::from(scale));
+    }
+    let scale: u32 = u32::from(scale.unsigned_abs());
+    let Some(divisor) = 10i128.checked_pow(scale) else {
+        // the scale exceeds the range of i128, so the value is smaller than 1
+        return value as f64 / 10f64.powi(scale as i32);
+    };
+    // one division of two exactly representable numbers is rounded only once
+    if value.unsigned_abs() <= MAX_EXACT_F64_INTEGER && scale <= MAX_EXACT_F64_POWER_OF_TEN {
+        return value as f64 / divisor as f64;
+    }
+    // otherwise the integer part and the fraction are converted separately, so that the fraction is not lost for large values
+    (value / divisor) as f64 + (value % divisor) as f64 / divisor as f64
+}
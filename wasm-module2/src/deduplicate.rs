@@ -0,0 +1,93 @@
+//! Deduplication of data in Arrow IPC format by a key field
+use std::collections::HashSet;
+
+use arrow::array::{Array, BooleanArray};
+use arrow::datatypes::{DataType, UInt64Type};
+use arrow::record_batch::RecordBatch;
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Deduplicates data in Arrow IPC format from the WASM module memory by a key field. Only the first occurrence of each key is retained
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `key_field_offset` - position of the start of the name of the key field as UTF-8 string. The key field must be of type Utf8 or UInt64
+/// * `key_field_size` - size of the name of the key field
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the deduplicated data in Arrow IPC format. If the deduplication failed, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_deduplicate_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    key_field_offset: *mut u32,
+    key_field_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - key field
+    let input_vec_key_field: Vec<u8> = match read_shared_memory(key_field_offset, key_field_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match deduplicate_arrow(&input_vec_data, &input_vec_key_field) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, deduplicates it and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `key_field` - name of the key field as UTF-8 string
+///
+/// returns the deduplicated data in Arrow IPC format
+fn deduplicate_arrow(serialized_data: &[u8], key_field: &[u8]) -> Result<Vec<u8>, String> {
+    let key_field: &str = std::str::from_utf8(key_field)
+        .map_err(|e| format!("Name of the key field is not valid UTF-8: {e}"))?;
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let result_batch: RecordBatch = deduplicate(&batch, key_field)?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Deduplicates a record batch by a key field. The type of the key field is detected from the schema. Rows with a null key are always retained
+/// # Arguments
+/// * `batch` - record batch to deduplicate
+/// * `key_field` - name of the key field
+///
+/// returns the record batch containing only the first occurrence of each key
+fn deduplicate(batch: &RecordBatch, key_field: &str) -> Result<RecordBatch, String> {
+    let key_index: usize = batch
+        .schema()
+        .index_of(key_field)
+        .map_err(|_| format!("Field '{key_field}' not found in schema"))?;
+    let key_column = batch.column(key_index);
+    // select the first occurrence of each key
+    let selection: BooleanArray = match key_column.data_type() {
+        DataType::Utf8 => {
+            let mut seen_keys: HashSet<String> = HashSet::new();
+            arrow::array::as_string_array(key_column)
+                .iter()
+                .map(|key| Some(key.is_none_or(|key| seen_keys.insert(key.to_string()))))
+                .collect()
+        }
+        DataType::UInt64 => {
+            let mut seen_keys: HashSet<u64> = HashSet::new();
+            arrow::array::as_primitive_array::<UInt64Type>(key_column)
+                .iter()
+                .map(|key| Some(key.is_none_or(|key| seen_keys.insert(key))))
+                .collect()
+        }
+        data_type => {
+            return Err(format!(
+                "Key field '{key_field}' has unsupported type {data_type}, only Utf8 and UInt64 are supported"
+            ))
+        }
+    };
+    arrow::compute::filter_record_batch(batch, &selection).map_err(|e| e.to_string())
+}
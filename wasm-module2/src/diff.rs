@@ -0,0 +1,201 @@
+//! Difference of two versions of data in Arrow IPC format by a key field, e.g. for change data capture (CDC) between two snapshots
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, UInt64Type};
+use arrow::record_batch::RecordBatch;
+use arrow::row::{RowConverter, Rows, SortField};
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Computes the changes between two versions of data in Arrow IPC format from the WASM module memory by a key field
+/// # Arguments
+/// * `old_offset` - position of the start of the old version in Arrow IPC format
+/// * `old_size` - size of the old version
+/// * `new_offset` - position of the start of the new version in Arrow IPC format. It must have the same fields as the old version
+/// * `new_size` - size of the new version
+/// * `key_field_offset` - position of the start of the name of the key field (UInt64 in both versions) as UTF-8 string
+/// * `key_field_size` - size of the name of the key field
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing one row per changed key with the fields key (UInt64) and change_type (Utf8) in Arrow IPC format. The change type is "added" if the key only exists in the new version, "modified" if any other field differs and "removed" if the key only exists in the old version. Unchanged keys are omitted. If the key field is missing, not of type UInt64, contains nulls or is not unique, or the fields of the versions differ, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_diff_arrow(
+    old_offset: *mut u32,
+    old_size: u32,
+    new_offset: *mut u32,
+    new_size: u32,
+    key_field_offset: *mut u32,
+    key_field_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - old version
+    let input_vec_old: Vec<u8> = match read_shared_memory(old_offset, old_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - new version
+    let input_vec_new: Vec<u8> = match read_shared_memory(new_offset, new_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - key field
+    let input_vec_key_field: Vec<u8> = match read_shared_memory(key_field_offset, key_field_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match diff_arrow(&input_vec_old, &input_vec_new, &input_vec_key_field) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes both versions, computes the changes and serializes them
+/// # Arguments
+/// * `serialized_old` - old version in Arrow IPC format
+/// * `serialized_new` - new version in Arrow IPC format
+/// * `key_field` - name of the key field as UTF-8 string
+///
+/// returns the changes in Arrow IPC format
+fn diff_arrow(
+    serialized_old: &[u8],
+    serialized_new: &[u8],
+    key_field: &[u8],
+) -> Result<Vec<u8>, String> {
+    let key_field: &str = std::str::from_utf8(key_field)
+        .map_err(|e| format!("Name of the key field is not valid UTF-8: {e}"))?;
+    let old: RecordBatch = read_arrow_batch(serialized_old).map_err(|e| e.to_string())?;
+    let new: RecordBatch = read_arrow_batch(serialized_new).map_err(|e| e.to_string())?;
+    let old_keys: &UInt64Array = key_column(&old, key_field, "old")?;
+    let new_keys: &UInt64Array = key_column(&new, key_field, "new")?;
+    let (old_values, new_values): (Rows, Rows) = value_rows(&old, &new, key_field)?;
+    // index of the keys of the old version
+    let mut old_index: HashMap<u64, usize> = HashMap::with_capacity(old_keys.len());
+    for (i, key) in old_keys.values().iter().enumerate() {
+        if old_index.insert(*key, i).is_some() {
+            return Err(format!("Key {key} is not unique in the old version"));
+        }
+    }
+    let mut keys: Vec<u64> = Vec::new();
+    let mut change_types: Vec<&str> = Vec::new();
+    let mut new_index: HashMap<u64, usize> = HashMap::with_capacity(new_keys.len());
+    for (i, key) in new_keys.values().iter().enumerate() {
+        if new_index.insert(*key, i).is_some() {
+            return Err(format!("Key {key} is not unique in the new version"));
+        }
+        match old_index.get(key) {
+            None => {
+                keys.push(*key);
+                change_types.push("added");
+            }
+            Some(old_row) if old_values.row(*old_row) != new_values.row(i) => {
+                keys.push(*key);
+                change_types.push("modified");
+            }
+            Some(_) => {}
+        }
+    }
+    // removed keys are appended in the order of the old version
+    for key in old_keys.values().iter() {
+        if !new_index.contains_key(key) {
+            keys.push(*key);
+            change_types.push("removed");
+        }
+    }
+    let schema = Schema::new(vec![
+        Field::new("key", DataType::UInt64, false),
+        Field::new("change_type", DataType::Utf8, false),
+    ]);
+    let result_batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(keys)),
+            Arc::new(StringArray::from(change_types)),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Fetches the key field of a version
+/// # Arguments
+/// * `batch` - data of the version
+/// * `key_field` - name of the key field
+/// * `version` - version of the data, ie "old" or "new"
+///
+/// returns the keys. Returns an error if the field does not exist, is not of type UInt64 or contains nulls
+fn key_column<'a>(
+    batch: &'a RecordBatch,
+    key_field: &str,
+    version: &str,
+) -> Result<&'a UInt64Array, String> {
+    let column: &ArrayRef = batch.column_by_name(key_field).ok_or(format!(
+        "Key field '{key_field}' not found in schema of the {version} version"
+    ))?;
+    if column.data_type() != &DataType::UInt64 {
+        return Err(format!(
+            "Key field '{key_field}' of the {version} version has type {} instead of UInt64",
+            column.data_type()
+        ));
+    }
+    if column.null_count() > 0 {
+        return Err(format!(
+            "Key field '{key_field}' of the {version} version contains nulls"
+        ));
+    }
+    Ok(column.as_primitive::<UInt64Type>())
+}
+
+/// Converts the fields other than the key field of both versions to rows, so that the values of a key can be compared with a single comparison
+/// # Arguments
+/// * `old` - data of the old version
+/// * `new` - data of the new version
+/// * `key_field` - name of the key field
+///
+/// returns the rows of the old and the new version. Returns an error if the fields other than the key field differ in name or type. The order of the fields does not matter
+fn value_rows(
+    old: &RecordBatch,
+    new: &RecordBatch,
+    key_field: &str,
+) -> Result<(Rows, Rows), String> {
+    let mut old_columns: Vec<ArrayRef> = Vec::with_capacity(old.num_columns());
+    let mut new_columns: Vec<ArrayRef> = Vec::with_capacity(old.num_columns());
+    let mut sort_fields: Vec<SortField> = Vec::with_capacity(old.num_columns());
+    for (field, column) in old.schema_ref().fields().iter().zip(old.columns()) {
+        if field.name() == key_field {
+            continue;
+        }
+        let new_column: &ArrayRef = new.column_by_name(field.name()).ok_or(format!(
+            "Field '{}' not found in schema of the new version",
+            field.name()
+        ))?;
+        if new_column.data_type() != field.data_type() {
+            return Err(format!(
+                "Field '{}' has type {} in the new version instead of {}",
+                field.name(),
+                new_column.data_type(),
+                field.data_type()
+            ));
+        }
+        old_columns.push(column.clone());
+        new_columns.push(new_column.clone());
+        sort_fields.push(SortField::new(field.data_type().clone()));
+    }
+    if new.num_columns() != old.num_columns() {
+        return Err(format!(
+            "New version has {} fields instead of {}",
+            new.num_columns(),
+            old.num_columns()
+        ));
+    }
+    let row_converter = RowConverter::new(sort_fields).map_err(|e| e.to_string())?;
+    let old_rows: Rows = row_converter
+        .convert_columns(&old_columns)
+        .map_err(|e| e.to_string())?;
+    let new_rows: Rows = row_converter
+        .convert_columns(&new_columns)
+        .map_err(|e| e.to_string())?;
+    Ok((old_rows, new_rows))
+}
@@ -0,0 +1,85 @@
+//! Dry-run mode of wasm_memory_process_data_arrow, e.g. to check in a deployment pipeline that the module can handle the data before it is processed
+use std::cell::Cell;
+use std::sync::Arc;
+
+use arrow::array::{BooleanArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+
+use crate::alias::rename_aliased_fields;
+use crate::coerce::coerce_batch;
+use crate::filter::FILTER_COMMAND;
+use crate::output_schema::enforce_output_schema;
+use crate::timestamp_precision::normalize_timestamp_precision;
+use crate::validate::VALIDATE_COMMAND;
+use crate::{expected_data_schema, validate_data_batch_structure, write_arrow_batch};
+
+// Global variable that is true if wasm_memory_process_data_arrow should only validate the data. The application enables it via wasm_set_dry_run
+thread_local!(
+    static DRY_RUN: Cell<bool> = const { Cell::new(false) };
+);
+
+/// Enables or disables the dry-run mode of wasm_memory_process_data_arrow. It applies to all following calls of the instance until it is disabled again
+/// # Arguments
+/// * `enabled` - 1 (or any other non-zero value) to enable the dry-run mode, 0 to disable it
+#[no_mangle]
+pub extern "C" fn wasm_set_dry_run(enabled: u32) {
+    DRY_RUN.with(|dry_run| dry_run.set(enabled != 0));
+}
+
+/// Checks if the dry-run mode is enabled
+///
+/// returns true if the data should only be validated
+pub(crate) fn is_dry_run() -> bool {
+    DRY_RUN.with(|dry_run| dry_run.get())
+}
+
+/// Validates the data the same way as wasm_memory_process_data_arrow without processing it
+/// # Arguments
+/// * `command` - command of the meta data, ie "test", "validate" or "filter"
+/// * `serialized_data` - data in Arrow IPC format
+///
+/// returns one row in Arrow IPC format with the schema {would_process_rows: UInt64, input_valid: Boolean, estimated_output_rows: UInt64}. input_valid is false if a record batch does not have the expected structure or cannot be coerced to the expected schema, estimated_output_rows is 0 in this case. Returns an error if the data is not in Arrow IPC format
+pub(crate) fn dry_run_data_arrow(command: &str, serialized_data: &[u8]) -> Result<Vec<u8>, String> {
+    let stream_reader = StreamReader::try_new(serialized_data, None).map_err(|e| e.to_string())?;
+    let mut would_process_rows: u64 = 0;
+    let mut input_valid: bool = true;
+    for item in stream_reader {
+        let batch: RecordBatch = item.map_err(|e| e.to_string())?;
+        would_process_rows += batch.num_rows() as u64;
+        input_valid &= validate_data_batch_structure(&batch).is_ok()
+            && rename_aliased_fields(&batch, &expected_data_schema())
+                .and_then(|batch| normalize_timestamp_precision(&batch))
+                .and_then(|batch| coerce_batch(&batch, &expected_data_schema()))
+                .is_ok();
+    }
+    // the command "test" returns one document, the command "validate" one row of verdicts per row of the data and the command "filter" at most all rows of the data
+    let estimated_output_rows: u64 = match (input_valid, command) {
+        (false, _) => 0,
+        (true, VALIDATE_COMMAND | FILTER_COMMAND) => would_process_rows,
+        (true, _) => 1,
+    };
+    let result_batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(dry_run_result_schema()),
+        vec![
+            Arc::new(UInt64Array::from(vec![would_process_rows])),
+            Arc::new(BooleanArray::from(vec![input_valid])),
+            Arc::new(UInt64Array::from(vec![estimated_output_rows])),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    let result_batch: RecordBatch = enforce_output_schema(result_batch, &dry_run_result_schema())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Schema of the result in dry-run mode
+///
+/// returns the schema {would_process_rows: UInt64, input_valid: Boolean, estimated_output_rows: UInt64}
+fn dry_run_result_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("would_process_rows", DataType::UInt64, false),
+        Field::new("input_valid", DataType::Boolean, false),
+        Field::new("estimated_output_rows", DataType::UInt64, false),
+    ])
+}
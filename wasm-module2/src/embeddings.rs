@@ -0,0 +1,112 @@
+//! Processing of embeddings (fixed-length float vectors) of documents in Arrow IPC format, e.g. as computed by machine learning models
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, FixedSizeListArray, Float64Array};
+use arrow::datatypes::{DataType, Field, Float32Type, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::telemetry::ProcessingTimer;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Number of components of an embedding
+const EMBEDDING_DIMENSION: i32 = 128;
+
+/// Computes the L2 norm of the embeddings of documents in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format with the schema {id: UInt64, embedding: FixedSizeList<Float32, 128>}
+/// * `data_size` - size of the data in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing one row per document in Arrow IPC format with the schema {id: UInt64, l2_norm: Float64, normalized_first_component: Float64}. l2_norm and normalized_first_component are null for null embeddings, normalized_first_component also for embeddings with a norm of 0. If an embedding does not have exactly 128 components or contains null components, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_process_embeddings_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // the duration of the processing is recorded for wasm_get_last_processing_duration_ns when the function returns
+    let _processing_timer: ProcessingTimer = ProcessingTimer::start();
+    match process_embeddings_arrow(&input_vec_data) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, computes the norms of the embeddings and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+///
+/// returns the norms of the embeddings in Arrow IPC format
+fn process_embeddings_arrow(serialized_data: &[u8]) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let id_column: &ArrayRef = batch
+        .column_by_name("id")
+        .ok_or("Field 'id' not found in schema".to_string())?;
+    if id_column.data_type() != &DataType::UInt64 {
+        return Err(format!(
+            "Field 'id' has type {} instead of UInt64",
+            id_column.data_type()
+        ));
+    }
+    let embeddings: &FixedSizeListArray = batch
+        .column_by_name("embedding")
+        .ok_or("Field 'embedding' not found in schema".to_string())?
+        .as_fixed_size_list_opt()
+        .ok_or("Field 'embedding' is not of type FixedSizeList".to_string())?;
+    if embeddings.value_length() != EMBEDDING_DIMENSION {
+        return Err(format!(
+            "Embeddings have {} components instead of {EMBEDDING_DIMENSION}",
+            embeddings.value_length()
+        ));
+    }
+    if embeddings.value_type() != DataType::Float32 {
+        return Err(format!(
+            "Components of the embeddings have type {} instead of Float32",
+            embeddings.value_type()
+        ));
+    }
+    let mut l2_norms: Vec<Option<f64>> = Vec::with_capacity(embeddings.len());
+    let mut normalized_first_components: Vec<Option<f64>> = Vec::with_capacity(embeddings.len());
+    for i in 0..embeddings.len() {
+        if embeddings.is_null(i) {
+            l2_norms.push(None);
+            normalized_first_components.push(None);
+            continue;
+        }
+        let embedding: ArrayRef = embeddings.value(i);
+        let components = embedding.as_primitive::<Float32Type>();
+        if components.null_count() > 0 {
+            return Err(format!("Embedding of row {i} contains null components"));
+        }
+        let l2_norm: f64 = components
+            .values()
+            .iter()
+            .map(|x| (*x as f64) * (*x as f64))
+            .sum::<f64>()
+            .sqrt();
+        l2_norms.push(Some(l2_norm));
+        normalized_first_components
+            .push((l2_norm != 0.0).then(|| components.value(0) as f64 / l2_norm));
+    }
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, id_column.is_nullable()),
+        Field::new("l2_norm", DataType::Float64, true),
+        Field::new("normalized_first_component", DataType::Float64, true),
+    ]);
+    let result_batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            id_column.clone(),
+            Arc::new(Float64Array::from(l2_norms)),
+            Arc::new(Float64Array::from(normalized_first_components)),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
@@ -0,0 +1,75 @@
+//! Injection of errors into wasm_memory_process_data_arrow, so that the application can test its error handling. It must not be enabled in production
+use std::cell::Cell;
+
+use crate::{allocate_error, set_last_error, WasmResultStatus};
+
+/// Error message of an injected error that the application can fetch via wasm_last_error
+const INJECTED_ERROR_MESSAGE: &str = "Injected error for testing";
+
+/// Maximum rate of injected errors per thousand calls, ie every call fails
+const MAX_ERROR_INJECTION_RATE: u32 = 1000;
+
+/// Multiplier of the linear congruential generator (Knuth, MMIX)
+const LCG_MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+
+/// Increment of the linear congruential generator (Knuth, MMIX)
+const LCG_INCREMENT: u64 = 1_442_695_040_888_963_407;
+
+// Global variable with the rate of injected errors per thousand calls (default: 0, ie no errors are injected)
+thread_local!(
+    static ERROR_INJECTION_RATE: Cell<u32> = const { Cell::new(0) };
+);
+
+// Global variable with the state of the linear congruential generator that decides which calls fail, so that the injected errors are reproducible
+thread_local!(
+    static ERROR_INJECTION_STATE: Cell<u64> = const { Cell::new(0) };
+);
+
+/// Sets the rate of calls of wasm_memory_process_data_arrow that fail with an injected error. A failing call returns a WasmResult with the status ErrorProcessing (-2) and wasm_last_error returns "Injected error for testing". Note: This is only for testing the error handling of the application, the rate must be 0 in production
+/// # Arguments
+/// * `rate_per_thousand` - number of failing calls per thousand calls, e.g. 1000 to let every call fail
+///
+/// returns 0 if the rate has been set. Returns -1 if the rate is greater than 1000, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_set_error_injection_rate(rate_per_thousand: u32) -> i32 {
+    if rate_per_thousand > MAX_ERROR_INJECTION_RATE {
+        set_last_error(format!(
+            "Rate of injected errors {rate_per_thousand} is greater than {MAX_ERROR_INJECTION_RATE} per thousand calls"
+        ));
+        return -1;
+    }
+    ERROR_INJECTION_RATE.with(|rate| rate.set(rate_per_thousand));
+    0
+}
+
+/// Disables the injection of errors, ie sets the rate of injected errors to 0
+#[no_mangle]
+pub extern "C" fn wasm_clear_error_injection() {
+    ERROR_INJECTION_RATE.with(|rate| rate.set(0));
+}
+
+/// Decides whether the current call fails with an injected error
+///
+/// returns a pointer to a WasmResult with the status ErrorProcessing and the injected error as last error if an error is injected. It is None if the call does not fail
+pub(crate) fn inject_error() -> Option<u32> {
+    let rate: u32 = ERROR_INJECTION_RATE.with(|rate| rate.get());
+    if rate == 0 {
+        return None;
+    }
+    let state: u64 = ERROR_INJECTION_STATE.with(|state| {
+        let next_state: u64 = state
+            .get()
+            .wrapping_mul(LCG_MULTIPLIER)
+            .wrapping_add(LCG_INCREMENT);
+        state.set(next_state);
+        next_state
+    });
+    // the high bits of a linear congruential generator are the most random ones
+    let injected: bool = ((state >> 32) % u64::from(MAX_ERROR_INJECTION_RATE)) < u64::from(rate);
+    injected.then(|| {
+        allocate_error(
+            WasmResultStatus::ErrorProcessing,
+            INJECTED_ERROR_MESSAGE.to_string(),
+        )
+    })
+}
@@ -0,0 +1,105 @@
+//! Exploding (unnesting) of a list field of data in Arrow IPC format into one row per list element, e.g. for tags of documents
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, ListArray, StringBuilder, UInt64Builder};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Explodes a list field of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `list_field_offset` - position of the start of the name of the list field (List<Utf8>) as UTF-8 string
+/// * `list_field_size` - size of the name of the list field
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the exploded data in Arrow IPC format. Each row results in one row per element of its list, the other fields are repeated. The list field is replaced by a Utf8 field with the same name. Rows with an empty or null list result in no row. If the list field is missing or not of type List<Utf8>, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_explode_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    list_field_offset: *mut u32,
+    list_field_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - list field
+    let input_vec_list_field: Vec<u8> = match read_shared_memory(list_field_offset, list_field_size)
+    {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match explode_arrow(&input_vec_data, &input_vec_list_field) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, explodes the list field and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `list_field` - name of the list field as UTF-8 string
+///
+/// returns the exploded data in Arrow IPC format
+fn explode_arrow(serialized_data: &[u8], list_field: &[u8]) -> Result<Vec<u8>, String> {
+    let list_field: &str = std::str::from_utf8(list_field)
+        .map_err(|e| format!("Name of the list field is not valid UTF-8: {e}"))?;
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let schema = batch.schema();
+    let list_index: usize = schema
+        .index_of(list_field)
+        .map_err(|_| format!("Field '{list_field}' not found in schema"))?;
+    let list_column: &ArrayRef = batch.column(list_index);
+    let is_list_of_utf8: bool = match list_column.data_type() {
+        DataType::List(item_field) => item_field.data_type() == &DataType::Utf8,
+        _ => false,
+    };
+    if !is_list_of_utf8 {
+        return Err(format!(
+            "Field '{list_field}' has type {} instead of List<Utf8>",
+            list_column.data_type()
+        ));
+    }
+    let lists: &ListArray = list_column.as_list::<i32>();
+    // one row per list element: the row of the other fields and the element
+    let mut rows = UInt64Builder::new();
+    let mut elements = StringBuilder::new();
+    for row in 0..lists.len() {
+        if lists.is_null(row) {
+            continue;
+        }
+        let list = lists.value(row);
+        let list = list.as_string::<i32>();
+        for i in 0..list.len() {
+            rows.append_value(row as u64);
+            elements.append_option(list.is_valid(i).then(|| list.value(i)));
+        }
+    }
+    let rows = rows.finish();
+    let mut fields: Vec<Field> = Vec::with_capacity(schema.fields().len());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+    for (i, field) in schema.fields().iter().enumerate() {
+        if i == list_index {
+            fields.push(Field::new(list_field, DataType::Utf8, true));
+            columns.push(Arc::new(elements.finish()));
+        } else {
+            fields.push(field.as_ref().clone());
+            columns.push(
+                arrow::compute::take(batch.column(i), &rows, None).map_err(|e| e.to_string())?,
+            );
+        }
+    }
+    let result_batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
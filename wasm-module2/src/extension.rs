@@ -0,0 +1,276 @@
+//! Semantic validation of fields annotated with an Arrow extension type (field metadata ARROW:extension:name) in the data of wasm_memory_process_data_arrow
+use std::cell::{Cell, RefCell};
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::uuid::is_valid_uuid;
+use crate::{allocate_error, allocate_result, write_arrow_batch, WasmResultStatus};
+
+/// Key of the field metadata with the name of the extension type
+const EXTENSION_NAME_KEY: &str = "ARROW:extension:name";
+
+/// Schemes accepted for values of fields with the extension type url
+const URL_SCHEMES: [&str; 3] = ["http", "https", "ftp"];
+
+// Global variable with the counts of valid and invalid values per annotated field of the last call of wasm_memory_process_data_arrow. The application fetches it via wasm_memory_extension_type_report
+thread_local!(
+    static EXTENSION_TYPE_REPORT: RefCell<Vec<ExtensionTypeCount>> =
+        const { RefCell::new(Vec::new()) };
+);
+
+// Global variable with the number of times the report has been stored, so that it can be detected if a call changed the report
+thread_local!(
+    static EXTENSION_TYPE_REPORT_VERSION: Cell<u64> = const { Cell::new(0) };
+);
+
+/// Number of valid and invalid values of a field annotated with an extension type
+#[derive(Clone)]
+pub(crate) struct ExtensionTypeCount {
+    /// name of the field
+    field: String,
+    /// name of the extension type, e.g. uuid
+    extension_type: String,
+    /// number of non-null values that are valid for the extension type
+    valid_count: u64,
+    /// number of non-null values that are not valid for the extension type
+    invalid_count: u64,
+}
+
+/// Returns the report of the extension types of the data of the last successful call of wasm_memory_process_data_arrow that processed data (ie not with the commands validate or filter or in dry-run mode). Calls answered from the cache (see wasm_set_cache_ttl_ms) restore the report of the cached call
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing one row per field annotated with a known extension type in Arrow IPC format with the schema {field: Utf8, extension_type: Utf8, valid_count: UInt64, invalid_count: UInt64}. Null values are not counted. If the report cannot be serialized, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_extension_type_report() -> u32 {
+    let report: RecordBatch =
+        EXTENSION_TYPE_REPORT.with(|report| extension_type_report(&report.borrow()));
+    match write_arrow_batch(&report) {
+        Ok(serialized_report) => allocate_result(serialized_report),
+        Err(e) => allocate_error(WasmResultStatus::ErrorProcessing, e.to_string()),
+    }
+}
+
+/// Stores the report of the extension types of a call of wasm_memory_process_data_arrow, so that it can be fetched via wasm_memory_extension_type_report
+/// # Arguments
+/// * `counts` - counts of the annotated fields
+pub(crate) fn set_extension_type_report(counts: Vec<ExtensionTypeCount>) {
+    EXTENSION_TYPE_REPORT.with(|report| *report.borrow_mut() = counts);
+    EXTENSION_TYPE_REPORT_VERSION.with(|version| version.set(version.get() + 1));
+}
+
+/// Returns the version of the report of the extension types, e.g. to check if a call changed the report
+///
+/// returns the number of times the report has been stored
+pub(crate) fn extension_type_report_version() -> u64 {
+    EXTENSION_TYPE_REPORT_VERSION.with(|version| version.get())
+}
+
+/// Returns the report of the extension types, e.g. to cache it with the result of a call
+///
+/// returns the counts of the annotated fields
+pub(crate) fn extension_type_counts() -> Vec<ExtensionTypeCount> {
+    EXTENSION_TYPE_REPORT.with(|report| report.borrow().clone())
+}
+
+/// Validates the values of the fields of a record batch annotated with one of the known extension types uuid (FixedSizeBinary(16) or string in the format 8-4-4-4-12 hex digits with a version from 1 to 5 and the variant rfc4122), json (string that can be parsed as JSON), url (string with the scheme http, https or ftp followed by :// and a host) and email (string with a non-empty local part, one @ and a domain with a dot). Fields with other extension types are ignored
+/// # Arguments
+/// * `batch` - record batch of data
+/// * `counts` - counts of previous record batches of the same data. The counts of the record batch are added
+///
+/// returns an error if an annotated field has a type that is not supported by its extension type
+pub(crate) fn validate_extension_types(
+    batch: &RecordBatch,
+    counts: &mut Vec<ExtensionTypeCount>,
+) -> Result<(), String> {
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        let Some(extension_type) = field.metadata().get(EXTENSION_NAME_KEY) else {
+            continue;
+        };
+        let Some(valid_count) = count_valid_values(field.name(), column, extension_type)? else {
+            continue;
+        };
+        let non_null_count: u64 = (column.len() - column.null_count()) as u64;
+        add_count(
+            counts,
+            field.name(),
+            extension_type,
+            valid_count,
+            non_null_count - valid_count,
+        );
+    }
+    Ok(())
+}
+
+/// Counts the values of a field that are valid for its extension type
+/// # Arguments
+/// * `name` - name of the field
+/// * `column` - values of the field
+/// * `extension_type` - name of the extension type
+///
+/// returns the number of non-null values that are valid. It is None if the extension type is not known. Returns an error if the field has a type that is not supported by the extension type
+fn count_valid_values(
+    name: &str,
+    column: &ArrayRef,
+    extension_type: &str,
+) -> Result<Option<u64>, String> {
+    if extension_type == "uuid" && column.data_type() == &DataType::FixedSizeBinary(16) {
+        let uuids = column.as_fixed_size_binary();
+        return Ok(Some(
+            uuids.iter().flatten().filter(|x| is_valid_uuid(x)).count() as u64,
+        ));
+    }
+    let is_valid: fn(&str) -> bool = match extension_type {
+        "uuid" => is_valid_uuid_string,
+        "json" => is_valid_json,
+        "url" => is_valid_url,
+        "email" => is_valid_email,
+        _ => return Ok(None),
+    };
+    let values: ArrayRef = match column.data_type() {
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+            arrow::compute::cast(column, &DataType::Utf8).map_err(|e| e.to_string())?
+        }
+        data_type => {
+            return Err(format!(
+                "Field '{name}' with the extension type {extension_type} has the unsupported type {data_type}"
+            ))
+        }
+    };
+    Ok(Some(
+        values
+            .as_string::<i32>()
+            .iter()
+            .flatten()
+            .filter(|x| is_valid(x))
+            .count() as u64,
+    ))
+}
+
+/// Adds the number of valid and invalid values of a field to the counts
+/// # Arguments
+/// * `counts` - counts of the annotated fields
+/// * `field` - name of the field
+/// * `extension_type` - name of the extension type
+/// * `valid_count` - number of valid values
+/// * `invalid_count` - number of invalid values
+fn add_count(
+    counts: &mut Vec<ExtensionTypeCount>,
+    field: &str,
+    extension_type: &str,
+    valid_count: u64,
+    invalid_count: u64,
+) {
+    match counts
+        .iter_mut()
+        .find(|count| count.field == field && count.extension_type == extension_type)
+    {
+        Some(count) => {
+            count.valid_count += valid_count;
+            count.invalid_count += invalid_count;
+        }
+        None => counts.push(ExtensionTypeCount {
+            field: field.to_string(),
+            extension_type: extension_type.to_string(),
+            valid_count,
+            invalid_count,
+        }),
+    }
+}
+
+/// Creates the report of the extension types
+/// # Arguments
+/// * `counts` - counts of the annotated fields
+///
+/// returns the record batch with the schema {field: Utf8, extension_type: Utf8, valid_count: UInt64, invalid_count: UInt64}
+fn extension_type_report(counts: &[ExtensionTypeCount]) -> RecordBatch {
+    let schema = Schema::new(vec![
+        Field::new("field", DataType::Utf8, false),
+        Field::new("extension_type", DataType::Utf8, false),
+        Field::new("valid_count", DataType::UInt64, false),
+        Field::new("invalid_count", DataType::UInt64, false),
+    ]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from_iter_values(
+                counts.iter().map(|count| count.field.as_str()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                counts.iter().map(|count| count.extension_type.as_str()),
+            )),
+            Arc::new(UInt64Array::from_iter_values(
+                counts.iter().map(|count| count.valid_count),
+            )),
+            Arc::new(UInt64Array::from_iter_values(
+                counts.iter().map(|count| count.invalid_count),
+            )),
+        ],
+    )
+    .unwrap()
+}
+
+/// Checks if a string is a UUID in the format 8-4-4-4-12 hex digits with a version from 1 to 5 and the variant rfc4122
+/// # Arguments
+/// * `value` - string to check
+///
+/// returns true if the string is a valid UUID
+fn is_valid_uuid_string(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+    if groups
+        .iter()
+        .map(|group| group.len())
+        .collect::<Vec<usize>>()
+        != [8, 4, 4, 4, 12]
+    {
+        return false;
+    }
+    let hex: String = groups.concat();
+    let bytes: Option<Vec<u8>> = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect();
+    bytes.is_some_and(|bytes| is_valid_uuid(&bytes))
+}
+
+/// Checks if a string can be parsed as JSON
+/// # Arguments
+/// * `value` - string to check
+///
+/// returns true if the string is valid JSON
+fn is_valid_json(value: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(value).is_ok()
+}
+
+/// Checks if a string is a URL with the scheme http, https or ftp followed by :// and a host
+/// # Arguments
+/// * `value` - string to check
+///
+/// returns true if the string is a valid URL
+fn is_valid_url(value: &str) -> bool {
+    let Some((scheme, rest)) = value.split_once("://") else {
+        return false;
+    };
+    let host: &str = rest.split(['/', '?', '#']).next().unwrap_or("");
+    URL_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str())
+        && !host.is_empty()
+        && !host.contains(char::is_whitespace)
+}
+
+/// Checks if a string is an email address with a non-empty local part, one @ and a domain with a dot that is neither the first nor the last character
+/// # Arguments
+/// * `value` - string to check
+///
+/// returns true if the string is a valid email address
+fn is_valid_email(value: &str) -> bool {
+    let Some((local_part, domain)) = value.split_once('@') else {
+        return false;
+    };
+    !local_part.is_empty()
+        && !domain.contains('@')
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !value.contains(char::is_whitespace)
+}
@@ -0,0 +1,131 @@
+//! Filtering of the rows of data in Arrow IPC format by a predicate on the value of a field, selected by the command "filter" of wasm_memory_process_data_arrow
+use arrow::array::{ArrayRef, BooleanArray, Scalar, StringArray};
+use arrow::compute::kernels::cmp::{eq, gt, gt_eq, lt, lt_eq};
+use arrow::compute::CastOptions;
+use arrow::record_batch::RecordBatch;
+
+use crate::alias::resolve_field_by_name_or_alias;
+use crate::config::ProcessingConfig;
+use crate::output_schema::enforce_output_schema;
+use crate::{read_arrow_batch, write_arrow_batch};
+
+/// Command of the meta data that selects the filtering of the data
+pub(crate) const FILTER_COMMAND: &str = "filter";
+
+/// Comparison operator of a filter
+enum FilterOp {
+    /// value of the field > value of the filter
+    Gt,
+    /// value of the field < value of the filter
+    Lt,
+    /// value of the field == value of the filter
+    Eq,
+    /// value of the field >= value of the filter
+    Ge,
+    /// value of the field <= value of the filter
+    Le,
+}
+
+/// Predicate of the command "filter", e.g. {field: "score", op: "gt", value: "1.0"}
+pub(crate) struct FilterPredicate {
+    /// name (or alias) of the field to compare
+    field: String,
+    /// comparison operator
+    op: FilterOp,
+    /// value to compare with, cast to the type of the field
+    value: String,
+}
+
+impl FilterPredicate {
+    /// Reads the predicate from the configuration of the meta data
+    /// # Arguments
+    /// * `config` - configuration with the keys field, op ("gt", "lt", "eq", "ge" or "le") and value
+    ///
+    /// returns the predicate. Returns an error if a key is missing or the operator is unknown
+    pub(crate) fn from_config(config: &ProcessingConfig) -> Result<FilterPredicate, String> {
+        let field: &str = config
+            .filter_field
+            .as_deref()
+            .ok_or("The command \"filter\" requires the key field in the config")?;
+        let op: &str = config
+            .filter_op
+            .as_deref()
+            .ok_or("The command \"filter\" requires the key op in the config")?;
+        let value: &str = config
+            .filter_value
+            .as_deref()
+            .ok_or("The command \"filter\" requires the key value in the config")?;
+        let op: FilterOp = match op {
+            "gt" => FilterOp::Gt,
+            "lt" => FilterOp::Lt,
+            "eq" => FilterOp::Eq,
+            "ge" => FilterOp::Ge,
+            "le" => FilterOp::Le,
+            _ => {
+                return Err(format!(
+                    "Operator '{op}' of the filter is not supported, only gt, lt, eq, ge and le"
+                ))
+            }
+        };
+        Ok(FilterPredicate {
+            field: field.to_string(),
+            op,
+            value: value.to_string(),
+        })
+    }
+}
+
+/// Filters the rows of the data by a predicate
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `predicate` - predicate of the meta data
+///
+/// returns the rows of the data that fulfill the predicate in Arrow IPC format with the schema of the data. Rows with a null value of the field do not fulfill the predicate
+pub(crate) fn filter_data_arrow(
+    serialized_data: &[u8],
+    predicate: &FilterPredicate,
+) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    // the result has the schema of the data
+    let result_batch: RecordBatch =
+        enforce_output_schema(filter(&batch, predicate)?, &batch.schema())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Applies a predicate to all columns of a record batch
+/// # Arguments
+/// * `batch` - record batch of data
+/// * `predicate` - predicate of the meta data
+///
+/// returns the rows that fulfill the predicate. Returns an error if the field does not exist or the value cannot be cast to the type of the field
+fn filter(batch: &RecordBatch, predicate: &FilterPredicate) -> Result<RecordBatch, String> {
+    let (_, column) = resolve_field_by_name_or_alias(batch, &predicate.field)
+        .ok_or(format!("Field '{}' not found in schema", predicate.field))?;
+    // the value of the config is a string, it is compared as a value of the type of the field (e.g. "1.0" as Float64)
+    let value: ArrayRef = arrow::compute::cast_with_options(
+        &StringArray::from(vec![predicate.value.as_str()]),
+        column.data_type(),
+        &CastOptions {
+            safe: false,
+            ..Default::default()
+        },
+    )
+    .map_err(|e| {
+        format!(
+            "Value '{}' of the filter cannot be compared with field '{}' of type {}: {e}",
+            predicate.value,
+            predicate.field,
+            column.data_type()
+        )
+    })?;
+    let value: Scalar<ArrayRef> = Scalar::new(value);
+    let mask: BooleanArray = match predicate.op {
+        FilterOp::Gt => gt(column, &value),
+        FilterOp::Lt => lt(column, &value),
+        FilterOp::Eq => eq(column, &value),
+        FilterOp::Ge => gt_eq(column, &value),
+        FilterOp::Le => lt_eq(column, &value),
+    }
+    .map_err(|e| e.to_string())?;
+    arrow::compute::filter_record_batch(batch, &mask).map_err(|e| e.to_string())
+}
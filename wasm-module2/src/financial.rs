@@ -0,0 +1,113 @@
+//! Processing of financial data with exact decimal values in Arrow IPC format
+use std::sync::Arc;
+
+use arrow::array::{Array, Decimal128Array, Int64Array, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::telemetry::ProcessingTimer;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Maximum price (without fractional digits) accepted for financial data
+const MAX_PRICE: i128 = 1_000_000_000_000;
+
+/// Processes financial data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format with the schema {id: UInt64, price: Decimal128(18, 6), quantity: Int64}
+/// * `data_size` - size of the data in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the result data in Arrow IPC format with the schema {id: UInt64, total_value: Decimal128(18, 6)}. total_value is null if price or quantity is null. If the processing failed, e.g. a price is not within [0, 1000000000000], the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_process_financial_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // the duration of the processing is recorded for wasm_get_last_processing_duration_ns when the function returns
+    let _processing_timer: ProcessingTimer = ProcessingTimer::start();
+    match process_financial_arrow(&input_vec_data) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the financial data, calculates the total values and serializes the result
+/// # Arguments
+/// * `serialized_data` - financial data in Arrow IPC format
+///
+/// returns the result in Arrow IPC format
+fn process_financial_arrow(serialized_data: &[u8]) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let result_batch: RecordBatch = process_financial(&batch)?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Validates the prices and calculates the total value (price * quantity) of each row. Calculations are done on the raw i128 representation of the decimals to avoid rounding errors
+/// # Arguments
+/// * `batch` - record batch with the financial data
+///
+/// returns a record batch with the id and the total value of each row
+fn process_financial(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    let ids: &UInt64Array = batch
+        .column_by_name("id")
+        .and_then(|column| column.as_any().downcast_ref::<UInt64Array>())
+        .ok_or("Field 'id' of type UInt64 not found in schema")?;
+    let prices: &Decimal128Array = batch
+        .column_by_name("price")
+        .and_then(|column| column.as_any().downcast_ref::<Decimal128Array>())
+        .ok_or("Field 'price' of type Decimal128 not found in schema")?;
+    let quantities: &Int64Array = batch
+        .column_by_name("quantity")
+        .and_then(|column| column.as_any().downcast_ref::<Int64Array>())
+        .ok_or("Field 'quantity' of type Int64 not found in schema")?;
+    let precision: u8 = prices.precision();
+    let scale: i8 = prices.scale();
+    // raw representation of the maximum price with the scale of the prices
+    let max_price_raw: i128 = match scale >= 0 {
+        true => MAX_PRICE * 10_i128.pow(scale as u32),
+        false => MAX_PRICE / 10_i128.pow(scale.unsigned_abs() as u32),
+    };
+    let mut total_values: Vec<Option<i128>> = Vec::with_capacity(batch.num_rows());
+    for i in 0..batch.num_rows() {
+        if prices.is_null(i) || quantities.is_null(i) {
+            total_values.push(None);
+            continue;
+        }
+        let price_raw: i128 = prices.value(i);
+        if !(0..=max_price_raw).contains(&price_raw) {
+            return Err(format!(
+                "Price {} of row {i} is not within [0, {MAX_PRICE}]",
+                prices.value_as_string(i)
+            ));
+        }
+        // price has the scale of the decimal and quantity is an integer, so the product has the same scale
+        let total_value_raw: i128 = price_raw
+            .checked_mul(quantities.value(i) as i128)
+            .ok_or(format!("Total value of row {i} overflows"))?;
+        total_values.push(Some(total_value_raw));
+    }
+    let total_values: Decimal128Array = Decimal128Array::from(total_values)
+        .with_precision_and_scale(precision, scale)
+        .map_err(|e| e.to_string())?;
+    total_values
+        .validate_decimal_precision(precision)
+        .map_err(|e| format!("Total value exceeds the precision of the price: {e}"))?;
+    // define schema
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("total_value", DataType::Decimal128(precision, scale), true),
+    ]);
+    // build a record batch
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(ids.clone()), Arc::new(total_values)],
+    )
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,79 @@
+//! Fingerprints of the schemas expected and returned by the module, e.g. so that the application can skip a full schema comparison if a cached fingerprint matches
+use arrow::datatypes::Schema;
+
+use crate::{expected_data_schema, process_data_result_schema, set_last_error};
+
+/// Offset basis of the 64 bit FNV-1a hash
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+/// Prime of the 64 bit FNV-1a hash
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Separator hashed after each variable-length part of a field, so that e.g. the names "ab" + "c" and "a" + "bc" have different fingerprints. It is not valid UTF-8 and thus cannot occur in a name or data type string
+const FIELD_PART_SEPARATOR: u8 = 0xff;
+
+/// Identifier of a schema of which the application can fetch the fingerprint
+enum SchemaId {
+    /// schema of the data expected by wasm_memory_process_data_arrow
+    ExpectedInput = 0,
+    /// schema of the result returned by wasm_memory_process_data_arrow
+    ExpectedOutput = 1,
+}
+
+/// Returns the fingerprint of a schema of the module
+/// # Arguments
+/// * `schema_id` - 0 for the schema of the data expected by wasm_memory_process_data_arrow, 1 for the schema of its result
+///
+/// returns the fingerprint (see schema_fingerprint). Returns 0 if the schema ID is unknown, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_get_schema_fingerprint(schema_id: u32) -> u64 {
+    match schema_id {
+        x if x == SchemaId::ExpectedInput as u32 => schema_fingerprint(&expected_data_schema()),
+        x if x == SchemaId::ExpectedOutput as u32 => {
+            schema_fingerprint(&process_data_result_schema(false))
+        }
+        _ => {
+            set_last_error(format!("Unknown schema ID {schema_id}"));
+            0
+        }
+    }
+}
+
+/// Computes the FNV-1a hash of the name, the data type (as string) and the nullable flag of each field of a schema in the order of the fields
+/// # Arguments
+/// * `schema` - schema to fingerprint
+///
+/// returns the fingerprint. Schemas with the same fields in the same order have the same fingerprint. Metadata is ignored
+pub(crate) fn schema_fingerprint(schema: &Schema) -> u64 {
+    schema
+        .fields()
+        .iter()
+        .fold(FNV_OFFSET_BASIS, |hash, field| {
+            let hash: u64 = fnv1a_update(hash, field.name().as_bytes());
+            let hash: u64 = fnv1a_update(hash, &[FIELD_PART_SEPARATOR]);
+            let hash: u64 = fnv1a_update(hash, field.data_type().to_string().as_bytes());
+            let hash: u64 = fnv1a_update(hash, &[FIELD_PART_SEPARATOR]);
+            fnv1a_update(hash, &[field.is_nullable() as u8])
+        })
+}
+
+/// Computes the 64 bit FNV-1a hash of bytes
+/// # Arguments
+/// * `bytes` - bytes to hash
+///
+/// returns the hash
+pub(crate) fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    fnv1a_update(FNV_OFFSET_BASIS, bytes)
+}
+
+/// Continues a 64 bit FNV-1a hash with further bytes
+/// # Arguments
+/// * `hash` - hash of the preceding bytes
+/// * `bytes` - bytes to hash
+///
+/// returns the hash of the preceding bytes and the bytes
+fn fnv1a_update(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
@@ -0,0 +1,170 @@
+//! Aggregation of a numeric field of data in Arrow IPC format per value of a grouping field
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{Array, AsArray, Float64Array, Float64Builder, StringArray, StringBuilder};
+use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Groups data in Arrow IPC format from the WASM module memory by a field and aggregates a numeric field per group
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `group_field_offset` - position of the start of the name of the grouping field (Utf8) as UTF-8 string
+/// * `group_field_size` - size of the name of the grouping field
+/// * `agg_field_offset` - position of the start of the name of the numeric field to aggregate as UTF-8 string
+/// * `agg_field_size` - size of the name of the field to aggregate
+/// * `agg_fn_offset` - position of the start of the aggregation function as UTF-8 string. Supported functions are sum, count, mean, min and max
+/// * `agg_fn_size` - size of the aggregation function
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the result data in Arrow IPC format. The result has one row per group in the order the groups first occur with the schema {group: Utf8, result: Float64}. Rows with a null group are ignored, null values are not aggregated. If the aggregation failed, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub extern "C" fn wasm_memory_groupby_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    group_field_offset: *mut u32,
+    group_field_size: u32,
+    agg_field_offset: *mut u32,
+    agg_field_size: u32,
+    agg_fn_offset: *mut u32,
+    agg_fn_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - grouping field
+    let input_vec_group_field: Vec<u8> =
+        match read_shared_memory(group_field_offset, group_field_size) {
+            Some(x) => x,
+            None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+        };
+    // fetch from WASM module memory - field to aggregate
+    let input_vec_agg_field: Vec<u8> = match read_shared_memory(agg_field_offset, agg_field_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - aggregation function
+    let input_vec_agg_fn: Vec<u8> = match read_shared_memory(agg_fn_offset, agg_fn_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match groupby_arrow(
+        &input_vec_data,
+        &input_vec_group_field,
+        &input_vec_agg_field,
+        &input_vec_agg_fn,
+    ) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, groups and aggregates it and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `group_field` - name of the grouping field as UTF-8 string
+/// * `agg_field` - name of the field to aggregate as UTF-8 string
+/// * `agg_fn` - aggregation function as UTF-8 string
+///
+/// returns the result in Arrow IPC format
+fn groupby_arrow(
+    serialized_data: &[u8],
+    group_field: &[u8],
+    agg_field: &[u8],
+    agg_fn: &[u8],
+) -> Result<Vec<u8>, String> {
+    let group_field: &str = std::str::from_utf8(group_field)
+        .map_err(|e| format!("Name of the grouping field is not valid UTF-8: {e}"))?;
+    let agg_field: &str = std::str::from_utf8(agg_field)
+        .map_err(|e| format!("Name of the field to aggregate is not valid UTF-8: {e}"))?;
+    let agg_fn: &str = std::str::from_utf8(agg_fn)
+        .map_err(|e| format!("Aggregation function is not valid UTF-8: {e}"))?;
+    if !["sum", "count", "mean", "min", "max"].contains(&agg_fn) {
+        return Err(format!("Unknown aggregation function '{agg_fn}'"));
+    }
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let groups_column = batch
+        .column_by_name(group_field)
+        .ok_or(format!("Field '{group_field}' not found in schema"))?;
+    if groups_column.data_type() != &DataType::Utf8 {
+        return Err(format!(
+            "Grouping field '{group_field}' has type {} instead of Utf8",
+            groups_column.data_type()
+        ));
+    }
+    let groups: &StringArray = groups_column.as_string::<i32>();
+    let values_column = batch
+        .column_by_name(agg_field)
+        .ok_or(format!("Field '{agg_field}' not found in schema"))?;
+    // Float64 is aggregated directly, other numeric types are casted
+    let values_column = match values_column.data_type() {
+        DataType::Float64 => Arc::clone(values_column),
+        data_type if data_type.is_numeric() => {
+            arrow::compute::cast(values_column, &DataType::Float64).map_err(|e| e.to_string())?
+        }
+        data_type => {
+            return Err(format!(
+                "Field '{agg_field}' has non-numeric type {data_type} and cannot be aggregated"
+            ))
+        }
+    };
+    let values: &Float64Array = values_column.as_primitive::<Float64Type>();
+    // values per group. The order of the groups is kept for a deterministic result
+    let mut group_order: Vec<String> = Vec::new();
+    let mut group_values: HashMap<String, Vec<f64>> = HashMap::new();
+    for row in 0..batch.num_rows() {
+        if groups.is_null(row) {
+            continue;
+        }
+        let group: &str = groups.value(row);
+        let entry: &mut Vec<f64> = match group_values.get_mut(group) {
+            Some(entry) => entry,
+            None => {
+                group_order.push(group.to_string());
+                group_values.entry(group.to_string()).or_default()
+            }
+        };
+        if values.is_valid(row) {
+            entry.push(values.value(row));
+        }
+    }
+    // aggregate each group
+    let mut group_builder = StringBuilder::with_capacity(group_order.len(), 0);
+    let mut result_builder = Float64Builder::with_capacity(group_order.len());
+    for group in &group_order {
+        let values: &Vec<f64> = &group_values[group];
+        let result: Option<f64> = match agg_fn {
+            "sum" => Some(values.iter().sum()),
+            "count" => Some(values.len() as f64),
+            "mean" => {
+                (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+            }
+            "min" => values.iter().copied().reduce(f64::min),
+            _ => values.iter().copied().reduce(f64::max),
+        };
+        group_builder.append_value(group);
+        result_builder.append_option(result);
+    }
+    // define schema
+    let schema = Schema::new(vec![
+        Field::new("group", DataType::Utf8, false),
+        Field::new("result", DataType::Float64, true),
+    ]);
+    let result_batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(group_builder.finish()),
+            Arc::new(result_builder.finish()),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
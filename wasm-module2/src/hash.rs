@@ -0,0 +1,107 @@
+//! Hashing of string and binary fields of data in Arrow IPC format with SHA-256, e.g. to check the integrity of payloads without returning them to the application
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, FixedSizeBinaryArray, FixedSizeBinaryBuilder};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use sha2::{Digest, Sha256};
+
+use crate::validate::column;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Size of a SHA-256 hash in bytes
+const SHA256_SIZE: i32 = 32;
+
+/// Hashes the values of fields of data in Arrow IPC format from the WASM module memory with SHA-256
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `column_names_offset` - position of the start of the fields to hash as UTF-8 comma-separated list of field names, e.g. "content,title". The fields must be of type Utf8 or Binary
+/// * `column_names_size` - size of the list of field names
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the field id and one field {name}_sha256 (FixedSizeBinary(32)) per hashed field in Arrow IPC format. The hash of a null value is null. If a field is missing or has another type, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_hash_columns_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    column_names_offset: *mut u32,
+    column_names_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - column names
+    let input_vec_column_names: Vec<u8> =
+        match read_shared_memory(column_names_offset, column_names_size) {
+            Some(x) => x,
+            None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+        };
+    match hash_columns_arrow(&input_vec_data, &input_vec_column_names) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, hashes the fields and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `column_names` - UTF-8 comma-separated list of field names
+///
+/// returns the ids and hashes in Arrow IPC format
+fn hash_columns_arrow(serialized_data: &[u8], column_names: &[u8]) -> Result<Vec<u8>, String> {
+    let column_names: &str = std::str::from_utf8(column_names)
+        .map_err(|e| format!("List of field names is not valid UTF-8: {e}"))?;
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let id_column: &ArrayRef = column(&batch, "id")?;
+    let mut fields: Vec<Field> = vec![Field::new("id", id_column.data_type().clone(), true)];
+    let mut columns: Vec<ArrayRef> = vec![id_column.clone()];
+    for name in column_names.split(',').map(|name| name.trim()) {
+        let hashes: ArrayRef = Arc::new(hash_column(column(&batch, name)?, name)?);
+        fields.push(Field::new(
+            format!("{name}_sha256"),
+            DataType::FixedSizeBinary(SHA256_SIZE),
+            true,
+        ));
+        columns.push(hashes);
+    }
+    let result_batch: RecordBatch =
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Hashes each value of a field with SHA-256
+/// # Arguments
+/// * `column` - column of the field of type Utf8 or Binary
+/// * `name` - name of the field
+///
+/// returns one hash per value. Returns an error if the field has another type
+fn hash_column(column: &ArrayRef, name: &str) -> Result<FixedSizeBinaryArray, String> {
+    if !matches!(column.data_type(), DataType::Utf8 | DataType::Binary) {
+        return Err(format!(
+            "Field '{name}' has type {} instead of Utf8 or Binary",
+            column.data_type()
+        ));
+    }
+    let value_bytes = |i: usize| -> &[u8] {
+        match column.data_type() {
+            DataType::Utf8 => column.as_string::<i32>().value(i).as_bytes(),
+            _ => column.as_binary::<i32>().value(i),
+        }
+    };
+    let mut hashes = FixedSizeBinaryBuilder::with_capacity(column.len(), SHA256_SIZE);
+    for i in 0..column.len() {
+        if column.is_null(i) {
+            hashes.append_null();
+        } else {
+            hashes
+                .append_value(Sha256::digest(value_bytes(i)))
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(hashes.finish())
+}
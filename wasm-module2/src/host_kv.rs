@@ -0,0 +1,55 @@
+//! Access to the key-value store of the application via the host function host_kv_get, e.g. to read configuration values of tenants without handing them over in each call
+use crate::host_kv_get;
+
+/// Initial size of the buffer for a value in bytes. It is doubled until the value fits
+const INITIAL_VALUE_SIZE: usize = 256;
+
+/// Maximum size of a value in bytes that the module reads from the key-value store
+const MAX_VALUE_SIZE: usize = 1024 * 1024;
+
+/// Return code of host_kv_get if no value is returned
+enum HostKvReturnCode {
+    ErrorKeyNotFound = -1,
+    ErrorBufferTooSmall = -2,
+}
+
+/// Reads a value from the key-value store of the application
+/// # Arguments
+/// * `key` - key of the value
+///
+/// returns the value. It is None if the key does not exist. Returns an error if the value is larger than 1 MiB or the application returns an unknown return code
+pub(crate) fn host_kv_value(key: &str) -> Result<Option<Vec<u8>>, String> {
+    let mut value: Vec<u8> = vec![0; INITIAL_VALUE_SIZE];
+    loop {
+        let return_code: i32 = unsafe {
+            host_kv_get(
+                key.as_ptr(),
+                key.len() as u32,
+                value.as_mut_ptr(),
+                value.len() as u32,
+            )
+        };
+        match return_code {
+            size if size >= 0 => {
+                value.truncate(size as usize);
+                return Ok(Some(value));
+            }
+            x if x == HostKvReturnCode::ErrorKeyNotFound as i32 => return Ok(None),
+            x if x == HostKvReturnCode::ErrorBufferTooSmall as i32
+                && value.len() < MAX_VALUE_SIZE =>
+            {
+                value.resize(value.len() * 2, 0);
+            }
+            x if x == HostKvReturnCode::ErrorBufferTooSmall as i32 => {
+                return Err(format!(
+                    "Value of the key '{key}' is larger than {MAX_VALUE_SIZE} bytes"
+                ))
+            }
+            x => {
+                return Err(format!(
+                    "Reading the key '{key}' from the application failed with return code {x}"
+                ))
+            }
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! Calendar arithmetic with intervals of months, days and nanoseconds on data in Arrow IPC format, e.g. for financial contracts with durations in calendar terms
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, Int32Array, Int64Array, IntervalMonthDayNanoBuilder,
+    TimestampSecondArray, TimestampSecondBuilder,
+};
+use arrow::datatypes::{
+    DataType, Field, Int32Type, Int64Type, IntervalMonthDayNanoType, IntervalUnit, Schema,
+    TimeUnit, TimestampSecondType,
+};
+use arrow::record_batch::RecordBatch;
+use time::{Date, Duration, Month, OffsetDateTime};
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, normalize_tz, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Adds calendar intervals to the timestamps of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format with the schema {start_ts: Timestamp(Second, UTC), months: Int32, days: Int32, nanos: Int64}
+/// * `data_size` - size of the data in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the data in Arrow IPC format with the schema {start_ts: Timestamp(Second, UTC), result_ts: Timestamp(Second, UTC), interval: Interval(MonthDayNano)}. The interval is applied by adding the months first, where the day is clamped to the end of the month (e.g. 31 January plus one month is 28 or 29 February), then the days and then the nanoseconds. result_ts is truncated to seconds. A row with a null value has a null result_ts and interval. If a field is missing, has another type or the result is out of range, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_add_calendar_interval_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match add_calendar_interval_arrow(&input_vec_data) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, adds the intervals to the timestamps and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+///
+/// returns the timestamps, the resulting timestamps and the intervals in Arrow IPC format
+fn add_calendar_interval_arrow(serialized_data: &[u8]) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let start_ts_column: &ArrayRef = batch
+        .column_by_name("start_ts")
+        .ok_or("Field 'start_ts' not found in schema")?;
+    // different representations of UTC are accepted
+    let timestamp_type: DataType = match start_ts_column.data_type() {
+        DataType::Timestamp(TimeUnit::Second, Some(tz)) if normalize_tz(tz) == "+00:00" => {
+            start_ts_column.data_type().clone()
+        }
+        data_type => {
+            return Err(format!(
+                "Field 'start_ts' has type {data_type} instead of Timestamp(Second, \"+00:00\")"
+            ))
+        }
+    };
+    let start_ts: &TimestampSecondArray = start_ts_column.as_primitive::<TimestampSecondType>();
+    let months: &Int32Array =
+        column(&batch, "months", &DataType::Int32)?.as_primitive::<Int32Type>();
+    let days: &Int32Array = column(&batch, "days", &DataType::Int32)?.as_primitive::<Int32Type>();
+    let nanos: &Int64Array = column(&batch, "nanos", &DataType::Int64)?.as_primitive::<Int64Type>();
+    let mut result_ts = TimestampSecondBuilder::with_capacity(batch.num_rows());
+    let mut intervals = IntervalMonthDayNanoBuilder::with_capacity(batch.num_rows());
+    for i in 0..batch.num_rows() {
+        if start_ts.is_null(i) || months.is_null(i) || days.is_null(i) || nanos.is_null(i) {
+            result_ts.append_null();
+            intervals.append_null();
+            continue;
+        }
+        result_ts.append_value(
+            add_calendar_interval(
+                start_ts.value(i),
+                months.value(i),
+                days.value(i),
+                nanos.value(i),
+            )
+            .map_err(|e| format!("Interval of row {i} cannot be applied: {e}"))?,
+        );
+        // Arrow stores the interval as months, days and nanoseconds and not as one encoded i128 value
+        intervals.append_value(IntervalMonthDayNanoType::make_value(
+            months.value(i),
+            days.value(i),
+            nanos.value(i),
+        ));
+    }
+    let result_ts: ArrayRef = Arc::new(result_ts.finish().with_data_type(timestamp_type.clone()));
+    let schema = Schema::new_with_metadata(
+        vec![
+            Field::new("start_ts", timestamp_type.clone(), true),
+            Field::new("result_ts", timestamp_type, true),
+            Field::new(
+                "interval",
+                DataType::Interval(IntervalUnit::MonthDayNano),
+                true,
+            ),
+        ],
+        batch.schema_ref().metadata().clone(),
+    );
+    let result_batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            start_ts_column.clone(),
+            result_ts,
+            Arc::new(intervals.finish()),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Fetches a field of the data
+/// # Arguments
+/// * `batch` - data
+/// * `name` - name of the field
+/// * `data_type` - expected type of the field
+///
+/// returns the column of the field. Returns an error if the field does not exist or has another type
+fn column<'a>(
+    batch: &'a RecordBatch,
+    name: &str,
+    data_type: &DataType,
+) -> Result<&'a ArrayRef, String> {
+    let column: &ArrayRef = batch
+        .column_by_name(name)
+        .ok_or(format!("Field '{name}' not found in schema"))?;
+    if column.data_type() != data_type {
+        return Err(format!(
+            "Field '{name}' has type {} instead of {data_type}",
+            column.data_type()
+        ));
+    }
+    Ok(column)
+}
+
+/// Adds a calendar interval to a timestamp
+/// # Arguments
+/// * `start_ts` - timestamp in seconds since the Unix epoch (UTC)
+/// * `months` - months to add first. The day is clamped to the end of the resulting month
+/// * `days` - days to add second
+/// * `nanos` - nanoseconds to add last
+///
+/// returns the resulting timestamp in seconds since the Unix epoch, truncated to seconds. Returns an error if a timestamp is out of range
+fn add_calendar_interval(start_ts: i64, months: i32, days: i32, nanos: i64) -> Result<i64, String> {
+    let start: OffsetDateTime =
+        OffsetDateTime::from_unix_timestamp(start_ts).map_err(|e| e.to_string())?;
+    let total_months: i64 =
+        start.year() as i64 * 12 + (u8::from(start.month()) as i64 - 1) + months as i64;
+    let year: i32 = i32::try_from(total_months.div_euclid(12)).map_err(|e| e.to_string())?;
+    let month: Month =
+        Month::try_from((total_months.rem_euclid(12) + 1) as u8).map_err(|e| e.to_string())?;
+    let day: u8 = start.day().min(time::util::days_in_month(month, year));
+    let date: Date = Date::from_calendar_date(year, month, day).map_err(|e| e.to_string())?;
+    start
+        .replace_date(date)
+        .checked_add(Duration::days(days as i64))
+        .and_then(|result| result.checked_add(Duration::nanoseconds(nanos)))
+        .map(|result| result.unix_timestamp())
+        .ok_or("Resulting timestamp is out of range".to_string())
+}
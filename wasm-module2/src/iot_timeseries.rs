@@ -0,0 +1,137 @@
+//! Processing of readings of IoT sensors with the time of day of the reading as seconds since midnight (Time32(Second))
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{Array, AsArray, Float32Array, Time32SecondArray, UInt64Array};
+use arrow::datatypes::{
+    DataType, Field, Float32Type, Schema, Time32SecondType, TimeUnit, UInt64Type,
+};
+use arrow::record_batch::RecordBatch;
+
+use crate::telemetry::ProcessingTimer;
+use crate::validate::column;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Last second of a day, ie 23:59:59
+const MAX_SECOND_OF_DAY: i32 = 86_399;
+
+/// Determines the peak reading of each device in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format with the schema {device_id: UInt64, reading_time: Time32(Second), sensor_value: Float32}
+/// * `data_size` - size of the data in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing one row per device in the order the devices first occur in Arrow IPC format with the schema {device_id: UInt64, peak_value: Float32, peak_time: Time32(Second)}. If a device has several readings with the peak value, the first one is returned. Rows with a null value or a sensor value NaN are ignored. If a field is missing, has another type or a reading time is not between 0 and 86399, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_process_iot_timeseries_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // the duration of the processing is recorded for wasm_get_last_processing_duration_ns when the function returns
+    let _processing_timer: ProcessingTimer = ProcessingTimer::start();
+    match process_iot_timeseries_arrow(&input_vec_data) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the readings, determines the peak reading of each device and serializes the result
+/// # Arguments
+/// * `serialized_data` - readings in Arrow IPC format
+///
+/// returns the result in Arrow IPC format
+fn process_iot_timeseries_arrow(serialized_data: &[u8]) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let result_batch: RecordBatch = peak_readings(&batch)?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Determines the peak sensor value and its reading time of each device
+/// # Arguments
+/// * `batch` - record batch with the readings
+///
+/// returns a record batch with the device id, the peak value and the reading time of the peak value of each device
+fn peak_readings(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    let device_ids = column(batch, "device_id")?;
+    let reading_times = column(batch, "reading_time")?;
+    let sensor_values = column(batch, "sensor_value")?;
+    for (name, data_type, expected_data_type) in [
+        ("device_id", device_ids.data_type(), DataType::UInt64),
+        (
+            "reading_time",
+            reading_times.data_type(),
+            DataType::Time32(TimeUnit::Second),
+        ),
+        ("sensor_value", sensor_values.data_type(), DataType::Float32),
+    ] {
+        if data_type != &expected_data_type {
+            return Err(format!(
+                "Field '{name}' has type {data_type} instead of {expected_data_type}"
+            ));
+        }
+    }
+    let device_ids: &UInt64Array = device_ids.as_primitive::<UInt64Type>();
+    let reading_times: &Time32SecondArray = reading_times.as_primitive::<Time32SecondType>();
+    let sensor_values: &Float32Array = sensor_values.as_primitive::<Float32Type>();
+    // index of the peak reading per device. The order of the devices is kept for a deterministic result
+    let mut peak_indices: HashMap<u64, usize> = HashMap::new();
+    let mut devices: Vec<u64> = Vec::new();
+    for i in 0..batch.num_rows() {
+        if reading_times.is_valid(i) && !(0..=MAX_SECOND_OF_DAY).contains(&reading_times.value(i)) {
+            return Err(format!(
+                "Reading time of row {i} is {} seconds, expected between 0 and {MAX_SECOND_OF_DAY} seconds since midnight",
+                reading_times.value(i)
+            ));
+        }
+        if device_ids.is_null(i)
+            || reading_times.is_null(i)
+            || sensor_values.is_null(i)
+            || sensor_values.value(i).is_nan()
+        {
+            continue;
+        }
+        let device_id: u64 = device_ids.value(i);
+        match peak_indices.get_mut(&device_id) {
+            Some(peak_index) => {
+                if sensor_values.value(i) > sensor_values.value(*peak_index) {
+                    *peak_index = i;
+                }
+            }
+            None => {
+                peak_indices.insert(device_id, i);
+                devices.push(device_id);
+            }
+        }
+    }
+    let peak_values: Vec<f32> = devices
+        .iter()
+        .map(|device_id| sensor_values.value(peak_indices[device_id]))
+        .collect();
+    let peak_times: Vec<i32> = devices
+        .iter()
+        .map(|device_id| reading_times.value(peak_indices[device_id]))
+        .collect();
+    // define schema
+    let schema = Schema::new(vec![
+        Field::new("device_id", DataType::UInt64, false),
+        Field::new("peak_value", DataType::Float32, false),
+        Field::new("peak_time", DataType::Time32(TimeUnit::Second), false),
+    ]);
+    // build a record batch
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt64Array::from(devices)),
+            Arc::new(Float32Array::from(peak_values)),
+            Arc::new(Time32SecondArray::from(peak_times)),
+        ],
+    )
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,155 @@
+//! Inner join of two streams of data in Arrow IPC format on a key field, e.g. to enrich documents with data from another source
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, UInt32Array, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, UInt64Type};
+use arrow::record_batch::RecordBatch;
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Joins two streams of data in Arrow IPC format from the WASM module memory on a key field (inner join)
+/// # Arguments
+/// * `left_offset` - position of the start of the left stream in Arrow IPC format
+/// * `left_size` - size of the left stream
+/// * `right_offset` - position of the start of the right stream in Arrow IPC format. It should be the smaller stream, as an index of its keys is built
+/// * `right_size` - size of the right stream
+/// * `key_field_offset` - position of the start of the name of the key field (UInt64 in both streams) as UTF-8 string
+/// * `key_field_size` - size of the name of the key field
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing one row per left row with a matching right row in Arrow IPC format. The fields are the fields of the left stream followed by the fields of the right stream. Fields that exist in both streams (including the key field) are prefixed with "left_" and "right_". Null keys do not match. If the key field is missing, not of type UInt64 or not unique in the right stream, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_join_arrow(
+    left_offset: *mut u32,
+    left_size: u32,
+    right_offset: *mut u32,
+    right_size: u32,
+    key_field_offset: *mut u32,
+    key_field_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - left stream
+    let input_vec_left: Vec<u8> = match read_shared_memory(left_offset, left_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - right stream
+    let input_vec_right: Vec<u8> = match read_shared_memory(right_offset, right_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - key field
+    let input_vec_key_field: Vec<u8> = match read_shared_memory(key_field_offset, key_field_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match join_arrow(&input_vec_left, &input_vec_right, &input_vec_key_field) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes both streams, joins them and serializes the result
+/// # Arguments
+/// * `serialized_left` - left stream in Arrow IPC format
+/// * `serialized_right` - right stream in Arrow IPC format
+/// * `key_field` - name of the key field as UTF-8 string
+///
+/// returns the joined data in Arrow IPC format
+fn join_arrow(
+    serialized_left: &[u8],
+    serialized_right: &[u8],
+    key_field: &[u8],
+) -> Result<Vec<u8>, String> {
+    let key_field: &str = std::str::from_utf8(key_field)
+        .map_err(|e| format!("Name of the key field is not valid UTF-8: {e}"))?;
+    let left: RecordBatch = read_arrow_batch(serialized_left).map_err(|e| e.to_string())?;
+    let right: RecordBatch = read_arrow_batch(serialized_right).map_err(|e| e.to_string())?;
+    let left_keys: &UInt64Array = key_column(&left, key_field, "left")?;
+    let right_keys: &UInt64Array = key_column(&right, key_field, "right")?;
+    // index of the keys of the right stream
+    let mut right_index: HashMap<u64, usize> = HashMap::with_capacity(right_keys.len());
+    for i in 0..right_keys.len() {
+        if right_keys.is_null(i) {
+            continue;
+        }
+        if right_index.insert(right_keys.value(i), i).is_some() {
+            return Err(format!(
+                "Key {} is not unique in the right stream",
+                right_keys.value(i)
+            ));
+        }
+    }
+    // rows of both streams that match
+    let mut left_rows: Vec<u32> = Vec::new();
+    let mut right_rows: Vec<u32> = Vec::new();
+    for i in 0..left_keys.len() {
+        if left_keys.is_null(i) {
+            continue;
+        }
+        if let Some(right_row) = right_index.get(&left_keys.value(i)) {
+            left_rows.push(i as u32);
+            right_rows.push(*right_row as u32);
+        }
+    }
+    let left_rows = UInt32Array::from(left_rows);
+    let right_rows = UInt32Array::from(right_rows);
+    let left_names: HashSet<&String> = left
+        .schema_ref()
+        .fields()
+        .iter()
+        .map(|f| f.name())
+        .collect();
+    let right_names: HashSet<&String> = right
+        .schema_ref()
+        .fields()
+        .iter()
+        .map(|f| f.name())
+        .collect();
+    let mut fields: Vec<Field> = Vec::with_capacity(left.num_columns() + right.num_columns());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(left.num_columns() + right.num_columns());
+    for (batch, rows, other_names, prefix) in [
+        (&left, &left_rows, &right_names, "left_"),
+        (&right, &right_rows, &left_names, "right_"),
+    ] {
+        for (field, column) in batch.schema_ref().fields().iter().zip(batch.columns()) {
+            // fields that exist in both streams are renamed
+            let name: String = if other_names.contains(field.name()) {
+                format!("{prefix}{}", field.name())
+            } else {
+                field.name().clone()
+            };
+            fields.push(field.as_ref().clone().with_name(name));
+            columns.push(arrow::compute::take(column, rows, None).map_err(|e| e.to_string())?);
+        }
+    }
+    let result_batch: RecordBatch =
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Fetches the key field of a stream
+/// # Arguments
+/// * `batch` - data of the stream
+/// * `key_field` - name of the key field
+/// * `side` - side of the stream in the join, ie "left" or "right"
+///
+/// returns the keys. Returns an error if the field does not exist or is not of type UInt64
+fn key_column<'a>(
+    batch: &'a RecordBatch,
+    key_field: &str,
+    side: &str,
+) -> Result<&'a UInt64Array, String> {
+    let column: &ArrayRef = batch.column_by_name(key_field).ok_or(format!(
+        "Key field '{key_field}' not found in schema of the {side} stream"
+    ))?;
+    if column.data_type() != &DataType::UInt64 {
+        return Err(format!(
+            "Key field '{key_field}' of the {side} stream has type {} instead of UInt64",
+            column.data_type()
+        ));
+    }
+    Ok(column.as_primitive::<UInt64Type>())
+}
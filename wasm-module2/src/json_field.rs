@@ -0,0 +1,148 @@
+//! Extraction of values from a field containing JSON documents as strings, e.g. metadata of documents embedded by the source system
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, StringArray, StringBuilder};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::validate::column;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Extracts a value from a field with JSON documents of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `src_field_offset` - position of the start of the name of the field (Utf8) with the JSON documents as UTF-8 string
+/// * `src_field_size` - size of the name of the field with the JSON documents
+/// * `json_path_offset` - position of the start of the path of the value in the JSON documents as UTF-8 string. The path consists of the keys of the nested objects separated by dots, e.g. "metadata.author.name". Elements of arrays are selected by their index, e.g. "authors.0"
+/// * `json_path_size` - size of the path
+/// * `out_field_offset` - position of the start of the name of the extracted field as UTF-8 string
+/// * `out_field_size` - size of the name of the extracted field
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the data with the extracted field (Utf8) appended in Arrow IPC format. String values are extracted as they are, other values as JSON. The extracted value is null if the document is null, not valid JSON, does not contain the path or contains null at the path. If the field with the JSON documents is missing or not of type Utf8, the path is empty or the extracted field already exists, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub extern "C" fn wasm_memory_parse_json_field_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    src_field_offset: *mut u32,
+    src_field_size: u32,
+    json_path_offset: *mut u32,
+    json_path_size: u32,
+    out_field_offset: *mut u32,
+    out_field_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - field with the JSON documents
+    let input_vec_src_field: Vec<u8> = match read_shared_memory(src_field_offset, src_field_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - path of the value
+    let input_vec_json_path: Vec<u8> = match read_shared_memory(json_path_offset, json_path_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - extracted field
+    let input_vec_out_field: Vec<u8> = match read_shared_memory(out_field_offset, out_field_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match parse_json_field_arrow(
+        &input_vec_data,
+        &input_vec_src_field,
+        &input_vec_json_path,
+        &input_vec_out_field,
+    ) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, extracts the value of the path from each JSON document and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `src_field` - name of the field with the JSON documents as UTF-8 string
+/// * `json_path` - path of the value as UTF-8 string
+/// * `out_field` - name of the extracted field as UTF-8 string
+///
+/// returns the data with the extracted field in Arrow IPC format
+fn parse_json_field_arrow(
+    serialized_data: &[u8],
+    src_field: &[u8],
+    json_path: &[u8],
+    out_field: &[u8],
+) -> Result<Vec<u8>, String> {
+    let src_field: &str = std::str::from_utf8(src_field).map_err(|e| {
+        format!("Name of the field with the JSON documents is not valid UTF-8: {e}")
+    })?;
+    let json_path: &str = std::str::from_utf8(json_path)
+        .map_err(|e| format!("Path of the value is not valid UTF-8: {e}"))?;
+    let out_field: &str = std::str::from_utf8(out_field)
+        .map_err(|e| format!("Name of the extracted field is not valid UTF-8: {e}"))?;
+    if json_path.is_empty() {
+        return Err("Path of the value is empty".to_string());
+    }
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let schema = batch.schema();
+    if schema.index_of(out_field).is_ok() {
+        return Err(format!("Field '{out_field}' already exists in schema"));
+    }
+    let documents: &ArrayRef = column(&batch, src_field)?;
+    if documents.data_type() != &DataType::Utf8 {
+        return Err(format!(
+            "Field '{src_field}' has type {} instead of Utf8",
+            documents.data_type()
+        ));
+    }
+    let documents: &StringArray = documents.as_string::<i32>();
+    let path: Vec<&str> = json_path.split('.').collect();
+    let mut values = StringBuilder::new();
+    for document in documents.iter() {
+        values.append_option(document.and_then(|document| extract_value(document, &path)));
+    }
+    let mut fields: Vec<Field> = schema
+        .fields()
+        .iter()
+        .map(|field| field.as_ref().clone())
+        .collect();
+    fields.push(Field::new(out_field, DataType::Utf8, true));
+    let mut columns: Vec<ArrayRef> = batch.columns().to_vec();
+    columns.push(Arc::new(values.finish()));
+    let result_batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Extracts the value of a path from a JSON document
+/// # Arguments
+/// * `document` - JSON document
+/// * `path` - keys of the nested objects or indexes of the arrays of the path
+///
+/// returns the value as string. String values are returned as they are, other values as JSON. It is None if the document is not valid JSON, does not contain the path or contains null at the path
+fn extract_value(document: &str, path: &[&str]) -> Option<String> {
+    let document: serde_json::Value = serde_json::from_str(document).ok()?;
+    let mut value: &serde_json::Value = &document;
+    for segment in path {
+        value = match value {
+            serde_json::Value::Object(object) => object.get(*segment)?,
+            serde_json::Value::Array(array) => array.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(value) => Some(value.clone()),
+        value => Some(value.to_string()),
+    }
+}
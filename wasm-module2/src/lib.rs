@@ -1,69 +1,537 @@
+use std::alloc::Layout;
 use std::cell::Cell;
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::mem::ManuallyDrop;
 use std::sync::Arc;
 
-use arrow::array::{StringArray, UInt64Array};
+use arrow::array::{ArrayRef, AsArray, Float16Array, LargeStringArray, StringArray, UInt64Array};
 use arrow::datatypes::{
     DataType, Field, Float64Type, Schema, TimeUnit, TimestampSecondType, UInt64Type,
 };
+use arrow::error::ArrowError;
 use arrow::ipc::reader::StreamReader;
 use arrow::ipc::writer::StreamWriter;
 use arrow::record_batch::RecordBatch;
 
+use half::f16;
+
 use time::macros::datetime;
+use time::OffsetDateTime;
+
+use alias::{rename_aliased_fields, resolve_field_by_name_or_alias};
+use attachment::{append_attachment_column, attachment_result_field, split_attachment};
+use bloom::{
+    add_processed_ids, append_duplicate_column, bloom_filter_enabled, duplicate_result_field,
+    skip_duplicates,
+};
+use cache::{cache_enabled, cache_key, cache_result, cached_result};
+use coerce::{coerce_batch, materialize_dictionaries};
+use config::{check_max_length, read_config, ProcessingConfig};
+use config_store::{config_settings, max_content_length, tenant_config_settings};
+use context::current_trace_id;
+use date64::{convert_date64_to_date32, date_source_type, DATE_SOURCE_TYPE_KEY};
+use decimal_score::{coerce_decimal_score, has_decimal_score, SCORE_PRECISION_LOSS_KEY};
+use dry_run::{dry_run_data_arrow, is_dry_run};
+use error_injection::inject_error;
+use extension::{
+    extension_type_counts, extension_type_report_version, set_extension_type_report,
+    validate_extension_types, ExtensionTypeCount,
+};
+use filter::{filter_data_arrow, FilterPredicate, FILTER_COMMAND};
+use null_handling::{
+    append_null_handling_column, handle_nulls, null_handling_mode, null_handling_result_field,
+};
+use output_limit::check_output_size;
+use output_schema::enforce_output_schema;
+use run_encoding::{min_run_length, run_encode_batch};
+use schema_pin::check_pinned_schema;
+use telemetry::{record_memory_usage, CallTelemetry, ProcessingTimer};
+use tenant::current_tenant_id;
+use timestamp_precision::{
+    normalize_timestamp_precision, original_timestamp_precision, ORIGINAL_TIMESTAMP_PRECISION_KEY,
+};
+use unflatten::unflatten_batch;
+use validate::{validate_data_arrow, VALIDATE_COMMAND};
+use writer_pool::write_arrow_batch_pooled;
+
+mod aggregate;
+mod alias;
+mod attachment;
+mod bloom;
+mod build_info;
+mod cache;
+mod cardinality;
+mod checksum;
+mod chunked;
+mod coerce;
+mod concat;
+mod config;
+mod config_store;
+mod context;
+mod csv;
+mod date64;
+mod datetime;
+mod decimal_score;
+mod deduplicate;
+mod diff;
+mod dry_run;
+mod embeddings;
+mod error_injection;
+mod explode;
+mod extension;
+mod filter;
+mod financial;
+mod fingerprint;
+mod groupby;
+mod hash;
+mod host_kv;
+mod interval;
+mod iot_timeseries;
+mod join;
+mod json_field;
+mod lz4;
+mod merge_sort;
+mod normalize;
+mod null_handling;
+mod output_limit;
+mod output_schema;
+mod parquet;
+mod partition;
+mod project;
+mod quality;
+mod ranges;
+mod run_encoding;
+mod sample;
+mod schema_pin;
+mod stats;
+mod stream;
+mod tagged_docs;
+mod telemetry;
+mod tenant;
+mod timestamp_precision;
+mod timezone;
+mod unflatten;
+mod union;
+mod unpivot;
+mod uuid;
+mod validate;
+mod versioned;
+mod window;
+mod writer_pool;
+
+// Functions provided by the application to the module
+extern "C" {
+    /// Logs a UTF-8 message via the logger of the application
+    /// # Arguments
+    /// * `level` - log level, see HostLogLevel
+    /// * `msg_ptr` - pointer to the message in the WASM module memory
+    /// * `msg_len` - length of the message
+    fn host_log(level: i32, msg_ptr: *const u8, msg_len: u32);
+    /// Reads a value from the key-value store of the application
+    /// # Arguments
+    /// * `key_ptr` - pointer to the key in the WASM module memory
+    /// * `key_len` - length of the key
+    /// * `val_ptr` - pointer to the memory in the WASM module to which the value is written
+    /// * `val_max_len` - size of the memory of the value
+    ///
+    /// returns the size of the value (0 or more) if it has been written. Returns -1 if the key does not exist and -2 if the value is larger than val_max_len
+    fn host_kv_get(key_ptr: *const u8, key_len: u32, val_ptr: *mut u8, val_max_len: u32) -> i32;
+    /// Writes a value to the key-value store of the application
+    /// # Arguments
+    /// * `key_ptr` - pointer to the key in the WASM module memory
+    /// * `key_len` - length of the key
+    /// * `val_ptr` - pointer to the value in the WASM module memory
+    /// * `val_len` - length of the value
+    ///
+    /// returns 0 if the value has been written
+    // the module currently only reads from the key-value store
+    #[allow(dead_code)]
+    fn host_kv_set(key_ptr: *const u8, key_len: u32, val_ptr: *const u8, val_len: u32) -> i32;
+}
 
-// Global variable to keep track of allocated memory
+/// Log levels understood by the host function host_log
+enum HostLogLevel {
+    Error = 0,
+    Warn = 1,
+    Debug = 3,
+}
+
+/// Key of a memory area: the tenant for which it has been allocated (see wasm_set_tenant_id) and the pointer
+type MemoryAreaKey = (u32, *const u8);
+
+// Global variable to keep track of allocated memory by (tenant, pointer), see wasm_set_tenant_id
 // Note: This is really an execption as allocate by the app to the module should have only for parameters
 // Otherwise it would be really bad for performance.
 thread_local!(
-    static MEMORY_AREAS: RefCell<HashMap<*const u8, (usize, ManuallyDrop<Box<[u8]>>)>> =
+    static MEMORY_AREAS: RefCell<HashMap<MemoryAreaKey, (usize, MemoryArea)>> =
         RefCell::new(HashMap::new());
 );
 
+// Global variable to keep track of memory that has been deallocated by (tenant, pointer), so that deallocating it again can be reported as such
+// A pointer is removed once the same address is allocated again
+thread_local!(
+    static FREED_AREAS: RefCell<HashSet<MemoryAreaKey>> = RefCell::new(HashSet::new());
+);
+
+/// Total size of the values of a Utf8 field above which it is processed as LargeUtf8 (64-bit offsets)
+const LARGE_UTF8_THRESHOLD_BYTES: usize = 1 << 30;
+
+/// Fields of the data that are accepted as strings as views (Utf8View) and casted to Utf8
+const UTF8_VIEW_FIELDS: [&str; 2] = ["content", "title"];
+
+/// Version of the processing recorded in the schema metadata of results (processing_version)
+const PROCESSING_VERSION: &str = "1.0.0";
+
+/// Alignment of memory allocated for the application. The Arrow IPC specification recommends 8-byte aligned buffers
+const MEMORY_ALIGNMENT: usize = 8;
+
+/// Memory area shared between the application and the module
+enum MemoryArea {
+    /// memory allocated for the application to write parameters with its layout (alignment MEMORY_ALIGNMENT)
+    Aligned(*mut u8, Layout),
+    /// memory allocated by the module to return results
+    Boxed(ManuallyDrop<Box<[u8]>>),
+}
+
+// Global variable to keep track of the last error that occurred in the module
+// The application can fetch it via wasm_last_error after a function signaled an error (e.g. by returning 0)
+thread_local!(
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+);
+
+/// Default minimum number of rows of a record batch of data processed by wasm_memory_process_data_arrow
+const DEFAULT_MIN_ROWS: usize = 1;
+
+/// Default maximum number of rows of a record batch of data processed by wasm_memory_process_data_arrow
+const DEFAULT_MAX_ROWS: usize = 10_000;
+
+// Global variable with the limits (minimum, maximum) of the number of rows of a record batch of data. The application can adjust them per session via wasm_set_row_limits
+thread_local!(
+    static ROW_LIMITS: Cell<(usize, usize)> =
+        const { Cell::new((DEFAULT_MIN_ROWS, DEFAULT_MAX_ROWS)) };
+);
+
+/// Result of a function of the module returned to the application as pointer. The application reads status first: if it is non-zero, it can fetch details via wasm_last_error. If it is zero, data_ptr and data_len describe the result data
+/// Layout in the WASM module memory (little endian): status at byte 0, data_ptr at byte 4, data_len at byte 8
+#[repr(C)]
+pub struct WasmResult {
+    pub status: i32,
+    pub data_ptr: u32,
+    pub data_len: u32,
+}
+
+impl WasmResult {
+    /// Converts the result to its representation in the WASM module memory
+    ///
+    /// returns the result as bytes
+    fn to_bytes(&self) -> Box<[u8]> {
+        let mut vec_result: Vec<u8> = Vec::new();
+        vec_result.extend_from_slice(&self.status.to_le_bytes());
+        vec_result.extend_from_slice(&self.data_ptr.to_le_bytes());
+        vec_result.extend_from_slice(&self.data_len.to_le_bytes());
+        vec_result.into_boxed_slice()
+    }
+}
+
+/// Status of a WasmResult
+enum WasmResultStatus {
+    Success = 0,
+    ErrorInvalidMemory = -1,
+    ErrorProcessing = -2,
+    ErrorSchemaMismatch = -3,
+    ErrorTimeout = -4,
+    ErrorOutOfMemory = -5,
+    ErrorChecksum = -20,
+    ErrorOutputTooLarge = -40,
+}
+
 enum MemoryAreasReturnCode {
     Success = 0,
     ErrorMemmoryNotAllocated = -1,
+    ErrorMemoryAlreadyFreed = -2,
+}
+
+/// Return code of wasm_health_check
+enum HealthCheckReturnCode {
+    Success = 0,
+    ErrorAllocation = -1,
+    ErrorPatternMismatch = -2,
+    ErrorDeallocation = -3,
+    ErrorMemoryLeaked = -100,
 }
 
+/// Return code of wasm_warmup
+enum WarmupReturnCode {
+    Success = 0,
+    ErrorAllocation = -1,
+    ErrorDeallocation = -2,
+}
+
+/// Number of times the buffers are allocated and deallocated by wasm_warmup
+const WARMUP_ROUNDS: usize = 10;
+
+/// Number of bytes written and read back by wasm_health_check
+const HEALTH_CHECK_SIZE: usize = 64;
+
+/// First HEALTH_CHECK_SIZE primes used as pattern by wasm_health_check
+const HEALTH_CHECK_PRIMES: [u16; HEALTH_CHECK_SIZE] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241, 251, 257, 263, 269, 271, 277, 281, 283, 293, 307,
+    311,
+];
+
 /// Allocate some memory for the application to write data for the module
 /// Note: It is up to the application (and not the WASM module) to provide enough pages, so the module does not run out of memory
 /// # Arguments
 /// * `size` - size of memory to allocaten
-/// returns a pointer to the allocated memory area
+/// returns a pointer to the allocated memory area. The pointer is aligned to MEMORY_ALIGNMENT (8 bytes)
 #[no_mangle]
 pub extern "C" fn wasm_allocate(size: u32) -> *const u8 {
-    // create a Box with empty memory
-    let alloc_box = ManuallyDrop::new(vec![0u8; size as usize].into_boxed_slice());
-    return allocate(size as usize, alloc_box);
+    return allocate_aligned(size as usize);
+}
+
+/// Allocate several memory areas for the application at once to avoid one call of wasm_allocate per memory area, e.g. for meta data and data
+/// # Arguments
+/// * `sizes_ptr` - pointer to `count` sizes (u32, little endian) of memory to allocate. The sizes need to be written by the application to memory allocated with wasm_allocate
+/// * `count` - number of memory areas to allocate
+///
+/// returns a pointer to `count` pointers (u32, little endian) to the allocated memory areas in the order of the sizes. Returns 0 if the sizes are not valid allocated memory or the memory cannot be allocated. Note: The calling application must deallocate the returned pointer as well as each allocated memory area with wasm_deallocate
+#[no_mangle]
+pub extern "C" fn wasm_allocate_batch(sizes_ptr: *mut u32, count: u32) -> u32 {
+    // fetch from WASM module memory - sizes
+    let sizes_size: u32 = match count.checked_mul(4) {
+        Some(x) => x,
+        None => return 0,
+    };
+    let input_vec_sizes: Vec<u8> = match read_shared_memory(sizes_ptr, sizes_size) {
+        Some(x) => x,
+        None => return 0, // return if no valid allocated memory was provided
+    };
+    let mut ptrs: Vec<*const u8> = Vec::with_capacity(count as usize);
+    for size_bytes in input_vec_sizes.chunks_exact(4) {
+        let size: u32 = u32::from_le_bytes(size_bytes.try_into().unwrap());
+        let ptr: *const u8 = allocate_aligned(size as usize);
+        if ptr.is_null() {
+            // do not leave any memory areas allocated if one cannot be allocated
+            for allocated_ptr in ptrs {
+                wasm_deallocate(allocated_ptr);
+            }
+            return 0;
+        }
+        ptrs.push(ptr);
+    }
+    let vec_ptrs: Vec<u8> = ptrs
+        .iter()
+        .flat_map(|ptr| (*ptr as u32).to_le_bytes())
+        .collect();
+    let vec_ptrs_len: usize = vec_ptrs.len();
+    allocate(vec_ptrs_len, ManuallyDrop::new(vec_ptrs.into_boxed_slice())) as u32
 }
 
 /// Deallocates existing memory for the purpose of the application
 /// # Arguments
 /// * `ptr` - mutuable pointer to the memory to deallocate
-/// returns a code if it was successful or not
+/// returns a code if it was successful or not. It is -2 if the memory has already been deallocated and -1 if it has never been allocated for the current tenant (see wasm_set_tenant_id)
 #[no_mangle]
 pub extern "C" fn wasm_deallocate(ptr: *const u8) -> i32 {
-    // check if the ptr exists
-    let cell: Cell<Option<(usize, ManuallyDrop<Box<[u8]>>)>> = Cell::new(None);
-    MEMORY_AREAS.with(|mem_map| cell.set(mem_map.borrow_mut().remove(&ptr)));
-    let memory_area: Option<(usize, ManuallyDrop<Box<[u8]>>)> = cell.into_inner();
+    // check if the ptr exists for the current tenant
+    let key: MemoryAreaKey = (current_tenant_id(), ptr);
+    let cell: Cell<Option<(usize, MemoryArea)>> = Cell::new(None);
+    MEMORY_AREAS.with(|mem_map| cell.set(mem_map.borrow_mut().remove(&key)));
+    let memory_area: Option<(usize, MemoryArea)> = cell.into_inner();
     match memory_area {
-        Some(x) => ManuallyDrop::into_inner(x.1), // will then be deleted after function returns
-        None => return MemoryAreasReturnCode::ErrorMemmoryNotAllocated as i32,
+        // free memory allocated for the application with the same layout
+        Some((_, MemoryArea::Aligned(aligned_ptr, layout))) => unsafe {
+            std::alloc::dealloc(aligned_ptr, layout)
+        },
+        Some((_, MemoryArea::Boxed(x))) => drop(ManuallyDrop::into_inner(x)),
+        None if FREED_AREAS.with(|freed| freed.borrow().contains(&key)) => {
+            log(
+                HostLogLevel::Warn,
+                &format!("Cannot deallocate memory at {ptr:?} that has already been deallocated"),
+            );
+            return MemoryAreasReturnCode::ErrorMemoryAlreadyFreed as i32;
+        }
+        None => {
+            log(
+                HostLogLevel::Warn,
+                &format!("Cannot deallocate memory at {ptr:?} that has not been allocated"),
+            );
+            return MemoryAreasReturnCode::ErrorMemmoryNotAllocated as i32;
+        }
     };
+    FREED_AREAS.with(|freed| freed.borrow_mut().insert(key));
     // return success
     return MemoryAreasReturnCode::Success as i32;
 }
 
+/// Validates if a pointer has been allocated in this module for the current tenant (see wasm_set_tenant_id) and not yet deallocated
+/// # Arguments
+/// * `ptr` - pointer to the memory area
+///
+/// returns the size of the memory area. It is 0 if the pointer is invalid
+#[no_mangle]
+pub extern "C" fn wasm_validate_pointer(ptr: *const u8) -> u32 {
+    validate_pointer(ptr) as u32
+}
+
+/// Returns the total size of the memory areas that have been allocated in this module for the current tenant (see wasm_set_tenant_id) and not yet deallocated
+///
+/// returns the total size in bytes
+#[no_mangle]
+pub extern "C" fn wasm_allocated_bytes() -> u32 {
+    live_memory_bytes(Some(current_tenant_id())) as u32
+}
+
+/// Initializes the module. The application should call it once after instantiating the module
+///
+/// Installs a panic hook that stores the panic message as last error before the module traps, so that the application can fetch the reason of the trap via wasm_last_error
+#[no_mangle]
+pub extern "C" fn wasm_init() {
+    std::panic::set_hook(Box::new(|info| {
+        let msg = info.to_string();
+        LAST_ERROR.with(|e| *e.borrow_mut() = Some(msg));
+    }));
+}
+
+/// Returns the last error that occurred in the module
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the error message (a Rust str). Returns 0 if no error occurred. Note: The calling application must signal to the module that the memory can be fred by calling deallocate on the returned pointer and the error message pointer
+#[no_mangle]
+pub extern "C" fn wasm_last_error() -> u32 {
+    match LAST_ERROR.with(|last_error| last_error.borrow().clone()) {
+        Some(error_message) => allocate_result(error_message.into_bytes()),
+        None => 0,
+    }
+}
+
+/// Sets the limits of the number of rows of a record batch of data processed by wasm_memory_process_data_arrow. The limits apply to all following calls of the instance (default: 1 to 10000 rows)
+/// # Arguments
+/// * `min` - minimum number of rows
+/// * `max` - maximum number of rows
+///
+/// returns 0 if the limits have been set. Returns -1 if min is greater than max, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_set_row_limits(min: u32, max: u32) -> i32 {
+    if min > max {
+        set_last_error(format!(
+            "Minimum number of rows {min} is greater than the maximum number of rows {max}"
+        ));
+        return -1;
+    }
+    ROW_LIMITS.with(|row_limits| row_limits.set((min as usize, max as usize)));
+    0
+}
+
+/// Returns the version of the module
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the version of the module (a Rust str), e.g. 0.1.0. Note: The calling application must signal to the module that the memory can be fred by calling deallocate on the returned pointer and the version pointer
+#[no_mangle]
+pub extern "C" fn wasm_version() -> u32 {
+    allocate_result(env!("CARGO_PKG_VERSION").as_bytes().to_vec())
+}
+
+/// Checks that the module is healthy, e.g. before an idle instance is reused. Memory is allocated, a known pattern (the first 64 primes, truncated to u8) is written and read back and the memory is deallocated again
+/// Note: The application must not hold any memory allocated in the module while calling the health check
+///
+/// returns 0 if the module is healthy. Returns -100 if memory allocated in the module has not been deallocated (leaked) and another negative code if a step of the self test failed (see HealthCheckReturnCode)
+#[no_mangle]
+pub extern "C" fn wasm_health_check() -> i32 {
+    // check that no memory has been leaked
+    if live_memory_areas() > 0 {
+        log(
+            HostLogLevel::Warn,
+            "Health check failed: memory has been allocated, but not deallocated",
+        );
+        return HealthCheckReturnCode::ErrorMemoryLeaked as i32;
+    }
+    // write the pattern
+    let ptr: *const u8 = allocate_aligned(HEALTH_CHECK_SIZE);
+    if ptr.is_null() {
+        return HealthCheckReturnCode::ErrorAllocation as i32;
+    }
+    let pattern: Vec<u8> = health_check_pattern();
+    unsafe { std::ptr::copy_nonoverlapping(pattern.as_ptr(), ptr as *mut u8, HEALTH_CHECK_SIZE) };
+    // read the pattern back
+    let read_pattern: Option<Vec<u8>> =
+        read_shared_memory(ptr as *mut u32, HEALTH_CHECK_SIZE as u32);
+    if read_pattern != Some(pattern) {
+        wasm_deallocate(ptr);
+        return HealthCheckReturnCode::ErrorPatternMismatch as i32;
+    }
+    if wasm_deallocate(ptr) != MemoryAreasReturnCode::Success as i32 {
+        return HealthCheckReturnCode::ErrorDeallocation as i32;
+    }
+    HealthCheckReturnCode::Success as i32
+}
+
+/// Warms up a new instance, so that the first call of e.g. wasm_memory_process_data_arrow is not slowed down by a cold allocator and untouched memory pages. Buffers of the typical sizes are allocated the same way as for the application (input) and for results (output), filled with a known pattern (the health check pattern repeated) and deallocated again, WARMUP_ROUNDS (10) times
+/// Note: The application should call it before the instance is used, e.g. directly after instantiation
+/// # Arguments
+/// * `typical_input_size` - typical size of the data handed over by the application
+/// * `typical_output_size` - typical size of the result data
+///
+/// returns 0 if the warm-up succeeded and a negative code otherwise (see WarmupReturnCode)
+#[no_mangle]
+pub extern "C" fn wasm_warmup(typical_input_size: u32, typical_output_size: u32) -> i32 {
+    let pattern: Vec<u8> = health_check_pattern();
+    for _ in 0..WARMUP_ROUNDS {
+        // input: allocated for the application, which writes the data
+        let input_ptr: *const u8 = allocate_aligned(typical_input_size as usize);
+        if input_ptr.is_null() {
+            return WarmupReturnCode::ErrorAllocation as i32;
+        }
+        let input_buffer: &mut [u8] = unsafe {
+            std::slice::from_raw_parts_mut(input_ptr as *mut u8, typical_input_size as usize)
+        };
+        for (byte, pattern_byte) in input_buffer.iter_mut().zip(pattern.iter().cycle()) {
+            *byte = *pattern_byte;
+        }
+        // output: allocated by the module to return results
+        let output_data: Vec<u8> = pattern
+            .iter()
+            .cycle()
+            .take(typical_output_size as usize)
+            .copied()
+            .collect();
+        let output_ptr: *const u8 = allocate(
+            output_data.len(),
+            ManuallyDrop::new(output_data.into_boxed_slice()),
+        );
+        if wasm_deallocate(input_ptr) != MemoryAreasReturnCode::Success as i32
+            || wasm_deallocate(output_ptr) != MemoryAreasReturnCode::Success as i32
+        {
+            return WarmupReturnCode::ErrorDeallocation as i32;
+        }
+    }
+    WarmupReturnCode::Success as i32
+}
+
 /// A simple example function that processes data in Arrow IPC format from the WASM module memory
 /// # Arguments
-/// * `meta_data_offset` - position of the start of the meta data ("command") in Arrow IPC format
+/// * `meta_data_offset` - position of the start of the meta data ("command") in Arrow IPC format with the schema {command: Utf8, config: Map(Utf8, Utf8)}. The config may also be a Struct with one field per key or, for applications without support of nested types, flattened to one field per key named config.<key> (e.g. config.filename). Unknown keys of the config are ignored. The config contains the keys:
+///   * `filename` - name of the file of the data
+///   * `field`, `op` and `value` - comparison of the command "filter"
+///   * `encoding` - encoding of the strings (only "utf-8")
+///   * `max_length` - maximum number of characters of the content of a document (default: max_content_length of wasm_config_set)
+///   * `tenant` - name of a tenant whose configuration is read from the key-value store of the application under the keys "tenant/<tenant>/<key>", e.g. "tenant/acme/score_threshold", before falling back to wasm_config_set. Cached results are only reused if these values of the tenant are unchanged
 /// * `meta_data_size` - size of the meta data in Arrow IPC format
 /// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
 /// * `data_size` - size of the data in Arrow IPC format
-/// Returns an offset in the WASM module memory where an offset and size of the result data in Arrow IPC format are stored
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the result data in Arrow IPC format. If a stage fails, the status is non-zero, see wasm_last_error for details. The stages of the processing are:
+/// * Error injection: if errors are injected for testing (see wasm_set_error_injection_rate), the status of the failing calls is ErrorProcessing (-2) and wasm_last_error returns "Injected error for testing"
+/// * Schema pinning: if a schema has been pinned (see wasm_pin_schema) and the schema of the data does not contain it, the status is ErrorSchemaMismatch (-3)
+/// * Cache: if the cache is enabled (see wasm_set_cache_ttl_ms), the result of identical meta data and data is returned from the cache
+/// * Dry run: if the dry-run mode is enabled (see wasm_set_dry_run), the data is only validated and the result has the schema {would_process_rows: UInt64, input_valid: Boolean, estimated_output_rows: UInt64}
+/// * Validation: each record batch of data must have 5 fields (without the optional field attachment) and a number of rows within the limits set by wasm_set_row_limits (default: 1 to 10000)
+/// * Coercion: fields with a compatible type (e.g. id: Int32 instead of UInt64) are coerced to the expected type and dictionary encoded fields (e.g. Dictionary(Int32, Utf8)) are decoded to their value type. The date is accepted as timestamp with a precision of second, millisecond, microsecond or nanosecond. Other precisions than second are truncated to second and the schema metadata of the result contains the original precision as original_timestamp_precision, e.g. "millisecond". The date is also accepted as Date64, whose time of day is truncated (the schema metadata of the result contains date_source_type "date64"), or as Date32, which are processed as midnight. Renamed fields are accepted if their field metadata contains the expected name as alias (e.g. {"alias": "content"} for a field body)
+/// * Command "test": returns the processed document. If the data has a field attachment with the binary payload of the document of type Binary or LargeBinary (64-bit offsets for payloads exceeding 2 GB), the result has the processed payload as field attachment of the same type
+/// * Command "validate": returns one row per document with the verdicts {id: UInt64, score_valid: Boolean, content_valid: Boolean, id_valid: Boolean, all_valid: Boolean}
+/// * Command "filter": returns the rows of the data whose field (key field of the config) fulfills the comparison (key op: "gt", "lt", "eq", "ge" or "le") with a value (key value, cast to the type of the field)
 #[no_mangle]
 pub extern "C" fn wasm_memory_process_data_arrow(
     meta_data_offset: *mut u32,
@@ -71,39 +539,150 @@ pub extern "C" fn wasm_memory_process_data_arrow(
     data_offset: *mut u32,
     data_size: u32,
 ) -> u32 {
-    // validate meta data pointer
-    let expected_size_meta_data: usize = validate_pointer(meta_data_offset as *const u8);
-    if (expected_size_meta_data == 0) | (expected_size_meta_data != meta_data_size as usize) {
-        return 0;
-    }; // return if no valid allocated memory was provided
-       // validate data pointer
-    let expected_size_data: usize = validate_pointer(data_offset as *const u8);
-    if (expected_size_data == 0) | (expected_size_data != data_size as usize) {
-        return 0;
-    }; // return if no valid allocated memory was provided
-       // fetch from WASM module memory - meta data
-    let mut input_vec_meta_data: Vec<u8> = Vec::new();
-    unsafe {
-        Vec::extend_from_slice(
-            &mut input_vec_meta_data,
-            std::slice::from_raw_parts(meta_data_offset as *mut u8, meta_data_size as usize),
-        )
+    // the call is recorded for wasm_get_telemetry when the function returns
+    let mut call_telemetry: CallTelemetry = CallTelemetry::start();
+    // the injected error is counted as failed call
+    if let Some(injected_error) = inject_error() {
+        return injected_error;
+    }
+    // fetch from WASM module memory - meta data
+    let input_vec_meta_data: Vec<u8> = match read_shared_memory(meta_data_offset, meta_data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
     };
-    // fetch from WASM module memory - / data
-    let mut input_vec_data: Vec<u8> = Vec::new();
-    unsafe {
-        Vec::extend_from_slice(
-            &mut input_vec_data,
-            std::slice::from_raw_parts(data_offset as *mut u8, data_size as usize),
-        )
+    // fetch from WASM module memory - data. The application can retry smaller data if there is no memory for the copy
+    let input_vec_data: Vec<u8> = match try_read_shared_memory(data_offset, data_size) {
+        Ok(Some(x)) => x,
+        Ok(None) => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+        Err(error_message) => {
+            return allocate_error(WasmResultStatus::ErrorOutOfMemory, error_message)
+        }
     };
+    // the duration of the processing is recorded for wasm_get_last_processing_duration_ns when the function returns
+    let _processing_timer: ProcessingTimer = ProcessingTimer::start();
+    process_data_arrow_pipeline(&input_vec_meta_data, &input_vec_data, &mut call_telemetry)
+}
+
+/// Processes the meta data and data in Arrow IPC format read from the WASM module memory by wasm_memory_process_data_arrow and wasm_memory_process_data_arrow_checked
+/// # Arguments
+/// * `input_vec_meta_data` - meta data ("command") in Arrow IPC format
+/// * `input_vec_data` - data in Arrow IPC format
+/// * `call_telemetry` - telemetry of the call, which is marked as succeeded if the data has been processed
+///
+/// returns a pointer to a WasmResult in the WASM module memory containing the result data in Arrow IPC format. Data of another schema than the pinned one is rejected and identical inputs are answered from the cache if it is enabled
+pub(crate) fn process_data_arrow_pipeline(
+    input_vec_meta_data: &[u8],
+    input_vec_data: &[u8],
+    call_telemetry: &mut CallTelemetry,
+) -> u32 {
+    log(
+        HostLogLevel::Debug,
+        &format!(
+            "Processing {} bytes of meta data and {} bytes of data",
+            input_vec_meta_data.len(),
+            input_vec_data.len()
+        ),
+    );
+    // data of another schema than the pinned one is rejected, also if a result is cached
+    if let Err(error_message) = check_pinned_schema(input_vec_data) {
+        return allocate_error(WasmResultStatus::ErrorSchemaMismatch, error_message);
+    }
+    // identical inputs with identical settings are answered from the cache if it is enabled. The result depends on the ids seen before if there is a Bloom filter
+    let result_cache_key: Option<[u8; 32]> = if cache_enabled() && !bloom_filter_enabled() {
+        // the configuration of the tenant in the key-value store of the application is part of the key. The result is not cached if it cannot be read, the processing reports the error
+        tenant_config_settings(meta_data_tenant(input_vec_meta_data).as_deref())
+            .ok()
+            .and_then(|tenant_settings| {
+                cache_key(&[
+                    &processing_settings(),
+                    &tenant_settings,
+                    input_vec_meta_data,
+                    input_vec_data,
+                ])
+            })
+    } else {
+        None
+    };
+    if let Some((cached_result, cached_report)) = result_cache_key.as_ref().and_then(cached_result)
+    {
+        // the report of the extension types is the same as if the data had been processed again
+        if let Some(cached_report) = cached_report {
+            set_extension_type_report(cached_report);
+        }
+        call_telemetry.succeeded();
+        return allocate_result(cached_result);
+    }
+    let report_version: u64 = extension_type_report_version();
+    match process_data_arrow(input_vec_meta_data, input_vec_data) {
+        // allocate memory for the answer
+        Ok(serialized_result_batch) => {
+            if let Some(result_cache_key) = result_cache_key {
+                // the report is only cached if the call changed it, e.g. not for the command validate
+                let report: Option<Vec<ExtensionTypeCount>> =
+                    (extension_type_report_version() != report_version).then(extension_type_counts);
+                cache_result(result_cache_key, &serialized_result_batch, report);
+            }
+            call_telemetry.succeeded();
+            allocate_result(serialized_result_batch)
+        }
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Settings of the instance that change the result of wasm_memory_process_data_arrow, so that cached results are only reused with the same settings
+///
+/// returns the row limits, the dry-run mode, the null handling mode, the run-end encoding and the configuration of the instance (see wasm_config_set) as UTF-8 string
+fn processing_settings() -> Vec<u8> {
+    let (min_rows, max_rows): (usize, usize) = ROW_LIMITS.with(|row_limits| row_limits.get());
+    let mut settings: Vec<u8> = format!(
+        "{min_rows},{max_rows},{},{},{:?},",
+        is_dry_run(),
+        null_handling_mode(),
+        min_run_length()
+    )
+    .into_bytes();
+    settings.extend(config_settings());
+    settings
+}
+
+/// Reads the tenant of the meta data, e.g. to include its configuration in the key of cached results
+/// # Arguments
+/// * `input_vec_meta_data` - meta data ("command") in Arrow IPC format
+///
+/// returns the value of the key tenant of the config of the last record batch. It is None if there is no tenant or the meta data is not valid
+fn meta_data_tenant(input_vec_meta_data: &[u8]) -> Option<String> {
+    let batch: RecordBatch = StreamReader::try_new(input_vec_meta_data, None)
+        .ok()?
+        .last()?
+        .ok()?;
+    let batch: RecordBatch = unflatten_batch(&batch, "config").ok()?;
+    if batch.num_columns() < 2 || batch.num_rows() == 0 {
+        return None;
+    }
+    read_config(batch.column(1), 0).ok()?.tenant
+}
+
+/// Processes the meta data and data in Arrow IPC format
+/// # Arguments
+/// * `input_vec_meta_data` - meta data ("command") in Arrow IPC format
+/// * `input_vec_data` - data in Arrow IPC format
+///
+/// returns the result data in Arrow IPC format. Returns an error if the data cannot be coerced to the expected schema
+fn process_data_arrow(
+    input_vec_meta_data: &[u8],
+    input_vec_data: &[u8],
+) -> Result<Vec<u8>, String> {
     // check the meta data and data
     // deserialize the meta data
-    let stream_reader_meta_data =
-        StreamReader::try_new(input_vec_meta_data.as_slice(), None).unwrap();
+    let stream_reader_meta_data = StreamReader::try_new(input_vec_meta_data, None).unwrap();
     // check if the meta data content is as expected (ie hardcoded in app)
+    let mut command: String = String::new();
+    let mut max_length: Option<usize> = None;
+    let mut tenant: Option<String> = None;
+    let mut filter_predicate: Option<FilterPredicate> = None;
     for item in stream_reader_meta_data {
-        let arrow_record_batch = item.unwrap();
+        // applications without support of nested types provide the config flattened, e.g. as config.filename
+        let arrow_record_batch: RecordBatch = unflatten_batch(&item.unwrap(), "config")?;
         // validate schema
         assert_eq!(arrow_record_batch.schema().field(0).name(), "command");
         assert_eq!(
@@ -111,140 +690,716 @@ pub extern "C" fn wasm_memory_process_data_arrow(
             &DataType::Utf8
         );
         assert_eq!(arrow_record_batch.schema().field(1).name(), "config");
-        assert_eq!(
+        // the configuration is a map, so that new keys do not change the schema
+        assert!(matches!(
             arrow_record_batch.schema().field(1).data_type(),
-            &DataType::Struct(arrow::datatypes::Fields::from(vec![Field::new("filename", DataType::Utf8, false)]))
-        );
+            DataType::Map(_, _) | DataType::Struct(_)
+        ));
 
         // validate meta_data
         assert_eq!(arrow_record_batch.num_rows(), 1);
         let first_row_command =
             arrow::array::as_string_array(arrow_record_batch.column(0)).value(0);
-        assert_eq!(first_row_command, "test");
-        let first_row_config =
-            arrow::array::as_struct_array(arrow_record_batch.column(1)).column(0);
-        let first_row_config_filename = arrow::array::as_string_array(first_row_config).value(0);
-        assert_eq!(first_row_config_filename, "test.txt");
+        assert!(matches!(
+            first_row_command,
+            "test" | VALIDATE_COMMAND | FILTER_COMMAND
+        ));
+        command = first_row_command.to_string();
+        let first_row_config: ProcessingConfig = read_config(arrow_record_batch.column(1), 0)?;
+        assert_eq!(first_row_config.filename.as_deref(), Some("test.txt"));
+        // the meta data takes precedence over the configuration of the instance
+        max_length = match first_row_config.max_length {
+            Some(max_length) => Some(max_length),
+            None => max_content_length(first_row_config.tenant.as_deref())?,
+        };
+        if command == FILTER_COMMAND {
+            filter_predicate = Some(FilterPredicate::from_config(&first_row_config)?);
+        }
+        tenant = first_row_config.tenant;
     }
+    process_command_data_arrow(
+        &command,
+        max_length,
+        tenant.as_deref(),
+        filter_predicate.as_ref(),
+        input_vec_data,
+    )
+}
 
+/// Processes the data in Arrow IPC format with a command of the meta data
+/// # Arguments
+/// * `command` - command of the meta data, e.g. "test"
+/// * `max_length` - maximum number of characters of the content of a document. It is None if the length is not limited
+/// * `tenant` - tenant of the meta data, see tenant_config_value
+/// * `filter_predicate` - predicate of the command "filter". It is None for other commands
+/// * `input_vec_data` - data in Arrow IPC format
+///
+/// returns the result data in Arrow IPC format. Returns an error if the data cannot be coerced to the expected schema
+pub(crate) fn process_command_data_arrow(
+    command: &str,
+    max_length: Option<usize>,
+    tenant: Option<&str>,
+    filter_predicate: Option<&FilterPredicate>,
+    input_vec_data: &[u8],
+) -> Result<Vec<u8>, String> {
+    // in dry-run mode the data is only validated
+    if is_dry_run() {
+        return dry_run_data_arrow(command, input_vec_data);
+    }
+    // the command selects how the data is processed
+    if command == VALIDATE_COMMAND {
+        return validate_data_arrow(input_vec_data, tenant);
+    }
+    if let Some(filter_predicate) = filter_predicate {
+        return filter_data_arrow(input_vec_data, filter_predicate);
+    }
     // deserialize the  data
-    let stream_reader_data = StreamReader::try_new(input_vec_data.as_slice(), None).unwrap();
+    let stream_reader_data = StreamReader::try_new(input_vec_data, None).unwrap();
+    // the provenance of the data is propagated to the result
+    let mut metadata: HashMap<String, String> =
+        provenance_metadata(stream_reader_data.schema().metadata());
     // check if the  data content is as expected (ie hardcoded in app)
+    let mut large_utf8: bool = false;
+    let mut null_count: u64 = 0;
+    let mut duplicate_count: u64 = 0;
+    let mut processed_ids: HashSet<u64> = HashSet::new();
+    let mut extension_type_counts: Vec<ExtensionTypeCount> = Vec::new();
+    let mut attachments: Option<ArrayRef> = None;
     for item in stream_reader_data {
-        let arrow_record_batch = item.unwrap();
-        // validate schema
-        assert_eq!(arrow_record_batch.schema().field(0).name(), "id");
-        assert_eq!(
-            arrow_record_batch.schema().field(0).data_type(),
-            &DataType::UInt64
-        );
-        assert_eq!(arrow_record_batch.schema().field(1).name(), "content");
-        assert_eq!(
-            arrow_record_batch.schema().field(1).data_type(),
-            &DataType::Utf8
-        );
-        assert_eq!(arrow_record_batch.schema().field(2).name(), "title");
-        assert_eq!(
-            arrow_record_batch.schema().field(2).data_type(),
-            &DataType::Utf8
-        );
-        assert_eq!(arrow_record_batch.schema().field(3).name(), "date");
-        assert_eq!(
-            arrow_record_batch.schema().field(3).data_type(),
-            &DataType::Timestamp(TimeUnit::Second, Some("+00:00".to_string().into()))
-        );
-        assert_eq!(arrow_record_batch.schema().field(4).name(), "score");
-        assert_eq!(
-            arrow_record_batch.schema().field(4).data_type(),
-            &DataType::Float64
-        );
-        // validate data
-        assert_eq!(arrow_record_batch.num_rows(), 1);
-        let first_row_id =
-            arrow::array::as_primitive_array::<UInt64Type>(arrow_record_batch.column(0)).value(0);
-        assert_eq!(first_row_id, 1);
-        let first_row_content =
-            arrow::array::as_string_array(arrow_record_batch.column(1)).value(0);
-        assert_eq!(first_row_content, "this is a test");
-        let first_row_title = arrow::array::as_string_array(arrow_record_batch.column(2)).value(0);
-        assert_eq!(first_row_title, "test");
-        let first_row_date =
-            arrow::array::as_primitive_array::<TimestampSecondType>(arrow_record_batch.column(3))
-                .value(0);
-        assert_eq!(
-            first_row_date,
-            datetime!(2022-01-01 12:00:00 UTC).unix_timestamp()
-        );
-        let first_row_score =
-            arrow::array::as_primitive_array::<Float64Type>(arrow_record_batch.column(4)).value(0);
-        assert_eq!(first_row_score, 1.123456f64);
+        // dictionary encoded fields are processed like the fields of their value type
+        let arrow_record_batch: RecordBatch = materialize_dictionaries(&item.unwrap())?;
+        // fields annotated with an extension type are validated semantically, see wasm_memory_extension_type_report
+        validate_extension_types(&arrow_record_batch, &mut extension_type_counts)?;
+        large_utf8 |= has_large_utf8(&arrow_record_batch);
+        // the application is informed that the precision of the timestamps has been reduced
+        if let Some(precision) = original_timestamp_precision(&arrow_record_batch) {
+            metadata.insert(
+                ORIGINAL_TIMESTAMP_PRECISION_KEY.to_string(),
+                precision.to_string(),
+            );
+        }
+        // the application is informed that the time of day of the dates has been truncated
+        if let Some(source_type) = date_source_type(&arrow_record_batch) {
+            metadata.insert(DATE_SOURCE_TYPE_KEY.to_string(), source_type.to_string());
+        }
+        // the application is informed that the scores have been coerced from Decimal128 to Float64
+        if has_decimal_score(&arrow_record_batch) {
+            metadata.insert(SCORE_PRECISION_LOSS_KEY.to_string(), "true".to_string());
+        }
+        if let Some(max_length) = max_length {
+            check_max_length(&arrow_record_batch, max_length)?;
+        }
+        // documents with ids seen in previous calls are skipped if there is a Bloom filter
+        let (arrow_record_batch, batch_duplicate_count) =
+            skip_duplicates(&arrow_record_batch, &mut processed_ids)?;
+        duplicate_count += batch_duplicate_count;
+        // batches without rows that are not duplicates are not validated
+        if batch_duplicate_count > 0 && arrow_record_batch.num_rows() == 0 {
+            continue;
+        }
+        // the binary payloads of the documents are processed separately from the other fields
+        let (arrow_record_batch, batch_attachments) = split_attachment(&arrow_record_batch)?;
+        null_count += process_data_batch(&arrow_record_batch)?;
+        if batch_attachments.is_some() {
+            attachments = batch_attachments;
+        }
+    }
+    let result_batch: RecordBatch =
+        append_null_handling_column(process_data_result(large_utf8, metadata), null_count)?;
+    let result_batch: RecordBatch = append_duplicate_column(result_batch, duplicate_count)?;
+    let result_batch: RecordBatch = append_attachment_column(result_batch, attachments.as_ref())?;
+    let result_batch: RecordBatch = enforce_output_schema(
+        result_batch,
+        &process_data_output_schema(large_utf8, attachments.as_ref()),
+    )?;
+    set_extension_type_report(extension_type_counts);
+    let serialized_result_batch: Vec<u8> =
+        write_arrow_batch(&result_batch).map_err(|e| e.to_string())?;
+    // the ids are only added if the call succeeded, so that the data of a failed call can be retried
+    add_processed_ids(&processed_ids);
+    Ok(serialized_result_batch)
+}
+
+/// Processes one record batch of data, ie checks that the data content is as expected (ie hardcoded in app)
+/// # Arguments
+/// * `batch` - record batch of data
+///
+/// returns the number of null values handled according to wasm_set_null_handling (see append_null_handling_column). Returns an error if the data cannot be coerced to the expected schema or contains null values that cannot be handled
+fn process_data_batch(batch: &RecordBatch) -> Result<u64, String> {
+    // reject batches that would make the processing below panic
+    validate_data_batch_structure(batch)?;
+    // fields renamed by the application are accepted via their alias
+    let arrow_record_batch = rename_aliased_fields(batch, &expected_data_schema())?;
+    // timestamps with a precision of millisecond, microsecond or nanosecond are processed with a precision of second
+    let arrow_record_batch = normalize_timestamp_precision(&arrow_record_batch)?;
+    // dates in milliseconds (Date64) are processed as days (Date32), ie the time of day is truncated
+    let arrow_record_batch = convert_date64_to_date32(&arrow_record_batch, "date")?;
+    // dates without a time of day are compared with the day of the expected date
+    let date_only: bool = arrow_record_batch
+        .schema()
+        .field_with_name("date")
+        .is_ok_and(|field| field.data_type() == &DataType::Date32);
+    // strings as views are processed as Utf8
+    let arrow_record_batch = cast_utf8_view_columns(&arrow_record_batch)?;
+    // scores with half precision are processed as Float64
+    let (arrow_record_batch, float16_score) = widen_float16_score(&arrow_record_batch)?;
+    // scores with a fixed precision are processed as Float64
+    let arrow_record_batch = coerce_decimal_score(&arrow_record_batch)?;
+    // tolerate compatible changes of the schema by the application
+    let arrow_record_batch = coerce_batch(&arrow_record_batch, &expected_data_schema())?;
+    let arrow_record_batch = widen_large_utf8_columns(&arrow_record_batch)?;
+    let (arrow_record_batch, null_count) = handle_nulls(&arrow_record_batch)?;
+    // skipping rows with null values may leave too few rows
+    validate_data_batch_structure(&arrow_record_batch)?;
+    // validate schema
+    assert_eq!(arrow_record_batch.schema().field(0).name(), "id");
+    assert_eq!(
+        arrow_record_batch.schema().field(0).data_type(),
+        &DataType::UInt64
+    );
+    // strings are accepted with 32-bit (Utf8) and 64-bit (LargeUtf8) offsets
+    assert_eq!(arrow_record_batch.schema().field(1).name(), "content");
+    assert!(matches!(
+        arrow_record_batch.schema().field(1).data_type(),
+        DataType::Utf8 | DataType::LargeUtf8
+    ));
+    assert_eq!(arrow_record_batch.schema().field(2).name(), "title");
+    assert!(matches!(
+        arrow_record_batch.schema().field(2).data_type(),
+        DataType::Utf8 | DataType::LargeUtf8
+    ));
+    assert_eq!(arrow_record_batch.schema().field(3).name(), "date");
+    // different representations of UTC are accepted
+    let date_data_type: DataType = match arrow_record_batch.schema().field(3).data_type() {
+        DataType::Timestamp(time_unit, Some(tz)) => {
+            DataType::Timestamp(*time_unit, Some(normalize_tz(tz).into()))
+        }
+        data_type => data_type.clone(),
+    };
+    assert_eq!(
+        date_data_type,
+        DataType::Timestamp(TimeUnit::Second, Some("+00:00".to_string().into()))
+    );
+    assert_eq!(arrow_record_batch.schema().field(4).name(), "score");
+    assert_eq!(
+        arrow_record_batch.schema().field(4).data_type(),
+        &DataType::Float64
+    );
+    // validate data
+    assert_eq!(arrow_record_batch.num_rows(), 1);
+    let first_row_id =
+        arrow::array::as_primitive_array::<UInt64Type>(arrow_record_batch.column(0)).value(0);
+    assert_eq!(first_row_id, 1);
+    let first_row_content = string_value(arrow_record_batch.column(1), 0);
+    assert_eq!(first_row_content, "this is a test");
+    let first_row_title = string_value(arrow_record_batch.column(2), 0);
+    assert_eq!(first_row_title, "test");
+    let first_row_date =
+        arrow::array::as_primitive_array::<TimestampSecondType>(arrow_record_batch.column(3))
+            .value(0);
+    let expected_date: OffsetDateTime = if date_only {
+        datetime!(2022-01-01 00:00:00 UTC)
+    } else {
+        datetime!(2022-01-01 12:00:00 UTC)
+    };
+    assert_eq!(first_row_date, expected_date.unix_timestamp());
+    let first_row_score =
+        arrow::array::as_primitive_array::<Float64Type>(arrow_record_batch.column(4)).value(0);
+    // a score with half precision is the expected score rounded to Float16
+    let expected_score: f64 = if float16_score {
+        f16::from_f64(1.123456f64).to_f64()
+    } else {
+        1.123456f64
+    };
+    assert_eq!(first_row_score, expected_score);
+    Ok(null_count)
+}
+
+/// Casts the fields content and title from Utf8View to Utf8. The application can send strings as views (StringView), which inline strings of up to 12 bytes and improve the cache performance of the application for short strings
+/// # Arguments
+/// * `batch` - record batch of data
+///
+/// returns the record batch with the fields content and title as Utf8. Other fields are not changed
+fn cast_utf8_view_columns(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = Vec::with_capacity(batch.num_columns());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(batch.num_columns());
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        if UTF8_VIEW_FIELDS.contains(&field.name().as_str())
+            && field.data_type() == &DataType::Utf8View
+        {
+            fields.push(field.as_ref().clone().with_data_type(DataType::Utf8));
+            columns.push(arrow::compute::cast(column, &DataType::Utf8).map_err(|e| e.to_string())?);
+        } else {
+            fields.push(field.as_ref().clone());
+            columns.push(column.clone());
+        }
     }
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Casts the field score from Float16 to Float64. The application can send scores with half precision to reduce the size of the data if the precision of Float64 is not needed. They are processed and returned as Float64 to avoid further rounding errors
+/// # Arguments
+/// * `batch` - record batch of data
+///
+/// returns the record batch with the score as Float64 and true if the score was of type Float16. Other record batches are returned unchanged with false
+fn widen_float16_score(batch: &RecordBatch) -> Result<(RecordBatch, bool), String> {
+    let schema = batch.schema();
+    let score_index: usize = match resolve_field_by_name_or_alias(batch, "score") {
+        Some((score_index, _)) => score_index,
+        None => return Ok((batch.clone(), false)),
+    };
+    let scores: &Float16Array = match batch.column(score_index).as_primitive_opt() {
+        Some(scores) => scores,
+        None => return Ok((batch.clone(), false)),
+    };
+    let widened_scores: ArrayRef =
+        arrow::compute::cast(scores, &DataType::Float64).map_err(|e| e.to_string())?;
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields[score_index] = fields[score_index]
+        .clone()
+        .with_data_type(DataType::Float64);
+    let mut columns: Vec<ArrayRef> = batch.columns().to_vec();
+    columns[score_index] = widened_scores;
+    let widened_batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok((widened_batch, true))
+}
+
+/// Generates the result of processing the data
+/// # Arguments
+/// * `large_utf8` - true if the strings of the result should have 64-bit offsets (LargeUtf8), e.g. because the data contained LargeUtf8 fields
+/// * `metadata` - schema metadata of the result, e.g. created by provenance_metadata
+///
+/// returns the result data
+fn process_data_result(large_utf8: bool, metadata: HashMap<String, String>) -> RecordBatch {
     // lets generate a return answer to the processing request modifying the field content of document with id 1
     // define schema
-    let schema = Schema::new(vec![
-        Field::new("id", DataType::UInt64, false),
-        Field::new("content", DataType::Utf8, false),
-    ]);
+    let schema: Schema = Schema::new_with_metadata(
+        process_data_result_schema(large_utf8).fields().clone(),
+        metadata,
+    );
     let ids = UInt64Array::from(vec![1]);
-    let contents = StringArray::from(vec!["this is a test2"]);
+    let contents: ArrayRef = if large_utf8 {
+        Arc::new(LargeStringArray::from(vec!["this is a test2"]))
+    } else {
+        Arc::new(StringArray::from(vec!["this is a test2"]))
+    };
 
     // build a record batch
-    let result_batch = RecordBatch::try_new(
-        Arc::new(schema.clone()),
-        vec![Arc::new(ids), Arc::new(contents)],
-    )
-    .unwrap();
-    // serialize it
-    let buffer: Vec<u8> = Vec::new();
+    RecordBatch::try_new(Arc::new(schema.clone()), vec![Arc::new(ids), contents]).unwrap()
+}
 
-    let mut stream_writer = StreamWriter::try_new(buffer, &schema).unwrap();
-    stream_writer.write(&result_batch).unwrap();
+/// Schema of the result of processing the data
+/// # Arguments
+/// * `large_utf8` - true if the strings of the result have 64-bit offsets (LargeUtf8)
+///
+/// returns the schema {id: UInt64, content: Utf8} or {id: UInt64, content: LargeUtf8}
+fn process_data_result_schema(large_utf8: bool) -> Schema {
+    let string_data_type: DataType = if large_utf8 {
+        DataType::LargeUtf8
+    } else {
+        DataType::Utf8
+    };
+    Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", string_data_type, false),
+    ])
+}
 
-    let serialized_result_batch: Vec<u8> = stream_writer.into_inner().unwrap();
-    // allocate memory for the answer
-    let serialized_result_batch_alloc: ManuallyDrop<Box<[u8]>> =
-        ManuallyDrop::new(serialized_result_batch.into_boxed_slice());
-    let serialized_result_batch_alloc_len: usize = serialized_result_batch_alloc.len();
+/// Declared schema of the result of the command "test", depending on the settings of the instance and the data
+/// # Arguments
+/// * `large_utf8` - true if the strings of the result have 64-bit offsets (LargeUtf8)
+/// * `attachments` - attachments of the data, if any
+///
+/// returns the schema of process_data_result with the additional fields of the null handling (see wasm_set_null_handling), the Bloom filter (see wasm_init_bloom_filter) and the attachments
+fn process_data_output_schema(large_utf8: bool, attachments: Option<&ArrayRef>) -> Schema {
+    let mut fields: Vec<Field> = process_data_result_schema(large_utf8)
+        .fields()
+        .iter()
+        .map(|f| f.as_ref().clone())
+        .collect();
+    fields.extend(null_handling_result_field());
+    fields.extend(duplicate_result_field());
+    fields.extend(attachment_result_field(attachments));
+    Schema::new(fields)
+}
 
-    let serialized_result_batch_ptr = allocate(
-        serialized_result_batch_alloc_len,
-        serialized_result_batch_alloc,
-    );
-    // return position of WASM memory where we can find a offset, length pair
-    let mut vec_meta: Vec<u8> = Vec::new();
-    let serialized_result_batch_ptr_array: [u8; (usize::BITS / 8) as usize] =
-        (serialized_result_batch_ptr as usize).to_le_bytes();
-    let serialized_result_batch_alloc_len: [u8; (usize::BITS / 8) as usize] =
-        serialized_result_batch_alloc_len.to_le_bytes();
-    for byte in serialized_result_batch_ptr_array {
-        vec_meta.push(byte);
+/// Checks if a record batch contains string fields with 64-bit offsets
+/// # Arguments
+/// * `batch` - record batch to check
+///
+/// returns true if at least one field is of type LargeUtf8
+fn has_large_utf8(batch: &RecordBatch) -> bool {
+    batch
+        .schema()
+        .fields()
+        .iter()
+        .any(|field| field.data_type() == &DataType::LargeUtf8)
+}
+
+/// Casts Utf8 fields whose values exceed LARGE_UTF8_THRESHOLD_BYTES to LargeUtf8, so that they can be processed like LargeUtf8 fields. Other fields are not changed
+/// # Arguments
+/// * `batch` - record batch to widen
+///
+/// returns the record batch with the widened fields
+fn widen_large_utf8_columns(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    let mut fields: Vec<Field> = Vec::with_capacity(batch.num_columns());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(batch.num_columns());
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        let value_bytes: usize = match column.as_string_opt::<i32>() {
+            Some(string_column) => {
+                let offsets: &[i32] = string_column.value_offsets();
+                (offsets[offsets.len() - 1] - offsets[0]) as usize
+            }
+            None => 0,
+        };
+        if value_bytes > LARGE_UTF8_THRESHOLD_BYTES {
+            fields.push(field.as_ref().clone().with_data_type(DataType::LargeUtf8));
+            columns.push(
+                arrow::compute::cast(column, &DataType::LargeUtf8).map_err(|e| e.to_string())?,
+            );
+        } else {
+            fields.push(field.as_ref().clone());
+            columns.push(column.clone());
+        }
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(|e| e.to_string())
+}
+
+/// Reads a value of a string field with 32-bit (Utf8) or 64-bit (LargeUtf8) offsets
+/// # Arguments
+/// * `array` - array of type Utf8 or LargeUtf8
+/// * `index` - index of the value
+///
+/// returns the value
+fn string_value(array: &ArrayRef, index: usize) -> &str {
+    match array.data_type() {
+        DataType::LargeUtf8 => array.as_string::<i64>().value(index),
+        _ => array.as_string::<i32>().value(index),
+    }
+}
+
+/// Validates the structure of a record batch of data against the expected schema and the row limits set by wasm_set_row_limits
+/// # Arguments
+/// * `batch` - record batch of data
+///
+/// returns an error if the number of rows or fields is not as expected
+fn validate_data_batch_structure(batch: &RecordBatch) -> Result<(), String> {
+    let (min_rows, max_rows): (usize, usize) = ROW_LIMITS.with(|row_limits| row_limits.get());
+    validate_batch_structure(
+        batch,
+        min_rows,
+        max_rows,
+        expected_data_schema().fields().len(),
+    )
+}
+
+/// Validates the structure of a record batch before it is processed
+/// # Arguments
+/// * `batch` - record batch of data
+/// * `min_rows` - minimum number of rows
+/// * `max_rows` - maximum number of rows
+/// * `expected_num_fields` - number of fields the batch must have
+///
+/// returns an error if the number of rows is not between min_rows and max_rows (inclusive) or the batch does not have expected_num_fields fields
+fn validate_batch_structure(
+    batch: &RecordBatch,
+    min_rows: usize,
+    max_rows: usize,
+    expected_num_fields: usize,
+) -> Result<(), String> {
+    if batch.num_rows() < min_rows || batch.num_rows() > max_rows {
+        return Err(format!(
+            "Record batch has {} rows, expected between {min_rows} and {max_rows} rows",
+            batch.num_rows()
+        ));
     }
-    for byte in serialized_result_batch_alloc_len {
-        vec_meta.push(byte);
+    if batch.num_columns() != expected_num_fields {
+        return Err(format!(
+            "Record batch has {} fields, expected {expected_num_fields} fields",
+            batch.num_columns()
+        ));
     }
-    let serialized_result_batch_meta: Box<[u8]> = vec_meta.into_boxed_slice();
-    let serialized_result_batch_meta_len: usize = serialized_result_batch_meta.len();
-    let serialized_result_batch_meta_ptr = allocate(
-        serialized_result_batch_meta_len,
-        ManuallyDrop::new(serialized_result_batch_meta),
+    Ok(())
+}
+
+/// Adds the provenance of the processing by the module to the schema metadata of the data, so that it is propagated to the result
+/// # Arguments
+/// * `input_metadata` - schema metadata of the data, e.g. {source, version, created_at} set by the application
+///
+/// returns the schema metadata of the data with the additional entries processed_by ("wasm-module2") and processing_version (PROCESSING_VERSION)
+fn provenance_metadata(input_metadata: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut metadata: HashMap<String, String> = input_metadata.clone();
+    metadata.insert(
+        "processed_by".to_string(),
+        env!("CARGO_PKG_NAME").to_string(),
     );
+    metadata.insert(
+        "processing_version".to_string(),
+        PROCESSING_VERSION.to_string(),
+    );
+    metadata
+}
+
+/// Schema of the data expected by wasm_memory_process_data_arrow
+///
+/// returns the schema {id: UInt64, content: Utf8, title: Utf8, date: Timestamp(Second, "+00:00"), score: Float64}
+fn expected_data_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "date",
+            DataType::Timestamp(TimeUnit::Second, Some("+00:00".to_string().into())),
+            false,
+        ),
+        Field::new("score", DataType::Float64, false),
+    ])
+}
+
+/// Pattern written and read back by wasm_health_check
+///
+/// returns the first HEALTH_CHECK_SIZE primes truncated to u8
+fn health_check_pattern() -> Vec<u8> {
+    HEALTH_CHECK_PRIMES
+        .iter()
+        .map(|prime| *prime as u8)
+        .collect()
+}
 
-    return serialized_result_batch_meta_ptr as u32;
+/// Normalizes the different representations of the UTC timezone to "+00:00"
+/// # Arguments
+/// * `tz` - timezone, e.g. "UTC", "Z", "Etc/UTC", "+0000" or "+00:00"
+///
+/// returns "+00:00" for representations of UTC. Other timezones are returned unchanged
+pub(crate) fn normalize_tz(tz: &str) -> &str {
+    match tz {
+        "UTC" | "Z" | "Etc/UTC" | "+0000" => "+00:00",
+        _ => tz,
+    }
+}
+
+/// Sets the last error that occurred in the module, so that the application can fetch it via wasm_last_error
+/// # Arguments
+/// * `error_message` - description of the error
+fn set_last_error(error_message: String) {
+    log(HostLogLevel::Error, &error_message);
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(error_message));
+}
+
+/// Logs a message via the host function host_log of the application
+/// # Arguments
+/// * `level` - log level
+/// * `message` - message to log
+fn log(level: HostLogLevel, message: &str) {
+    // messages logged during a call with an execution context carry its trace ID
+    let message: String = match current_trace_id() {
+        Some(trace_id) => format!("[trace_id={trace_id}] {message}"),
+        None => message.to_string(),
+    };
+    unsafe { host_log(level as i32, message.as_ptr(), message.len() as u32) };
+}
+
+/// Reads data that the application has written into memory allocated in this module
+/// # Arguments
+/// * `offset` - position of the start of the data
+/// * `size` - size of the data
+///
+/// returns a copy of the data. It is None if no valid allocated memory was provided
+fn read_shared_memory(offset: *mut u32, size: u32) -> Option<Vec<u8>> {
+    // validate pointer
+    let expected_size: usize = validate_pointer_aligned(offset as *const u8, MEMORY_ALIGNMENT);
+    if (expected_size == 0) | (expected_size != size as usize) {
+        return None;
+    };
+    // fetch from WASM module memory
+    let mut input_vec: Vec<u8> = Vec::new();
+    unsafe {
+        Vec::extend_from_slice(
+            &mut input_vec,
+            std::slice::from_raw_parts(offset as *mut u8, size as usize),
+        )
+    };
+    Some(input_vec)
+}
+
+/// Reads data that the application has written into memory allocated in this module. In contrast to read_shared_memory it fails if there is no memory for the copy instead of aborting
+/// # Arguments
+/// * `offset` - position of the start of the data
+/// * `size` - size of the data
+///
+/// returns a copy of the data. It is None if no valid allocated memory was provided. Returns an error if the memory for the copy could not be allocated
+fn try_read_shared_memory(offset: *mut u32, size: u32) -> Result<Option<Vec<u8>>, String> {
+    // validate pointer
+    let expected_size: usize = validate_pointer_aligned(offset as *const u8, MEMORY_ALIGNMENT);
+    if (expected_size == 0) | (expected_size != size as usize) {
+        return Ok(None);
+    };
+    let mut input_vec: Vec<u8> = Vec::new();
+    input_vec
+        .try_reserve_exact(size as usize)
+        .map_err(|e| format!("Could not allocate {} bytes for the data: {}", size, e))?;
+    // fetch from WASM module memory
+    input_vec
+        .extend_from_slice(unsafe { std::slice::from_raw_parts(offset as *mut u8, size as usize) });
+    Ok(Some(input_vec))
+}
+
+/// Deserializes data in Arrow IPC stream format into one record batch. Multiple record batches in the stream are concatenated
+/// # Arguments
+/// * `serialized` - data in Arrow IPC stream format
+///
+/// returns the record batch
+fn read_arrow_batch(serialized: &[u8]) -> Result<RecordBatch, ArrowError> {
+    let stream_reader = StreamReader::try_new(serialized, None)?;
+    let schema = stream_reader.schema();
+    let batches: Vec<RecordBatch> =
+        stream_reader.collect::<Result<Vec<RecordBatch>, ArrowError>>()?;
+    arrow::compute::concat_batches(&schema, &batches)
+}
+
+/// Serializes a record batch in Arrow IPC stream format. The schema header and the buffer are reused for record batches with the same schema (see writer_pool). Numeric fields are run-end encoded if enabled (see wasm_set_use_run_encoding)
+/// # Arguments
+/// * `batch` - record batch to serialize
+///
+/// returns the binary representation of the record batch in Arrow IPC stream format
+fn write_arrow_batch(batch: &RecordBatch) -> Result<Vec<u8>, ArrowError> {
+    write_arrow_batch_pooled(&run_encode_batch(batch)?)
+}
+
+/// Serializes a record batch in Arrow IPC stream format with a new StreamWriter
+/// # Arguments
+/// * `batch` - record batch to serialize
+///
+/// returns the binary representation of the record batch in Arrow IPC stream format
+fn write_arrow_batch_unpooled(batch: &RecordBatch) -> Result<Vec<u8>, ArrowError> {
+    let buffer: Vec<u8> = Vec::new();
+    let mut stream_writer = StreamWriter::try_new(buffer, &batch.schema())?;
+    stream_writer.write(batch)?;
+    stream_writer.into_inner()
+}
+
+/// Allocates the result of a function so that the application can read it and release it after reading
+/// # Arguments
+/// * `result` - result data
+///
+/// returns a pointer to a WasmResult describing the result data. If the result exceeds the limit set by wasm_set_max_output_bytes, the status is ErrorOutputTooLarge
+fn allocate_result(result: Vec<u8>) -> u32 {
+    if let Err(error_message) = check_output_size(result.len()) {
+        return allocate_error(WasmResultStatus::ErrorOutputTooLarge, error_message);
+    }
+    let result_alloc: ManuallyDrop<Box<[u8]>> = ManuallyDrop::new(result.into_boxed_slice());
+    let result_alloc_len: usize = result_alloc.len();
+    let result_ptr = allocate(result_alloc_len, result_alloc);
+    allocate_wasm_result(WasmResult {
+        status: WasmResultStatus::Success as i32,
+        data_ptr: result_ptr as u32,
+        data_len: result_alloc_len as u32,
+    })
 }
 
-/// Validates if a pointer has been properly allocated in this module
+/// Allocates the result of a function that failed so that the application can read it and release it after reading
+/// # Arguments
+/// * `status` - status describing the failure
+/// * `error_message` - description of the error that the application can fetch via wasm_last_error
+///
+/// returns a pointer to a WasmResult with the status
+fn allocate_error(status: WasmResultStatus, error_message: String) -> u32 {
+    set_last_error(error_message);
+    allocate_wasm_result(WasmResult {
+        status: status as i32,
+        data_ptr: 0,
+        data_len: 0,
+    })
+}
+
+/// Allocates the result of a function that was called without valid allocated memory for a parameter
+///
+/// returns a pointer to a WasmResult with the status ErrorInvalidMemory
+fn allocate_error_invalid_memory() -> u32 {
+    allocate_error(
+        WasmResultStatus::ErrorInvalidMemory,
+        "No valid allocated memory was provided for a parameter".to_string(),
+    )
+}
+
+/// Allocates a WasmResult so that the application can read it and release it after reading
+/// # Arguments
+/// * `wasm_result` - result of a function
+///
+/// returns a pointer to the WasmResult
+fn allocate_wasm_result(wasm_result: WasmResult) -> u32 {
+    let wasm_result_bytes: Box<[u8]> = wasm_result.to_bytes();
+    let wasm_result_len: usize = wasm_result_bytes.len();
+    allocate(wasm_result_len, ManuallyDrop::new(wasm_result_bytes)) as u32
+}
+
+/// Validates if a pointer has been properly allocated in this module for the current tenant
 /// # Arguments
 /// * `ptr` - pointer
 /// returns the size of the allocated memory area. It is 0 if the pointer is invalid
 pub fn validate_pointer(ptr: *const u8) -> usize {
     let cell: Cell<usize> = Cell::new(0);
-    MEMORY_AREAS.with(|mem_map| match mem_map.borrow().get(&ptr) {
-        Some(x) => cell.set(x.0),
-        None => cell.set(0),
-    });
+    MEMORY_AREAS.with(
+        |mem_map| match mem_map.borrow().get(&(current_tenant_id(), ptr)) {
+            Some(x) => cell.set(x.0),
+            None => cell.set(0),
+        },
+    );
     return cell.get();
 }
 
+/// Validates that a pointer has been allocated and is aligned
+/// # Arguments
+/// * `ptr` - pointer to the memory area
+/// * `alignment` - required alignment of the pointer, e.g. 8 for Arrow IPC data
+///
+/// returns the size of the memory area. Returns 0 if the pointer is misaligned or has not been allocated
+pub fn validate_pointer_aligned(ptr: *const u8, alignment: usize) -> usize {
+    // an alignment of 0 is treated as misaligned
+    if (ptr as usize).checked_rem(alignment) != Some(0) {
+        return 0;
+    }
+    validate_pointer(ptr)
+}
+
+/// Allocate some zero-initialized memory aligned to MEMORY_ALIGNMENT for the application to write data for the module
+/// # Arguments
+/// * `size` - size of memory to allocate
+///
+/// returns a pointer to the allocated memory area. Returns a null pointer if the memory cannot be allocated
+fn allocate_aligned(size: usize) -> *const u8 {
+    // zero-sized allocations are not supported by the allocator
+    let layout: Layout = match Layout::from_size_align(size.max(1), MEMORY_ALIGNMENT) {
+        Ok(x) => x,
+        Err(_) => return std::ptr::null(),
+    };
+    let result_ptr: *mut u8 = unsafe { std::alloc::alloc_zeroed(layout) };
+    if result_ptr.is_null() {
+        return std::ptr::null();
+    }
+    let key: MemoryAreaKey = (current_tenant_id(), result_ptr);
+    // the address is in use again
+    FREED_AREAS.with(|freed| freed.borrow_mut().remove(&key));
+    // save allocated memory to be able to validate and deallocate it later
+    MEMORY_AREAS.with(|mem_map| {
+        mem_map
+            .borrow_mut()
+            .insert(key, (size, MemoryArea::Aligned(result_ptr, layout)))
+    });
+    record_memory_usage();
+    result_ptr
+}
+
 /// Allocate some memory for the application to write data for the module
 /// Note: It is up to the application (and not the WASM module) to provide enough pages, so the module does not run out of memory
 /// This function can also be used internally by the WASM module to return data to the calling application of the module
@@ -253,7 +1408,57 @@ pub fn validate_pointer(ptr: *const u8) -> usize {
 /// returns a pointer to the allocated memory area
 pub fn allocate(size: usize, alloc_box: ManuallyDrop<Box<[u8]>>) -> *const u8 {
     let result_ptr: *const u8 = alloc_box.as_ptr();
+    let key: MemoryAreaKey = (current_tenant_id(), result_ptr);
+    // the address is in use again
+    FREED_AREAS.with(|freed| freed.borrow_mut().remove(&key));
     // save allocated memory to avoid it is cleaned up after function exits
-    MEMORY_AREAS.with(|mem_map| mem_map.borrow_mut().insert(result_ptr, (size, alloc_box)));
+    MEMORY_AREAS.with(|mem_map| {
+        mem_map
+            .borrow_mut()
+            .insert(key, (size, MemoryArea::Boxed(alloc_box)))
+    });
+    record_memory_usage();
     return result_ptr;
 }
+
+/// Returns the number of memory areas currently allocated for all tenants
+///
+/// returns the number of memory areas
+pub(crate) fn live_memory_areas() -> usize {
+    MEMORY_AREAS.with(|mem_map| mem_map.borrow().len())
+}
+
+/// Returns the total size of the memory areas currently allocated
+/// # Arguments
+/// * `tenant_id` - tenant whose memory areas are counted. None counts the memory areas of all tenants
+///
+/// returns the total size in bytes
+pub(crate) fn live_memory_bytes(tenant_id: Option<u32>) -> usize {
+    MEMORY_AREAS.with(|mem_map| {
+        mem_map
+            .borrow()
+            .iter()
+            .filter(|((area_tenant_id, _), _)| tenant_id.is_none_or(|x| *area_tenant_id == x))
+            .map(|(_, x)| x.0)
+            .sum()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_tz;
+
+    #[test]
+    fn representations_of_utc_are_normalized() {
+        for tz in ["UTC", "Z", "Etc/UTC", "+0000", "+00:00"] {
+            assert_eq!(normalize_tz(tz), "+00:00", "{tz}");
+        }
+    }
+
+    #[test]
+    fn other_timezones_are_unchanged() {
+        for tz in ["Europe/Berlin", "+01:00", "-0500"] {
+            assert_eq!(normalize_tz(tz), tz);
+        }
+    }
+}
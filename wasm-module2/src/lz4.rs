@@ -0,0 +1,70 @@
+//! Processing of LZ4 compressed data in Arrow IPC format
+use crate::telemetry::ProcessingTimer;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, log, process_data_arrow,
+    read_shared_memory, HostLogLevel, WasmResultStatus,
+};
+
+/// Variant of wasm_memory_process_data_arrow for LZ4 compressed meta data and data. LZ4 decompresses faster than other compression algorithms at the cost of a worse compression ratio, which suits latency-sensitive calls of the WASM module
+/// # Arguments
+/// * `meta_data_offset` - position of the start of the LZ4 compressed meta data ("command") in Arrow IPC format, prepended by the uncompressed size (4 bytes, little endian)
+/// * `meta_data_size` - size of the compressed meta data including the prepended size
+/// * `data_offset` - position of the start of the LZ4 compressed data ("data") in Arrow IPC format, prepended by the uncompressed size (4 bytes, little endian)
+/// * `data_size` - size of the compressed data including the prepended size
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the LZ4 compressed result data in Arrow IPC format, prepended by the uncompressed size (4 bytes, little endian). If the data cannot be decompressed, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_process_data_arrow_lz4(
+    meta_data_offset: *mut u32,
+    meta_data_size: u32,
+    data_offset: *mut u32,
+    data_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - meta data
+    let input_vec_meta_data: Vec<u8> = match read_shared_memory(meta_data_offset, meta_data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // the duration of the processing is recorded for wasm_get_last_processing_duration_ns when the function returns
+    let _processing_timer: ProcessingTimer = ProcessingTimer::start();
+    // decompress meta data and data
+    let input_vec_meta_data: Vec<u8> =
+        match lz4_flex::decompress_size_prepended(&input_vec_meta_data) {
+            Ok(x) => x,
+            Err(e) => {
+                return allocate_error(
+                    WasmResultStatus::ErrorProcessing,
+                    format!("Meta data cannot be decompressed with LZ4: {e}"),
+                )
+            }
+        };
+    let input_vec_data: Vec<u8> = match lz4_flex::decompress_size_prepended(&input_vec_data) {
+        Ok(x) => x,
+        Err(e) => {
+            return allocate_error(
+                WasmResultStatus::ErrorProcessing,
+                format!("Data cannot be decompressed with LZ4: {e}"),
+            )
+        }
+    };
+    log(
+        HostLogLevel::Debug,
+        &format!(
+            "Processing {} bytes of meta data and {} bytes of data decompressed with LZ4",
+            input_vec_meta_data.len(),
+            input_vec_data.len()
+        ),
+    );
+    match process_data_arrow(&input_vec_meta_data, &input_vec_data) {
+        // compress the answer
+        Ok(serialized_result_batch) => {
+            allocate_result(lz4_flex::compress_prepend_size(&serialized_result_batch))
+        }
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
@@ -0,0 +1,207 @@
+//! Merging of multiple sorted streams of data in Arrow IPC format into one sorted stream, e.g. for external sorting or the reduce step of map-reduce
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use arrow::array::{Array, ArrayRef, AsArray, BooleanArray, StringArray, UInt64Array};
+use arrow::compute::SortOptions;
+use arrow::datatypes::{DataType, UInt64Type};
+use arrow::record_batch::RecordBatch;
+use arrow::row::{Row, RowConverter, Rows, SortField};
+
+use crate::concat::describe_schema_difference;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Input stream described by a row of the meta data
+struct InputStream {
+    /// position of the start of the stream in Arrow IPC format in the WASM module memory
+    data_ptr: u64,
+    /// size of the stream
+    data_size: u64,
+    /// name of the field the stream is sorted by
+    sort_field: String,
+    /// true if the stream is sorted in ascending order
+    ascending: bool,
+}
+
+/// Merges streams of data in Arrow IPC format from the WASM module memory that are each sorted by the same field (k-way merge)
+/// # Arguments
+/// * `meta_offset` - position of the start of the meta data in Arrow IPC format with one row per stream and the schema {data_ptr: UInt64, data_size: UInt64, sort_field: Utf8, ascending: Boolean}. data_ptr and data_size describe memory allocated with wasm_allocate containing the stream in Arrow IPC format
+/// * `meta_size` - size of the meta data
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing all rows of the streams sorted by the sort field in Arrow IPC format. Rows with the same value keep the order of the streams in the meta data. If the streams have different schemas, are sorted differently or the memory of a stream is not valid, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_merge_sort_arrow(meta_offset: *mut u32, meta_size: u32) -> u32 {
+    // fetch from WASM module memory - meta data
+    let input_vec_meta: Vec<u8> = match read_shared_memory(meta_offset, meta_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    let input_streams: Vec<InputStream> = match read_input_streams(&input_vec_meta) {
+        Ok(x) => x,
+        Err(error_message) => {
+            return allocate_error(WasmResultStatus::ErrorProcessing, error_message)
+        }
+    };
+    // fetch from WASM module memory - streams
+    let mut input_vec_streams: Vec<Vec<u8>> = Vec::with_capacity(input_streams.len());
+    for input_stream in &input_streams {
+        match read_shared_memory(
+            input_stream.data_ptr as *mut u32,
+            input_stream.data_size as u32,
+        ) {
+            Some(x) => input_vec_streams.push(x),
+            None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+        }
+    }
+    match merge_sort_arrow(&input_streams, &input_vec_streams) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the meta data describing the input streams
+/// # Arguments
+/// * `serialized_meta` - meta data in Arrow IPC format
+///
+/// returns one input stream per row of the meta data
+fn read_input_streams(serialized_meta: &[u8]) -> Result<Vec<InputStream>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_meta).map_err(|e| e.to_string())?;
+    let data_ptrs: &UInt64Array =
+        meta_column(&batch, "data_ptr", &DataType::UInt64)?.as_primitive::<UInt64Type>();
+    let data_sizes: &UInt64Array =
+        meta_column(&batch, "data_size", &DataType::UInt64)?.as_primitive::<UInt64Type>();
+    let sort_fields: &StringArray =
+        meta_column(&batch, "sort_field", &DataType::Utf8)?.as_string::<i32>();
+    let ascendings: &BooleanArray =
+        meta_column(&batch, "ascending", &DataType::Boolean)?.as_boolean();
+    (0..batch.num_rows())
+        .map(|i| {
+            if data_ptrs.is_null(i)
+                || data_sizes.is_null(i)
+                || sort_fields.is_null(i)
+                || ascendings.is_null(i)
+            {
+                return Err(format!("Meta data of stream {i} contains null values"));
+            }
+            Ok(InputStream {
+                data_ptr: data_ptrs.value(i),
+                data_size: data_sizes.value(i),
+                sort_field: sort_fields.value(i).to_string(),
+                ascending: ascendings.value(i),
+            })
+        })
+        .collect()
+}
+
+/// Fetches a field of the meta data
+/// # Arguments
+/// * `batch` - meta data
+/// * `name` - name of the field
+/// * `data_type` - expected type of the field
+///
+/// returns the column of the field. Returns an error if the field does not exist or has another type
+fn meta_column<'a>(
+    batch: &'a RecordBatch,
+    name: &str,
+    data_type: &DataType,
+) -> Result<&'a ArrayRef, String> {
+    let column: &ArrayRef = batch.column_by_name(name).ok_or(format!(
+        "Field '{name}' not found in schema of the meta data"
+    ))?;
+    if column.data_type() != data_type {
+        return Err(format!(
+            "Field '{name}' of the meta data has type {} instead of {data_type}",
+            column.data_type()
+        ));
+    }
+    Ok(column)
+}
+
+/// Deserializes the streams, merges them and serializes the result
+/// # Arguments
+/// * `input_streams` - description of the streams
+/// * `serialized_streams` - streams in Arrow IPC format in the order of input_streams
+///
+/// returns the merged data in Arrow IPC format
+fn merge_sort_arrow(
+    input_streams: &[InputStream],
+    serialized_streams: &[Vec<u8>],
+) -> Result<Vec<u8>, String> {
+    let first_stream: &InputStream = input_streams
+        .first()
+        .ok_or("Meta data does not describe any stream")?;
+    if let Some((i, _)) = input_streams.iter().enumerate().find(|(_, input_stream)| {
+        input_stream.sort_field != first_stream.sort_field
+            || input_stream.ascending != first_stream.ascending
+    }) {
+        return Err(format!(
+            "Stream {i} is sorted differently than stream 0, all streams must be sorted by the same field in the same order"
+        ));
+    }
+    let batches: Vec<RecordBatch> = serialized_streams
+        .iter()
+        .map(|serialized_stream| read_arrow_batch(serialized_stream).map_err(|e| e.to_string()))
+        .collect::<Result<Vec<RecordBatch>, String>>()?;
+    let schema = batches[0].schema();
+    for batch in &batches[1..] {
+        if batch.schema() != schema {
+            return Err(describe_schema_difference(&schema, &batch.schema()));
+        }
+    }
+    let sort_index: usize = schema
+        .index_of(&first_stream.sort_field)
+        .map_err(|_| format!("Field '{}' not found in schema", first_stream.sort_field))?;
+    // the sort field is converted to a representation that can be compared byte-wise in the sort order
+    let sort_options = SortOptions {
+        descending: !first_stream.ascending,
+        nulls_first: first_stream.ascending,
+    };
+    let row_converter = RowConverter::new(vec![SortField::new_with_options(
+        schema.field(sort_index).data_type().clone(),
+        sort_options,
+    )])
+    .map_err(|e| e.to_string())?;
+    let sort_values: Vec<Rows> = batches
+        .iter()
+        .map(|batch| {
+            row_converter
+                .convert_columns(&[batch.column(sort_index).clone()])
+                .map_err(|e| e.to_string())
+        })
+        .collect::<Result<Vec<Rows>, String>>()?;
+    // k-way merge: the heap contains the next row of each stream
+    let mut heap: BinaryHeap<Reverse<(Row, usize, usize)>> = BinaryHeap::new();
+    for (stream_index, stream_sort_values) in sort_values.iter().enumerate() {
+        if stream_sort_values.num_rows() > 0 {
+            heap.push(Reverse((stream_sort_values.row(0), stream_index, 0)));
+        }
+    }
+    let num_rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+    let mut merged_indices: Vec<(usize, usize)> = Vec::with_capacity(num_rows);
+    while let Some(Reverse((_, stream_index, row_index))) = heap.pop() {
+        merged_indices.push((stream_index, row_index));
+        let next_row_index: usize = row_index + 1;
+        if next_row_index < sort_values[stream_index].num_rows() {
+            heap.push(Reverse((
+                sort_values[stream_index].row(next_row_index),
+                stream_index,
+                next_row_index,
+            )));
+        }
+    }
+    let columns: Vec<ArrayRef> = (0..schema.fields().len())
+        .map(|i| {
+            let stream_columns: Vec<&dyn Array> = batches
+                .iter()
+                .map(|batch| batch.column(i).as_ref())
+                .collect();
+            arrow::compute::interleave(&stream_columns, &merged_indices).map_err(|e| e.to_string())
+        })
+        .collect::<Result<Vec<ArrayRef>, String>>()?;
+    let result_batch: RecordBatch =
+        RecordBatch::try_new(schema, columns).map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
@@ -0,0 +1,124 @@
+//! Normalization of text fields of data in Arrow IPC format, so that differences in casing, whitespace or Unicode normalization form do not cause false mismatches in comparisons
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, StringArray};
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+use regex::Regex;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Transformations applied to the text
+struct NormalizeOptions {
+    /// remove leading and trailing whitespace
+    trim: bool,
+    /// convert to lower case
+    lowercase: bool,
+    /// convert to Unicode Normalization Form C
+    nfc: bool,
+    /// replace each sequence of whitespace by a single space
+    collapse_whitespace: bool,
+}
+
+/// Normalizes the text fields (Utf8) of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `opts_offset` - position of the start of the options as JSON, e.g. {"trim": true, "lowercase": true, "nfc": true, "collapse_whitespace": true}. Options that are not specified are disabled
+/// * `opts_size` - size of the options
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the data with normalized text fields in Arrow IPC format. Fields of other types are returned unchanged. If the options are not valid, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_normalize_text_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    opts_offset: *mut u32,
+    opts_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - options
+    let input_vec_opts: Vec<u8> = match read_shared_memory(opts_offset, opts_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match normalize_text_arrow(&input_vec_data, &input_vec_opts) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Parses the options, normalizes the text fields of the data and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `opts` - options as JSON
+///
+/// returns the normalized data in Arrow IPC format
+fn normalize_text_arrow(serialized_data: &[u8], opts: &[u8]) -> Result<Vec<u8>, String> {
+    let opts: serde_json::Value =
+        serde_json::from_slice(opts).map_err(|e| format!("Invalid options: {e}"))?;
+    let option = |name: &str| -> Result<bool, String> {
+        match &opts[name] {
+            serde_json::Value::Null => Ok(false),
+            serde_json::Value::Bool(enabled) => Ok(*enabled),
+            value => Err(format!("Option \"{name}\" is {value} instead of a boolean")),
+        }
+    };
+    let opts = NormalizeOptions {
+        trim: option("trim")?,
+        lowercase: option("lowercase")?,
+        nfc: option("nfc")?,
+        collapse_whitespace: option("collapse_whitespace")?,
+    };
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let whitespace: Regex = Regex::new(r"\s+").map_err(|e| e.to_string())?;
+    let columns: Vec<ArrayRef> = batch
+        .columns()
+        .iter()
+        .map(|column| match column.data_type() {
+            DataType::Utf8 => Arc::new(
+                column
+                    .as_string::<i32>()
+                    .iter()
+                    .map(|value| value.map(|value| normalize(value, &opts, &whitespace)))
+                    .collect::<StringArray>(),
+            ) as ArrayRef,
+            _ => column.clone(),
+        })
+        .collect();
+    let result_batch: RecordBatch =
+        RecordBatch::try_new(batch.schema(), columns).map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Applies the enabled transformations to a text. Whitespace is collapsed before trimming, so that no single space remains at the start or end
+/// # Arguments
+/// * `value` - text to normalize
+/// * `opts` - transformations to apply
+/// * `whitespace` - regular expression matching sequences of whitespace
+///
+/// returns the normalized text
+fn normalize(value: &str, opts: &NormalizeOptions, whitespace: &Regex) -> String {
+    let mut value: String = if opts.nfc {
+        value.nfc().collect()
+    } else {
+        value.to_string()
+    };
+    if opts.collapse_whitespace {
+        value = whitespace.replace_all(&value, " ").into_owned();
+    }
+    if opts.trim {
+        value = value.trim().to_string();
+    }
+    if opts.lowercase {
+        value = value.to_lowercase();
+    }
+    value
+}
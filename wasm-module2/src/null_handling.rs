@@ -0,0 +1,191 @@
+//! Handling of null values in the data of wasm_memory_process_data_arrow, e.g. for data of upstream systems that do not enforce the non-nullable fields of the expected schema
+use std::cell::Cell;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, BooleanArray, Int64Array, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::set_last_error;
+
+/// How null values in the data are handled
+#[derive(Clone, Copy)]
+enum NullHandlingMode {
+    /// data with null values is rejected
+    Strict = 0,
+    /// null values are replaced by defaults: 0 for numbers, "" for strings and the Unix epoch for timestamps
+    Substitute = 1,
+    /// rows with null values are not processed
+    Skip = 2,
+}
+
+// Global variable with the handling of null values. The application sets it via wasm_set_null_handling
+thread_local!(
+    static NULL_HANDLING: Cell<NullHandlingMode> = const { Cell::new(NullHandlingMode::Strict) };
+);
+
+/// Sets how null values in the data of wasm_memory_process_data_arrow are handled. It applies to all following calls of the instance (default: strict)
+/// # Arguments
+/// * `mode` - 0 (strict) to reject data with null values, 1 (substitute) to replace null values by defaults (0 for numbers, "" for strings, the Unix epoch for timestamps) or 2 (skip) to skip rows with null values. The result has an additional field null_substitutions (mode 1) or null_rows_skipped (mode 2) of type UInt64
+///
+/// returns 0 if the mode has been set. Returns -1 if the mode is unknown, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_set_null_handling(mode: u32) -> i32 {
+    let mode: NullHandlingMode = match mode {
+        0 => NullHandlingMode::Strict,
+        1 => NullHandlingMode::Substitute,
+        2 => NullHandlingMode::Skip,
+        _ => {
+            set_last_error(format!("Unknown null handling mode {mode}"));
+            return -1;
+        }
+    };
+    NULL_HANDLING.with(|null_handling| null_handling.set(mode));
+    0
+}
+
+/// Returns the mode set by wasm_set_null_handling
+///
+/// returns 0 (strict), 1 (substitute) or 2 (skip)
+pub(crate) fn null_handling_mode() -> u32 {
+    NULL_HANDLING.with(|null_handling| null_handling.get()) as u32
+}
+
+/// Handles the null values of a record batch of data according to the mode set by wasm_set_null_handling
+/// # Arguments
+/// * `batch` - record batch of data with the types of the expected schema
+///
+/// returns the record batch without null values and the number of substituted values (mode substitute) or skipped rows (mode skip). Returns an error if the batch contains null values in mode strict or a null value of a type without default
+pub(crate) fn handle_nulls(batch: &RecordBatch) -> Result<(RecordBatch, u64), String> {
+    // most batches have no null values at all
+    if batch
+        .columns()
+        .iter()
+        .all(|column| column.null_count() == 0)
+    {
+        return Ok((batch.clone(), 0));
+    }
+    match NULL_HANDLING.with(|null_handling| null_handling.get()) {
+        NullHandlingMode::Strict => {
+            let (field, column) = batch
+                .schema_ref()
+                .fields()
+                .iter()
+                .zip(batch.columns())
+                .find(|(_, column)| column.null_count() > 0)
+                .ok_or("Record batch contains null values")?;
+            Err(format!(
+                "Field '{}' contains {} null values",
+                field.name(),
+                column.null_count()
+            ))
+        }
+        NullHandlingMode::Substitute => substitute_nulls(batch),
+        NullHandlingMode::Skip => skip_null_rows(batch),
+    }
+}
+
+/// Field of the result with the number of null values handled while processing the data, depending on the mode set by wasm_set_null_handling
+///
+/// returns the field null_substitutions (mode substitute) or null_rows_skipped (mode skip). It is None in mode strict
+pub(crate) fn null_handling_result_field() -> Option<Field> {
+    let name: &str = match NULL_HANDLING.with(|null_handling| null_handling.get()) {
+        NullHandlingMode::Strict => return None,
+        NullHandlingMode::Substitute => "null_substitutions",
+        NullHandlingMode::Skip => "null_rows_skipped",
+    };
+    Some(Field::new(name, DataType::UInt64, false))
+}
+
+/// Adds the number of null values handled while processing the data to the result, depending on the mode set by wasm_set_null_handling
+/// # Arguments
+/// * `batch` - result of processing the data
+/// * `null_count` - number of substituted values (mode substitute) or skipped rows (mode skip) returned by handle_nulls
+///
+/// returns the result with the additional field null_substitutions (mode substitute) or null_rows_skipped (mode skip). The result is not changed in mode strict
+pub(crate) fn append_null_handling_column(
+    batch: RecordBatch,
+    null_count: u64,
+) -> Result<RecordBatch, String> {
+    let Some(null_handling_field) = null_handling_result_field() else {
+        return Ok(batch);
+    };
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields.push(null_handling_field);
+    let mut columns: Vec<ArrayRef> = batch.columns().to_vec();
+    columns.push(Arc::new(UInt64Array::from(vec![
+        null_count;
+        batch.num_rows()
+    ])));
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Replaces the null values of a record batch by defaults
+/// # Arguments
+/// * `batch` - record batch with null values
+///
+/// returns the record batch without null values and the number of replaced values
+fn substitute_nulls(batch: &RecordBatch) -> Result<(RecordBatch, u64), String> {
+    let mut substitutions: u64 = 0;
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(batch.num_columns());
+    for (field, column) in batch.schema_ref().fields().iter().zip(batch.columns()) {
+        if column.null_count() == 0 {
+            columns.push(column.clone());
+            continue;
+        }
+        substitutions += column.null_count() as u64;
+        let defaults: ArrayRef = match column.data_type() {
+            DataType::Utf8 => Arc::new(StringArray::from(vec![""; column.len()])),
+            DataType::LargeUtf8 => arrow::compute::cast(
+                &StringArray::from(vec![""; column.len()]),
+                &DataType::LargeUtf8,
+            )
+            .map_err(|e| e.to_string())?,
+            // 0 is the Unix epoch for timestamps
+            data_type if data_type.is_numeric() || data_type.is_temporal() => {
+                arrow::compute::cast(&Int64Array::from(vec![0; column.len()]), data_type)
+                    .map_err(|e| e.to_string())?
+            }
+            data_type => {
+                return Err(format!(
+                    "Field '{}' contains null values, but type {data_type} has no default",
+                    field.name()
+                ))
+            }
+        };
+        let is_valid: BooleanArray =
+            arrow::compute::is_not_null(column.as_ref()).map_err(|e| e.to_string())?;
+        columns.push(
+            arrow::compute::kernels::zip::zip(&is_valid, column, &defaults)
+                .map_err(|e| e.to_string())?,
+        );
+    }
+    let batch: RecordBatch =
+        RecordBatch::try_new(batch.schema(), columns).map_err(|e| e.to_string())?;
+    Ok((batch, substitutions))
+}
+
+/// Removes the rows of a record batch that contain null values
+/// # Arguments
+/// * `batch` - record batch with null values
+///
+/// returns the record batch without null values and the number of removed rows
+fn skip_null_rows(batch: &RecordBatch) -> Result<(RecordBatch, u64), String> {
+    let columns_with_nulls: Vec<&ArrayRef> = batch
+        .columns()
+        .iter()
+        .filter(|column| column.null_count() > 0)
+        .collect();
+    let keep: BooleanArray = (0..batch.num_rows())
+        .map(|row| Some(columns_with_nulls.iter().all(|column| !column.is_null(row))))
+        .collect();
+    let skipped_rows: u64 = keep.false_count() as u64;
+    let batch: RecordBatch =
+        arrow::compute::filter_record_batch(batch, &keep).map_err(|e| e.to_string())?;
+    Ok((batch, skipped_rows))
+}
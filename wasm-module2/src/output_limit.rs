@@ -0,0 +1,32 @@
+//! Limit of the size of the results of the module, so that a buggy or malicious handler of a command cannot make the application run out of memory while reading its result
+use std::cell::Cell;
+
+// Global variable with the maximum size of a result in bytes. The application sets it via wasm_set_max_output_bytes (default: no limit)
+thread_local!(
+    static MAX_OUTPUT_BYTES: Cell<usize> = const { Cell::new(usize::MAX) };
+);
+
+/// Sets the maximum size of the results of all following calls of the instance. Functions whose result exceeds the limit fail with the status ErrorOutputTooLarge (-40), so that the application can retry with less data
+/// # Arguments
+/// * `limit` - maximum size of a result in bytes. 0 removes the limit (default)
+#[no_mangle]
+pub extern "C" fn wasm_set_max_output_bytes(limit: u32) {
+    let limit: usize = match limit {
+        0 => usize::MAX,
+        limit => limit as usize,
+    };
+    MAX_OUTPUT_BYTES.with(|max_output_bytes| max_output_bytes.set(limit));
+}
+
+/// Checks that the size of a result does not exceed the limit set by wasm_set_max_output_bytes
+/// # Arguments
+/// * `size` - size of the result in bytes
+///
+/// returns an error if the size exceeds the limit
+pub(crate) fn check_output_size(size: usize) -> Result<(), String> {
+    let limit: usize = MAX_OUTPUT_BYTES.with(|max_output_bytes| max_output_bytes.get());
+    if size > limit {
+        return Err(format!("Result size {size} exceeds limit {limit}"));
+    }
+    Ok(())
+}
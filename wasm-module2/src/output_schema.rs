@@ -0,0 +1,52 @@
+//! Enforcement of the declared schema of the results of the commands, so that the application can rely on the schema independently of the implementation of a command
+use std::sync::Arc;
+
+use arrow::array::{new_null_array, ArrayRef};
+use arrow::datatypes::{Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+/// Brings the result of a command into its declared schema. The fields are selected by name in the order of the declared schema and casted to the declared types. Fields that are not declared are removed
+/// # Arguments
+/// * `batch` - result of a command
+/// * `expected` - declared schema of the result
+///
+/// returns the result with the declared schema and the schema metadata of the result. Missing nullable fields are filled with null values. Returns an error if a non-nullable field is missing or cannot be casted to the declared type
+pub(crate) fn enforce_output_schema(
+    batch: RecordBatch,
+    expected: &Schema,
+) -> Result<RecordBatch, String> {
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = Vec::with_capacity(expected.fields().len());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(expected.fields().len());
+    for expected_field in expected.fields() {
+        let column: ArrayRef = match schema.index_of(expected_field.name()) {
+            Ok(index) if batch.column(index).data_type() == expected_field.data_type() => {
+                batch.column(index).clone()
+            }
+            Ok(index) => arrow::compute::cast(batch.column(index), expected_field.data_type())
+                .map_err(|e| {
+                    format!(
+                        "Field '{}' of the result cannot be casted to the declared type {}: {e}",
+                        expected_field.name(),
+                        expected_field.data_type()
+                    )
+                })?,
+            Err(_) if expected_field.is_nullable() => {
+                new_null_array(expected_field.data_type(), batch.num_rows())
+            }
+            Err(_) => {
+                return Err(format!(
+                    "Field '{}' of the declared schema is missing in the result",
+                    expected_field.name()
+                ))
+            }
+        };
+        fields.push(expected_field.as_ref().clone());
+        columns.push(column);
+    }
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())
+}
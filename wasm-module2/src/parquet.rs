@@ -0,0 +1,106 @@
+//! Writing of processed data as Parquet files via the WASI filesystem, so that results do not need to be returned to the application to persist them
+use std::collections::HashMap;
+use std::fs::File;
+
+use arrow::ipc::reader::StreamReader;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_writer::ArrowWriter;
+
+use crate::telemetry::ProcessingTimer;
+use crate::{
+    append_null_handling_column, has_large_utf8, process_data_batch, process_data_result,
+    provenance_metadata, read_shared_memory, set_last_error,
+};
+
+/// Return code of wasm_memory_process_and_write_parquet
+enum WriteParquetReturnCode {
+    Success = 0,
+    ErrorIo = -1,
+    ErrorProcessing = -2,
+}
+
+/// Processes data in Arrow IPC format from the WASM module memory the same way as wasm_memory_process_data_arrow (command "test") and writes the result to a Parquet file in a directory preopened by the application
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `output_path_offset` - position of the start of the path of the Parquet file as UTF-8 string, relative to the preopened directory, e.g. "result.parquet". An existing file is overwritten
+/// * `output_path_len` - length of the path of the Parquet file
+///
+/// returns 0 if the result has been written. Returns -1 if the file cannot be written and -2 if the data cannot be processed, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_process_and_write_parquet(
+    data_offset: *mut u32,
+    data_size: u32,
+    output_path_offset: *mut u32,
+    output_path_len: u32,
+) -> i32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => {
+            set_last_error("Invalid memory: data has not been allocated".to_string());
+            return WriteParquetReturnCode::ErrorProcessing as i32;
+        }
+    };
+    // fetch from WASM module memory - output path
+    let input_vec_output_path: Vec<u8> =
+        match read_shared_memory(output_path_offset, output_path_len) {
+            Some(x) => x,
+            None => {
+                set_last_error("Invalid memory: output path has not been allocated".to_string());
+                return WriteParquetReturnCode::ErrorProcessing as i32;
+            }
+        };
+    // the duration of the processing is recorded for wasm_get_last_processing_duration_ns when the function returns
+    let _processing_timer: ProcessingTimer = ProcessingTimer::start();
+    match process_and_write_parquet(&input_vec_data, &input_vec_output_path) {
+        Ok(()) => WriteParquetReturnCode::Success as i32,
+        Err((return_code, error_message)) => {
+            set_last_error(error_message);
+            return_code as i32
+        }
+    }
+}
+
+/// Deserializes and processes the data and writes the result as Parquet file
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `output_path` - UTF-8 path of the Parquet file relative to the preopened directory
+///
+/// returns the return code and description of the error if the data cannot be processed or the file cannot be written
+fn process_and_write_parquet(
+    serialized_data: &[u8],
+    output_path: &[u8],
+) -> Result<(), (WriteParquetReturnCode, String)> {
+    let processing_error = |e: String| (WriteParquetReturnCode::ErrorProcessing, e);
+    let output_path: &str = std::str::from_utf8(output_path)
+        .map_err(|e| processing_error(format!("Output path is not valid UTF-8: {e}")))?;
+    let stream_reader = StreamReader::try_new(serialized_data, None)
+        .map_err(|e| processing_error(e.to_string()))?;
+    // the provenance of the data is propagated to the result
+    let metadata: HashMap<String, String> = provenance_metadata(stream_reader.schema().metadata());
+    let mut large_utf8: bool = false;
+    let mut null_count: u64 = 0;
+    for item in stream_reader {
+        let batch: RecordBatch = item.map_err(|e| processing_error(e.to_string()))?;
+        large_utf8 |= has_large_utf8(&batch);
+        null_count += process_data_batch(&batch).map_err(processing_error)?;
+    }
+    let result_batch: RecordBatch =
+        append_null_handling_column(process_data_result(large_utf8, metadata), null_count)
+            .map_err(processing_error)?;
+    let io_error = |e: String| {
+        (
+            WriteParquetReturnCode::ErrorIo,
+            format!("Cannot write Parquet file '{output_path}': {e}"),
+        )
+    };
+    let file: File = File::create(output_path).map_err(|e| io_error(e.to_string()))?;
+    let mut arrow_writer = ArrowWriter::try_new(file, result_batch.schema(), None)
+        .map_err(|e| io_error(e.to_string()))?;
+    arrow_writer
+        .write(&result_batch)
+        .map_err(|e| io_error(e.to_string()))?;
+    arrow_writer.close().map_err(|e| io_error(e.to_string()))?;
+    Ok(())
+}
@@ -0,0 +1,146 @@
+//! Partitioning of data in Arrow IPC format by the hash of a key field, e.g. to distribute it across the workers of a pipeline
+use std::mem::ManuallyDrop;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, BooleanArray, UInt32Array, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+
+use crate::fingerprint::fnv1a_hash;
+use crate::{
+    allocate, allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, wasm_deallocate, write_arrow_batch, WasmResultStatus,
+};
+
+/// Partitions data in Arrow IPC format from the WASM module memory by the FNV-1a hash of a key field
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `key_field_offset` - position of the start of the name of the key field as UTF-8 string
+/// * `key_field_size` - size of the name of the key field
+/// * `num_partitions` - number of partitions, must be greater than 0
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing one row per partition in Arrow IPC format with the schema {partition_id: UInt32, data_ptr: UInt64, data_size: UInt64}. Each partition is allocated separately in the WASM module memory at data_ptr and contains the rows where hash(key) % num_partitions == partition_id in Arrow IPC format. The hash is computed from the key formatted as string (null keys are hashed as empty string). If the partitioning failed, the status is non-zero, see wasm_last_error for details. Note: The calling application must deallocate each data_ptr with wasm_deallocate
+#[no_mangle]
+pub extern "C" fn wasm_memory_partition_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    key_field_offset: *mut u32,
+    key_field_size: u32,
+    num_partitions: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - key field
+    let input_vec_key_field: Vec<u8> = match read_shared_memory(key_field_offset, key_field_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match partition_arrow(&input_vec_data, &input_vec_key_field, num_partitions) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, partitions it, allocates the serialized partitions and serializes the description of the partitions
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `key_field` - name of the key field as UTF-8 string
+/// * `num_partitions` - number of partitions
+///
+/// returns the description of the partitions in Arrow IPC format
+fn partition_arrow(
+    serialized_data: &[u8],
+    key_field: &[u8],
+    num_partitions: u32,
+) -> Result<Vec<u8>, String> {
+    let key_field: &str = std::str::from_utf8(key_field)
+        .map_err(|e| format!("Name of the key field is not valid UTF-8: {e}"))?;
+    if num_partitions == 0 {
+        return Err("Number of partitions must be greater than 0".to_string());
+    }
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let partitions: Vec<RecordBatch> = partition(&batch, key_field, num_partitions)?;
+    let serialized_partitions: Vec<Vec<u8>> = partitions
+        .iter()
+        .map(write_arrow_batch)
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+    let data_sizes: Vec<u64> = serialized_partitions
+        .iter()
+        .map(|serialized_partition| serialized_partition.len() as u64)
+        .collect();
+    let data_ptrs: Vec<u64> = serialized_partitions
+        .into_iter()
+        .map(|serialized_partition| {
+            allocate(
+                serialized_partition.len(),
+                ManuallyDrop::new(serialized_partition.into_boxed_slice()),
+            ) as u64
+        })
+        .collect();
+    let schema = Schema::new(vec![
+        Field::new("partition_id", DataType::UInt32, false),
+        Field::new("data_ptr", DataType::UInt64, false),
+        Field::new("data_size", DataType::UInt64, false),
+    ]);
+    let result: Result<Vec<u8>, String> = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt32Array::from_iter_values(0..num_partitions)),
+            Arc::new(UInt64Array::from(data_ptrs.clone())),
+            Arc::new(UInt64Array::from(data_sizes)),
+        ],
+    )
+    .and_then(|result_batch| write_arrow_batch(&result_batch))
+    .map_err(|e| e.to_string());
+    if result.is_err() {
+        // the application cannot deallocate partitions it does not know about
+        for data_ptr in data_ptrs {
+            wasm_deallocate(data_ptr as *const u8);
+        }
+    }
+    result
+}
+
+/// Partitions a record batch by the hash of a key field
+/// # Arguments
+/// * `batch` - record batch to partition
+/// * `key_field` - name of the key field
+/// * `num_partitions` - number of partitions
+///
+/// returns one record batch per partition in the order of the partition IDs. Partitions without rows are empty record batches
+fn partition(
+    batch: &RecordBatch,
+    key_field: &str,
+    num_partitions: u32,
+) -> Result<Vec<RecordBatch>, String> {
+    let key_column: &ArrayRef = batch
+        .column_by_name(key_field)
+        .ok_or(format!("Field '{key_field}' not found in schema"))?;
+    let formatter = ArrayFormatter::try_new(key_column.as_ref(), &FormatOptions::default())
+        .map_err(|e| e.to_string())?;
+    let partition_ids: Vec<u32> = (0..key_column.len())
+        .map(|i| {
+            let key: String = if key_column.is_valid(i) {
+                formatter.value(i).to_string()
+            } else {
+                String::new()
+            };
+            (fnv1a_hash(key.as_bytes()) % num_partitions as u64) as u32
+        })
+        .collect();
+    (0..num_partitions)
+        .map(|partition_id| {
+            let predicate: BooleanArray = partition_ids
+                .iter()
+                .map(|x| Some(*x == partition_id))
+                .collect();
+            arrow::compute::filter_record_batch(batch, &predicate).map_err(|e| e.to_string())
+        })
+        .collect()
+}
@@ -0,0 +1,72 @@
+//! Projection of data in Arrow IPC format to a subset of its fields
+use arrow::record_batch::RecordBatch;
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Projects data in Arrow IPC format from the WASM module memory to a subset of its fields
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `columns_offset` - position of the start of the fields to retain as UTF-8 comma-separated list of field names, e.g. "id,title"
+/// * `columns_size` - size of the list of field names
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the projected data in Arrow IPC format. If the projection failed, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_project_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    columns_offset: *mut u32,
+    columns_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - columns
+    let input_vec_columns: Vec<u8> = match read_shared_memory(columns_offset, columns_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match project_arrow(&input_vec_data, &input_vec_columns) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, projects it and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `columns` - UTF-8 comma-separated list of field names
+///
+/// returns the projected data in Arrow IPC format
+fn project_arrow(serialized_data: &[u8], columns: &[u8]) -> Result<Vec<u8>, String> {
+    let columns: &str = std::str::from_utf8(columns)
+        .map_err(|e| format!("List of field names is not valid UTF-8: {e}"))?;
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let result_batch: RecordBatch = project(&batch, columns)?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Projects a record batch to a subset of its fields in the given order
+/// # Arguments
+/// * `batch` - record batch to project
+/// * `columns` - comma-separated list of field names
+///
+/// returns the projected record batch
+fn project(batch: &RecordBatch, columns: &str) -> Result<RecordBatch, String> {
+    let schema = batch.schema();
+    let indices: Vec<usize> = columns
+        .split(',')
+        .map(|name| name.trim())
+        .map(|name| {
+            schema
+                .index_of(name)
+                .map_err(|_| format!("Field '{name}' not found in schema"))
+        })
+        .collect::<Result<Vec<usize>, String>>()?;
+    batch.project(&indices).map_err(|e| e.to_string())
+}
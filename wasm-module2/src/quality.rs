@@ -0,0 +1,206 @@
+//! Validation of the quality of data in Arrow IPC format, e.g. before processing it in a pipeline
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use arrow::array::{
+    downcast_primitive_array, Array, ArrayRef, AsArray, PrimitiveArray, StringArray, UInt64Array,
+};
+use arrow::datatypes::{ArrowPrimitiveType, DataType, Field, Float64Type, Schema};
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Validates the quality of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing one row per field of the data in Arrow IPC format with the schema {column: Utf8, null_count: UInt64, distinct_count: UInt64, min_str: Utf8, max_str: Utf8, constraint_violations: UInt64}. The constraints id > 0, 0.0 <= score <= 10.0 and content not empty are validated for the fields with these names (null values are not counted as violations). If the data has no rows, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_validate_data_quality_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match validate_data_quality_arrow(&input_vec_data) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, validates its quality and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+///
+/// returns the quality of each field in Arrow IPC format
+fn validate_data_quality_arrow(serialized_data: &[u8]) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    if batch.num_rows() == 0 {
+        return Err("Data quality cannot be validated for data without rows".to_string());
+    }
+    let mut columns: Vec<String> = Vec::with_capacity(batch.num_columns());
+    let mut null_counts: Vec<u64> = Vec::with_capacity(batch.num_columns());
+    let mut distinct_counts: Vec<u64> = Vec::with_capacity(batch.num_columns());
+    let mut min_strs: Vec<Option<String>> = Vec::with_capacity(batch.num_columns());
+    let mut max_strs: Vec<Option<String>> = Vec::with_capacity(batch.num_columns());
+    let mut constraint_violations: Vec<u64> = Vec::with_capacity(batch.num_columns());
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        let (min_str, max_str) = min_max_str(column)?;
+        columns.push(field.name().clone());
+        null_counts.push(column.null_count() as u64);
+        distinct_counts.push(distinct_count(column)?);
+        min_strs.push(min_str);
+        max_strs.push(max_str);
+        constraint_violations.push(count_constraint_violations(field.name(), column)?);
+    }
+    let schema = Schema::new(vec![
+        Field::new("column", DataType::Utf8, false),
+        Field::new("null_count", DataType::UInt64, false),
+        Field::new("distinct_count", DataType::UInt64, false),
+        Field::new("min_str", DataType::Utf8, true),
+        Field::new("max_str", DataType::Utf8, true),
+        Field::new("constraint_violations", DataType::UInt64, false),
+    ]);
+    let result_batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from(columns)),
+            Arc::new(UInt64Array::from(null_counts)),
+            Arc::new(UInt64Array::from(distinct_counts)),
+            Arc::new(StringArray::from(min_strs)),
+            Arc::new(StringArray::from(max_strs)),
+            Arc::new(UInt64Array::from(constraint_violations)),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Counts the distinct non-null values of a column
+/// # Arguments
+/// * `column` - column to count
+///
+/// returns the exact number of distinct values
+fn distinct_count(column: &ArrayRef) -> Result<u64, String> {
+    let formatter = ArrayFormatter::try_new(column.as_ref(), &FormatOptions::default())
+        .map_err(|e| e.to_string())?;
+    let distinct_values: HashSet<String> = (0..column.len())
+        .filter(|i| column.is_valid(*i))
+        .map(|i| formatter.value(i).to_string())
+        .collect();
+    Ok(distinct_values.len() as u64)
+}
+
+/// Determines the minimum and maximum value of a column. Supported are primitive (e.g. numbers, timestamps), boolean and string columns
+/// # Arguments
+/// * `column` - column to analyze
+///
+/// returns the minimum and maximum formatted as string. They are None if the column contains only null values or its type is not supported
+fn min_max_str(column: &ArrayRef) -> Result<(Option<String>, Option<String>), String> {
+    downcast_primitive_array!(
+        column => primitive_min_max_str(column),
+        DataType::Boolean => {
+            let column = column.as_boolean();
+            Ok((
+                arrow::compute::min_boolean(column).map(|x| x.to_string()),
+                arrow::compute::max_boolean(column).map(|x| x.to_string()),
+            ))
+        }
+        DataType::Utf8 => {
+            let column = column.as_string::<i32>();
+            Ok((
+                arrow::compute::min_string(column).map(|x| x.to_string()),
+                arrow::compute::max_string(column).map(|x| x.to_string()),
+            ))
+        }
+        DataType::LargeUtf8 => {
+            let column = column.as_string::<i64>();
+            Ok((
+                arrow::compute::min_string(column).map(|x| x.to_string()),
+                arrow::compute::max_string(column).map(|x| x.to_string()),
+            ))
+        }
+        _ => Ok((None, None))
+    )
+}
+
+/// Determines the minimum and maximum value of a primitive column
+/// # Arguments
+/// * `column` - column to analyze
+///
+/// returns the minimum and maximum formatted according to the type of the column, e.g. timestamps as date and time
+fn primitive_min_max_str<T: ArrowPrimitiveType>(
+    column: &PrimitiveArray<T>,
+) -> Result<(Option<String>, Option<String>), String> {
+    let format = |value: Option<T::Native>| -> Result<Option<String>, String> {
+        match value {
+            Some(x) => {
+                // format the value like the column, e.g. with the timezone of a timestamp
+                let value_array: PrimitiveArray<T> = PrimitiveArray::<T>::from_iter_values([x])
+                    .with_data_type(column.data_type().clone());
+                let formatter = ArrayFormatter::try_new(&value_array, &FormatOptions::default())
+                    .map_err(|e| e.to_string())?;
+                Ok(Some(formatter.value(0).to_string()))
+            }
+            None => Ok(None),
+        }
+    };
+    Ok((
+        format(arrow::compute::min(column))?,
+        format(arrow::compute::max(column))?,
+    ))
+}
+
+/// Counts the non-null values of a column that violate the constraint defined for its field name. Constraints: id > 0, 0.0 <= score <= 10.0, content not empty
+/// # Arguments
+/// * `name` - name of the field
+/// * `column` - column to validate
+///
+/// returns the number of violations. It is 0 for fields without a constraint
+fn count_constraint_violations(name: &str, column: &ArrayRef) -> Result<u64, String> {
+    match name {
+        "id" => count_number_violations(name, column, |x| x > 0.0),
+        "score" => count_number_violations(name, column, |x| (0.0..=10.0).contains(&x)),
+        "content" => {
+            let content_column: ArrayRef = arrow::compute::cast(column, &DataType::LargeUtf8)
+                .map_err(|e| format!("Field '{name}' cannot be validated: {e}"))?;
+            Ok(content_column
+                .as_string::<i64>()
+                .iter()
+                .flatten()
+                .filter(|x| x.is_empty())
+                .count() as u64)
+        }
+        _ => Ok(0),
+    }
+}
+
+/// Counts the non-null values of a numeric column that violate a constraint
+/// # Arguments
+/// * `name` - name of the field
+/// * `column` - column to validate
+/// * `constraint` - constraint the values need to fulfil
+///
+/// returns the number of violations
+fn count_number_violations(
+    name: &str,
+    column: &ArrayRef,
+    constraint: impl Fn(f64) -> bool,
+) -> Result<u64, String> {
+    let number_column: ArrayRef = arrow::compute::cast(column, &DataType::Float64)
+        .map_err(|e| format!("Field '{name}' cannot be validated: {e}"))?;
+    Ok(number_column
+        .as_primitive::<Float64Type>()
+        .iter()
+        .flatten()
+        .filter(|x| !constraint(*x))
+        .count() as u64)
+}
@@ -0,0 +1,132 @@
+//! Validation of the value ranges of the fields of data in Arrow IPC format, e.g. to reject data with semantically invalid values before processing it
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, BooleanArray, Float64Array, UInt64Array};
+use arrow::compute::kernels::cmp::{gt, gt_eq, lt_eq};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::coerce::coerce_batch;
+use crate::validate::column;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, expected_data_schema,
+    read_arrow_batch, read_shared_memory, string_value, write_arrow_batch, WasmResultStatus,
+};
+
+/// Minimum valid score (inclusive)
+const SCORE_MIN: f64 = 0.0;
+
+/// Maximum valid score (inclusive)
+const SCORE_MAX: f64 = 10.0;
+
+/// Validates the value ranges of the fields of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format with the schema expected by wasm_memory_process_data_arrow
+/// * `data_size` - size of the data in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing one row per row of the data in Arrow IPC format with the schema {id: UInt64, id_valid: Boolean, score_valid: Boolean, content_valid: Boolean, all_valid: Boolean, violation_count: UInt64}. The conditions are id > 0, score between 0.0 and 10.0 (inclusive) and non-empty content. Null values do not fulfill a condition. If the data does not have the expected schema, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_validate_ranges_arrow(data_offset: *mut u32, data_size: u32) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match validate_ranges_arrow(&input_vec_data) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, validates the value ranges and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+///
+/// returns the verdicts in Arrow IPC format
+fn validate_ranges_arrow(serialized_data: &[u8]) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    // tolerate compatible changes of the schema by the application
+    let batch: RecordBatch = coerce_batch(&batch, &expected_data_schema())?;
+    write_arrow_batch(&validate_ranges(&batch)?).map_err(|e| e.to_string())
+}
+
+/// Evaluates the value ranges for each row of a record batch
+/// # Arguments
+/// * `batch` - record batch with the fields id (UInt64), content (Utf8 or LargeUtf8) and score (Float64)
+///
+/// returns a record batch with the id, one flag per condition, a flag for all conditions and the number of violated conditions for each row
+fn validate_ranges(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    let id_column: &ArrayRef = column(batch, "id")?;
+    let score_column: &ArrayRef = column(batch, "score")?;
+    let content_column: &ArrayRef = column(batch, "content")?;
+    if id_column.data_type() != &DataType::UInt64 {
+        return Err("Field 'id' is not of type UInt64".to_string());
+    }
+    if score_column.data_type() != &DataType::Float64 {
+        return Err("Field 'score' is not of type Float64".to_string());
+    }
+    if !matches!(
+        content_column.data_type(),
+        DataType::Utf8 | DataType::LargeUtf8
+    ) {
+        return Err("Field 'content' is not of type Utf8 or LargeUtf8".to_string());
+    }
+    let id_valid: BooleanArray =
+        non_null(gt(id_column, &UInt64Array::new_scalar(0)).map_err(|e| e.to_string())?);
+    let score_valid: BooleanArray = non_null(
+        arrow::compute::and(
+            &gt_eq(score_column, &Float64Array::new_scalar(SCORE_MIN))
+                .map_err(|e| e.to_string())?,
+            &lt_eq(score_column, &Float64Array::new_scalar(SCORE_MAX))
+                .map_err(|e| e.to_string())?,
+        )
+        .map_err(|e| e.to_string())?,
+    );
+    // strings have no comparison kernel for their length
+    let content_valid: BooleanArray = (0..batch.num_rows())
+        .map(|i| Some(content_column.is_valid(i) && !string_value(content_column, i).is_empty()))
+        .collect();
+    let all_valid: BooleanArray = arrow::compute::and(
+        &arrow::compute::and(&id_valid, &score_valid).map_err(|e| e.to_string())?,
+        &content_valid,
+    )
+    .map_err(|e| e.to_string())?;
+    let violation_count: UInt64Array = (0..batch.num_rows())
+        .map(|i| {
+            [&id_valid, &score_valid, &content_valid]
+                .iter()
+                .filter(|valid| !valid.value(i))
+                .count() as u64
+        })
+        .map(Some)
+        .collect();
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, true),
+        Field::new("id_valid", DataType::Boolean, false),
+        Field::new("score_valid", DataType::Boolean, false),
+        Field::new("content_valid", DataType::Boolean, false),
+        Field::new("all_valid", DataType::Boolean, false),
+        Field::new("violation_count", DataType::UInt64, false),
+    ]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            id_column.clone(),
+            Arc::new(id_valid),
+            Arc::new(score_valid),
+            Arc::new(content_valid),
+            Arc::new(all_valid),
+            Arc::new(violation_count),
+        ],
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Replaces the null values of the result of a comparison, ie of null values of the data, by false
+/// # Arguments
+/// * `flags` - result of a comparison
+///
+/// returns the flags without null values
+fn non_null(flags: BooleanArray) -> BooleanArray {
+    arrow::compute::prep_null_mask_filter(&flags)
+}
@@ -0,0 +1,117 @@
+//! Run-end encoding of numeric fields of the results, so that results with many consecutive identical values (e.g. scores or categories) are smaller
+use std::cell::Cell;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, ArrowPrimitiveType, AsArray, PrimitiveArray, PrimitiveRunBuilder,
+};
+use arrow::datatypes::{
+    DataType, Field, Float16Type, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
+    Int8Type, Schema, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatch;
+
+// Global variable with the minimum average length of the runs of a field to encode it. Run-end encoding is disabled if it is None. The application sets it via wasm_set_use_run_encoding
+thread_local!(
+    static MIN_RUN_LENGTH: Cell<Option<usize>> = const { Cell::new(None) };
+);
+
+/// Enables or disables the run-end encoding of numeric fields (integers and floating point numbers) of all results in Arrow IPC format. It applies to all following calls of the instance (default: disabled)
+/// # Arguments
+/// * `enabled` - 1 to enable the run-end encoding, 0 to disable it
+/// * `min_run_length` - minimum average number of consecutive identical values of a field, ie number of rows divided by number of runs, to encode it. Fields with shorter runs are returned unchanged. A field is encoded as RunEndEncoded(Int32, type of the field)
+#[no_mangle]
+pub extern "C" fn wasm_set_use_run_encoding(enabled: u32, min_run_length: u32) {
+    let min_run_length: Option<usize> = (enabled != 0).then_some(min_run_length as usize);
+    MIN_RUN_LENGTH.with(|run_length| run_length.set(min_run_length));
+}
+
+/// Returns the minimum average run length set by wasm_set_use_run_encoding, e.g. to include it in the key of cached results
+///
+/// returns the minimum average run length. It is None if the run-end encoding is disabled
+pub(crate) fn min_run_length() -> Option<usize> {
+    MIN_RUN_LENGTH.with(|run_length| run_length.get())
+}
+
+/// Encodes the numeric fields of a record batch with runs that are long enough as run-end encoded arrays if the run-end encoding is enabled
+/// # Arguments
+/// * `batch` - record batch of a result
+///
+/// returns the record batch with run-end encoded fields. The record batch is returned unchanged if the run-end encoding is disabled
+pub(crate) fn run_encode_batch(batch: &RecordBatch) -> Result<RecordBatch, ArrowError> {
+    let Some(min_run_length) = min_run_length() else {
+        return Ok(batch.clone());
+    };
+    let mut fields: Vec<Field> = Vec::with_capacity(batch.num_columns());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(batch.num_columns());
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        match run_encode_column(column, min_run_length) {
+            Some(encoded) => {
+                fields.push(
+                    field
+                        .as_ref()
+                        .clone()
+                        .with_data_type(encoded.data_type().clone()),
+                );
+                columns.push(encoded);
+            }
+            None => {
+                fields.push(field.as_ref().clone());
+                columns.push(column.clone());
+            }
+        }
+    }
+    let schema = Schema::new_with_metadata(fields, batch.schema().metadata().clone());
+    RecordBatch::try_new(Arc::new(schema), columns)
+}
+
+/// Encodes a column as run-end encoded array if it is numeric and its runs are long enough
+/// # Arguments
+/// * `column` - column of a result
+/// * `min_run_length` - minimum average length of the runs
+///
+/// returns the run-end encoded column. It is None if the column is not numeric or its runs are too short
+fn run_encode_column(column: &ArrayRef, min_run_length: usize) -> Option<ArrayRef> {
+    match column.data_type() {
+        DataType::Int8 => run_encode::<Int8Type>(column.as_primitive(), min_run_length),
+        DataType::Int16 => run_encode::<Int16Type>(column.as_primitive(), min_run_length),
+        DataType::Int32 => run_encode::<Int32Type>(column.as_primitive(), min_run_length),
+        DataType::Int64 => run_encode::<Int64Type>(column.as_primitive(), min_run_length),
+        DataType::UInt8 => run_encode::<UInt8Type>(column.as_primitive(), min_run_length),
+        DataType::UInt16 => run_encode::<UInt16Type>(column.as_primitive(), min_run_length),
+        DataType::UInt32 => run_encode::<UInt32Type>(column.as_primitive(), min_run_length),
+        DataType::UInt64 => run_encode::<UInt64Type>(column.as_primitive(), min_run_length),
+        DataType::Float16 => run_encode::<Float16Type>(column.as_primitive(), min_run_length),
+        DataType::Float32 => run_encode::<Float32Type>(column.as_primitive(), min_run_length),
+        DataType::Float64 => run_encode::<Float64Type>(column.as_primitive(), min_run_length),
+        _ => None,
+    }
+}
+
+/// Encodes the values of a numeric column as run-end encoded array if their runs are long enough
+/// # Arguments
+/// * `values` - values of the column
+/// * `min_run_length` - minimum average length of the runs
+///
+/// returns the run-end encoded values. It is None if the column has no values or its runs are too short
+fn run_encode<T: ArrowPrimitiveType>(
+    values: &PrimitiveArray<T>,
+    min_run_length: usize,
+) -> Option<ArrayRef> {
+    // the runs are counted with a linear scan before encoding, so that short runs are not encoded in vain
+    let mut runs: usize = 0;
+    let mut previous: Option<Option<T::Native>> = None;
+    for value in values.iter() {
+        if previous != Some(value) {
+            runs += 1;
+            previous = Some(value);
+        }
+    }
+    if runs == 0 || values.len() < runs.saturating_mul(min_run_length) {
+        return None;
+    }
+    let mut builder = PrimitiveRunBuilder::<Int32Type, T>::with_capacity(runs);
+    builder.extend(values.iter());
+    Some(Arc::new(builder.finish()))
+}
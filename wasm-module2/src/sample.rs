@@ -0,0 +1,112 @@
+//! Reproducible random sampling of the rows of data in Arrow IPC format, e.g. to test or check the quality of a representative subset of a large batch
+use arrow::array::{ArrayRef, UInt32Array};
+use arrow::record_batch::RecordBatch;
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// State of the PRNG used instead of a seed of 0, as Xorshift32 would only generate 0 from it
+const ZERO_SEED_STATE: u32 = 0x9E37_79B9;
+
+/// Deterministic pseudo random number generator Xorshift32 (Marsaglia, shift triple 13, 17, 5)
+struct Xorshift32 {
+    state: u32,
+}
+
+impl Xorshift32 {
+    /// Creates a new PRNG
+    /// # Arguments
+    /// * `seed` - initial state. A seed of 0 is replaced by 0x9E3779B9
+    ///
+    /// returns the PRNG
+    fn new(seed: u32) -> Xorshift32 {
+        Xorshift32 {
+            state: if seed == 0 { ZERO_SEED_STATE } else { seed },
+        }
+    }
+
+    /// Generates the next number
+    ///
+    /// returns the next state: x ^= x << 13; x ^= x >> 17; x ^= x << 5
+    fn next_u32(&mut self) -> u32 {
+        let mut x: u32 = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+}
+
+/// Draws a random sample of the rows of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `sample_size` - number of rows of the sample
+/// * `seed` - seed of the PRNG, the same seed results in the same sample
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the sampled rows in their original order in Arrow IPC format. If sample_size is greater than or equal to the number of rows, all rows are returned unchanged. If the sampling failed, the status is non-zero, see wasm_last_error for details
+///
+/// The rows are selected without replacement by a partial Fisher-Yates shuffle of the row indices 0..n-1 using the PRNG Xorshift32 (state = seed, or 0x9E3779B9 if seed is 0; next: x ^= x << 13; x ^= x >> 17; x ^= x << 5). For i in 0..sample_size, the index at position i is swapped with the index at position i + next % (n - i). The first sample_size indices are sorted ascending, so that the application can reproduce the sample
+#[no_mangle]
+pub extern "C" fn wasm_memory_sample_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    sample_size: u32,
+    seed: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match sample_arrow(&input_vec_data, sample_size, seed) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, takes the sampled rows and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `sample_size` - number of rows of the sample
+/// * `seed` - seed of the PRNG
+///
+/// returns the sampled rows in Arrow IPC format
+fn sample_arrow(serialized_data: &[u8], sample_size: u32, seed: u32) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    if sample_size as usize >= batch.num_rows() {
+        return write_arrow_batch(&batch).map_err(|e| e.to_string());
+    }
+    let indices: UInt32Array = sample_indices(batch.num_rows() as u32, sample_size, seed);
+    let columns: Vec<ArrayRef> = batch
+        .columns()
+        .iter()
+        .map(|column| arrow::compute::take(column, &indices, None))
+        .collect::<Result<Vec<ArrayRef>, _>>()
+        .map_err(|e| e.to_string())?;
+    let result_batch: RecordBatch =
+        RecordBatch::try_new(batch.schema(), columns).map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Selects random row indices without replacement (see wasm_memory_sample_arrow for the algorithm)
+/// # Arguments
+/// * `num_rows` - number of rows to select from
+/// * `sample_size` - number of indices to select, must be less than num_rows
+/// * `seed` - seed of the PRNG
+///
+/// returns the selected indices in ascending order
+fn sample_indices(num_rows: u32, sample_size: u32, seed: u32) -> UInt32Array {
+    let mut rng: Xorshift32 = Xorshift32::new(seed);
+    let mut indices: Vec<u32> = (0..num_rows).collect();
+    for i in 0..sample_size {
+        let j: u32 = i + rng.next_u32() % (num_rows - i);
+        indices.swap(i as usize, j as usize);
+    }
+    indices.truncate(sample_size as usize);
+    indices.sort_unstable();
+    UInt32Array::from(indices)
+}
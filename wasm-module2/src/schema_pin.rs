@@ -0,0 +1,92 @@
+//! Pinning of the schema of the data of wasm_memory_process_data_arrow, so that schema drift between calls of a long-lived instance is detected, e.g. in streaming pipelines with several callers
+use std::cell::RefCell;
+
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::ipc::reader::StreamReader;
+
+use crate::{read_shared_memory, set_last_error};
+
+/// Return code of wasm_pin_schema
+enum PinSchemaReturnCode {
+    Success = 0,
+    ErrorInvalidMemory = -1,
+    ErrorInvalidSchema = -2,
+}
+
+// Global variable with the pinned schema. No schema is enforced if it is None. The application sets it via wasm_pin_schema
+thread_local!(
+    static PINNED_SCHEMA: RefCell<Option<Schema>> = const { RefCell::new(None) };
+);
+
+/// Pins the schema of the data of wasm_memory_process_data_arrow. It applies to all following calls of the instance until wasm_unpin_schema is called. Data whose schema does not contain all fields of the pinned schema (same name, type and nullability) is rejected with the status ErrorSchemaMismatch (-3)
+/// # Arguments
+/// * `schema_ipc_offset` - position of the start of the schema as Arrow IPC stream without record batches
+/// * `schema_ipc_size` - size of the schema in Arrow IPC format
+///
+/// returns 0 if the schema has been pinned. Returns -1 if no valid memory was provided and -2 if the stream cannot be read or contains record batches, see wasm_last_error for details. A previously pinned schema is replaced only on success
+#[no_mangle]
+pub extern "C" fn wasm_pin_schema(schema_ipc_offset: *mut u32, schema_ipc_size: u32) -> i32 {
+    // fetch from WASM module memory - schema
+    let input_vec_schema: Vec<u8> = match read_shared_memory(schema_ipc_offset, schema_ipc_size) {
+        Some(x) => x,
+        None => {
+            set_last_error("Invalid memory: schema has not been allocated".to_string());
+            return PinSchemaReturnCode::ErrorInvalidMemory as i32;
+        }
+    };
+    match read_schema(&input_vec_schema) {
+        Ok(schema) => {
+            PINNED_SCHEMA.with(|pinned_schema| *pinned_schema.borrow_mut() = Some(schema));
+            PinSchemaReturnCode::Success as i32
+        }
+        Err(error_message) => {
+            set_last_error(error_message);
+            PinSchemaReturnCode::ErrorInvalidSchema as i32
+        }
+    }
+}
+
+/// Removes the schema pinned by wasm_pin_schema, so that data of any schema is accepted again
+#[no_mangle]
+pub extern "C" fn wasm_unpin_schema() {
+    PINNED_SCHEMA.with(|pinned_schema| *pinned_schema.borrow_mut() = None);
+}
+
+/// Reads the schema of an Arrow IPC stream without record batches
+/// # Arguments
+/// * `serialized_schema` - schema as Arrow IPC stream
+///
+/// returns the schema. Returns an error if the stream cannot be read or contains record batches
+fn read_schema(serialized_schema: &[u8]) -> Result<Schema, String> {
+    let mut stream_reader = StreamReader::try_new(serialized_schema, None)
+        .map_err(|e| format!("Schema cannot be read: {e}"))?;
+    let schema: SchemaRef = stream_reader.schema();
+    if stream_reader.next().is_some() {
+        return Err("Schema must be an Arrow IPC stream without record batches".to_string());
+    }
+    Ok(schema.as_ref().clone())
+}
+
+/// Checks the schema of data against the schema pinned by wasm_pin_schema. Only the schema is read, not the record batches
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+///
+/// returns an error if a schema is pinned and the schema of the data does not contain it or cannot be read
+pub(crate) fn check_pinned_schema(serialized_data: &[u8]) -> Result<(), String> {
+    PINNED_SCHEMA.with(|pinned_schema| {
+        let pinned_schema = pinned_schema.borrow();
+        let Some(pinned_schema) = pinned_schema.as_ref() else {
+            return Ok(());
+        };
+        let data_schema: SchemaRef = StreamReader::try_new(serialized_data, None)
+            .map_err(|e| format!("Schema of the data cannot be read: {e}"))?
+            .schema();
+        // additional fields of the data are accepted, as they are ignored by the processing
+        if !data_schema.contains(pinned_schema) {
+            return Err(format!(
+                "Schema of the data {data_schema} does not match the pinned schema {pinned_schema}"
+            ));
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,127 @@
+//! Descriptive statistics of the numeric columns of data in Arrow IPC format, e.g. to profile data
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, Float64Array, StringArray, UInt64Array};
+use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Statistics of a numeric column
+struct ColumnStats {
+    min: Option<f64>,
+    max: Option<f64>,
+    mean: Option<f64>,
+    std_dev: Option<f64>,
+    count: u64,
+    null_count: u64,
+}
+
+/// Computes descriptive statistics of all numeric columns of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing one row per numeric field of the data in Arrow IPC format with the schema {column: Utf8, min_f64: Float64, max_f64: Float64, mean: Float64, std_dev: Float64, count: UInt64, null_count: UInt64}. Values are cast to Float64 and count is the number of non-null values. min_f64, max_f64, mean and std_dev (population standard deviation) are null if a column contains only null values
+#[no_mangle]
+pub extern "C" fn wasm_memory_compute_column_stats_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match compute_column_stats_arrow(&input_vec_data) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, computes the statistics of its numeric columns and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+///
+/// returns the statistics of each numeric field in Arrow IPC format
+fn compute_column_stats_arrow(serialized_data: &[u8]) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let mut columns: Vec<String> = Vec::new();
+    let mut mins: Vec<Option<f64>> = Vec::new();
+    let mut maxs: Vec<Option<f64>> = Vec::new();
+    let mut means: Vec<Option<f64>> = Vec::new();
+    let mut std_devs: Vec<Option<f64>> = Vec::new();
+    let mut counts: Vec<u64> = Vec::new();
+    let mut null_counts: Vec<u64> = Vec::new();
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        if !field.data_type().is_numeric() {
+            continue;
+        }
+        let stats: ColumnStats = column_stats(field.name(), column)?;
+        columns.push(field.name().clone());
+        mins.push(stats.min);
+        maxs.push(stats.max);
+        means.push(stats.mean);
+        std_devs.push(stats.std_dev);
+        counts.push(stats.count);
+        null_counts.push(stats.null_count);
+    }
+    let schema = Schema::new(vec![
+        Field::new("column", DataType::Utf8, false),
+        Field::new("min_f64", DataType::Float64, true),
+        Field::new("max_f64", DataType::Float64, true),
+        Field::new("mean", DataType::Float64, true),
+        Field::new("std_dev", DataType::Float64, true),
+        Field::new("count", DataType::UInt64, false),
+        Field::new("null_count", DataType::UInt64, false),
+    ]);
+    let result_batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(StringArray::from(columns)),
+            Arc::new(Float64Array::from(mins)),
+            Arc::new(Float64Array::from(maxs)),
+            Arc::new(Float64Array::from(means)),
+            Arc::new(Float64Array::from(std_devs)),
+            Arc::new(UInt64Array::from(counts)),
+            Arc::new(UInt64Array::from(null_counts)),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Computes the statistics of a numeric column. The first pass computes min, max and sum with arrow::compute, the second pass computes the standard deviation with Welford's algorithm, which is numerically more stable than summing up squares
+/// # Arguments
+/// * `name` - name of the field
+/// * `column` - numeric column
+///
+/// returns the statistics of the column
+fn column_stats(name: &str, column: &ArrayRef) -> Result<ColumnStats, String> {
+    let float_column: ArrayRef = arrow::compute::cast(column, &DataType::Float64)
+        .map_err(|e| format!("Field '{name}' cannot be cast to Float64: {e}"))?;
+    let float_column: &Float64Array = float_column.as_primitive::<Float64Type>();
+    let count: u64 = (float_column.len() - float_column.null_count()) as u64;
+    let mean: Option<f64> =
+        arrow::compute::sum(float_column).and_then(|sum| (count > 0).then(|| sum / count as f64));
+    let mut welford_count: u64 = 0;
+    let mut welford_mean: f64 = 0.0;
+    let mut welford_m2: f64 = 0.0;
+    for value in float_column.iter().flatten() {
+        welford_count += 1;
+        let delta: f64 = value - welford_mean;
+        welford_mean += delta / welford_count as f64;
+        welford_m2 += delta * (value - welford_mean);
+    }
+    Ok(ColumnStats {
+        min: arrow::compute::min(float_column),
+        max: arrow::compute::max(float_column),
+        mean,
+        std_dev: (welford_count > 0).then(|| (welford_m2 / welford_count as f64).sqrt()),
+        count,
+        null_count: float_column.null_count() as u64,
+    })
+}
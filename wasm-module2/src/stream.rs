@@ -0,0 +1,104 @@
+//! Streaming of processed data in Arrow IPC format via WASI stdout, so that large results do not need to be returned in a single allocation of the WASM module memory
+use std::collections::HashMap;
+use std::io::Write;
+
+use arrow::ipc::reader::StreamReader;
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+
+use crate::{
+    append_null_handling_column, has_large_utf8, process_data_batch, process_data_result,
+    provenance_metadata, read_shared_memory, set_last_error,
+};
+
+/// Return code of wasm_stream_process_arrow
+enum StreamReturnCode {
+    Success = 0,
+    ErrorIo = -1,
+    ErrorProcessing = -2,
+}
+
+/// Processes data in Arrow IPC format from the WASM module memory the same way as wasm_memory_process_data_arrow (command "test") and writes the result to WASI stdout
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `chunk_rows` - maximum number of rows of a record batch written to stdout. Must be greater than 0
+///
+/// returns 0 if the result has been written to stdout as one Arrow IPC stream with one record batch per chunk of chunk_rows rows. Returns -1 if stdout cannot be written and -2 if the data cannot be processed, see wasm_last_error for details. Note: The application needs to capture stdout of the instance, e.g. with a pipe
+#[no_mangle]
+pub extern "C" fn wasm_stream_process_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    chunk_rows: u32,
+) -> i32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => {
+            set_last_error("Invalid memory: data has not been allocated".to_string());
+            return StreamReturnCode::ErrorProcessing as i32;
+        }
+    };
+    match stream_process_arrow(&input_vec_data, chunk_rows as usize) {
+        Ok(()) => StreamReturnCode::Success as i32,
+        Err((return_code, error_message)) => {
+            set_last_error(error_message);
+            return_code as i32
+        }
+    }
+}
+
+/// Deserializes and processes the data and writes the result in chunks to stdout
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `chunk_rows` - maximum number of rows of a record batch written to stdout
+///
+/// returns the return code and description of the error if the data cannot be processed or stdout cannot be written
+fn stream_process_arrow(
+    serialized_data: &[u8],
+    chunk_rows: usize,
+) -> Result<(), (StreamReturnCode, String)> {
+    let processing_error = |e: String| (StreamReturnCode::ErrorProcessing, e);
+    if chunk_rows == 0 {
+        return Err(processing_error(
+            "Number of rows of a chunk must be greater than 0".to_string(),
+        ));
+    }
+    let stream_reader = StreamReader::try_new(serialized_data, None)
+        .map_err(|e| processing_error(e.to_string()))?;
+    // the provenance of the data is propagated to the result
+    let metadata: HashMap<String, String> = provenance_metadata(stream_reader.schema().metadata());
+    let mut large_utf8: bool = false;
+    let mut null_count: u64 = 0;
+    for item in stream_reader {
+        let batch: RecordBatch = item.map_err(|e| processing_error(e.to_string()))?;
+        large_utf8 |= has_large_utf8(&batch);
+        null_count += process_data_batch(&batch).map_err(processing_error)?;
+    }
+    let result_batch: RecordBatch =
+        append_null_handling_column(process_data_result(large_utf8, metadata), null_count)
+            .map_err(processing_error)?;
+    let io_error = |e: String| {
+        (
+            StreamReturnCode::ErrorIo,
+            format!("Cannot write result to stdout: {e}"),
+        )
+    };
+    let mut stream_writer = StreamWriter::try_new(std::io::stdout().lock(), &result_batch.schema())
+        .map_err(|e| io_error(e.to_string()))?;
+    // the chunks are written one by one, so that only one chunk needs to be serialized at a time
+    for offset in (0..result_batch.num_rows()).step_by(chunk_rows) {
+        let chunk: RecordBatch =
+            result_batch.slice(offset, chunk_rows.min(result_batch.num_rows() - offset));
+        stream_writer
+            .write(&chunk)
+            .map_err(|e| io_error(e.to_string()))?;
+    }
+    stream_writer
+        .finish()
+        .map_err(|e| io_error(e.to_string()))?;
+    stream_writer
+        .get_mut()
+        .flush()
+        .map_err(|e| io_error(e.to_string()))
+}
@@ -0,0 +1,126 @@
+//! Processing of documents with multiple tags (multi-value field) in Arrow IPC format
+use std::sync::Arc;
+
+use arrow::array::{Array, ListArray, StringArray, UInt32Array, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::telemetry::ProcessingTimer;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Processes documents with tags in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format with the schema {id: UInt64, title: Utf8, tags: List<Utf8>}
+/// * `data_size` - size of the data in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the result data in Arrow IPC format with the schema {id: UInt64, first_tag: Utf8, tag_count: UInt32}. first_tag is null if a document has no tags. If the processing failed, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_process_tagged_docs_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // the duration of the processing is recorded for wasm_get_last_processing_duration_ns when the function returns
+    let _processing_timer: ProcessingTimer = ProcessingTimer::start();
+    match process_tagged_docs_arrow(&input_vec_data) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the documents, processes their tags and serializes the result
+/// # Arguments
+/// * `serialized_data` - documents in Arrow IPC format
+///
+/// returns the result in Arrow IPC format
+fn process_tagged_docs_arrow(serialized_data: &[u8]) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let result_batch: RecordBatch = process_tagged_docs(&batch)?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Determines the first tag and the number of tags of each document
+/// # Arguments
+/// * `batch` - record batch with the documents
+///
+/// returns a record batch with the id, the first tag and the number of tags of each document
+fn process_tagged_docs(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    let ids: &UInt64Array = batch
+        .column_by_name("id")
+        .and_then(|column| column.as_any().downcast_ref::<UInt64Array>())
+        .ok_or("Field 'id' of type UInt64 not found in schema")?;
+    let (first_tags, tag_counts): (Vec<Option<String>>, Vec<u32>) =
+        summarize_tags(tags_column(batch)?)?;
+    // define schema
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("first_tag", DataType::Utf8, true),
+        Field::new("tag_count", DataType::UInt32, false),
+    ]);
+    // build a record batch
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(ids.clone()),
+            Arc::new(StringArray::from(first_tags)),
+            Arc::new(UInt32Array::from(tag_counts)),
+        ],
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Finds the field tags of a record batch
+/// # Arguments
+/// * `batch` - record batch with the documents
+///
+/// returns the tags of the documents. Returns an error if the field tags is missing or not of type List<Utf8>
+pub(crate) fn tags_column(batch: &RecordBatch) -> Result<&ListArray, String> {
+    let tags: &ListArray = batch
+        .column_by_name("tags")
+        .and_then(|column| column.as_any().downcast_ref::<ListArray>())
+        .ok_or("Field 'tags' of type List<Utf8> not found in schema")?;
+    if tags.value_type() != DataType::Utf8 {
+        return Err(format!(
+            "Field 'tags' has type List<{}> instead of List<Utf8>",
+            tags.value_type()
+        ));
+    }
+    Ok(tags)
+}
+
+/// Determines the first tag and the number of tags of each document
+/// # Arguments
+/// * `tags` - tags of the documents
+///
+/// returns the first tag (None if a document has no tags) and the number of tags of each document
+pub(crate) fn summarize_tags(tags: &ListArray) -> Result<(Vec<Option<String>>, Vec<u32>), String> {
+    let mut first_tags: Vec<Option<String>> = Vec::with_capacity(tags.len());
+    let mut tag_counts: Vec<u32> = Vec::with_capacity(tags.len());
+    for i in 0..tags.len() {
+        // documents without a list of tags are treated as having no tags
+        if tags.is_null(i) {
+            first_tags.push(None);
+            tag_counts.push(0);
+            continue;
+        }
+        let doc_tags_array = tags.value(i);
+        let doc_tags: &StringArray = doc_tags_array
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .ok_or("Tags are not of type Utf8")?;
+        let first_tag: Option<String> = match doc_tags.is_empty() || doc_tags.is_null(0) {
+            true => None,
+            false => Some(doc_tags.value(0).to_string()),
+        };
+        first_tags.push(first_tag);
+        tag_counts.push(doc_tags.len() as u32);
+    }
+    Ok((first_tags, tag_counts))
+}
@@ -0,0 +1,158 @@
+//! Telemetry of the module (memory, calls and cache) that the application can fetch with a single call, e.g. for monitoring
+use std::cell::Cell;
+use std::mem::ManuallyDrop;
+use std::time::Instant;
+
+use crate::cache::cache_hits_misses;
+use crate::{allocate, live_memory_areas, live_memory_bytes};
+
+/// Version of the layout of the telemetry returned by wasm_get_telemetry. Fields are only added at the end, so that applications can read older layouts
+const TELEMETRY_VERSION: u32 = 1;
+
+/// Size of the telemetry of version TELEMETRY_VERSION in bytes
+const TELEMETRY_SIZE: usize = 64;
+
+// Global variable with the largest total size of allocated memory areas since the instantiation
+thread_local!(
+    static PEAK_BYTES: Cell<u64> = const { Cell::new(0) };
+);
+
+// Global variable with the statistics of the calls of wasm_memory_process_data_arrow
+thread_local!(
+    static CALL_STATS: Cell<CallStats> = const {
+        Cell::new(CallStats {
+            total_calls: 0,
+            failed_calls: 0,
+            total_processing_ns: 0,
+        })
+    };
+);
+
+// Global variable with the duration of the processing of the last call of a wasm_memory_process_* function in nanoseconds
+thread_local!(
+    static LAST_PROCESSING_NS: Cell<u64> = const { Cell::new(0) };
+);
+
+/// Counters of the calls of wasm_memory_process_data_arrow
+#[derive(Clone, Copy)]
+struct CallStats {
+    /// all calls
+    total_calls: u64,
+    /// calls that returned a non-zero status
+    failed_calls: u64,
+    /// total duration of all calls in nanoseconds
+    total_processing_ns: u64,
+}
+
+/// Returns the telemetry of the module
+///
+/// returns a pointer to the telemetry (64 bytes, little endian) in the WASM module memory with the following layout:
+/// * byte 0 - version of the layout (u32), currently 1
+/// * byte 4 - memory areas currently allocated (u32)
+/// * byte 8 - total size of the memory areas currently allocated in bytes (u64)
+/// * byte 16 - largest total size of the allocated memory areas since the instantiation in bytes (u64)
+/// * byte 24 - calls of wasm_memory_process_data_arrow (u64)
+/// * byte 32 - calls of wasm_memory_process_data_arrow that returned a non-zero status (u64)
+/// * byte 40 - total duration of the calls of wasm_memory_process_data_arrow in nanoseconds (u64)
+/// * byte 48 - calls answered from the cache (u64), see wasm_set_cache_ttl_ms
+/// * byte 56 - calls not answered from the cache (u64)
+///
+/// The memory of the telemetry itself is not included. Returns 0 if the memory cannot be allocated. Note: The calling application must deallocate the returned pointer with wasm_deallocate
+#[no_mangle]
+pub extern "C" fn wasm_get_telemetry() -> u32 {
+    let (live_allocs, live_bytes): (usize, usize) = (live_memory_areas(), live_memory_bytes(None));
+    let call_stats: CallStats = CALL_STATS.with(|call_stats| call_stats.get());
+    let (cache_hits, cache_misses): (u64, u64) = cache_hits_misses();
+    let mut telemetry: Vec<u8> = Vec::with_capacity(TELEMETRY_SIZE);
+    telemetry.extend(TELEMETRY_VERSION.to_le_bytes());
+    telemetry.extend((live_allocs as u32).to_le_bytes());
+    telemetry.extend((live_bytes as u64).to_le_bytes());
+    telemetry.extend(PEAK_BYTES.with(|peak_bytes| peak_bytes.get()).to_le_bytes());
+    telemetry.extend(call_stats.total_calls.to_le_bytes());
+    telemetry.extend(call_stats.failed_calls.to_le_bytes());
+    telemetry.extend(call_stats.total_processing_ns.to_le_bytes());
+    telemetry.extend(cache_hits.to_le_bytes());
+    telemetry.extend(cache_misses.to_le_bytes());
+    allocate(
+        TELEMETRY_SIZE,
+        ManuallyDrop::new(telemetry.into_boxed_slice()),
+    ) as u32
+}
+
+/// Returns the duration of the processing of the last call of a wasm_memory_process_* function, e.g. wasm_memory_process_data_arrow. The duration starts after the inputs have been copied from the shared memory, ie before their deserialization, and ends after the result has been written. The application can compare it with the duration of the call measured by itself to determine the overhead of the call
+///
+/// returns the duration in nanoseconds. It is 0 before the first call. Calls with inputs that are not valid allocated memory do not change it
+#[no_mangle]
+pub extern "C" fn wasm_get_last_processing_duration_ns() -> u64 {
+    LAST_PROCESSING_NS.with(|last_processing_ns| last_processing_ns.get())
+}
+
+/// Updates the largest total size of the allocated memory areas. It needs to be called after each allocation
+pub(crate) fn record_memory_usage() {
+    let live_bytes: u64 = live_memory_bytes(None) as u64;
+    PEAK_BYTES.with(|peak_bytes| peak_bytes.set(peak_bytes.get().max(live_bytes)));
+}
+
+/// Records a call of wasm_memory_process_data_arrow when it is dropped. The call is recorded as failed unless succeeded is called before
+pub(crate) struct CallTelemetry {
+    /// time the call started
+    start: Instant,
+    /// true if the call returned the status Success
+    success: bool,
+}
+
+impl CallTelemetry {
+    /// Starts recording a call
+    ///
+    /// returns the recording of the call
+    pub(crate) fn start() -> CallTelemetry {
+        CallTelemetry {
+            start: Instant::now(),
+            success: false,
+        }
+    }
+
+    /// Marks the call as successful
+    pub(crate) fn succeeded(&mut self) {
+        self.success = true;
+    }
+}
+
+impl Drop for CallTelemetry {
+    fn drop(&mut self) {
+        let processing_ns: u64 = self.start.elapsed().as_nanos() as u64;
+        CALL_STATS.with(|call_stats| {
+            let mut stats: CallStats = call_stats.get();
+            stats.total_calls += 1;
+            if !self.success {
+                stats.failed_calls += 1;
+            }
+            stats.total_processing_ns += processing_ns;
+            call_stats.set(stats);
+        });
+    }
+}
+
+/// Records the duration of the processing of a call of a wasm_memory_process_* function when it is dropped, see wasm_get_last_processing_duration_ns
+pub(crate) struct ProcessingTimer {
+    /// time the processing started
+    start: Instant,
+}
+
+impl ProcessingTimer {
+    /// Starts measuring the processing of a call
+    ///
+    /// returns the measurement of the processing
+    pub(crate) fn start() -> ProcessingTimer {
+        ProcessingTimer {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for ProcessingTimer {
+    fn drop(&mut self) {
+        let processing_ns: u64 = self.start.elapsed().as_nanos() as u64;
+        LAST_PROCESSING_NS.with(|last_processing_ns| last_processing_ns.set(processing_ns));
+    }
+}
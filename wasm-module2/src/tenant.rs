@@ -0,0 +1,22 @@
+//! Isolation of the memory areas of tenants that share an instance, so that a tenant cannot validate or deallocate memory allocated for another tenant
+use std::cell::Cell;
+
+// Global variable with the tenant of the current request. Memory areas are allocated, validated and deallocated for this tenant. The application sets it via wasm_set_tenant_id
+thread_local!(
+    static TENANT_ID: Cell<u32> = const { Cell::new(0) };
+);
+
+/// Sets the tenant of the following calls of the instance, e.g. at the start of each request of a pool. Memory allocated for one tenant cannot be validated or deallocated by another tenant (default: 0)
+/// # Arguments
+/// * `id` - ID of the tenant
+#[no_mangle]
+pub extern "C" fn wasm_set_tenant_id(id: u32) {
+    TENANT_ID.with(|tenant_id| tenant_id.set(id));
+}
+
+/// Returns the tenant set by wasm_set_tenant_id, e.g. to include it in the key of the memory areas
+///
+/// returns the ID of the tenant
+pub(crate) fn current_tenant_id() -> u32 {
+    TENANT_ID.with(|tenant_id| tenant_id.get())
+}
@@ -0,0 +1,79 @@
+//! Normalization of the precision of the timestamps of the field date, so that the application can send them in any precision (second, millisecond, microsecond or nanosecond)
+use std::sync::Arc;
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+
+use crate::alias::resolve_field_by_name_or_alias;
+use crate::{log, HostLogLevel};
+
+/// Key of the schema metadata of the result with the precision of the field date of the data if it was not second, e.g. "millisecond"
+pub(crate) const ORIGINAL_TIMESTAMP_PRECISION_KEY: &str = "original_timestamp_precision";
+
+/// Casts the field date from a timestamp with a precision of millisecond, microsecond or nanosecond to a timestamp with a precision of second. The timezone is not changed. Fractions of a second are truncated
+/// # Arguments
+/// * `batch` - record batch of data
+///
+/// returns the record batch with the field date with a precision of second. Other record batches, e.g. with a date that is not a timestamp, are returned unchanged
+pub(crate) fn normalize_timestamp_precision(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    let Some((date_index, dates)) = resolve_field_by_name_or_alias(batch, "date") else {
+        return Ok(batch.clone());
+    };
+    let DataType::Timestamp(time_unit, tz) = dates.data_type() else {
+        return Ok(batch.clone());
+    };
+    if *time_unit == TimeUnit::Second {
+        return Ok(batch.clone());
+    }
+    log(
+        HostLogLevel::Debug,
+        &format!(
+            "Normalizing the precision of field 'date' from {} to second",
+            time_unit_name(time_unit)
+        ),
+    );
+    let normalized_data_type = DataType::Timestamp(TimeUnit::Second, tz.clone());
+    let normalized_dates: ArrayRef = arrow::compute::cast(dates, &normalized_data_type)
+        .map_err(|e| format!("Field 'date' cannot be normalized: {e}"))?;
+    let schema = batch.schema();
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields[date_index] = fields[date_index]
+        .clone()
+        .with_data_type(normalized_data_type);
+    let mut columns: Vec<ArrayRef> = batch.columns().to_vec();
+    columns[date_index] = normalized_dates;
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Returns the precision of the field date if normalize_timestamp_precision changes it
+/// # Arguments
+/// * `batch` - record batch of data
+///
+/// returns the name of the precision, ie "millisecond", "microsecond" or "nanosecond". It is None if the date is a timestamp with a precision of second or not a timestamp
+pub(crate) fn original_timestamp_precision(batch: &RecordBatch) -> Option<&'static str> {
+    let (_, dates) = resolve_field_by_name_or_alias(batch, "date")?;
+    match dates.data_type() {
+        DataType::Timestamp(TimeUnit::Second, _) => None,
+        DataType::Timestamp(time_unit, _) => Some(time_unit_name(time_unit)),
+        _ => None,
+    }
+}
+
+/// Returns the name of the precision of a timestamp
+/// # Arguments
+/// * `time_unit` - precision of a timestamp
+///
+/// returns the name in lower case, e.g. "millisecond"
+fn time_unit_name(time_unit: &TimeUnit) -> &'static str {
+    match time_unit {
+        TimeUnit::Second => "second",
+        TimeUnit::Millisecond => "millisecond",
+        TimeUnit::Microsecond => "microsecond",
+        TimeUnit::Nanosecond => "nanosecond",
+    }
+}
@@ -0,0 +1,111 @@
+//! Conversion of the timestamps of data in Arrow IPC format to another timezone, e.g. the local timezone of the caller
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow::array::timezone::Tz;
+use arrow::array::ArrayRef;
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Converts all timestamp fields of data in Arrow IPC format from the WASM module memory to a timezone
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `target_tz_offset` - position of the start of the target timezone as UTF-8 string, either an IANA timezone name (e.g. "Europe/Berlin") or an UTC offset (e.g. "+02:00")
+/// * `target_tz_size` - size of the target timezone
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the data in Arrow IPC format where all timestamp fields are of type Timestamp(Second, target timezone). Timestamps without timezone are considered to be in UTC. If the timezone is not valid, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_convert_timestamp_tz_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    target_tz_offset: *mut u32,
+    target_tz_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - target timezone
+    let input_vec_target_tz: Vec<u8> = match read_shared_memory(target_tz_offset, target_tz_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match convert_timestamp_tz_arrow(&input_vec_data, &input_vec_target_tz) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, converts its timestamps and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `target_tz` - UTF-8 target timezone
+///
+/// returns the converted data in Arrow IPC format
+fn convert_timestamp_tz_arrow(serialized_data: &[u8], target_tz: &[u8]) -> Result<Vec<u8>, String> {
+    let target_tz: &str = std::str::from_utf8(target_tz)
+        .map_err(|e| format!("Target timezone is not valid UTF-8: {e}"))?;
+    // known IANA timezone names (tz database) and UTC offsets, e.g. +02:00, are valid
+    if Tz::from_str(target_tz).is_err() {
+        return Err(format!(
+            "Target timezone '{target_tz}' is neither a known IANA timezone nor an UTC offset (+HH:MM)"
+        ));
+    }
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let result_batch: RecordBatch = convert_timestamp_tz(&batch, target_tz)?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Converts all timestamp fields of a record batch to Timestamp(Second, target timezone). Other fields are not changed
+/// # Arguments
+/// * `batch` - record batch to convert
+/// * `target_tz` - valid target timezone
+///
+/// returns the converted record batch
+fn convert_timestamp_tz(batch: &RecordBatch, target_tz: &str) -> Result<RecordBatch, String> {
+    let target_data_type: DataType =
+        DataType::Timestamp(TimeUnit::Second, Some(Arc::from(target_tz)));
+    let mut fields: Vec<Field> = Vec::with_capacity(batch.num_columns());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(batch.num_columns());
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        let column: ArrayRef = match field.data_type() {
+            // timestamps are stored in UTC, so only the timezone annotation changes
+            DataType::Timestamp(_, Some(_)) => column.clone(),
+            // timestamps without timezone are annotated as UTC first, otherwise they would be considered as local time of the target timezone
+            DataType::Timestamp(time_unit, None) => arrow::compute::cast(
+                column,
+                &DataType::Timestamp(*time_unit, Some(Arc::from("+00:00"))),
+            )
+            .map_err(|e| format!("Field '{}' cannot be converted: {e}", field.name()))?,
+            _ => {
+                fields.push(field.as_ref().clone());
+                columns.push(column.clone());
+                continue;
+            }
+        };
+        let converted_column: ArrayRef = arrow::compute::cast(&column, &target_data_type)
+            .map_err(|e| format!("Field '{}' cannot be converted: {e}", field.name()))?;
+        fields.push(
+            field
+                .as_ref()
+                .clone()
+                .with_data_type(target_data_type.clone()),
+        );
+        columns.push(converted_column);
+    }
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(
+            fields,
+            batch.schema().metadata().clone(),
+        )),
+        columns,
+    )
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,63 @@
+//! Reconstruction of nested fields from flattened fields named with dots (e.g. config.filename), so that applications whose Arrow library does not support nested types can call the module
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, StructArray};
+use arrow::datatypes::{Field, Fields, Schema};
+use arrow::record_batch::RecordBatch;
+
+/// Separator between the name of the nested field and the name of its child in the name of a flattened field
+const FLATTEN_SEPARATOR: char = '.';
+
+/// Combines the flattened fields of a nested field (e.g. config.filename and config.encoding) to one field of type Struct (e.g. config: {filename, encoding}). It is placed at the position of the first flattened field
+/// # Arguments
+/// * `batch` - record batch, e.g. the meta data of wasm_memory_process_data_arrow
+/// * `nested_field` - name of the nested field, e.g. config
+///
+/// returns the record batch with the nested field. It is returned unchanged if it has no flattened fields of the nested field. Returns an error if it has both the nested field and flattened fields of it
+pub(crate) fn unflatten_batch(
+    batch: &RecordBatch,
+    nested_field: &str,
+) -> Result<RecordBatch, String> {
+    let prefix: String = format!("{nested_field}{FLATTEN_SEPARATOR}");
+    let schema = batch.schema();
+    let Some(first_index) = schema
+        .fields()
+        .iter()
+        .position(|field| field.name().starts_with(&prefix))
+    else {
+        return Ok(batch.clone());
+    };
+    if schema.field_with_name(nested_field).is_ok() {
+        return Err(format!(
+            "Field '{nested_field}' is provided both nested and flattened"
+        ));
+    }
+    let mut child_fields: Vec<Field> = Vec::new();
+    let mut child_columns: Vec<ArrayRef> = Vec::new();
+    let mut fields: Vec<Field> = Vec::new();
+    let mut columns: Vec<ArrayRef> = Vec::new();
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        match field.name().strip_prefix(&prefix) {
+            Some(child_name) => {
+                child_fields.push(field.as_ref().clone().with_name(child_name));
+                child_columns.push(column.clone());
+            }
+            None => {
+                fields.push(field.as_ref().clone());
+                columns.push(column.clone());
+            }
+        }
+    }
+    let nested: StructArray = StructArray::try_new(Fields::from(child_fields), child_columns, None)
+        .map_err(|e| e.to_string())?;
+    fields.insert(
+        first_index,
+        Field::new(nested_field, nested.data_type().clone(), false),
+    );
+    columns.insert(first_index, Arc::new(nested));
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())
+}
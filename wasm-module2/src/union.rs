@@ -0,0 +1,123 @@
+//! Processing of data in Arrow IPC format with a polymorphic field (Union), e.g. values that are integers, floats or strings depending on the row
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, StringArray, UnionArray};
+use arrow::datatypes::{DataType, Field, Float64Type, Int64Type, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::telemetry::ProcessingTimer;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Type name of variants of the union that are not supported by the module
+const UNKNOWN_TYPE_NAME: &str = "unknown";
+
+/// Converts the values of a union field of data in Arrow IPC format from the WASM module memory to strings
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format with the schema {id: UInt64, value: DenseUnion([Int64, Float64, Utf8])}
+/// * `data_size` - size of the data in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing one row per row of the data in Arrow IPC format with the schema {id: UInt64, value_str: Utf8, type_name: Utf8}. type_name is "int64", "float64" or "utf8" depending on the active variant of the row. Variants of other types (e.g. added by a newer version of the application) have the type_name "unknown" and value_str null. If the processing failed, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_process_union_arrow(data_offset: *mut u32, data_size: u32) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // the duration of the processing is recorded for wasm_get_last_processing_duration_ns when the function returns
+    let _processing_timer: ProcessingTimer = ProcessingTimer::start();
+    match process_union_arrow(&input_vec_data) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, converts the values of the union field to strings and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+///
+/// returns the ids with the values as strings and their type names in Arrow IPC format
+fn process_union_arrow(serialized_data: &[u8]) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let id_column: &ArrayRef = batch
+        .column_by_name("id")
+        .ok_or("Field 'id' not found in schema".to_string())?;
+    if id_column.data_type() != &DataType::UInt64 {
+        return Err(format!(
+            "Field 'id' has type {} instead of UInt64",
+            id_column.data_type()
+        ));
+    }
+    let values: &UnionArray = batch
+        .column_by_name("value")
+        .ok_or("Field 'value' not found in schema".to_string())?
+        .as_union_opt()
+        .ok_or("Field 'value' is not of type Union".to_string())?;
+    let mut value_strs: Vec<Option<String>> = Vec::with_capacity(values.len());
+    let mut type_names: Vec<&str> = Vec::with_capacity(values.len());
+    for i in 0..values.len() {
+        let (value_str, type_name): (Option<String>, &str) = union_value(values, i);
+        value_strs.push(value_str);
+        type_names.push(type_name);
+    }
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::UInt64, id_column.is_nullable()),
+        Field::new("value_str", DataType::Utf8, true),
+        Field::new("type_name", DataType::Utf8, false),
+    ]);
+    let result_batch: RecordBatch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            id_column.clone(),
+            Arc::new(StringArray::from(value_strs)),
+            Arc::new(StringArray::from(type_names)),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Extracts the value of a row of a union from the child array of its active variant
+/// # Arguments
+/// * `values` - union array (dense or sparse)
+/// * `index` - index of the row
+///
+/// returns the value as string and the type name of the variant. The value is None if it is null or the variant is unknown
+fn union_value(values: &UnionArray, index: usize) -> (Option<String>, &'static str) {
+    let type_id: i8 = values.type_id(index);
+    let is_known_type_id: bool = match values.data_type() {
+        DataType::Union(union_fields, _) => union_fields
+            .iter()
+            .any(|(known_type_id, _)| known_type_id == type_id),
+        _ => false,
+    };
+    if !is_known_type_id {
+        return (None, UNKNOWN_TYPE_NAME);
+    }
+    let child: &ArrayRef = values.child(type_id);
+    let offset: usize = values.value_offset(index);
+    let valid: bool = child.is_valid(offset);
+    match child.data_type() {
+        DataType::Int64 => (
+            valid.then(|| child.as_primitive::<Int64Type>().value(offset).to_string()),
+            "int64",
+        ),
+        DataType::Float64 => (
+            valid.then(|| {
+                child
+                    .as_primitive::<Float64Type>()
+                    .value(offset)
+                    .to_string()
+            }),
+            "float64",
+        ),
+        DataType::Utf8 => (
+            valid.then(|| child.as_string::<i32>().value(offset).to_string()),
+            "utf8",
+        ),
+        _ => (None, UNKNOWN_TYPE_NAME),
+    }
+}
@@ -0,0 +1,114 @@
+//! Unpivoting (melting) of wide data in Arrow IPC format to long format, e.g. sensor or financial data with one field per measurement
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, Float64Array, StringArray, UInt32Array};
+use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Unpivots data in Arrow IPC format from the WASM module memory from wide to long format
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `id_cols_offset` - position of the start of the identifier fields as UTF-8 comma-separated list of field names, e.g. "id,date". An empty list unpivots all fields
+/// * `id_cols_size` - size of the list of field names
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the data in long format in Arrow IPC format: the identifier fields followed by variable (Utf8, name of the field) and value (Float64). Each row of the data results in one row per remaining (value) field, in the order of the fields. If a value field is not numeric, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_unpivot_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    id_cols_offset: *mut u32,
+    id_cols_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - identifier fields
+    let input_vec_id_cols: Vec<u8> = match read_shared_memory(id_cols_offset, id_cols_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match unpivot_arrow(&input_vec_data, &input_vec_id_cols) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, unpivots it and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `id_cols` - UTF-8 comma-separated list of identifier field names
+///
+/// returns the data in long format in Arrow IPC format
+fn unpivot_arrow(serialized_data: &[u8], id_cols: &[u8]) -> Result<Vec<u8>, String> {
+    let id_cols: &str = std::str::from_utf8(id_cols)
+        .map_err(|e| format!("List of identifier field names is not valid UTF-8: {e}"))?;
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let schema = batch.schema();
+    let id_indices: Vec<usize> = id_cols
+        .split(',')
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            schema
+                .index_of(name)
+                .map_err(|_| format!("Field '{name}' not found in schema"))
+        })
+        .collect::<Result<Vec<usize>, String>>()?;
+    // all other fields are value fields
+    let mut value_names: Vec<&str> = Vec::new();
+    let mut value_columns: Vec<ArrayRef> = Vec::new();
+    for (i, field) in schema.fields().iter().enumerate() {
+        if id_indices.contains(&i) {
+            continue;
+        }
+        if !field.data_type().is_numeric() {
+            return Err(format!(
+                "Value field '{}' has type {} that is not numeric",
+                field.name(),
+                field.data_type()
+            ));
+        }
+        value_names.push(field.name());
+        value_columns.push(
+            arrow::compute::cast(batch.column(i), &DataType::Float64).map_err(|e| e.to_string())?,
+        );
+    }
+    let n_value_cols: usize = value_columns.len();
+    // one row per row of the data and value field
+    let mut id_rows: Vec<u32> = Vec::with_capacity(batch.num_rows() * n_value_cols);
+    let mut variables: Vec<&str> = Vec::with_capacity(batch.num_rows() * n_value_cols);
+    let mut values: Vec<Option<f64>> = Vec::with_capacity(batch.num_rows() * n_value_cols);
+    for row in 0..batch.num_rows() {
+        for (value_name, value_column) in value_names.iter().zip(&value_columns) {
+            let value_column = value_column.as_primitive::<Float64Type>();
+            id_rows.push(row as u32);
+            variables.push(value_name);
+            values.push(value_column.is_valid(row).then(|| value_column.value(row)));
+        }
+    }
+    // repeat each identifier n_value_cols times
+    let id_rows = UInt32Array::from(id_rows);
+    let mut fields: Vec<Field> = Vec::with_capacity(id_indices.len() + 2);
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(id_indices.len() + 2);
+    for i in &id_indices {
+        fields.push(schema.field(*i).clone());
+        columns.push(
+            arrow::compute::take(batch.column(*i), &id_rows, None).map_err(|e| e.to_string())?,
+        );
+    }
+    fields.push(Field::new("variable", DataType::Utf8, false));
+    fields.push(Field::new("value", DataType::Float64, true));
+    columns.push(Arc::new(StringArray::from(variables)));
+    columns.push(Arc::new(Float64Array::from(values)));
+    let result_batch: RecordBatch =
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(|e| e.to_string())?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
@@ -0,0 +1,115 @@
+//! Validation of UUIDs stored as raw bytes (FixedSizeBinary(16)) in data in Arrow IPC format, which is more space-efficient than UUIDs as strings
+use std::sync::Arc;
+
+use arrow::array::{Array, AsArray, BooleanArray, FixedSizeBinaryArray, StringArray, UInt8Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::validate::column;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Size of a UUID in bytes
+const UUID_SIZE: i32 = 16;
+
+/// Validates the version and variant of the UUIDs of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format with a field uuid of type FixedSizeBinary(16) (big-endian bytes)
+/// * `data_size` - size of the data in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing one row per row of the data in Arrow IPC format with the schema {uuid: FixedSizeBinary(16), version: UInt8, variant: Utf8, valid: Boolean}. The version is the upper nibble of byte 6, the variant ("ncs", "rfc4122", "microsoft" or "future") is given by the upper bits of byte 8. A UUID is valid if it has a version from 1 to 5 and the variant rfc4122. Version and variant are null and valid is false for null UUIDs. If the field uuid is missing or has another type, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_validate_uuids_arrow(data_offset: *mut u32, data_size: u32) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match validate_uuids_arrow(&input_vec_data) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, validates the UUIDs and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+///
+/// returns the verdicts in Arrow IPC format
+fn validate_uuids_arrow(serialized_data: &[u8]) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    write_arrow_batch(&validate_uuids(&batch)?).map_err(|e| e.to_string())
+}
+
+/// Determines the version and variant of each UUID of a record batch
+/// # Arguments
+/// * `batch` - record batch with the field uuid (FixedSizeBinary(16))
+///
+/// returns a record batch with the UUID, its version, its variant and a flag if it is valid for each row
+fn validate_uuids(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    let uuid_column = column(batch, "uuid")?;
+    if uuid_column.data_type() != &DataType::FixedSizeBinary(UUID_SIZE) {
+        return Err(format!(
+            "Field 'uuid' has type {} instead of {}",
+            uuid_column.data_type(),
+            DataType::FixedSizeBinary(UUID_SIZE)
+        ));
+    }
+    let uuids: &FixedSizeBinaryArray = uuid_column.as_fixed_size_binary();
+    let mut versions: Vec<Option<u8>> = Vec::with_capacity(uuids.len());
+    let mut variants: Vec<Option<&str>> = Vec::with_capacity(uuids.len());
+    let mut valid: Vec<bool> = Vec::with_capacity(uuids.len());
+    for i in 0..uuids.len() {
+        if uuids.is_null(i) {
+            versions.push(None);
+            variants.push(None);
+            valid.push(false);
+            continue;
+        }
+        let bytes: &[u8] = uuids.value(i);
+        versions.push(Some(bytes[6] >> 4));
+        variants.push(Some(uuid_variant(bytes[8])));
+        valid.push(is_valid_uuid(bytes));
+    }
+    let schema = Schema::new(vec![
+        Field::new("uuid", DataType::FixedSizeBinary(UUID_SIZE), true),
+        Field::new("version", DataType::UInt8, true),
+        Field::new("variant", DataType::Utf8, true),
+        Field::new("valid", DataType::Boolean, false),
+    ]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            uuid_column.clone(),
+            Arc::new(UInt8Array::from(versions)),
+            Arc::new(StringArray::from(variants)),
+            Arc::new(BooleanArray::from(valid)),
+        ],
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Checks if a UUID has a version from 1 to 5 and the variant rfc4122
+/// # Arguments
+/// * `bytes` - UUID as 16 big-endian bytes
+///
+/// returns true if the UUID is valid
+pub(crate) fn is_valid_uuid(bytes: &[u8]) -> bool {
+    (1..=5).contains(&(bytes[6] >> 4)) && uuid_variant(bytes[8]) == "rfc4122"
+}
+
+/// Determines the variant of a UUID (RFC 4122, section 4.1.1)
+/// # Arguments
+/// * `byte` - byte 8 of the UUID
+///
+/// returns the name of the variant, ie "ncs" (0xx), "rfc4122" (10x), "microsoft" (110) or "future" (111)
+fn uuid_variant(byte: u8) -> &'static str {
+    match byte >> 5 {
+        0b000..=0b011 => "ncs",
+        0b100 | 0b101 => "rfc4122",
+        0b110 => "microsoft",
+        _ => "future",
+    }
+}
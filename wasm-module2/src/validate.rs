@@ -0,0 +1,113 @@
+//! Validation of data in Arrow IPC format with one verdict (Boolean flag) per row and condition, selected by the command "validate" of wasm_memory_process_data_arrow
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, BooleanBuilder};
+use arrow::datatypes::{DataType, Field, Float64Type, Schema, UInt64Type};
+use arrow::record_batch::RecordBatch;
+
+use crate::alias::{rename_aliased_fields, resolve_field_by_name_or_alias};
+use crate::coerce::coerce_batch;
+use crate::config_store::score_threshold;
+use crate::output_schema::enforce_output_schema;
+use crate::timestamp_precision::normalize_timestamp_precision;
+use crate::{expected_data_schema, read_arrow_batch, string_value, write_arrow_batch};
+
+/// Command of the meta data that selects the validation of the data
+pub(crate) const VALIDATE_COMMAND: &str = "validate";
+
+/// Validates each row of the data against the conditions score >= score_threshold (see wasm_config_set, default: 0.0), non-empty content and id > 0
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format with the schema expected by wasm_memory_process_data_arrow
+/// * `tenant` - tenant of the meta data. The score_threshold of the tenant in the key-value store of the application takes precedence over the one of wasm_config_set
+///
+/// returns the verdicts in Arrow IPC format with the schema {id: UInt64, score_valid: Boolean, content_valid: Boolean, id_valid: Boolean, all_valid: Boolean}. Null values do not fulfill a condition
+pub(crate) fn validate_data_arrow(
+    serialized_data: &[u8],
+    tenant: Option<&str>,
+) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    // tolerate compatible changes of the schema by the application
+    let batch: RecordBatch = coerce_batch(
+        &normalize_timestamp_precision(&rename_aliased_fields(&batch, &expected_data_schema())?)?,
+        &expected_data_schema(),
+    )?;
+    let result_batch: RecordBatch = enforce_output_schema(
+        validate(&batch, score_threshold(tenant)?)?,
+        &validate_result_schema(),
+    )?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Evaluates the conditions for each row of a record batch
+/// # Arguments
+/// * `batch` - record batch with the fields id (UInt64), content (Utf8 or LargeUtf8) and score (Float64)
+/// * `score_threshold` - minimum valid score
+///
+/// returns a record batch with the id and one flag per condition for each row
+fn validate(batch: &RecordBatch, score_threshold: f64) -> Result<RecordBatch, String> {
+    let id_column: &ArrayRef = column(batch, "id")?;
+    let content_column: &ArrayRef = column(batch, "content")?;
+    let score_column: &ArrayRef = column(batch, "score")?;
+    let ids = id_column
+        .as_primitive_opt::<UInt64Type>()
+        .ok_or("Field 'id' is not of type UInt64".to_string())?;
+    if !matches!(
+        content_column.data_type(),
+        DataType::Utf8 | DataType::LargeUtf8
+    ) {
+        return Err("Field 'content' is not of type Utf8 or LargeUtf8".to_string());
+    }
+    let scores = score_column
+        .as_primitive_opt::<Float64Type>()
+        .ok_or("Field 'score' is not of type Float64".to_string())?;
+    let mut score_valid = BooleanBuilder::with_capacity(batch.num_rows());
+    let mut content_valid = BooleanBuilder::with_capacity(batch.num_rows());
+    let mut id_valid = BooleanBuilder::with_capacity(batch.num_rows());
+    let mut all_valid = BooleanBuilder::with_capacity(batch.num_rows());
+    for i in 0..batch.num_rows() {
+        let score_ok: bool = scores.is_valid(i) && scores.value(i) >= score_threshold;
+        let content_ok: bool =
+            content_column.is_valid(i) && !string_value(content_column, i).is_empty();
+        let id_ok: bool = ids.is_valid(i) && ids.value(i) > 0;
+        score_valid.append_value(score_ok);
+        content_valid.append_value(content_ok);
+        id_valid.append_value(id_ok);
+        all_valid.append_value(score_ok && content_ok && id_ok);
+    }
+    RecordBatch::try_new(
+        Arc::new(validate_result_schema()),
+        vec![
+            id_column.clone(),
+            Arc::new(score_valid.finish()),
+            Arc::new(content_valid.finish()),
+            Arc::new(id_valid.finish()),
+            Arc::new(all_valid.finish()),
+        ],
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Schema of the verdicts of the command "validate"
+///
+/// returns the schema {id: UInt64, score_valid: Boolean, content_valid: Boolean, id_valid: Boolean, all_valid: Boolean}
+fn validate_result_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("id", DataType::UInt64, true),
+        Field::new("score_valid", DataType::Boolean, false),
+        Field::new("content_valid", DataType::Boolean, false),
+        Field::new("id_valid", DataType::Boolean, false),
+        Field::new("all_valid", DataType::Boolean, false),
+    ])
+}
+
+/// Fetches a field of a record batch by name
+/// # Arguments
+/// * `batch` - record batch
+/// * `name` - name of the field
+///
+/// returns the column of the field. A field with the name as alias in its field metadata is returned if no field has the name. Returns an error if the field does not exist
+pub(crate) fn column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a ArrayRef, String> {
+    resolve_field_by_name_or_alias(batch, name)
+        .map(|(_, column)| column)
+        .ok_or(format!("Field '{name}' not found in schema"))
+}
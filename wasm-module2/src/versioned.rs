@@ -0,0 +1,131 @@
+//! Processing of data in different versions of the expected schema, so that applications can migrate to a new version of the schema independently of each other
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::alias::with_alias;
+use crate::null_handling::append_null_handling_column;
+use crate::tagged_docs::{summarize_tags, tags_column};
+use crate::telemetry::ProcessingTimer;
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, has_large_utf8,
+    process_data_batch, process_data_result, provenance_metadata, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Processes data of a given version of the expected schema in Arrow IPC format from the WASM module memory like the command "test" of wasm_memory_process_data_arrow
+///
+/// Version history of the schema:
+/// * 1 - {id: UInt64, content: Utf8, title: Utf8, date: Timestamp(Second, "+00:00"), score: Float64}
+/// * 2 - version 1 with an additional field tags: List<Utf8>. The result has an additional field first_tag: Utf8 (null if a document has no tags)
+/// * 3 - version 1 with the field content renamed to body
+/// # Arguments
+/// * `schema_version` - version of the schema of the data
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the result data in Arrow IPC format. If the version is unknown ("Unknown schema version N") or the processing failed, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_process_versioned_arrow(
+    schema_version: u32,
+    data_offset: *mut u32,
+    data_size: u32,
+) -> u32 {
+    let handler: fn(&RecordBatch) -> Result<RecordBatch, String> = match schema_version {
+        1 => process_version_1,
+        2 => process_version_2,
+        3 => process_version_3,
+        _ => {
+            return allocate_error(
+                WasmResultStatus::ErrorProcessing,
+                format!("Unknown schema version {schema_version}"),
+            )
+        }
+    };
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // the duration of the processing is recorded for wasm_get_last_processing_duration_ns when the function returns
+    let _processing_timer: ProcessingTimer = ProcessingTimer::start();
+    match process_versioned_arrow(&input_vec_data, handler) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, processes it with the handler of its version and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `handler` - handler of the version of the schema of the data
+///
+/// returns the result in Arrow IPC format
+fn process_versioned_arrow(
+    serialized_data: &[u8],
+    handler: fn(&RecordBatch) -> Result<RecordBatch, String>,
+) -> Result<Vec<u8>, String> {
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let result_batch: RecordBatch = handler(&batch)?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Processes data of version 1 of the schema, ie the current schema
+/// # Arguments
+/// * `batch` - record batch of data
+///
+/// returns the result
+fn process_version_1(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    process_core(batch)
+}
+
+/// Processes data of version 2 of the schema, ie with the additional field tags
+/// # Arguments
+/// * `batch` - record batch of data
+///
+/// returns the result with the additional field first_tag
+fn process_version_2(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    let (first_tags, _) = summarize_tags(tags_column(batch)?)?;
+    // the remaining fields are the ones of version 1
+    let mut batch: RecordBatch = batch.clone();
+    let tags_index: usize = batch.schema().index_of("tags").map_err(|e| e.to_string())?;
+    batch.remove_column(tags_index);
+    let result_batch: RecordBatch = process_core(&batch)?;
+    let schema = result_batch.schema();
+    let mut fields: Vec<Field> = schema.fields().iter().map(|f| f.as_ref().clone()).collect();
+    fields.push(Field::new("first_tag", DataType::Utf8, true));
+    let mut columns: Vec<ArrayRef> = result_batch.columns().to_vec();
+    columns.push(Arc::new(StringArray::from(first_tags)));
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Processes data of version 3 of the schema, ie with the field body instead of content
+/// # Arguments
+/// * `batch` - record batch of data
+///
+/// returns the result
+fn process_version_3(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    process_core(&with_alias(batch, "body", "content")?)
+}
+
+/// Processes data of version 1 of the schema, shared by the handlers of all versions
+/// # Arguments
+/// * `batch` - record batch of data with the fields of version 1
+///
+/// returns the result of processing the data as with the command "test" of wasm_memory_process_data_arrow
+fn process_core(batch: &RecordBatch) -> Result<RecordBatch, String> {
+    let null_count: u64 = process_data_batch(batch)?;
+    append_null_handling_column(
+        process_data_result(
+            has_large_utf8(batch),
+            provenance_metadata(batch.schema().metadata()),
+        ),
+        null_count,
+    )
+}
@@ -0,0 +1,584 @@
+//! Window functions over numeric fields of data in Arrow IPC format, e.g. rolling sums, rolling statistics, rolling correlations or exponential moving averages of time series
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, Float64Array, Float64Builder};
+use arrow::datatypes::{DataType, Field, Float64Type, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::{
+    allocate_error, allocate_error_invalid_memory, allocate_result, read_arrow_batch,
+    read_shared_memory, write_arrow_batch, WasmResultStatus,
+};
+
+/// Aggregation applied to the values of a window
+enum WindowFunction {
+    Sum,
+    Mean,
+}
+
+/// Computes a rolling sum or mean over a numeric field of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `field_offset` - position of the start of the name of the numeric field as UTF-8 string
+/// * `field_size` - size of the name of the field
+/// * `window_size` - number of rows of a window, must be greater than 0
+/// * `function_offset` - position of the start of the window function as UTF-8 string, ie "sum" or "mean"
+/// * `function_size` - size of the window function
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the data with the additional field windowed_result (Float64) in Arrow IPC format. The window of the row i covers the rows i - window_size + 1 to i. windowed_result is null for the first window_size - 1 rows (insufficient history) and for windows containing a null or non-finite (NaN, infinity) value. If the computation failed, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_rolling_window_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    field_offset: *mut u32,
+    field_size: u32,
+    window_size: u32,
+    function_offset: *mut u32,
+    function_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - field
+    let input_vec_field: Vec<u8> = match read_shared_memory(field_offset, field_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - function
+    let input_vec_function: Vec<u8> = match read_shared_memory(function_offset, function_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match rolling_window_arrow(
+        &input_vec_data,
+        &input_vec_field,
+        window_size,
+        &input_vec_function,
+    ) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Computes rolling statistics over all fields of type Float64 or Int64 of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `window_size` - number of rows of a window, must be greater than 0
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the data with the additional fields <field>_rolling_mean, <field>_rolling_min, <field>_rolling_max and <field>_rolling_std (population standard deviation) of type Float64 for each field of type Float64 or Int64 in Arrow IPC format. Int64 values are cast to Float64. The window of the row i covers the rows i - window_size + 1 to i. The statistics are null for the first window_size - 1 rows (insufficient history) and for windows containing a null or non-finite (NaN, infinity) value. If the computation failed, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_rolling_stats_all_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    window_size: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match rolling_stats_all_arrow(&input_vec_data, window_size) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Computes the exponential moving average (EMA) over a numeric field of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `field_offset` - position of the start of the name of the numeric field as UTF-8 string
+/// * `field_size` - size of the name of the field
+/// * `alpha_numerator` - numerator of the smoothing factor alpha
+/// * `alpha_denominator` - denominator of the smoothing factor alpha. alpha must be greater than 0 and at most 1
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the data with the additional field ema (Float64) in Arrow IPC format. ema of the first row is its value, ema of the row i is alpha * value of row i + (1 - alpha) * ema of row i - 1. ema is null for null values and the computation restarts at the next non-null value. If the computation failed, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_ema_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    field_offset: *mut u32,
+    field_size: u32,
+    alpha_numerator: u32,
+    alpha_denominator: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - field
+    let input_vec_field: Vec<u8> = match read_shared_memory(field_offset, field_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match ema_arrow(
+        &input_vec_data,
+        &input_vec_field,
+        alpha_numerator,
+        alpha_denominator,
+    ) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Computes the rolling Pearson correlation between two numeric fields of data in Arrow IPC format from the WASM module memory
+/// # Arguments
+/// * `data_offset` - position of the start of the data ("data") in Arrow IPC format
+/// * `data_size` - size of the data in Arrow IPC format
+/// * `field_a_offset` - position of the start of the name of the first numeric field as UTF-8 string
+/// * `field_a_size` - size of the name of the first field
+/// * `field_b_offset` - position of the start of the name of the second numeric field as UTF-8 string
+/// * `field_b_size` - size of the name of the second field
+/// * `window` - number of rows of a window, must be greater than 1
+///
+/// Returns a pointer to a WasmResult in the WASM module memory containing the data with the additional field rolling_correlation (Float64) in Arrow IPC format. The window of the row i covers the rows i - window + 1 to i. rolling_correlation is null for the first window - 1 rows (insufficient history), for windows containing a null value or NaN in one of the fields and for windows in which one of the fields is constant. If the computation failed, the status is non-zero, see wasm_last_error for details
+#[no_mangle]
+pub extern "C" fn wasm_memory_rolling_correlation_arrow(
+    data_offset: *mut u32,
+    data_size: u32,
+    field_a_offset: *mut u32,
+    field_a_size: u32,
+    field_b_offset: *mut u32,
+    field_b_size: u32,
+    window: u32,
+) -> u32 {
+    // fetch from WASM module memory - data
+    let input_vec_data: Vec<u8> = match read_shared_memory(data_offset, data_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - first field
+    let input_vec_field_a: Vec<u8> = match read_shared_memory(field_a_offset, field_a_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    // fetch from WASM module memory - second field
+    let input_vec_field_b: Vec<u8> = match read_shared_memory(field_b_offset, field_b_size) {
+        Some(x) => x,
+        None => return allocate_error_invalid_memory(), // return if no valid allocated memory was provided
+    };
+    match rolling_correlation_arrow(
+        &input_vec_data,
+        &input_vec_field_a,
+        &input_vec_field_b,
+        window,
+    ) {
+        Ok(serialized_result_batch) => allocate_result(serialized_result_batch),
+        Err(error_message) => allocate_error(WasmResultStatus::ErrorProcessing, error_message),
+    }
+}
+
+/// Deserializes the data, appends the result of the window function and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `field` - name of the numeric field as UTF-8 string
+/// * `window_size` - number of rows of a window
+/// * `function` - window function as UTF-8 string, ie "sum" or "mean"
+///
+/// returns the data with the field windowed_result in Arrow IPC format
+fn rolling_window_arrow(
+    serialized_data: &[u8],
+    field: &[u8],
+    window_size: u32,
+    function: &[u8],
+) -> Result<Vec<u8>, String> {
+    let field: &str = std::str::from_utf8(field)
+        .map_err(|e| format!("Name of the field is not valid UTF-8: {e}"))?;
+    let function: WindowFunction = match std::str::from_utf8(function) {
+        Ok("sum") => WindowFunction::Sum,
+        Ok("mean") => WindowFunction::Mean,
+        Ok(x) => {
+            return Err(format!(
+                "Unknown window function '{x}', expected sum or mean"
+            ))
+        }
+        Err(e) => return Err(format!("Window function is not valid UTF-8: {e}")),
+    };
+    if window_size == 0 {
+        return Err("Window size must be greater than 0".to_string());
+    }
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let values: ArrayRef = numeric_values(&batch, field)?;
+    let windowed_result: Float64Array = rolling_window(
+        values.as_primitive::<Float64Type>(),
+        window_size as usize,
+        &function,
+    );
+    let result_batch: RecordBatch = append_column(&batch, "windowed_result", windowed_result)?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Deserializes the data, appends the rolling statistics of all fields of type Float64 or Int64 and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `window_size` - number of rows of a window
+///
+/// returns the data with the fields <field>_rolling_mean, <field>_rolling_min, <field>_rolling_max and <field>_rolling_std in Arrow IPC format
+fn rolling_stats_all_arrow(serialized_data: &[u8], window_size: u32) -> Result<Vec<u8>, String> {
+    if window_size == 0 {
+        return Err("Window size must be greater than 0".to_string());
+    }
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let mut result_batch: RecordBatch = batch.clone();
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        if !matches!(field.data_type(), DataType::Float64 | DataType::Int64) {
+            continue;
+        }
+        let values: ArrayRef =
+            arrow::compute::cast(column, &DataType::Float64).map_err(|e| e.to_string())?;
+        let [mean, min, max, std_dev] =
+            rolling_stats(values.as_primitive::<Float64Type>(), window_size as usize);
+        for (suffix, statistic) in [
+            ("rolling_mean", mean),
+            ("rolling_min", min),
+            ("rolling_max", max),
+            ("rolling_std", std_dev),
+        ] {
+            result_batch = append_column(
+                &result_batch,
+                &format!("{}_{suffix}", field.name()),
+                statistic,
+            )?;
+        }
+    }
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Deserializes the data, appends the exponential moving average and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `field` - name of the numeric field as UTF-8 string
+/// * `alpha_numerator` - numerator of the smoothing factor alpha
+/// * `alpha_denominator` - denominator of the smoothing factor alpha
+///
+/// returns the data with the field ema in Arrow IPC format
+fn ema_arrow(
+    serialized_data: &[u8],
+    field: &[u8],
+    alpha_numerator: u32,
+    alpha_denominator: u32,
+) -> Result<Vec<u8>, String> {
+    let field: &str = std::str::from_utf8(field)
+        .map_err(|e| format!("Name of the field is not valid UTF-8: {e}"))?;
+    if alpha_denominator == 0 {
+        return Err("Denominator of alpha must be greater than 0".to_string());
+    }
+    let alpha: f64 = alpha_numerator as f64 / alpha_denominator as f64;
+    if alpha <= 0.0 || alpha > 1.0 {
+        return Err(format!(
+            "Alpha {alpha_numerator}/{alpha_denominator} must be greater than 0 and at most 1"
+        ));
+    }
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let values: ArrayRef = numeric_values(&batch, field)?;
+    let ema: Float64Array = exponential_moving_average(values.as_primitive::<Float64Type>(), alpha);
+    let result_batch: RecordBatch = append_column(&batch, "ema", ema)?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Deserializes the data, appends the rolling correlation and serializes the result
+/// # Arguments
+/// * `serialized_data` - data in Arrow IPC format
+/// * `field_a` - name of the first numeric field as UTF-8 string
+/// * `field_b` - name of the second numeric field as UTF-8 string
+/// * `window` - number of rows of a window
+///
+/// returns the data with the field rolling_correlation in Arrow IPC format
+fn rolling_correlation_arrow(
+    serialized_data: &[u8],
+    field_a: &[u8],
+    field_b: &[u8],
+    window: u32,
+) -> Result<Vec<u8>, String> {
+    let field_a: &str = std::str::from_utf8(field_a)
+        .map_err(|e| format!("Name of the first field is not valid UTF-8: {e}"))?;
+    let field_b: &str = std::str::from_utf8(field_b)
+        .map_err(|e| format!("Name of the second field is not valid UTF-8: {e}"))?;
+    // the correlation of a single value is not defined
+    if window < 2 {
+        return Err("Window size must be greater than 1".to_string());
+    }
+    let batch: RecordBatch = read_arrow_batch(serialized_data).map_err(|e| e.to_string())?;
+    let values_a: ArrayRef = numeric_values(&batch, field_a)?;
+    let values_b: ArrayRef = numeric_values(&batch, field_b)?;
+    let rolling_correlation: Float64Array = rolling_correlation(
+        values_a.as_primitive::<Float64Type>(),
+        values_b.as_primitive::<Float64Type>(),
+        window as usize,
+    );
+    let result_batch: RecordBatch =
+        append_column(&batch, "rolling_correlation", rolling_correlation)?;
+    write_arrow_batch(&result_batch).map_err(|e| e.to_string())
+}
+
+/// Fetches the values of a numeric field as Float64
+/// # Arguments
+/// * `batch` - record batch of data
+/// * `field` - name of the numeric field
+///
+/// returns the values of the field as Float64Array. Returns an error if the field does not exist or is not numeric
+fn numeric_values(batch: &RecordBatch, field: &str) -> Result<ArrayRef, String> {
+    let column: &ArrayRef = batch
+        .column_by_name(field)
+        .ok_or(format!("Field '{field}' not found in schema"))?;
+    if !column.data_type().is_numeric() {
+        return Err(format!(
+            "Field '{field}' has type {} that is not numeric",
+            column.data_type()
+        ));
+    }
+    arrow::compute::cast(column, &DataType::Float64).map_err(|e| e.to_string())
+}
+
+/// Appends a nullable Float64 field to a record batch
+/// # Arguments
+/// * `batch` - record batch of data
+/// * `name` - name of the field
+/// * `values` - values of the field
+///
+/// returns the record batch with the additional field
+fn append_column(
+    batch: &RecordBatch,
+    name: &str,
+    values: Float64Array,
+) -> Result<RecordBatch, String> {
+    let mut fields: Vec<Field> = batch
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.as_ref().clone())
+        .collect();
+    fields.push(Field::new(name, DataType::Float64, true));
+    let mut columns: Vec<ArrayRef> = batch.columns().to_vec();
+    columns.push(Arc::new(values));
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns).map_err(|e| e.to_string())
+}
+
+/// Applies a window function to each window of values using a running sum, so that each value is added and removed only once
+/// # Arguments
+/// * `values` - values of the field
+/// * `window_size` - number of values of a window
+/// * `function` - window function
+///
+/// returns the result for each window. It is null if the window is incomplete or contains a null or non-finite value
+fn rolling_window(
+    values: &Float64Array,
+    window_size: usize,
+    function: &WindowFunction,
+) -> Float64Array {
+    let mut running_sum: f64 = 0.0;
+    let mut null_count: usize = 0;
+    // non-finite values (NaN, infinity) are treated like null values, so that they are never added to the running sum. Otherwise they could not be removed again and would affect all later windows
+    let is_finite = |i: usize| values.is_valid(i) && values.value(i).is_finite();
+    (0..values.len())
+        .map(|i| {
+            if is_finite(i) {
+                running_sum += values.value(i);
+            } else {
+                null_count += 1;
+            }
+            // remove the value that left the window
+            if i >= window_size {
+                let left: usize = i - window_size;
+                if is_finite(left) {
+                    running_sum -= values.value(left);
+                } else {
+                    null_count -= 1;
+                }
+            }
+            if i + 1 < window_size || null_count > 0 {
+                return None;
+            }
+            match function {
+                WindowFunction::Sum => Some(running_sum),
+                WindowFunction::Mean => Some(running_sum / window_size as f64),
+            }
+        })
+        .collect()
+}
+
+/// Computes the mean, minimum, maximum and population standard deviation of each window of values
+/// # Arguments
+/// * `values` - values of the field
+/// * `window_size` - number of values of a window
+///
+/// returns the mean, minimum, maximum and standard deviation for each window. They are null if the window is incomplete or contains a null value
+fn rolling_stats(values: &Float64Array, window_size: usize) -> [Float64Array; 4] {
+    let mut mean = Float64Builder::with_capacity(values.len());
+    let mut min = Float64Builder::with_capacity(values.len());
+    let mut max = Float64Builder::with_capacity(values.len());
+    let mut std_dev = Float64Builder::with_capacity(values.len());
+    for i in 0..values.len() {
+        let window = (i + 1).saturating_sub(window_size)..i + 1;
+        if i + 1 < window_size || window.clone().any(|j| values.is_null(j)) {
+            mean.append_null();
+            min.append_null();
+            max.append_null();
+            std_dev.append_null();
+            continue;
+        }
+        let window_values: &[f64] = &values.values()[window];
+        let window_mean: f64 = window_values.iter().sum::<f64>() / window_size as f64;
+        let variance: f64 = window_values
+            .iter()
+            .map(|value| (value - window_mean).powi(2))
+            .sum::<f64>()
+            / window_size as f64;
+        mean.append_value(window_mean);
+        min.append_value(window_values.iter().copied().fold(f64::INFINITY, f64::min));
+        max.append_value(
+            window_values
+                .iter()
+                .copied()
+                .fold(f64::NEG_INFINITY, f64::max),
+        );
+        std_dev.append_value(variance.sqrt());
+    }
+    [mean.finish(), min.finish(), max.finish(), std_dev.finish()]
+}
+
+/// Computes the Pearson correlation of each window of pairs of values with Welford's online algorithm, so that each pair is added and removed only once
+/// # Arguments
+/// * `values_a` - values of the first field
+/// * `values_b` - values of the second field
+/// * `window_size` - number of pairs of a window
+///
+/// returns the correlation for each window. It is null if the window is incomplete, contains a null value or NaN or one of the fields is constant
+fn rolling_correlation(
+    values_a: &Float64Array,
+    values_b: &Float64Array,
+    window_size: usize,
+) -> Float64Array {
+    let is_valid = |i: usize| -> bool {
+        values_a.is_valid(i)
+            && values_b.is_valid(i)
+            && !values_a.value(i).is_nan()
+            && !values_b.value(i).is_nan()
+    };
+    let mut correlation = Float64Builder::with_capacity(values_a.len());
+    let mut moments = CoMoments::default();
+    let mut invalid_count: usize = 0;
+    for i in 0..values_a.len() {
+        if is_valid(i) {
+            moments.add(values_a.value(i), values_b.value(i));
+        } else {
+            invalid_count += 1;
+        }
+        // remove the pair that left the window
+        if i >= window_size {
+            let left: usize = i - window_size;
+            if is_valid(left) {
+                moments.remove(values_a.value(left), values_b.value(left));
+            } else {
+                invalid_count -= 1;
+            }
+        }
+        if i + 1 < window_size || invalid_count > 0 {
+            correlation.append_null();
+            continue;
+        }
+        correlation.append_option(moments.correlation());
+    }
+    correlation.finish()
+}
+
+/// Means and (co-)moments of pairs of values that are updated online (Welford's algorithm)
+#[derive(Default)]
+struct CoMoments {
+    /// number of pairs
+    count: f64,
+    /// mean of the first values
+    mean_a: f64,
+    /// mean of the second values
+    mean_b: f64,
+    /// sum of the squared differences of the first values from their mean
+    m2_a: f64,
+    /// sum of the squared differences of the second values from their mean
+    m2_b: f64,
+    /// sum of the products of the differences of the values from their means
+    co_moment: f64,
+}
+
+impl CoMoments {
+    /// Tolerance relative to the sum of the squared values below which a field is considered constant. The updates of the sliding window accumulate rounding errors, so that the moments of a constant field are not always exactly 0
+    const CONSTANT_TOLERANCE: f64 = 1e-12;
+
+    /// Adds a pair of values
+    /// # Arguments
+    /// * `a` - first value
+    /// * `b` - second value
+    fn add(&mut self, a: f64, b: f64) {
+        self.count += 1.0;
+        let delta_a: f64 = a - self.mean_a;
+        self.mean_a += delta_a / self.count;
+        let delta_b: f64 = b - self.mean_b;
+        self.mean_b += delta_b / self.count;
+        self.m2_a += delta_a * (a - self.mean_a);
+        self.m2_b += delta_b * (b - self.mean_b);
+        self.co_moment += delta_a * (b - self.mean_b);
+    }
+
+    /// Removes a pair of values that has been added before
+    /// # Arguments
+    /// * `a` - first value
+    /// * `b` - second value
+    fn remove(&mut self, a: f64, b: f64) {
+        if self.count <= 1.0 {
+            *self = CoMoments::default();
+            return;
+        }
+        self.count -= 1.0;
+        let delta_a: f64 = a - self.mean_a;
+        self.mean_a -= delta_a / self.count;
+        let delta_b: f64 = b - self.mean_b;
+        self.mean_b -= delta_b / self.count;
+        self.m2_a -= delta_a * (a - self.mean_a);
+        self.m2_b -= delta_b * (b - self.mean_b);
+        self.co_moment -= delta_a * (b - self.mean_b);
+    }
+
+    /// Computes the Pearson correlation of the pairs of values
+    ///
+    /// returns the correlation between -1 and 1. It is None if one of the fields is constant
+    fn correlation(&self) -> Option<f64> {
+        // the sum of the squared values is m2 + count * mean^2, so that the tolerance scales with the magnitude of the values of the field
+        let is_constant = |m2: f64, mean: f64| -> bool {
+            m2 <= Self::CONSTANT_TOLERANCE * (m2 + self.count * mean.powi(2))
+        };
+        if is_constant(self.m2_a, self.mean_a) || is_constant(self.m2_b, self.mean_b) {
+            return None;
+        }
+        Some((self.co_moment / (self.m2_a * self.m2_b).sqrt()).clamp(-1.0, 1.0))
+    }
+}
+
+/// Computes the exponential moving average of values
+/// # Arguments
+/// * `values` - values of the field
+/// * `alpha` - smoothing factor, greater than 0 and at most 1
+///
+/// returns the exponential moving average for each value. It is null for null values, the computation restarts at the next non-null value
+fn exponential_moving_average(values: &Float64Array, alpha: f64) -> Float64Array {
+    let mut ema = Float64Builder::with_capacity(values.len());
+    let mut previous: Option<f64> = None;
+    for i in 0..values.len() {
+        if values.is_null(i) {
+            ema.append_null();
+            previous = None;
+            continue;
+        }
+        let current: f64 = match previous {
+            Some(previous) => alpha * values.value(i) + (1.0 - alpha) * previous,
+            None => values.value(i),
+        };
+        ema.append_value(current);
+        previous = Some(current);
+    }
+    ema.finish()
+}
@@ -0,0 +1,145 @@
+//! Reuse of the serialized schema header and the buffer of Arrow IPC streams, so that results with the same schema do not need to encode the schema and allocate a buffer for every call
+use std::cell::RefCell;
+
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::error::ArrowError;
+use arrow::ipc::writer::{
+    write_message, DictionaryTracker, IpcDataGenerator, IpcWriteOptions, StreamWriter,
+};
+use arrow::record_batch::RecordBatch;
+
+use crate::write_arrow_batch_unpooled;
+
+/// End of an Arrow IPC stream: continuation marker followed by a message length of 0
+const END_OF_STREAM: [u8; 8] = [0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0];
+
+/// Writer of Arrow IPC streams for one schema
+struct PooledWriter {
+    /// schema of the record batches
+    schema: SchemaRef,
+    /// the serialized schema header followed by the last written record batch
+    buffer: Vec<u8>,
+    /// length of the serialized schema header at the start of the buffer
+    header_len: usize,
+    data_gen: IpcDataGenerator,
+    write_options: IpcWriteOptions,
+}
+
+// Global variable with the writer for the schema of the last result. It is replaced if a result has another schema
+thread_local!(
+    static WRITER_POOL: RefCell<Option<PooledWriter>> = const { RefCell::new(None) };
+);
+
+impl PooledWriter {
+    /// Creates a writer and serializes the schema header
+    /// # Arguments
+    /// * `schema` - schema of the record batches
+    ///
+    /// returns the writer
+    fn try_new(schema: SchemaRef) -> Result<PooledWriter, ArrowError> {
+        let write_options = IpcWriteOptions::default();
+        let stream_writer =
+            StreamWriter::try_new_with_options(Vec::new(), &schema, write_options.clone())?;
+        let buffer: Vec<u8> = stream_writer.get_ref().clone();
+        Ok(PooledWriter {
+            schema,
+            header_len: buffer.len(),
+            buffer,
+            data_gen: IpcDataGenerator::default(),
+            write_options,
+        })
+    }
+
+    /// Writes one record batch after the schema header
+    /// # Arguments
+    /// * `batch` - record batch with the schema of the writer
+    ///
+    /// returns a copy of the stream. The buffer keeps its capacity for the next record batch
+    fn write(&mut self, batch: &RecordBatch) -> Result<Vec<u8>, ArrowError> {
+        self.buffer.truncate(self.header_len);
+        // the schema has no dictionaries, so the tracker does not need to know the schema header
+        let mut dictionary_tracker = DictionaryTracker::new(false);
+        let (_, encoded_batch) =
+            self.data_gen
+                .encoded_batch(batch, &mut dictionary_tracker, &self.write_options)?;
+        write_message(&mut self.buffer, encoded_batch, &self.write_options)?;
+        self.buffer.extend_from_slice(&END_OF_STREAM);
+        let stream: Vec<u8> = self.buffer.clone();
+        self.buffer.truncate(self.header_len);
+        Ok(stream)
+    }
+}
+
+/// Serializes a record batch in Arrow IPC format with the writer of its schema. Schemas with dictionaries are written without the pool, as their header and record batches depend on each other
+/// # Arguments
+/// * `batch` - record batch to serialize
+///
+/// returns the serialized record batch
+pub(crate) fn write_arrow_batch_pooled(batch: &RecordBatch) -> Result<Vec<u8>, ArrowError> {
+    if batch
+        .schema_ref()
+        .flattened_fields()
+        .iter()
+        .any(|field| matches!(field.data_type(), DataType::Dictionary(_, _)))
+    {
+        return write_arrow_batch_unpooled(batch);
+    }
+    WRITER_POOL.with(|writer_pool| {
+        let mut writer_pool = writer_pool.borrow_mut();
+        let pooled_writer: &mut PooledWriter = match writer_pool.as_mut() {
+            Some(pooled_writer) if pooled_writer.schema == batch.schema() => pooled_writer,
+            _ => writer_pool.insert(PooledWriter::try_new(batch.schema())?),
+        };
+        pooled_writer.write(batch)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use arrow::record_batch::RecordBatch;
+
+    use super::write_arrow_batch_pooled;
+    use crate::{process_data_result, write_arrow_batch_unpooled};
+
+    /// Number of serializations of the result to compare the writer pool with a new StreamWriter for each call
+    const BENCHMARK_ITERATIONS: u32 = 100_000;
+
+    /// Serializes the result of wasm_memory_process_data_arrow repeatedly
+    /// # Arguments
+    /// * `write` - function serializing a record batch
+    ///
+    /// returns the duration of all iterations
+    fn measure(write: fn(&RecordBatch) -> Result<Vec<u8>, arrow::error::ArrowError>) -> Duration {
+        let batch: RecordBatch = process_data_result(false, Default::default());
+        let start: Instant = Instant::now();
+        for _ in 0..BENCHMARK_ITERATIONS {
+            write(&batch).unwrap();
+        }
+        start.elapsed()
+    }
+
+    #[test]
+    fn pooled_stream_is_identical_to_unpooled_stream() {
+        let batch: RecordBatch = process_data_result(false, Default::default());
+        let unpooled: Vec<u8> = write_arrow_batch_unpooled(&batch).unwrap();
+        // the second call reuses the pooled writer
+        for _ in 0..2 {
+            assert_eq!(write_arrow_batch_pooled(&batch).unwrap(), unpooled);
+        }
+    }
+
+    /// Run it with cargo test --release -- --ignored --nocapture writer_pool
+    #[test]
+    #[ignore = "benchmark"]
+    fn benchmark_writer_pool() {
+        let unpooled: Duration = measure(write_arrow_batch_unpooled);
+        let pooled: Duration = measure(write_arrow_batch_pooled);
+        println!(
+            "Serializing the result {BENCHMARK_ITERATIONS} times without writer pool: {:.3} ms, with writer pool: {:.3} ms",
+            unpooled.as_secs_f64() * 1000.0,
+            pooled.as_secs_f64() * 1000.0
+        );
+    }
+}